@@ -0,0 +1,99 @@
+//! # File Signal Policy
+//!
+//! Rule file signals read files under the request's `project_path`, which a hosted
+//! server receives from clients. The policy decides whether signals may touch the
+//! filesystem at all, and if so, confines them to a sandbox root.
+
+use crate::CasialError;
+use anyhow::Result;
+use std::path::{Component, Path, PathBuf};
+
+/// How rule file signals may touch the filesystem
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FileSignalPolicy {
+    /// Evaluate signals under any `project_path`, for local and CLI use
+    #[default]
+    Unrestricted,
+    /// Never touch the filesystem; rules with file signals don't activate
+    Disabled,
+    /// Only evaluate paths that resolve inside this root
+    Sandboxed(PathBuf),
+}
+
+impl FileSignalPolicy {
+    /// Path a signal should check, or `None` when signals are disabled
+    ///
+    /// Under a sandbox, a relative `project_path` is taken from the root. Paths with `..`,
+    /// absolute signal paths, and symlinks leading outside the root fail with
+    /// `CasialError::CoordinationFailure`.
+    pub fn resolve(&self, project_path: &str, signal_path: &str) -> Result<Option<PathBuf>> {
+        let root = match self {
+            FileSignalPolicy::Unrestricted => {
+                return Ok(Some(Path::new(project_path).join(signal_path)))
+            }
+            FileSignalPolicy::Disabled => return Ok(None),
+            FileSignalPolicy::Sandboxed(root) => root,
+        };
+
+        let escapes = |reason: &str| {
+            CasialError::CoordinationFailure(format!(
+                "File signal '{}' under '{}' {} the sandbox root {}",
+                signal_path,
+                project_path,
+                reason,
+                root.display()
+            ))
+        };
+        let has_parent = |path: &Path| path.components().any(|c| c == Component::ParentDir);
+        let signal = Path::new(signal_path);
+        if signal.is_absolute() || has_parent(signal) || has_parent(Path::new(project_path)) {
+            return Err(escapes("escapes").into());
+        }
+
+        let root = root
+            .canonicalize()
+            .map_err(|e| escapes(&format!("cannot be checked against ({})", e)))?;
+        let candidate = root.join(project_path).join(signal);
+        // Symlinks are only followed when the target exists
+        let resolved = candidate.canonicalize().unwrap_or(candidate);
+        if !resolved.starts_with(&root) {
+            return Err(escapes("escapes").into());
+        }
+        Ok(Some(resolved))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_rejects_traversal_and_outside_paths() {
+        let dir = std::env::temp_dir().join(format!("casial-sandbox-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("project")).unwrap();
+        let root = dir.canonicalize().unwrap();
+        let policy = FileSignalPolicy::Sandboxed(root.clone());
+
+        assert_eq!(
+            policy.resolve("project", "Cargo.toml").unwrap(),
+            Some(root.join("project/Cargo.toml"))
+        );
+        for (project_path, signal_path) in [
+            ("project", "../../etc/passwd"),
+            ("../outside", "Cargo.toml"),
+            ("project", "/etc/passwd"),
+            ("/etc", "passwd"),
+        ] {
+            let err = policy.resolve(project_path, signal_path).unwrap_err();
+            assert!(err.to_string().contains("sandbox root"), "{}", err);
+        }
+
+        assert_eq!(
+            FileSignalPolicy::Disabled
+                .resolve("project", "Cargo.toml")
+                .unwrap(),
+            None
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+//! # Template Includes
+//!
+//! Templates can pull in other templates with `{{> template_id}}`. Expansion is
+//! depth-limited and rejects cycles, so self-referencing templates fail with a clear
+//! chain instead of expanding forever. It also stops once the output passes a character
+//! limit, so templates that include each other many times over cannot blow up.
+
+use crate::CasialError;
+use anyhow::Result;
+use regex::Regex;
+use std::{collections::HashMap, sync::OnceLock};
+
+/// Nesting limit for `{{> template_id}}` includes when the mission doesn't set one
+pub const DEFAULT_MAX_TEMPLATE_DEPTH: usize = 8;
+
+/// Highest nesting limit a mission may set; larger values are clamped to it
+pub const MAX_TEMPLATE_DEPTH_LIMIT: usize = 32;
+
+/// Output limit for a template's expansion when the budget sets no character limit
+pub const DEFAULT_MAX_EXPANDED_CHARS: usize = 1_000_000;
+
+fn include_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{\{>\s*([A-Za-z0-9_.:-]+)\s*\}\}").expect("valid regex"))
+}
+
+/// One template's expanded content
+#[derive(Clone)]
+struct Expansion {
+    content: String,
+    chars: usize,
+    /// Include levels below the template
+    height: usize,
+}
+
+/// Expands the includes of one coordination's templates
+///
+/// Each included template is expanded once and reused wherever it is included again.
+pub struct IncludeExpander<'a, 'l> {
+    lookup: &'l dyn Fn(&str) -> Option<&'a str>,
+    max_depth: usize,
+    max_chars: usize,
+    expanded: HashMap<String, Expansion>,
+}
+
+impl<'a, 'l> IncludeExpander<'a, 'l> {
+    /// `lookup` maps a template id to its content. `max_depth` is clamped to
+    /// [`MAX_TEMPLATE_DEPTH_LIMIT`].
+    pub fn new(
+        lookup: &'l dyn Fn(&str) -> Option<&'a str>,
+        max_depth: usize,
+        max_chars: usize,
+    ) -> Self {
+        Self {
+            lookup,
+            max_depth: max_depth.min(MAX_TEMPLATE_DEPTH_LIMIT),
+            max_chars,
+            expanded: HashMap::new(),
+        }
+    }
+
+    /// Expand the includes in a template's content
+    ///
+    /// Fails with `CasialError::TemplateError` naming the include chain on a cycle, an
+    /// unknown id, or nesting beyond the depth limit. Expansion stops as soon as the output
+    /// passes `max_chars`, so the result is then longer than the limit but cut short.
+    pub fn expand(&mut self, template_id: &str, content: &str) -> Result<String> {
+        let mut chain = vec![template_id.to_string()];
+        Ok(self.expand_in(content, &mut chain)?.content)
+    }
+
+    fn expand_in(&mut self, content: &str, chain: &mut Vec<String>) -> Result<Expansion> {
+        let mut expanded = String::with_capacity(content.len());
+        let mut chars = 0;
+        let mut height = 0;
+        let mut last = 0;
+        for capture in include_pattern().captures_iter(content) {
+            let whole = capture.get(0).expect("match");
+            let included = &capture[1];
+
+            if chain.iter().any(|id| id == included) {
+                return Err(CasialError::TemplateError(format!(
+                    "Template include cycle: {} -> {}",
+                    chain.join(" -> "),
+                    included
+                ))
+                .into());
+            }
+            if chain.len() > self.max_depth {
+                return Err(CasialError::TemplateError(format!(
+                    "Template include depth exceeds {}: {} -> {}",
+                    self.max_depth,
+                    chain.join(" -> "),
+                    included
+                ))
+                .into());
+            }
+
+            // A cached expansion only stands in if it fits under the depth limit here
+            let cached = self
+                .expanded
+                .get(included)
+                .filter(|cached| chain.len() + cached.height <= self.max_depth)
+                .cloned();
+            let inner = match cached {
+                Some(cached) => cached,
+                None => {
+                    let included_content = (self.lookup)(included).ok_or_else(|| {
+                        CasialError::TemplateError(format!(
+                            "Unknown template '{}' included by {}",
+                            included,
+                            chain.join(" -> ")
+                        ))
+                    })?;
+                    chain.push(included.to_string());
+                    let inner = self.expand_in(included_content, chain)?;
+                    chain.pop();
+                    self.expanded.insert(included.to_string(), inner.clone());
+                    inner
+                }
+            };
+
+            let literal = &content[last..whole.start()];
+            expanded.push_str(literal);
+            expanded.push_str(&inner.content);
+            chars += literal.chars().count() + inner.chars;
+            height = height.max(inner.height + 1);
+            last = whole.end();
+            if chars > self.max_chars {
+                return Ok(Expansion {
+                    content: expanded,
+                    chars,
+                    height,
+                });
+            }
+        }
+        let rest = &content[last..];
+        expanded.push_str(rest);
+        Ok(Expansion {
+            content: expanded,
+            chars: chars + rest.chars().count(),
+            height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_includes_stop_at_the_char_limit() {
+        // Each level includes the next one ten times: 10^20 characters unbounded
+        let templates: Vec<(String, String)> = (0..20)
+            .map(|level| {
+                let content = if level == 19 {
+                    "x".to_string()
+                } else {
+                    format!("{{{{> t{}}}}}", level + 1).repeat(10)
+                };
+                (format!("t{}", level), content)
+            })
+            .collect();
+        let lookup = |id: &str| {
+            templates
+                .iter()
+                .find(|(template_id, _)| template_id == id)
+                .map(|(_, content)| content.as_str())
+        };
+
+        let mut expander = IncludeExpander::new(&lookup, 100, 5_000);
+        let expanded = expander.expand("t0", &templates[0].1).unwrap();
+        assert!(expanded.chars().count() > 5_000);
+        assert!(expanded.chars().count() < 10_000);
+        assert!(expanded.chars().all(|c| c == 'x'));
+    }
+
+    #[test]
+    fn mission_depth_limits_are_clamped() {
+        let chain: Vec<String> = (0..40)
+            .map(|level| format!("{{{{> c{}}}}}", level + 1))
+            .collect();
+        let lookup = |id: &str| {
+            let level: usize = id.strip_prefix('c')?.parse().ok()?;
+            chain.get(level).map(String::as_str)
+        };
+
+        let mut expander = IncludeExpander::new(&lookup, usize::MAX, usize::MAX);
+        let error = expander.expand("c0", &chain[0]).expect_err("too deep");
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("depth exceeds {}", MAX_TEMPLATE_DEPTH_LIMIT)),
+            "{}",
+            error
+        );
+    }
+}
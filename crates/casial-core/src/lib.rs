@@ -10,21 +10,32 @@ use ahash::AHashMap;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use parking_lot::RwLock;
+use parking_lot::{Condvar, Mutex, RwLock};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 use uuid::Uuid;
 
 pub mod coordination;
+pub mod file_signals;
+pub mod ids;
+pub mod includes;
 pub mod paradox;
 pub mod perception;
 pub mod substrate;
+pub mod transport;
 
 // Re-exports for convenience
 pub use coordination::*;
+pub use file_signals::*;
+pub use ids::*;
+pub use includes::*;
 pub use paradox::*;
 pub use perception::*;
 pub use substrate::*;
+pub use transport::*;
 
 /// Core errors in the Casial system
 #[derive(thiserror::Error, Debug)]
@@ -56,6 +67,11 @@ impl PerceptionId {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
+
+    /// Create a perception id from an injectable id source
+    pub fn from_source(source: &dyn IdSource) -> Self {
+        Self(source.next_id())
+    }
 }
 
 impl Default for PerceptionId {
@@ -83,12 +99,16 @@ pub struct CasialTemplate {
     pub name: String,
     pub description: String,
     pub categories: Vec<String>,
+    /// Composition order: lower values compose first, equal priorities by `id`
     pub priority: u32,
     pub enabled: bool,
     pub content: String,
     pub perception_affinity: Vec<PerceptionId>,
     pub paradox_resistance: f64, // How well it handles contradictory contexts
     pub metadata: AHashMap<String, serde_json::Value>,
+    /// Mission feature flags that must all be enabled for this template to be injected
+    #[serde(default)]
+    pub required_flags: Vec<String>,
 }
 
 /// Rules for when and how to apply templates
@@ -100,7 +120,16 @@ pub struct CoordinationRule {
     pub conditions: RuleConditions,
     pub actions: RuleActions,
     pub perception_scope: Vec<PerceptionId>,
+    /// Also draw every template tagged with one of these categories, after `template_ids`
+    #[serde(default)]
+    pub category_scope: Vec<String>,
     pub paradox_handling: ParadoxStrategy,
+    /// Mission feature flags that must all be enabled for this rule to activate
+    #[serde(default)]
+    pub required_flags: Vec<String>,
+    /// Seconds after activating before the rule may activate again for the same session
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
 }
 
 /// Conditions that must be met for a rule to activate
@@ -121,6 +150,18 @@ pub struct RuleActions {
     pub target_field: Option<String>,
     pub char_limit: Option<usize>,
     pub perception_lock: bool,
+    #[serde(default)]
+    pub selection: TemplateSelection,
+}
+
+/// How an activated rule chooses among its templates
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum TemplateSelection {
+    /// Apply every listed template
+    #[default]
+    All,
+    /// Apply one template per activation, chosen by weight; unweighted ids are never picked
+    WeightedPick { weights: AHashMap<String, f64> },
 }
 
 /// File system signals for contextual awareness
@@ -133,7 +174,7 @@ pub struct FileSignal {
 }
 
 /// How to transform the injected content
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TransformType {
     Prepend,
     Append,
@@ -142,6 +183,17 @@ pub enum TransformType {
     PerceptionLayer,
 }
 
+impl TransformType {
+    /// Every transform type, in declaration order
+    pub const ALL: [TransformType; 5] = [
+        TransformType::Prepend,
+        TransformType::Append,
+        TransformType::InjectField,
+        TransformType::SystemInstruction,
+        TransformType::PerceptionLayer,
+    ];
+}
+
 /// Strategy for handling paradoxes (contradictory information)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ParadoxStrategy {
@@ -155,6 +207,16 @@ pub enum ParadoxStrategy {
     Expose,
 }
 
+impl ParadoxStrategy {
+    /// Every paradox strategy, in declaration order
+    pub const ALL: [ParadoxStrategy; 4] = [
+        ParadoxStrategy::Ignore,
+        ParadoxStrategy::Coexist,
+        ParadoxStrategy::Synthesize,
+        ParadoxStrategy::Expose,
+    ];
+}
+
 /// A mission defines the overall context coordination strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CasialMission {
@@ -167,6 +229,96 @@ pub struct CasialMission {
     pub budgets: BudgetConfiguration,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Base missions (file paths or ids) whose templates, rules and perceptions are inherited
+    #[serde(default)]
+    pub extends: Vec<String>,
+    /// Named switches gating templates and rules through their `required_flags`
+    #[serde(default)]
+    pub feature_flags: AHashMap<String, bool>,
+    /// Paradox tolerance used when a request doesn't specify one
+    #[serde(default)]
+    pub default_paradox_tolerance: Option<f64>,
+    /// Per-tool paradox tolerance, taking precedence over `default_paradox_tolerance`
+    #[serde(default)]
+    pub per_tool_tolerance: AHashMap<String, f64>,
+}
+
+impl CasialMission {
+    /// Inherit templates, rules and perceptions from a base mission.
+    ///
+    /// Definitions already present on this mission win over base definitions with the same id.
+    pub fn inherit_from(&mut self, base: &CasialMission) {
+        inherit_by_id(&mut self.templates, &base.templates, |t| t.id.clone());
+        inherit_by_id(&mut self.rules, &base.rules, |r| r.id.clone());
+        inherit_by_id(&mut self.perceptions, &base.perceptions, |p| p.id);
+        for (flag, enabled) in &base.feature_flags {
+            self.feature_flags.entry(flag.clone()).or_insert(*enabled);
+        }
+        for (tool, tolerance) in &base.per_tool_tolerance {
+            self.per_tool_tolerance
+                .entry(tool.clone())
+                .or_insert(*tolerance);
+        }
+        self.default_paradox_tolerance = self
+            .default_paradox_tolerance
+            .or(base.default_paradox_tolerance);
+    }
+
+    /// Paradox tolerance for a tool when the request leaves it unset
+    pub fn paradox_tolerance_for(&self, tool_name: &str) -> f64 {
+        self.configured_paradox_tolerance(tool_name)
+            .unwrap_or(DEFAULT_PARADOX_TOLERANCE)
+    }
+
+    /// The tolerance this mission sets for `tool_name`, if it sets one
+    fn configured_paradox_tolerance(&self, tool_name: &str) -> Option<f64> {
+        self.per_tool_tolerance
+            .get(tool_name)
+            .copied()
+            .or(self.default_paradox_tolerance)
+    }
+
+    /// Templates tagged with `category`, in mission order
+    pub fn get_templates_by_category<'a>(
+        &'a self,
+        category: &'a str,
+    ) -> impl Iterator<Item = &'a CasialTemplate> + 'a {
+        self.templates
+            .iter()
+            .filter(move |template| template.categories.iter().any(|c| c == category))
+    }
+
+    /// Ids a rule draws templates from: its `template_ids`, then its `category_scope` matches
+    pub fn rule_template_ids<'a>(&'a self, rule: &'a CoordinationRule) -> Vec<&'a String> {
+        let mut ids: Vec<&String> = rule.actions.template_ids.iter().collect();
+        for category in &rule.category_scope {
+            for template in self.get_templates_by_category(category) {
+                if !ids.contains(&&template.id) {
+                    ids.push(&template.id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Whether every flag in `required` is enabled; undeclared flags count as disabled
+    pub fn flags_enabled(&self, required: &[String]) -> bool {
+        required
+            .iter()
+            .all(|flag| self.feature_flags.get(flag).copied().unwrap_or(false))
+    }
+}
+
+/// Prepend base items whose ids are not already defined by the child
+fn inherit_by_id<T: Clone, K: PartialEq>(child: &mut Vec<T>, base: &[T], key: impl Fn(&T) -> K) {
+    let child_keys: Vec<K> = child.iter().map(&key).collect();
+    let mut merged: Vec<T> = base
+        .iter()
+        .filter(|item| !child_keys.contains(&key(item)))
+        .cloned()
+        .collect();
+    merged.append(child);
+    *child = merged;
 }
 
 /// Budget configuration for resource management
@@ -176,6 +328,90 @@ pub struct BudgetConfiguration {
     pub per_tool_limits: AHashMap<String, usize>,
     pub perception_quotas: AHashMap<PerceptionId, usize>,
     pub paradox_overhead: f64, // Additional resources for paradox handling
+    /// Regexes whose matches in composed template content are replaced with `***`
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+    /// Nesting limit for `{{> template_id}}` includes (default `DEFAULT_MAX_TEMPLATE_DEPTH`,
+    /// at most `MAX_TEMPLATE_DEPTH_LIMIT`)
+    #[serde(default)]
+    pub max_template_depth: Option<usize>,
+    /// Paradoxes recorded per coordination (default `DEFAULT_MAX_PARADOXES`)
+    #[serde(default)]
+    pub max_paradoxes: Option<usize>,
+    /// Delimiters wrapped around each injected section; none by default
+    #[serde(default)]
+    pub injection_markers: Option<InjectionMarkers>,
+    /// Cumulative limit on characters injected for this mission over a time window
+    #[serde(default)]
+    pub window_budget: Option<WindowBudget>,
+}
+
+/// Injection allowance that refills at the end of each fixed window
+///
+/// A window opens with the first coordination after the previous one ended. Once
+/// `char_limit` characters have been injected, coordinations inject nothing until the
+/// window resets. Concurrent coordinations are charged after composing, so they can
+/// overshoot the limit by their own size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowBudget {
+    pub char_limit: usize,
+    pub window_seconds: u64,
+}
+
+/// Characters injected for one mission in its current window
+#[derive(Debug, Clone)]
+struct WindowUsage {
+    started_at: DateTime<Utc>,
+    injected_chars: usize,
+}
+
+/// Begin/end lines around injected content, letting clients find and strip it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InjectionMarkers {
+    pub begin: String,
+    pub end: String,
+}
+
+impl InjectionMarkers {
+    fn wrap(&self, content: &str) -> String {
+        format!("{}\n{}{}", self.begin, content, self.end)
+    }
+}
+
+/// Paradox tolerance when neither the request nor the mission sets one
+pub const DEFAULT_PARADOX_TOLERANCE: f64 = 0.5;
+
+/// Coordinations kept in the engine's history when no other limit is set
+pub const DEFAULT_MAX_HISTORY_ENTRIES: usize = 10_000;
+
+/// Clamp a paradox tolerance to `[0.0, 1.0]`; NaN and infinite values give `None`
+pub fn sanitize_paradox_tolerance(tolerance: f64) -> Option<f64> {
+    tolerance.is_finite().then(|| tolerance.clamp(0.0, 1.0))
+}
+
+/// Replacement for content matched by a budget redaction pattern
+const REDACTION_MASK: &str = "***";
+
+/// Mask every match of `patterns` in `content`, returning the masked content and match count
+fn redact(content: &str, patterns: &[regex::Regex]) -> (String, usize) {
+    let mut content = content.to_string();
+    let mut redactions = 0;
+    for pattern in patterns {
+        let matches = pattern.find_iter(&content).count();
+        if matches > 0 {
+            redactions += matches;
+            content = pattern.replace_all(&content, REDACTION_MASK).into_owned();
+        }
+    }
+    (content, redactions)
+}
+
+/// Compile budget redaction patterns; they were validated when the mission was loaded
+fn redaction_regexes<'a>(patterns: impl IntoIterator<Item = &'a String>) -> Vec<regex::Regex> {
+    patterns
+        .into_iter()
+        .filter_map(|pattern| regex::Regex::new(pattern).ok())
+        .collect()
 }
 
 /// Input for context coordination
@@ -186,7 +422,104 @@ pub struct CoordinationRequest {
     pub environment: AHashMap<String, String>,
     pub project_path: Option<String>,
     pub active_perceptions: Vec<PerceptionId>,
-    pub paradox_tolerance: f64,
+    /// Highest paradox impact a template may be involved in and still be included, in
+    /// `[0.0, 1.0]` (0 = strict, 1 = adaptive); out-of-range values are clamped. Falls back to
+    /// the mission's per-tool or default tolerance when unset, NaN or infinite
+    #[serde(default)]
+    pub paradox_tolerance: Option<f64>,
+    /// Caller-requested cap on injected characters; narrows the mission's global limit
+    #[serde(default)]
+    pub max_context_size: Option<usize>,
+    /// Whether to detect and resolve paradoxes; when false, templates are injected as-is
+    #[serde(default = "default_detect_paradoxes")]
+    pub detect_paradoxes: bool,
+    /// When set, only these templates may be injected, whatever rules activate
+    #[serde(default)]
+    pub template_allow: Option<Vec<String>>,
+    /// Templates never injected for this request; takes precedence over the allow list
+    #[serde(default)]
+    pub template_deny: Vec<String>,
+    /// Templates with a higher priority number (less important) are skipped before
+    /// composition, for cheap/fast calls
+    #[serde(default)]
+    pub max_template_priority: Option<u32>,
+    /// Record which template and rule produced each range of injected content
+    #[serde(default)]
+    pub include_provenance: bool,
+    /// Attach the conflicting templates' content to each detected paradox, for drill-down
+    #[serde(default)]
+    pub include_paradox_evidence: bool,
+    /// Coordinate against only this loaded mission; every loaded mission when unset
+    #[serde(default)]
+    pub mission_id: Option<String>,
+    /// When each rule last activated for the caller's session, for `cooldown_secs`
+    #[serde(default)]
+    pub rule_activations: AHashMap<String, DateTime<Utc>>,
+}
+
+impl Default for CoordinationRequest {
+    fn default() -> Self {
+        Self {
+            tool_name: String::new(),
+            tool_args: serde_json::Value::Null,
+            environment: AHashMap::new(),
+            project_path: None,
+            active_perceptions: Vec::new(),
+            paradox_tolerance: None,
+            max_context_size: None,
+            detect_paradoxes: default_detect_paradoxes(),
+            template_allow: None,
+            template_deny: Vec::new(),
+            max_template_priority: None,
+            include_provenance: false,
+            include_paradox_evidence: false,
+            mission_id: None,
+            rule_activations: AHashMap::new(),
+        }
+    }
+}
+
+impl CoordinationRequest {
+    /// A request for one tool call, coordinated against every mission with default options
+    pub fn new(tool_name: impl Into<String>, tool_args: serde_json::Value) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            tool_args,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a rule activated too recently for this session to activate again
+    fn in_cooldown(&self, rule: &CoordinationRule, now: DateTime<Utc>) -> bool {
+        let (Some(cooldown), Some(last)) =
+            (rule.cooldown_secs, self.rule_activations.get(&rule.id))
+        else {
+            return false;
+        };
+        now.signed_duration_since(*last) < chrono::Duration::seconds(cooldown as i64)
+    }
+
+    /// Whether the caller's allow/deny lists permit a template
+    pub fn permits_template(&self, template_id: &str) -> bool {
+        if self.template_deny.iter().any(|id| id == template_id) {
+            return false;
+        }
+        self.template_allow
+            .as_ref()
+            .map_or(true, |allow| allow.iter().any(|id| id == template_id))
+    }
+
+    /// Whether a template is important enough for the caller's `max_template_priority`
+    ///
+    /// Lower priority numbers compose first, so they are the more important templates.
+    pub fn within_max_priority(&self, template: &CasialTemplate) -> bool {
+        self.max_template_priority
+            .map_or(true, |max| template.priority <= max)
+    }
+}
+
+fn default_detect_paradoxes() -> bool {
+    true
 }
 
 /// Result of context coordination
@@ -202,6 +535,92 @@ pub struct CoordinationResult {
     pub metadata: AHashMap<String, serde_json::Value>,
 }
 
+/// A coordination history entry: the request, what it produced, and when
+///
+/// `timestamp` is when the coordination started and matches the result's `timestamp`
+/// metadata; entries are keyed by a sequence number that is also reported as `sequence`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoordinationRecord {
+    pub request: CoordinationRequest,
+    pub result: CoordinationResult,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A range of `injected_content` contributed by one template
+///
+/// `start` and `end` are character (not byte) offsets, end-exclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceSpan {
+    pub template_id: String,
+    pub rule_id: Option<String>,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Rough LLM token count for text: one token per four characters, rounded up
+///
+/// A tokenizer-free heuristic for reasoning about budgets, not an exact count.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Field that injected content falls back to when the tool args have no string target
+const FALLBACK_CONTEXT_FIELD: &str = "casial_context";
+
+/// Where a rule's templates land in the tool arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Placement {
+    transform_type: TransformType,
+    target_field: Option<String>,
+}
+
+impl Placement {
+    fn prepend() -> Self {
+        Self {
+            transform_type: TransformType::Prepend,
+            target_field: None,
+        }
+    }
+}
+
+/// Injected content composed within budget, grouped by placement
+struct ComposedContext {
+    content: String,
+    sections: Vec<(Placement, String)>,
+    used_templates: Vec<String>,
+    /// Character ranges of `content` by template id
+    spans: Vec<(String, usize, usize)>,
+    redactions: usize,
+    /// Enabled templates left out because the budget ran out
+    dropped_templates: Vec<String>,
+    /// Whether the paradox disclosure was left out for not fitting the budget
+    disclosure_dropped: bool,
+    /// Characters charged against the budget, injection markers included
+    charged_chars: usize,
+}
+
+impl ComposedContext {
+    /// Put a block ahead of all composed content, routed through the prepend section
+    fn prepend_block(&mut self, block: &str) {
+        self.content.insert_str(0, block);
+        let shift = block.chars().count();
+        for (_, start, end) in &mut self.spans {
+            *start += shift;
+            *end += shift;
+        }
+        match self
+            .sections
+            .iter_mut()
+            .find(|(p, _)| p.transform_type == TransformType::Prepend)
+        {
+            Some((_, section)) => section.insert_str(0, block),
+            None => self
+                .sections
+                .insert(0, (Placement::prepend(), block.to_string())),
+        }
+    }
+}
+
 /// Report of paradox detection and handling
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParadoxReport {
@@ -210,29 +629,168 @@ pub struct ParadoxReport {
     pub conflicting_perceptions: Vec<PerceptionId>,
     pub resolution_strategy: ParadoxStrategy,
     pub confidence_impact: f64,
+    /// Set once the paradox has been resolved explicitly via [`CasialEngine::resolve_paradox`]
+    #[serde(default)]
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Known only for reports derived from a full [`Paradox`]
+    #[serde(default)]
+    pub severity: Option<ParadoxSeverity>,
+    /// The conflicting elements with content snippets; only when the request asked for them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<Vec<ParadoxElement>>,
+}
+
+/// Longest template excerpt kept as paradox evidence, in characters
+const EVIDENCE_SNIPPET_CHARS: usize = 280;
+
+impl ParadoxReport {
+    /// A template's side of a conflict, with a redacted excerpt of its content
+    fn template_evidence(
+        template: &CasialTemplate,
+        redaction_patterns: &[regex::Regex],
+    ) -> ParadoxElement {
+        let (content, _) = redact(&template.content, redaction_patterns);
+        let mut snippet: String = content.chars().take(EVIDENCE_SNIPPET_CHARS).collect();
+        if content.chars().count() > EVIDENCE_SNIPPET_CHARS {
+            snippet.push('…');
+        }
+        ParadoxElement {
+            element_type: ParadoxElementType::Template,
+            element_id: template.id.clone(),
+            confidence: 1.0 - template.paradox_resistance,
+            evidence: vec![snippet],
+            perspective: template.perception_affinity.first().copied(),
+        }
+    }
+}
+
+impl From<&Paradox> for ParadoxReport {
+    /// Summarize a paradox; the report keeps its id so the full paradox can be looked up
+    fn from(paradox: &Paradox) -> Self {
+        let confidence_impact = match &paradox.resolution_outcome {
+            Some(outcome) => outcome.confidence_impact,
+            None => paradox
+                .conflicting_elements
+                .iter()
+                .map(|element| element.confidence)
+                .fold(0.0, f64::max),
+        };
+
+        Self {
+            id: paradox.id,
+            description: paradox.description.clone(),
+            conflicting_perceptions: paradox
+                .conflicting_elements
+                .iter()
+                .filter_map(|element| element.perspective)
+                .collect(),
+            resolution_strategy: paradox.resolution_strategy.clone(),
+            confidence_impact,
+            resolved_at: paradox.resolved_at,
+            severity: Some(paradox.severity.clone()),
+            evidence: None,
+        }
+    }
+}
+
+/// Count of running coordinations, so shutdown can wait for them to finish
+#[derive(Debug, Default)]
+struct InFlight {
+    count: Mutex<usize>,
+    idle: Condvar,
+}
+
+impl InFlight {
+    fn enter(&self) -> InFlightGuard<'_> {
+        *self.count.lock() += 1;
+        InFlightGuard(self)
+    }
+}
+
+/// One running coordination; leaving wakes [`CasialEngine::quiesce`] once none remain
+struct InFlightGuard<'a>(&'a InFlight);
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut count = self.0.count.lock();
+        *count -= 1;
+        if *count == 0 {
+            self.0.idle.notify_all();
+        }
+    }
 }
 
 /// The main Casial coordination engine
 pub struct CasialEngine {
     missions: Arc<DashMap<String, Arc<CasialMission>>>,
     active_perceptions: Arc<DashMap<PerceptionId, Arc<RwLock<Perception>>>>,
-    coordination_history: Arc<DashMap<Uuid, CoordinationResult>>,
+    coordination_history: Arc<DashMap<u64, CoordinationRecord>>,
+    history_sequence: Arc<AtomicU64>,
+    history_limit: usize,
     paradox_registry: Arc<DashMap<Uuid, ParadoxReport>>,
+    window_usage: Arc<DashMap<String, WindowUsage>>,
+    id_source: Arc<dyn IdSource>,
+    file_signal_policy: FileSignalPolicy,
+    in_flight: InFlight,
 }
 
 impl CasialEngine {
     /// Create a new Casial engine
     pub fn new() -> Self {
+        Self::with_id_source(Arc::new(RandomIdSource))
+    }
+
+    /// Create a new Casial engine that draws perception and paradox ids from `id_source`
+    pub fn with_id_source(id_source: Arc<dyn IdSource>) -> Self {
         Self {
             missions: Arc::new(DashMap::new()),
             active_perceptions: Arc::new(DashMap::new()),
             coordination_history: Arc::new(DashMap::new()),
+            history_sequence: Arc::new(AtomicU64::new(0)),
+            history_limit: DEFAULT_MAX_HISTORY_ENTRIES,
             paradox_registry: Arc::new(DashMap::new()),
+            window_usage: Arc::new(DashMap::new()),
+            id_source,
+            file_signal_policy: FileSignalPolicy::default(),
+            in_flight: InFlight::default(),
         }
     }
 
+    /// Mint a perception id from the engine's id source
+    pub fn new_perception_id(&self) -> PerceptionId {
+        PerceptionId::from_source(self.id_source.as_ref())
+    }
+
+    /// Restrict how rule file signals may read the filesystem
+    pub fn with_file_signal_policy(mut self, policy: FileSignalPolicy) -> Self {
+        self.file_signal_policy = policy;
+        self
+    }
+
+    /// Keep only the latest `limit` coordinations in the history
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self
+    }
+
+    /// The policy rule file signals are evaluated under
+    pub fn file_signal_policy(&self) -> &FileSignalPolicy {
+        &self.file_signal_policy
+    }
+
     /// Load a mission into the engine
     pub fn load_mission(&self, mission: CasialMission) -> Result<()> {
+        for pattern in &mission.budgets.redaction_patterns {
+            regex::Regex::new(pattern)
+                .with_context(|| format!("Invalid redaction pattern '{}'", pattern))?;
+        }
+        if let Some(window) = &mission.budgets.window_budget {
+            anyhow::ensure!(
+                window.window_seconds > 0,
+                "budgets.window_budget.window_seconds must be at least 1"
+            );
+        }
+
         let mission_id = mission.id.clone();
         let mission_arc = Arc::new(mission);
 
@@ -246,14 +804,124 @@ impl CasialEngine {
         Ok(())
     }
 
+    /// Unload a mission and the perceptions no other loaded mission defines; returns
+    /// whether it was loaded
+    pub fn unload_mission(&self, id: &str) -> bool {
+        let Some((_, mission)) = self.missions.remove(id) else {
+            return false;
+        };
+        for perception in &mission.perceptions {
+            if !self.is_mission_perception(&perception.id) {
+                self.active_perceptions.remove(&perception.id);
+            }
+        }
+        true
+    }
+
+    /// Block until no coordination is running or `timeout` passes
+    ///
+    /// Call before snapshotting history or shutting down, so no coordination is still
+    /// writing to the history or paradox registry. Returns whether the engine went idle;
+    /// new coordinations are not refused meanwhile.
+    pub fn quiesce(&self, timeout: std::time::Duration) -> bool {
+        let mut count = self.in_flight.count.lock();
+        self.in_flight
+            .idle
+            .wait_while_for(&mut count, |count| *count > 0, timeout);
+        *count == 0
+    }
+
+    /// Templates tagged with `category` across the loaded missions
+    pub fn get_templates_by_category(&self, category: &str) -> Vec<CasialTemplate> {
+        self.missions
+            .iter()
+            .flat_map(|mission| {
+                mission
+                    .get_templates_by_category(category)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Number of loaded missions
+    pub fn mission_count(&self) -> usize {
+        self.missions.len()
+    }
+
+    /// Whether a mission with this id is loaded
+    pub fn has_mission(&self, id: &str) -> bool {
+        self.missions.contains_key(id)
+    }
+
+    /// Register a perception unless one with the same id is known; returns whether it was new
+    pub fn register_perception(&self, perception: Perception) -> bool {
+        match self.active_perceptions.entry(perception.id) {
+            dashmap::mapref::entry::Entry::Occupied(_) => false,
+            dashmap::mapref::entry::Entry::Vacant(entry) => {
+                entry.insert(Arc::new(RwLock::new(perception)));
+                true
+            }
+        }
+    }
+
+    /// Remove a perception registered outside any mission; returns whether it was removed
+    ///
+    /// Perceptions defined by a loaded mission stay registered.
+    pub fn unregister_perception(&self, id: &PerceptionId) -> bool {
+        !self.is_mission_perception(id) && self.active_perceptions.remove(id).is_some()
+    }
+
+    /// Whether a loaded mission defines this perception
+    pub fn is_mission_perception(&self, id: &PerceptionId) -> bool {
+        self.missions
+            .iter()
+            .any(|mission| mission.perceptions.iter().any(|p| p.id == *id))
+    }
+
+    /// A registered perception by id
+    pub fn get_perception(&self, id: &PerceptionId) -> Option<Perception> {
+        self.active_perceptions
+            .get(id)
+            .map(|entry| entry.value().read().clone())
+    }
+
+    /// Every registered perception, from missions and sessions alike, oldest first
+    pub fn get_perceptions(&self) -> Vec<Perception> {
+        let mut perceptions: Vec<Perception> = self
+            .active_perceptions
+            .iter()
+            .map(|entry| entry.value().read().clone())
+            .collect();
+        perceptions.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        perceptions
+    }
+
     /// Coordinate context for a tool request
     pub fn coordinate(&self, request: CoordinationRequest) -> Result<CoordinationResult> {
+        let _in_flight = self.in_flight.enter();
+
         // Find applicable missions (could be multiple for different perceptions)
-        let applicable_missions: Vec<Arc<CasialMission>> = self
-            .missions
-            .iter()
-            .map(|entry| entry.value().clone())
-            .collect();
+        let mut applicable_missions: Vec<Arc<CasialMission>> = match &request.mission_id {
+            Some(id) => vec![self
+                .missions
+                .get(id)
+                .map(|entry| entry.value().clone())
+                .ok_or_else(|| {
+                    CasialError::CoordinationFailure(format!("Unknown mission '{}'", id))
+                })?],
+            None => self
+                .missions
+                .iter()
+                .map(|entry| entry.value().clone())
+                .collect(),
+        };
+        // Mission id order, so seeded engines coordinate identically
+        applicable_missions.sort_by(|a, b| a.id.cmp(&b.id));
 
         if applicable_missions.is_empty() {
             return Ok(CoordinationResult {
@@ -271,33 +939,98 @@ impl CasialEngine {
         // Evaluate rules across all applicable missions
         let mut activated_rules = Vec::new();
         let mut applicable_templates = AHashMap::new();
+        let mut template_placements = AHashMap::new();
+        let mut template_rules = AHashMap::new();
         let mut detected_paradoxes = Vec::new();
+        let mut template_variants = serde_json::Map::new();
+        let mut suppressed_by_cooldown = Vec::new();
+        let mut contributing_missions: Vec<&CasialMission> = Vec::new();
+        // Missions coordinating together share the strictest of their limits
+        let mut budget = Self::effective_budget(
+            &Self::combined_budget(&applicable_missions),
+            request.max_context_size,
+        );
+        let max_paradoxes = budget.max_paradoxes.unwrap_or(DEFAULT_MAX_PARADOXES);
+        let mut paradoxes_over_cap = 0usize;
+        // Evidence may quote a template from any of the missions, so every mission's
+        // redactions apply to it
+        let evidence_redactions = if request.include_paradox_evidence {
+            redaction_regexes(&budget.redaction_patterns)
+        } else {
+            Vec::new()
+        };
+        let now = Utc::now();
 
         for mission in &applicable_missions {
             for rule in &mission.rules {
-                if !rule.enabled {
+                if !rule.enabled || !mission.flags_enabled(&rule.required_flags) {
                     continue;
                 }
 
                 if self.evaluate_rule_conditions(&rule.conditions, &request)? {
+                    if request.in_cooldown(rule, now) {
+                        suppressed_by_cooldown.push(rule.id.clone());
+                        continue;
+                    }
                     activated_rules.push(rule.id.clone());
+                    if !contributing_missions
+                        .iter()
+                        .any(|contributing| contributing.id == mission.id)
+                    {
+                        contributing_missions.push(mission);
+                    }
+
+                    let rule_template_ids = mission.rule_template_ids(rule);
+                    let template_ids: Vec<&String> = match &rule.actions.selection {
+                        TemplateSelection::All => rule_template_ids,
+                        TemplateSelection::WeightedPick { weights } => {
+                            let candidates: Vec<(&String, f64)> = rule_template_ids
+                                .into_iter()
+                                .filter(|id| {
+                                    request.permits_template(id)
+                                        && mission.templates.iter().any(|t| {
+                                            t.id == **id
+                                                && mission.flags_enabled(&t.required_flags)
+                                                && request.within_max_priority(t)
+                                        })
+                                })
+                                .map(|id| (id, weights.get(id).copied().unwrap_or(0.0)))
+                                .collect();
+                            let chosen = self.pick_weighted(&candidates);
+                            if let Some(id) = chosen {
+                                template_variants
+                                    .insert(rule.id.clone(), serde_json::Value::String(id.clone()));
+                            }
+                            chosen.into_iter().collect()
+                        }
+                    };
 
-                    // Collect templates from this rule
-                    for template_id in &rule.actions.template_ids {
-                        if let Some(template) =
-                            mission.templates.iter().find(|t| t.id == *template_id)
-                        {
+                    // Collect templates from this rule that the caller hasn't filtered out
+                    for template_id in template_ids {
+                        if !request.permits_template(template_id) {
+                            continue;
+                        }
+                        if let Some(template) = mission.templates.iter().find(|t| {
+                            t.id == *template_id
+                                && mission.flags_enabled(&t.required_flags)
+                                && request.within_max_priority(t)
+                        }) {
                             // Check for perception conflicts (paradoxes)
-                            if let Some(existing) = applicable_templates.get(template_id) {
+                            let existing = applicable_templates
+                                .get(template_id)
+                                .filter(|_| request.detect_paradoxes);
+                            if let Some(existing) = existing {
                                 let existing_template: &CasialTemplate = existing;
-                                if !existing_template.perception_affinity.is_empty()
+                                let conflicting = !existing_template.perception_affinity.is_empty()
                                     && !template.perception_affinity.is_empty()
                                     && existing_template.perception_affinity
-                                        != template.perception_affinity
-                                {
+                                        != template.perception_affinity;
+                                if conflicting && detected_paradoxes.len() >= max_paradoxes {
+                                    paradoxes_over_cap += 1;
+                                } else if conflicting {
                                     // Paradox detected!
                                     let paradox = ParadoxReport {
-                                        id: Uuid::new_v4(),
+                                        id: self.id_source.next_id(),
                                         description: format!(
                                             "Template '{}' has conflicting perception affinities",
                                             template_id
@@ -309,6 +1042,20 @@ impl CasialEngine {
                                         .concat(),
                                         resolution_strategy: rule.paradox_handling.clone(),
                                         confidence_impact: 1.0 - template.paradox_resistance,
+                                        resolved_at: None,
+                                        severity: None,
+                                        evidence: request.include_paradox_evidence.then(|| {
+                                            vec![
+                                                ParadoxReport::template_evidence(
+                                                    existing_template,
+                                                    &evidence_redactions,
+                                                ),
+                                                ParadoxReport::template_evidence(
+                                                    template,
+                                                    &evidence_redactions,
+                                                ),
+                                            ]
+                                        }),
                                     };
 
                                     detected_paradoxes.push(paradox.clone());
@@ -317,6 +1064,14 @@ impl CasialEngine {
                             }
 
                             applicable_templates.insert(template_id.clone(), template.clone());
+                            template_rules.insert(template_id.clone(), rule.id.clone());
+                            template_placements.insert(
+                                template_id.clone(),
+                                Placement {
+                                    transform_type: rule.actions.transform_type.clone(),
+                                    target_field: rule.actions.target_field.clone(),
+                                },
+                            );
                         }
                     }
                 }
@@ -324,42 +1079,227 @@ impl CasialEngine {
         }
 
         // Apply paradox handling strategies
-        let resolved_templates = self.resolve_paradoxes(
-            applicable_templates,
-            &detected_paradoxes,
-            request.paradox_tolerance,
-        )?;
+        let paradox_tolerance = request
+            .paradox_tolerance
+            .and_then(sanitize_paradox_tolerance)
+            .or_else(|| {
+                applicable_missions
+                    .iter()
+                    .filter_map(|mission| mission.configured_paradox_tolerance(&request.tool_name))
+                    .filter_map(sanitize_paradox_tolerance)
+                    .reduce(f64::min)
+            })
+            .unwrap_or(DEFAULT_PARADOX_TOLERANCE);
+        let resolved_templates =
+            self.resolve_paradoxes(applicable_templates, &detected_paradoxes, paradox_tolerance)?;
+
+        // Compose final content within the budget (narrowed by any caller cap) and by what
+        // is left of the tightest window allowance among the missions whose rules fired
+        let windows: Vec<(&CasialMission, &WindowBudget, usize)> = contributing_missions
+            .iter()
+            .filter_map(|mission| {
+                let window = mission.budgets.window_budget.as_ref()?;
+                Some((
+                    *mission,
+                    window,
+                    self.window_remaining(&mission.id, window, now),
+                ))
+            })
+            .collect();
+        let window_remaining = windows.iter().map(|(_, _, remaining)| *remaining).min();
+        if let Some(remaining) = window_remaining {
+            budget.global_char_limit = Some(
+                budget
+                    .global_char_limit
+                    .map_or(remaining, |limit| limit.min(remaining)),
+            );
+        }
 
-        // Compose final content
-        let (injected_content, used_templates) =
-            self.compose_context(resolved_templates, &applicable_missions[0].budgets)?;
+        // Expand `{{> template_id}}` includes against the loaded missions' templates; a
+        // template expanding past the budget is cut short and dropped during composition
+        let lookup = |id: &str| {
+            applicable_missions
+                .iter()
+                .flat_map(|mission| mission.templates.iter())
+                .find(|template| template.id == id)
+                .map(|template| template.content.as_str())
+        };
+        let mut expander = IncludeExpander::new(
+            &lookup,
+            budget
+                .max_template_depth
+                .unwrap_or(DEFAULT_MAX_TEMPLATE_DEPTH),
+            budget
+                .global_char_limit
+                .unwrap_or(DEFAULT_MAX_EXPANDED_CHARS),
+        );
+        let resolved_templates = resolved_templates
+            .into_iter()
+            .map(|mut template| {
+                template.content = expander.expand(&template.id, &template.content)?;
+                Ok(template)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let window_exhausted = window_remaining == Some(0);
+        // Disclose paradoxes whose rules asked for them to be exposed
+        let disclosure =
+            Self::paradox_disclosure(&detected_paradoxes).filter(|_| !window_exhausted);
+        let composed = self.compose_context(
+            resolved_templates,
+            &template_placements,
+            &budget,
+            disclosure,
+        )?;
 
-        // Apply transformations
-        let modified_args = self.apply_transformation(
+        // Apply each transform type's section to its destination field
+        let (modified_args, fallback_fields) = self.apply_transformation(
             &request.tool_args,
-            &injected_content,
-            &activated_rules,
-            &applicable_missions,
+            &composed.sections,
+            budget.injection_markers.as_ref(),
         )?;
 
+        // Sequence numbers increase monotonically, so they order history entries even when
+        // several coordinations finish within the same clock tick
+        let sequence = self.history_sequence.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut metadata = self.generate_metadata(&request, paradox_tolerance, sequence, now)?;
+        if !fallback_fields.is_empty() {
+            metadata.insert(
+                "fallback_fields".to_string(),
+                serde_json::json!(fallback_fields),
+            );
+        }
+        metadata.insert(
+            "estimated_tokens".to_string(),
+            serde_json::json!(estimate_tokens(&composed.content)),
+        );
+        if composed.redactions > 0 {
+            metadata.insert(
+                "redactions".to_string(),
+                serde_json::json!(composed.redactions),
+            );
+        }
+        if composed.disclosure_dropped {
+            metadata.insert(
+                "paradox_disclosure_dropped".to_string(),
+                serde_json::json!(true),
+            );
+        }
+        if !composed.dropped_templates.is_empty() {
+            metadata.insert(
+                "dropped_templates".to_string(),
+                serde_json::json!(composed.dropped_templates),
+            );
+        }
+        if request.include_provenance {
+            let provenance: Vec<ProvenanceSpan> = composed
+                .spans
+                .iter()
+                .map(|(template_id, start, end)| ProvenanceSpan {
+                    template_id: template_id.clone(),
+                    rule_id: template_rules.get(template_id).cloned(),
+                    start: *start,
+                    end: *end,
+                })
+                .collect();
+            metadata.insert("provenance".to_string(), serde_json::to_value(provenance)?);
+        }
+        if !suppressed_by_cooldown.is_empty() {
+            metadata.insert(
+                "suppressed_by_cooldown".to_string(),
+                serde_json::json!(suppressed_by_cooldown),
+            );
+        }
+        if !template_variants.is_empty() {
+            metadata.insert(
+                "template_variants".to_string(),
+                serde_json::Value::Object(template_variants),
+            );
+        }
+        // Every windowed mission that contributed is charged; the one closest to its limit
+        // is reported
+        let tightest_window = windows
+            .iter()
+            .map(|(mission, window, _)| {
+                let usage = self.charge_window(&mission.id, composed.charged_chars, now);
+                (*mission, *window, usage)
+            })
+            .min_by_key(|(_, window, usage)| {
+                window.char_limit.saturating_sub(usage.injected_chars)
+            });
+        if let Some((mission, window, usage)) = tightest_window {
+            metadata.insert(
+                "window_budget".to_string(),
+                serde_json::json!({
+                    "mission_id": mission.id,
+                    "char_limit": window.char_limit,
+                    "used": usage.injected_chars,
+                    "exhausted": window_exhausted
+                        || usage.injected_chars >= window.char_limit,
+                    "resets_at": (usage.started_at
+                        + chrono::Duration::seconds(
+                            i64::try_from(window.window_seconds).unwrap_or(i64::MAX)
+                        ))
+                    .to_rfc3339(),
+                }),
+            );
+        }
+        if paradoxes_over_cap > 0 {
+            metadata.insert(
+                "paradox_cap".to_string(),
+                serde_json::json!({ "limit": max_paradoxes, "skipped": paradoxes_over_cap }),
+            );
+        }
+
         let result = CoordinationResult {
-            applied: !used_templates.is_empty(),
-            injected_content,
+            applied: !composed.used_templates.is_empty(),
+            injected_content: composed.content,
             modified_args,
             activated_rules,
-            used_templates,
+            used_templates: composed.used_templates,
             perception_locks: request.active_perceptions.clone(),
             paradoxes_detected: detected_paradoxes,
-            metadata: self.generate_metadata(&request)?,
+            metadata,
         };
 
-        // Store in history
-        let history_id = Uuid::new_v4();
-        self.coordination_history.insert(history_id, result.clone());
+        // Only the environment variables rules look at are worth keeping with the record
+        let mut request = request;
+        request.environment.retain(|key, _| {
+            applicable_missions.iter().any(|mission| {
+                mission
+                    .rules
+                    .iter()
+                    .any(|rule| rule.conditions.environment_vars.contains_key(key))
+            })
+        });
+        self.record_coordination(
+            sequence,
+            CoordinationRecord {
+                request,
+                result: result.clone(),
+                timestamp: now,
+            },
+        );
 
         Ok(result)
     }
 
+    /// Add a record to the history, dropping the oldest ones beyond the history limit
+    fn record_coordination(&self, sequence: u64, record: CoordinationRecord) {
+        if self.history_limit == 0 {
+            return;
+        }
+        self.coordination_history.insert(sequence, record);
+        let limit = self.history_limit as u64;
+        if let Some(expired) = sequence.checked_sub(limit) {
+            self.coordination_history.remove(&expired);
+        }
+        // Concurrent coordinations can finish out of order and skip the single removal
+        if self.coordination_history.len() > self.history_limit {
+            self.coordination_history
+                .retain(|recorded, _| recorded + limit > sequence);
+        }
+    }
+
     /// Evaluate if rule conditions are met
     fn evaluate_rule_conditions(
         &self,
@@ -413,7 +1353,12 @@ impl CasialEngine {
 
     /// Evaluate a file signal condition
     fn evaluate_file_signal(&self, signal: &FileSignal, project_path: &str) -> Result<bool> {
-        let file_path = std::path::Path::new(project_path).join(&signal.path);
+        let Some(file_path) = self
+            .file_signal_policy
+            .resolve(project_path, &signal.path)?
+        else {
+            return Ok(false);
+        };
 
         let exists = file_path.exists();
         if signal.must_exist && !exists {
@@ -504,120 +1449,389 @@ impl CasialEngine {
         Ok(resolved)
     }
 
-    /// Compose context from resolved templates
+    /// The budget of missions coordinating together: the strictest of each of their limits
+    ///
+    /// Redaction patterns are combined, so every mission's apply to all injected content.
+    /// Injection markers and per-tool limits come from the first mission by id that sets
+    /// them. Window budgets are kept per mission and not combined here.
+    fn combined_budget(missions: &[Arc<CasialMission>]) -> BudgetConfiguration {
+        fn tighter(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+
+        let mut budget = missions[0].budgets.clone();
+        for mission in &missions[1..] {
+            let other = &mission.budgets;
+            budget.global_char_limit = tighter(budget.global_char_limit, other.global_char_limit);
+            budget.max_template_depth =
+                tighter(budget.max_template_depth, other.max_template_depth);
+            budget.max_paradoxes = tighter(budget.max_paradoxes, other.max_paradoxes);
+            budget.paradox_overhead = budget.paradox_overhead.max(other.paradox_overhead);
+            for pattern in &other.redaction_patterns {
+                if !budget.redaction_patterns.contains(pattern) {
+                    budget.redaction_patterns.push(pattern.clone());
+                }
+            }
+            if budget.injection_markers.is_none() {
+                budget.injection_markers = other.injection_markers.clone();
+            }
+            if budget.per_tool_limits.is_empty() {
+                budget.per_tool_limits = other.per_tool_limits.clone();
+            }
+        }
+        budget
+    }
+
+    /// Apply a caller-requested context cap, taking the smaller of it and the mission limit
+    fn effective_budget(
+        budget: &BudgetConfiguration,
+        max_context_size: Option<usize>,
+    ) -> BudgetConfiguration {
+        let mut budget = budget.clone();
+        if let Some(cap) = max_context_size {
+            budget.global_char_limit =
+                Some(budget.global_char_limit.map_or(cap, |limit| limit.min(cap)));
+        }
+        budget
+    }
+
+    /// Characters `mission_id` may still inject in its window, opening a new window when
+    /// the previous one has ended
+    fn window_remaining(
+        &self,
+        mission_id: &str,
+        window: &WindowBudget,
+        now: DateTime<Utc>,
+    ) -> usize {
+        let mut usage = self
+            .window_usage
+            .entry(mission_id.to_string())
+            .or_insert_with(|| WindowUsage {
+                started_at: now,
+                injected_chars: 0,
+            });
+        let elapsed = now.signed_duration_since(usage.started_at).num_seconds();
+        if u64::try_from(elapsed).is_ok_and(|elapsed| elapsed >= window.window_seconds) {
+            *usage = WindowUsage {
+                started_at: now,
+                injected_chars: 0,
+            };
+        }
+        window.char_limit.saturating_sub(usage.injected_chars)
+    }
+
+    /// Count injected characters against `mission_id`'s current window
+    fn charge_window(&self, mission_id: &str, chars: usize, now: DateTime<Utc>) -> WindowUsage {
+        let mut usage = self
+            .window_usage
+            .entry(mission_id.to_string())
+            .or_insert_with(|| WindowUsage {
+                started_at: now,
+                injected_chars: 0,
+            });
+        usage.injected_chars = usage.injected_chars.saturating_add(chars);
+        usage.clone()
+    }
+
+    /// Compose context from resolved templates, led by the paradox `disclosure` if any
+    ///
+    /// The budget spans all templates; content is also grouped into one section per
+    /// transform type (in order of first appearance) so each lands in its own field.
+    /// Redaction patterns are applied to each template before it counts against the budget.
+    /// The disclosure and each section's injection markers count against it too; a
+    /// disclosure larger than the `paradox_overhead` reserve takes room from templates, and
+    /// one that can't fit at all is left out.
     fn compose_context(
         &self,
         templates: Vec<CasialTemplate>,
+        placements: &AHashMap<String, Placement>,
         budget: &BudgetConfiguration,
-    ) -> Result<(String, Vec<String>)> {
+        disclosure: Option<String>,
+    ) -> Result<ComposedContext> {
         let mut sorted_templates = templates;
-        sorted_templates.sort_by_key(|t| t.priority);
+        sorted_templates.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.id.cmp(&b.id)));
+
+        let redaction_patterns = redaction_regexes(&budget.redaction_patterns);
+        let mut redactions = 0;
 
         let mut content = String::new();
+        let mut sections: Vec<(Placement, String)> = Vec::new();
         let mut used_templates = Vec::new();
+        let mut spans = Vec::new();
+        let mut dropped_templates = Vec::new();
         let mut char_count = 0;
+        let mut content_chars = 0;
 
         let char_limit = budget.global_char_limit.unwrap_or(usize::MAX);
         let paradox_overhead = (char_limit as f64 * budget.paradox_overhead) as usize;
-        let effective_limit = char_limit.saturating_sub(paradox_overhead);
+        let marker_overhead = budget
+            .injection_markers
+            .as_ref()
+            .map_or(0, |markers| markers.wrap("").chars().count());
+        let mut disclosure_cost = disclosure
+            .as_ref()
+            .map_or(0, |block| block.chars().count() + marker_overhead);
+        let disclosure_dropped = disclosure_cost > char_limit;
+        if disclosure_dropped {
+            disclosure_cost = 0;
+        }
+        let disclosure = disclosure.filter(|_| !disclosure_dropped);
+        let effective_limit = char_limit.saturating_sub(paradox_overhead.max(disclosure_cost));
 
         for template in sorted_templates {
             if !template.enabled {
                 continue;
             }
+            // Once one template overflows, everything after it is dropped
+            if !dropped_templates.is_empty() {
+                dropped_templates.push(template.id);
+                continue;
+            }
 
-            let template_content = format!("## {}\n\n{}\n\n", template.name, template.content);
+            let (template_content, template_redactions) = redact(
+                &format!("## {}\n\n{}\n\n", template.name, template.content),
+                &redaction_patterns,
+            );
+
+            let placement = placements
+                .get(&template.id)
+                .cloned()
+                .unwrap_or_else(Placement::prepend);
+            let section = sections.iter_mut().find(|(p, _)| *p == placement);
+            // The disclosure already pays for the prepend section's markers
+            let opens_section = section.is_none()
+                && !(disclosure.is_some() && placement.transform_type == TransformType::Prepend);
+            let template_chars = template_content.chars().count();
+            let cost = template_chars + if opens_section { marker_overhead } else { 0 };
+            if char_count + cost > effective_limit {
+                dropped_templates.push(template.id);
+                continue;
+            }
 
-            if char_count + template_content.len() > effective_limit {
-                break;
+            match section {
+                Some((_, section)) => section.push_str(&template_content),
+                None => sections.push((placement, template_content.clone())),
             }
 
             content.push_str(&template_content);
-            char_count += template_content.len();
+            spans.push((
+                template.id.clone(),
+                content_chars,
+                content_chars + template_chars,
+            ));
+            content_chars += template_chars;
+            char_count += cost;
+            redactions += template_redactions;
             used_templates.push(template.id.clone());
         }
 
-        Ok((content, used_templates))
+        let mut composed = ComposedContext {
+            content,
+            sections,
+            used_templates,
+            spans,
+            redactions,
+            dropped_templates,
+            disclosure_dropped,
+            charged_chars: char_count + disclosure_cost,
+        };
+        if let Some(block) = disclosure {
+            composed.prepend_block(&block);
+        }
+        Ok(composed)
+    }
+
+    /// Delimited block describing paradoxes handled with `ParadoxStrategy::Expose`
+    ///
+    /// The mission's `paradox_overhead` budget is reserved for it.
+    fn paradox_disclosure(paradoxes: &[ParadoxReport]) -> Option<String> {
+        let exposed: Vec<&ParadoxReport> = paradoxes
+            .iter()
+            .filter(|p| matches!(p.resolution_strategy, ParadoxStrategy::Expose))
+            .collect();
+        if exposed.is_empty() {
+            return None;
+        }
+
+        let mut block = String::from("<!-- casial:paradox -->\n## Paradox Detected\n\n");
+        for paradox in exposed {
+            let perceptions: Vec<String> = paradox
+                .conflicting_perceptions
+                .iter()
+                .map(|p| p.0.to_string())
+                .collect();
+            block.push_str(&format!(
+                "- {}\n  - Conflicting perceptions: {}\n  - Confidence impact: {:.2}\n",
+                paradox.description,
+                perceptions.join(", "),
+                paradox.confidence_impact
+            ));
+        }
+        block.push_str("<!-- /casial:paradox -->\n\n");
+
+        Some(block)
     }
 
-    /// Apply transformations to the tool arguments
+    /// Apply each composed section to the tool arguments, wrapped in `markers` if set
+    ///
+    /// Returns the modified args and any fallback fields created so content wasn't dropped.
     fn apply_transformation(
         &self,
         args: &serde_json::Value,
-        content: &str,
-        _rules: &[String],
-        missions: &[Arc<CasialMission>],
-    ) -> Result<serde_json::Value> {
+        sections: &[(Placement, String)],
+        markers: Option<&InjectionMarkers>,
+    ) -> Result<(serde_json::Value, Vec<String>)> {
         let mut modified_args = args.clone();
+        if modified_args.is_null() {
+            modified_args = serde_json::json!({});
+        }
 
-        // Find the primary transformation type (from the first applicable rule)
-        let transform_type = missions
-            .iter()
-            .flat_map(|m| &m.rules)
-            .find(|r| r.enabled)
-            .map(|r| &r.actions.transform_type)
-            .unwrap_or(&TransformType::Prepend);
-
-        match transform_type {
-            TransformType::Prepend => {
-                if let Some(query) = modified_args.get_mut("query") {
-                    if let Some(query_str) = query.as_str() {
-                        *query = serde_json::Value::String(format!("{}\n\n{}", content, query_str));
-                    }
-                } else if let Some(instructions) = modified_args.get_mut("instructions") {
-                    if let Some(instr_str) = instructions.as_str() {
-                        *instructions =
-                            serde_json::Value::String(format!("{}\n\n{}", content, instr_str));
-                    }
-                }
+        let mut fallback_fields = Vec::new();
+        for (placement, content) in sections {
+            let content = match markers {
+                Some(markers) => markers.wrap(content),
+                None => content.clone(),
+            };
+            if let Some(field) = Self::apply_section(&mut modified_args, placement, &content) {
+                fallback_fields.push(field);
             }
-            TransformType::Append => {
-                if let Some(query) = modified_args.get_mut("query") {
-                    if let Some(query_str) = query.as_str() {
-                        *query = serde_json::Value::String(format!("{}\n\n{}", query_str, content));
-                    }
+        }
+
+        Ok((modified_args, fallback_fields))
+    }
+
+    /// Place one section of content according to its placement
+    ///
+    /// Prepend/Append edit the first string among `target_field`, `query` and (for
+    /// Prepend) `instructions`. Without one, the content goes to `target_field` or
+    /// `casial_context`, and the created field's path is returned.
+    fn apply_section(
+        modified_args: &mut serde_json::Value,
+        placement: &Placement,
+        content: &str,
+    ) -> Option<String> {
+        let target = placement.target_field.as_deref();
+        match placement.transform_type {
+            TransformType::Prepend | TransformType::Append => {
+                let prepend = placement.transform_type == TransformType::Prepend;
+                let known_fields: &[&str] = if prepend {
+                    &["query", "instructions"]
+                } else {
+                    &["query"]
+                };
+                let fallback = target.unwrap_or(FALLBACK_CONTEXT_FIELD);
+                let candidates = target
+                    .into_iter()
+                    .chain(known_fields.iter().copied())
+                    .chain(std::iter::once(fallback));
+
+                for field in candidates {
+                    if let Some(serde_json::Value::String(existing)) =
+                        Self::field_mut(modified_args, field)
+                    {
+                        *existing = if prepend {
+                            format!("{}\n\n{}", content, existing)
+                        } else {
+                            format!("{}\n\n{}", existing, content)
+                        };
+                        return None;
+                    }
                 }
+
+                Self::insert_field(modified_args, fallback, content).then(|| fallback.to_string())
             }
             TransformType::InjectField => {
-                if let Some(obj) = modified_args.as_object_mut() {
-                    obj.insert(
-                        "casial_context".to_string(),
-                        serde_json::Value::String(content.to_string()),
-                    );
-                }
+                Self::insert_field(
+                    modified_args,
+                    target.unwrap_or(FALLBACK_CONTEXT_FIELD),
+                    content,
+                );
+                None
             }
             TransformType::SystemInstruction => {
-                if let Some(obj) = modified_args.as_object_mut() {
-                    obj.insert(
-                        "system_context".to_string(),
-                        serde_json::Value::String(content.to_string()),
-                    );
-                }
+                Self::insert_field(modified_args, "system_context", content);
+                None
             }
             TransformType::PerceptionLayer => {
-                if let Some(obj) = modified_args.as_object_mut() {
-                    obj.insert(
-                        "perception_context".to_string(),
-                        serde_json::Value::String(content.to_string()),
-                    );
-                }
+                Self::insert_field(modified_args, "perception_context", content);
+                None
             }
         }
+    }
+
+    /// Look up a dotted field path (e.g. `params.query`) in the tool arguments
+    fn field_mut<'a>(
+        args: &'a mut serde_json::Value,
+        path: &str,
+    ) -> Option<&'a mut serde_json::Value> {
+        path.split('.')
+            .try_fold(args, |value, key| value.as_object_mut()?.get_mut(key))
+    }
 
-        Ok(modified_args)
+    /// Set a dotted field path to `content`, creating intermediate objects as needed
+    fn insert_field(args: &mut serde_json::Value, path: &str, content: &str) -> bool {
+        let mut current = args;
+        let mut keys = path.split('.').peekable();
+        while let Some(key) = keys.next() {
+            let Some(object) = current.as_object_mut() else {
+                return false;
+            };
+            if keys.peek().is_none() {
+                object.insert(
+                    key.to_string(),
+                    serde_json::Value::String(content.to_string()),
+                );
+                return true;
+            }
+            current = object
+                .entry(key.to_string())
+                .or_insert_with(|| serde_json::json!({}));
+        }
+        false
+    }
+
+    /// Choose one candidate in proportion to its weight, drawing from the id source
+    fn pick_weighted<'a>(&self, candidates: &[(&'a String, f64)]) -> Option<&'a String> {
+        let total: f64 = candidates.iter().map(|(_, w)| w.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        // Top 53 bits of the id give a uniform fraction in [0, 1)
+        let (high, _) = self.id_source.next_id().as_u64_pair();
+        let mut target = (high >> 11) as f64 / (1u64 << 53) as f64 * total;
+        for (id, weight) in candidates {
+            let weight = weight.max(0.0);
+            if target < weight {
+                return Some(id);
+            }
+            target -= weight;
+        }
+        candidates
+            .iter()
+            .rev()
+            .find(|(_, w)| *w > 0.0)
+            .map(|(id, _)| *id)
     }
 
     /// Generate metadata for the coordination result
     fn generate_metadata(
         &self,
         request: &CoordinationRequest,
+        paradox_tolerance: f64,
+        sequence: u64,
+        timestamp: DateTime<Utc>,
     ) -> Result<AHashMap<String, serde_json::Value>> {
         let mut metadata = AHashMap::new();
 
         metadata.insert(
             "timestamp".to_string(),
-            serde_json::Value::String(Utc::now().to_rfc3339()),
+            serde_json::Value::String(timestamp.to_rfc3339()),
         );
+        metadata.insert("sequence".to_string(), serde_json::json!(sequence));
         metadata.insert(
             "tool_name".to_string(),
             serde_json::Value::String(request.tool_name.clone()),
@@ -628,22 +1842,85 @@ impl CasialEngine {
         );
         metadata.insert(
             "paradox_tolerance".to_string(),
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(request.paradox_tolerance).unwrap(),
-            ),
+            serde_json::Number::from_f64(paradox_tolerance)
+                .unwrap_or_else(|| serde_json::Number::from(0))
+                .into(),
         );
 
         Ok(metadata)
     }
 
-    /// Get coordination history for analysis
+    /// Get coordination history for analysis, oldest first
     pub fn get_coordination_history(&self) -> Vec<CoordinationResult> {
-        self.coordination_history
+        let mut entries: Vec<(u64, CoordinationResult)> = self
+            .coordination_history
             .iter()
-            .map(|entry| entry.value().clone())
+            .map(|entry| (*entry.key(), entry.value().result.clone()))
+            .collect();
+        entries.sort_unstable_by_key(|(sequence, _)| *sequence);
+        entries.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Get every history entry with the request that produced it, oldest first
+    pub fn get_coordination_records(&self) -> Vec<(u64, CoordinationRecord)> {
+        let mut records: Vec<(u64, CoordinationRecord)> = self
+            .coordination_history
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect();
+        records.sort_unstable_by_key(|(sequence, _)| *sequence);
+        records
+    }
+
+    /// Get up to `limit` history entries with sequence numbers after `after`, oldest first
+    ///
+    /// Sequence numbers start at 1, so the last sequence of a page is the cursor for the next.
+    pub fn coordination_history_page(
+        &self,
+        after: Option<u64>,
+        limit: usize,
+    ) -> Vec<(u64, CoordinationResult)> {
+        let after = after.unwrap_or(0);
+        let mut sequences: Vec<u64> = self
+            .coordination_history
+            .iter()
+            .map(|entry| *entry.key())
+            .filter(|sequence| *sequence > after)
+            .collect();
+        sequences.sort_unstable();
+
+        sequences
+            .into_iter()
+            .take(limit)
+            .filter_map(|sequence| {
+                self.coordination_history
+                    .get(&sequence)
+                    .map(|entry| (sequence, entry.value().result.clone()))
+            })
             .collect()
     }
 
+    /// Get history entries recorded while `perception_id` was active, oldest first
+    pub fn get_coordination_history_for_perception(
+        &self,
+        perception_id: &PerceptionId,
+    ) -> Vec<(u64, CoordinationResult)> {
+        let mut entries: Vec<(u64, CoordinationResult)> = self
+            .coordination_history
+            .iter()
+            .filter(|entry| {
+                entry
+                    .value()
+                    .result
+                    .perception_locks
+                    .contains(perception_id)
+            })
+            .map(|entry| (*entry.key(), entry.value().result.clone()))
+            .collect();
+        entries.sort_unstable_by_key(|(sequence, _)| *sequence);
+        entries
+    }
+
     /// Get paradox registry for analysis
     pub fn get_paradox_registry(&self) -> Vec<ParadoxReport> {
         self.paradox_registry
@@ -651,6 +1928,20 @@ impl CasialEngine {
             .map(|entry| entry.value().clone())
             .collect()
     }
+
+    /// Resolve a registered paradox with an explicit strategy, overriding the rule's choice
+    pub fn resolve_paradox(&self, id: Uuid, strategy: ParadoxStrategy) -> Result<ParadoxReport> {
+        let mut report = self
+            .paradox_registry
+            .get_mut(&id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown paradox: {}", id))?;
+        if report.resolved_at.is_some() {
+            anyhow::bail!("Paradox {} is already resolved", id);
+        }
+        report.resolution_strategy = strategy;
+        report.resolved_at = Some(Utc::now());
+        Ok(report.clone())
+    }
 }
 
 impl Default for CasialEngine {
@@ -670,6 +1961,1256 @@ mod tests {
         assert_eq!(engine.active_perceptions.len(), 0);
     }
 
+    fn template_json(id: &str, priority: u32, content: String) -> serde_json::Value {
+        serde_json::json!({
+            "id": id, "name": id, "description": "", "categories": [],
+            "priority": priority, "enabled": true, "content": content,
+            "perception_affinity": [], "paradox_resistance": 0.5, "metadata": {}
+        })
+    }
+
+    fn search_rule_json(
+        id: &str,
+        template_ids: &[&str],
+        transform_type: &str,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "id": id, "name": id, "enabled": true,
+            "conditions": {
+                "tool_patterns": ["search"], "environment_vars": {}, "file_signals": [],
+                "perception_states": [], "min_confidence": null
+            },
+            "actions": {
+                "template_ids": template_ids, "transform_type": transform_type,
+                "target_field": null, "char_limit": null, "perception_lock": false
+            },
+            "perception_scope": [],
+            "paradox_handling": "Ignore"
+        })
+    }
+
+    fn mission_from_parts(
+        global_char_limit: usize,
+        templates: Vec<serde_json::Value>,
+        rules: Vec<serde_json::Value>,
+    ) -> CasialMission {
+        serde_json::from_value(serde_json::json!({
+            "id": "budgeted", "name": "Budgeted", "description": "",
+            "templates": templates,
+            "rules": rules,
+            "perceptions": [],
+            "budgets": {
+                "global_char_limit": global_char_limit, "per_tool_limits": {},
+                "perception_quotas": {}, "paradox_overhead": 0.0
+            },
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z"
+        }))
+        .expect("valid mission")
+    }
+
+    fn budgeted_mission(global_char_limit: usize) -> CasialMission {
+        mission_from_parts(
+            global_char_limit,
+            vec![
+                template_json("short", 1, "a".repeat(40)),
+                template_json("long", 2, "b".repeat(400)),
+            ],
+            vec![search_rule_json(
+                "search-context",
+                &["short", "long"],
+                "Prepend",
+            )],
+        )
+    }
+
+    fn search_request(max_context_size: Option<usize>) -> CoordinationRequest {
+        CoordinationRequest {
+            max_context_size,
+            ..CoordinationRequest::new("web_search", serde_json::json!({ "query": "rust" }))
+        }
+    }
+
+    fn exposing_mission(id: &str, affinity: PerceptionId) -> CasialMission {
+        let mut template = template_json("guide", 1, "Cite sources.".to_string());
+        template["perception_affinity"] = serde_json::json!([affinity]);
+        let mut rule = search_rule_json("expose-guide", &["guide"], "Prepend");
+        rule["paradox_handling"] = serde_json::json!("Expose");
+
+        let mut mission = mission_from_parts(10_000, vec![template], vec![rule]);
+        mission.id = id.to_string();
+        mission
+    }
+
+    #[test]
+    fn test_expose_strategy_discloses_paradox_in_context() {
+        let engine = CasialEngine::new();
+        let (first, second) = (PerceptionId::new(), PerceptionId::new());
+        engine
+            .load_mission(exposing_mission("first", first))
+            .unwrap();
+        engine
+            .load_mission(exposing_mission("second", second))
+            .unwrap();
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+
+        assert_eq!(result.paradoxes_detected.len(), 1);
+        let content = &result.injected_content;
+        assert!(content.starts_with("<!-- casial:paradox -->"));
+        assert!(content.contains("Template 'guide' has conflicting perception affinities"));
+        assert!(content.contains(&first.0.to_string()));
+        assert!(content.contains(&second.0.to_string()));
+        assert!(content.contains("Confidence impact: 0.50"));
+        assert!(content.contains("<!-- /casial:paradox -->"));
+
+        let query = result.modified_args["query"].as_str().unwrap();
+        assert!(query.starts_with("<!-- casial:paradox -->"));
+    }
+
+    #[test]
+    fn test_paradox_disclosure_and_markers_stay_within_the_budget() {
+        let coordinate = |limit: usize| {
+            let engine = CasialEngine::new();
+            for id in ["first", "second"] {
+                let mut mission = exposing_mission(id, PerceptionId::new());
+                mission.budgets.global_char_limit = Some(limit);
+                mission.budgets.paradox_overhead = 0.05;
+                mission.budgets.injection_markers = Some(InjectionMarkers {
+                    begin: "<<C>>".to_string(),
+                    end: "<</C>>".to_string(),
+                });
+                engine.load_mission(mission).unwrap();
+            }
+            engine.coordinate(search_request(None)).unwrap()
+        };
+        let injected_len = |result: &CoordinationResult| {
+            result.modified_args["query"]
+                .as_str()
+                .unwrap()
+                .chars()
+                .count()
+                - "\n\nrust".len()
+        };
+        let template_chars = "## guide\n\nCite sources.\n\n".len();
+        let marker_chars = "<<C>>\n<</C>>".len();
+        let disclosure_chars = coordinate(10_000).injected_content.chars().count() - template_chars;
+
+        // The disclosure outgrows the paradox reserve and pushes the template out
+        let limit = disclosure_chars + marker_chars + template_chars - 1;
+        let result = coordinate(limit);
+        assert!(result
+            .injected_content
+            .starts_with("<!-- casial:paradox -->"));
+        assert_eq!(
+            result.metadata["dropped_templates"],
+            serde_json::json!(["guide"])
+        );
+        assert!(injected_len(&result) <= limit);
+
+        // A disclosure that can't fit at all is left out and reported
+        let limit = disclosure_chars + marker_chars - 1;
+        let result = coordinate(limit);
+        assert!(!result.injected_content.contains("casial:paradox"));
+        assert_eq!(result.used_templates, vec!["guide"]);
+        assert_eq!(
+            result.metadata["paradox_disclosure_dropped"],
+            serde_json::json!(true)
+        );
+        assert!(injected_len(&result) <= limit);
+    }
+
+    #[test]
+    fn test_paradoxes_per_coordination_are_capped() {
+        let engine = CasialEngine::new();
+        let ids: Vec<String> = (0..10).map(|i| format!("guide-{}", i)).collect();
+        let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+        for mission_id in ["first", "second"] {
+            let affinity = PerceptionId::new();
+            let templates = ids
+                .iter()
+                .map(|id| {
+                    let mut template = template_json(id, 1, format!("{} content", id));
+                    template["perception_affinity"] = serde_json::json!([affinity]);
+                    template
+                })
+                .collect();
+            let rule = search_rule_json("all-guides", &id_refs, "Prepend");
+            let mut mission = mission_from_parts(10_000, templates, vec![rule]);
+            mission.id = mission_id.to_string();
+            mission.budgets.max_paradoxes = Some(3);
+            engine.load_mission(mission).unwrap();
+        }
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+
+        assert_eq!(result.paradoxes_detected.len(), 3);
+        assert_eq!(engine.get_paradox_registry().len(), 3);
+        assert_eq!(
+            result.metadata["paradox_cap"],
+            serde_json::json!({ "limit": 3, "skipped": 7 })
+        );
+    }
+
+    #[test]
+    fn test_category_scope_pulls_every_template_in_the_category() {
+        let tagged = |id: &str, categories: &[&str]| {
+            let mut template = template_json(id, 1, format!("{} content", id));
+            template["categories"] = serde_json::json!(categories);
+            template
+        };
+        let mut rule = search_rule_json("style-pack", &["intro"], "Prepend");
+        rule["category_scope"] = serde_json::json!(["style"]);
+        let mission = mission_from_parts(
+            10_000,
+            vec![
+                tagged("intro", &[]),
+                tagged("tone", &["style"]),
+                tagged("citations", &["legal"]),
+                tagged("voice", &["style", "legal"]),
+            ],
+            vec![rule],
+        );
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+
+        let styled: Vec<String> = engine
+            .get_templates_by_category("style")
+            .into_iter()
+            .map(|template| template.id)
+            .collect();
+        assert_eq!(styled, ["tone", "voice"]);
+
+        let mut used = engine
+            .coordinate(search_request(None))
+            .unwrap()
+            .used_templates;
+        used.sort();
+        assert_eq!(used, ["intro", "tone", "voice"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_quiesce_waits_for_in_flight_coordinations() {
+        use std::time::{Duration, Instant};
+
+        // Reading the FIFO blocks the coordination until something writes to it
+        let project = std::env::temp_dir().join(format!("casial-quiesce-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&project).unwrap();
+        let gate = project.join("gate");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&gate)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let mut rule = search_rule_json("gated", &["short"], "Prepend");
+        rule["conditions"]["file_signals"] = serde_json::json!([
+            { "path": "gate", "must_exist": true, "contains": "open", "modified_since": null }
+        ]);
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(mission_from_parts(
+                1_000,
+                vec![template_json("short", 1, "a".repeat(40))],
+                vec![rule],
+            ))
+            .unwrap();
+        assert!(engine.quiesce(Duration::ZERO));
+
+        let engine = &engine;
+        std::thread::scope(|scope| {
+            let slow = scope.spawn(|| {
+                engine.coordinate(CoordinationRequest {
+                    project_path: Some(project.to_string_lossy().into_owned()),
+                    ..search_request(None)
+                })
+            });
+            // Opened even if the assertions below fail, so the scope can always join
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                std::fs::write(&gate, "open").unwrap();
+            });
+            let registered = Instant::now();
+            while engine.quiesce(Duration::ZERO) {
+                assert!(
+                    registered.elapsed() < Duration::from_secs(5),
+                    "coordinate never registered as in flight"
+                );
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            assert!(!engine.quiesce(Duration::from_millis(10)));
+            let waited = Instant::now();
+            assert!(engine.quiesce(Duration::from_secs(5)));
+            assert!(waited.elapsed() >= Duration::from_millis(50));
+            assert!(slow.join().unwrap().unwrap().applied);
+        });
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_paradox_evidence_only_when_requested() {
+        let engine = CasialEngine::new();
+        for (id, content) in [
+            ("first", "Cite primary sources."),
+            ("second", "Summarize without citations."),
+        ] {
+            let mut mission = exposing_mission(id, PerceptionId::new());
+            mission.templates[0].content = content.to_string();
+            engine.load_mission(mission).unwrap();
+        }
+
+        let terse = engine.coordinate(search_request(None)).unwrap();
+        assert!(terse.paradoxes_detected[0].evidence.is_none());
+        assert!(serde_json::to_value(&terse.paradoxes_detected[0])
+            .unwrap()
+            .get("evidence")
+            .is_none());
+
+        let verbose = engine
+            .coordinate(CoordinationRequest {
+                include_paradox_evidence: true,
+                ..search_request(None)
+            })
+            .unwrap();
+        let evidence = verbose.paradoxes_detected[0].evidence.as_ref().unwrap();
+        // Either mission's template may be seen first
+        let mut snippets: Vec<&str> = evidence
+            .iter()
+            .flat_map(|element| element.evidence.iter().map(String::as_str))
+            .collect();
+        snippets.sort_unstable();
+        assert_eq!(
+            snippets,
+            ["Cite primary sources.", "Summarize without citations."]
+        );
+        assert!(evidence
+            .iter()
+            .all(|element| element.element_id == "guide" && element.perspective.is_some()));
+    }
+
+    #[test]
+    fn test_paradox_evidence_is_redacted() {
+        let engine = CasialEngine::new();
+        for (id, content) in [
+            ("first", "Use key sk-live-abcdefghijklmnop."),
+            ("second", "Summarize without citations."),
+        ] {
+            let mut mission = exposing_mission(id, PerceptionId::new());
+            mission.templates[0].content = content.to_string();
+            mission.budgets.redaction_patterns = vec![r"sk-live-[a-z]{16}".to_string()];
+            engine.load_mission(mission).unwrap();
+        }
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                include_paradox_evidence: true,
+                ..search_request(None)
+            })
+            .unwrap();
+        let evidence = serde_json::to_string(&result.paradoxes_detected[0].evidence).unwrap();
+        assert!(!evidence.contains("sk-live"), "{}", evidence);
+        assert!(evidence.contains("Use key ***."), "{}", evidence);
+    }
+
+    #[test]
+    fn test_mission_id_limits_coordination_to_one_mission() {
+        let engine = CasialEngine::new();
+        for id in ["first", "second"] {
+            engine
+                .load_mission(exposing_mission(id, PerceptionId::new()))
+                .unwrap();
+        }
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                mission_id: Some("first".to_string()),
+                ..search_request(None)
+            })
+            .unwrap();
+        assert_eq!(result.used_templates, vec!["guide"]);
+        assert!(result.paradoxes_detected.is_empty());
+
+        let err = engine
+            .coordinate(CoordinationRequest {
+                mission_id: Some("missing".to_string()),
+                ..search_request(None)
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown mission 'missing'"));
+    }
+
+    #[test]
+    fn test_resolve_paradox_overrides_strategy_once() {
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(exposing_mission("first", PerceptionId::new()))
+            .unwrap();
+        engine
+            .load_mission(exposing_mission("second", PerceptionId::new()))
+            .unwrap();
+        let paradox_id = engine
+            .coordinate(search_request(None))
+            .unwrap()
+            .paradoxes_detected[0]
+            .id;
+
+        let resolved = engine
+            .resolve_paradox(paradox_id, ParadoxStrategy::Synthesize)
+            .unwrap();
+
+        assert!(matches!(
+            resolved.resolution_strategy,
+            ParadoxStrategy::Synthesize
+        ));
+        assert!(resolved.resolved_at.is_some());
+        assert!(engine.get_paradox_registry()[0].resolved_at.is_some());
+        assert!(engine
+            .resolve_paradox(paradox_id, ParadoxStrategy::Ignore)
+            .is_err());
+        assert!(engine
+            .resolve_paradox(Uuid::new_v4(), ParadoxStrategy::Ignore)
+            .is_err());
+    }
+
+    #[test]
+    fn test_per_tool_tolerance_overrides_mission_default() {
+        let engine = CasialEngine::new();
+        let mut mission = budgeted_mission(10_000);
+        mission.default_paradox_tolerance = Some(0.9);
+        mission
+            .per_tool_tolerance
+            .insert("web_search".to_string(), 0.2);
+        engine.load_mission(mission).unwrap();
+        let tolerance = |request: CoordinationRequest| {
+            engine.coordinate(request).unwrap().metadata["paradox_tolerance"].as_f64()
+        };
+
+        assert_eq!(tolerance(search_request(None)), Some(0.2));
+        let mut other_tool = search_request(None);
+        other_tool.tool_name = "crawl".to_string();
+        assert_eq!(tolerance(other_tool), Some(0.9));
+        let mut explicit = search_request(None);
+        explicit.paradox_tolerance = Some(0.7);
+        assert_eq!(tolerance(explicit), Some(0.7));
+    }
+
+    #[test]
+    fn test_out_of_range_tolerance_is_clamped() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        let tolerance = |value: f64| {
+            let mut request = search_request(None);
+            request.paradox_tolerance = Some(value);
+            engine.coordinate(request).unwrap().metadata["paradox_tolerance"].as_f64()
+        };
+
+        assert_eq!(tolerance(2.0), Some(1.0));
+        assert_eq!(tolerance(-1.0), Some(0.0));
+        assert_eq!(tolerance(0.3), Some(0.3));
+    }
+
+    #[test]
+    fn test_non_finite_tolerance_falls_back_without_panicking() {
+        let engine = CasialEngine::new();
+        let mut mission = budgeted_mission(10_000);
+        mission.default_paradox_tolerance = Some(0.6);
+        engine.load_mission(mission).unwrap();
+
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut request = search_request(None);
+            request.paradox_tolerance = Some(value);
+            let result = engine.coordinate(request).expect("coordination survives");
+            assert_eq!(result.metadata["paradox_tolerance"].as_f64(), Some(0.6));
+        }
+
+        // A NaN mission tolerance falls back to the engine default
+        let engine = CasialEngine::new();
+        let mut mission = budgeted_mission(10_000);
+        mission.default_paradox_tolerance = Some(f64::NAN);
+        engine.load_mission(mission).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert_eq!(
+            result.metadata["paradox_tolerance"].as_f64(),
+            Some(DEFAULT_PARADOX_TOLERANCE)
+        );
+    }
+
+    #[test]
+    fn test_template_includes_expand_and_cycles_fail_with_chain() {
+        let rule = search_rule_json("search-guide", &["guide"], "Prepend");
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(mission_from_parts(
+                10_000,
+                vec![
+                    template_json("guide", 1, "Intro. {{> footer}}".to_string()),
+                    template_json("footer", 2, "Cite sources.".to_string()),
+                ],
+                vec![rule.clone()],
+            ))
+            .unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert!(result.injected_content.contains("Intro. Cite sources."));
+
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(mission_from_parts(
+                10_000,
+                vec![
+                    template_json("guide", 1, "See {{> appendix}}".to_string()),
+                    template_json("appendix", 2, "Back to {{> guide}}".to_string()),
+                ],
+                vec![rule.clone()],
+            ))
+            .unwrap();
+        let error = engine
+            .coordinate(search_request(None))
+            .expect_err("cyclic includes");
+        assert!(matches!(
+            error.downcast_ref::<CasialError>(),
+            Some(CasialError::TemplateError(_))
+        ));
+        assert!(
+            error.to_string().contains("guide -> appendix -> guide"),
+            "{}",
+            error
+        );
+
+        let mut mission = mission_from_parts(
+            10_000,
+            vec![
+                template_json("guide", 1, "{{> a}}".to_string()),
+                template_json("a", 2, "{{> b}}".to_string()),
+                template_json("b", 3, "done".to_string()),
+            ],
+            vec![rule],
+        );
+        mission.budgets.max_template_depth = Some(1);
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+        let error = engine
+            .coordinate(search_request(None))
+            .expect_err("too deep");
+        assert!(
+            error
+                .to_string()
+                .contains("depth exceeds 1: guide -> a -> b"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_injection_markers_wrap_injected_content() {
+        let engine = CasialEngine::new();
+        let mut mission = budgeted_mission(10_000);
+        mission.budgets.injection_markers = Some(InjectionMarkers {
+            begin: "<<CASIAL_CONTEXT>>".to_string(),
+            end: "<</CASIAL_CONTEXT>>".to_string(),
+        });
+        engine.load_mission(mission).unwrap();
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+        let query = result.modified_args["query"].as_str().unwrap();
+        let (injected, user) = query
+            .strip_prefix("<<CASIAL_CONTEXT>>\n")
+            .and_then(|rest| rest.split_once("<</CASIAL_CONTEXT>>"))
+            .expect("markers around the injected content");
+        assert!(injected.contains(&"a".repeat(40)));
+        assert_eq!(user, "\n\nrust");
+
+        // Without markers the content is injected as before
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert!(!result.modified_args["query"]
+            .as_str()
+            .unwrap()
+            .contains("CASIAL_CONTEXT"));
+
+        // Markers are charged by characters, like the content they wrap
+        let template_chars = "## short\n\n\n\n".len() + 40;
+        let markers = InjectionMarkers {
+            begin: "«casial»".to_string(),
+            end: "«/casial»".to_string(),
+        };
+        let limit = template_chars + markers.wrap("").chars().count();
+        let mut mission = budgeted_mission(limit);
+        mission.budgets.injection_markers = Some(markers);
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert_eq!(result.used_templates, vec!["short"]);
+    }
+
+    #[test]
+    fn test_weighted_pick_follows_weights_with_seeded_ids() {
+        let engine = CasialEngine::with_id_source(Arc::new(SeededIdSource::new(7)));
+        let mut rule = search_rule_json("ab-guide", &["control", "variant"], "Prepend");
+        rule["actions"]["selection"] = serde_json::json!({ "WeightedPick": { "weights": { "control": 3.0, "variant": 1.0 } } });
+        let mission = mission_from_parts(
+            10_000,
+            vec![
+                template_json("control", 1, "Cite sources.".to_string()),
+                template_json("variant", 1, "Quote sources verbatim.".to_string()),
+            ],
+            vec![rule],
+        );
+        engine.load_mission(mission).unwrap();
+
+        let runs = 4_000;
+        let mut control = 0;
+        for _ in 0..runs {
+            let result = engine.coordinate(search_request(None)).unwrap();
+            assert_eq!(result.used_templates.len(), 1);
+            let chosen = result.metadata["template_variants"]["ab-guide"].as_str();
+            assert_eq!(chosen, Some(result.used_templates[0].as_str()));
+            if chosen == Some("control") {
+                control += 1;
+            }
+        }
+
+        let share = control as f64 / runs as f64;
+        assert!((share - 0.75).abs() < 0.03, "control share {}", share);
+    }
+
+    #[test]
+    fn test_seeded_engines_mint_identical_perception_and_paradox_ids() {
+        let run = || {
+            let engine = CasialEngine::with_id_source(Arc::new(SeededIdSource::new(11)));
+            let (first, second) = (engine.new_perception_id(), engine.new_perception_id());
+            engine
+                .load_mission(exposing_mission("first", first))
+                .unwrap();
+            engine
+                .load_mission(exposing_mission("second", second))
+                .unwrap();
+            let result = engine.coordinate(search_request(None)).unwrap();
+            let paradox_ids: Vec<Uuid> = result.paradoxes_detected.iter().map(|p| p.id).collect();
+            (vec![first, second], paradox_ids, result.injected_content)
+        };
+
+        let (perceptions, paradoxes, content) = run();
+        assert_ne!(perceptions[0], perceptions[1]);
+        assert_eq!(paradoxes.len(), 1);
+        assert_eq!(run(), (perceptions, paradoxes, content));
+    }
+
+    #[test]
+    fn test_template_allow_list_restricts_injection() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                template_allow: Some(vec!["long".to_string()]),
+                ..search_request(None)
+            })
+            .unwrap();
+
+        assert_eq!(result.activated_rules, vec!["search-context"]);
+        assert_eq!(result.used_templates, vec!["long"]);
+        assert!(!result.injected_content.contains('a'));
+    }
+
+    #[test]
+    fn test_max_template_priority_excludes_less_important_templates() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                max_template_priority: Some(1),
+                ..search_request(None)
+            })
+            .unwrap();
+
+        assert_eq!(result.used_templates, vec!["short"]);
+        assert!(!result.injected_content.contains('b'));
+    }
+
+    #[test]
+    fn test_provenance_attributes_content_ranges_to_templates() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let plain = engine.coordinate(search_request(None)).unwrap();
+        assert!(!plain.metadata.contains_key("provenance"));
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                include_provenance: true,
+                ..search_request(None)
+            })
+            .unwrap();
+        let spans: Vec<ProvenanceSpan> =
+            serde_json::from_value(result.metadata["provenance"].clone()).unwrap();
+
+        let chars: Vec<char> = result.injected_content.chars().collect();
+        assert_eq!(spans.len(), result.used_templates.len());
+        assert_eq!(spans.last().unwrap().end, chars.len());
+        for (span, template_id) in spans.iter().zip(&result.used_templates) {
+            assert_eq!(&span.template_id, template_id);
+            assert_eq!(span.rule_id.as_deref(), Some("search-context"));
+            let text: String = chars[span.start..span.end].iter().collect();
+            assert!(text.starts_with(&format!("## {}\n\n", template_id)));
+        }
+        assert_eq!(spans[0].start, 0);
+        assert_eq!(spans[1].start, spans[0].end);
+    }
+
+    #[test]
+    fn test_template_deny_list_wins_over_rules_and_allow_list() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                template_deny: vec!["short".to_string()],
+                ..search_request(None)
+            })
+            .unwrap();
+        assert_eq!(result.used_templates, vec!["long"]);
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                template_allow: Some(vec!["short".to_string()]),
+                template_deny: vec!["short".to_string()],
+                ..search_request(None)
+            })
+            .unwrap();
+        assert!(!result.applied);
+        assert!(result.used_templates.is_empty());
+    }
+
+    #[test]
+    fn test_max_context_size_narrows_mission_budget() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let uncapped = engine.coordinate(search_request(None)).unwrap();
+        assert_eq!(uncapped.used_templates, vec!["short", "long"]);
+
+        let capped = engine.coordinate(search_request(Some(100))).unwrap();
+        assert_eq!(capped.used_templates, vec!["short"]);
+        assert!(capped.injected_content.len() <= 100);
+        assert!(capped.injected_content.len() < uncapped.injected_content.len());
+
+        // A cap larger than the mission budget never widens it
+        let tight_engine = CasialEngine::new();
+        tight_engine.load_mission(budgeted_mission(100)).unwrap();
+        let widened = tight_engine
+            .coordinate(search_request(Some(1_000_000)))
+            .unwrap();
+        assert_eq!(widened.used_templates, vec!["short"]);
+    }
+
+    #[test]
+    fn test_char_limit_counts_characters_not_bytes() {
+        let template = template_json("accents", 1, "é".repeat(50));
+        let size = "## accents\n\n\n\n".len() + 50;
+        let mission = mission_from_parts(
+            size,
+            vec![template],
+            vec![search_rule_json("search-accents", &["accents"], "Prepend")],
+        );
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+
+        // 100 bytes of content, but exactly `size` characters
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert_eq!(result.used_templates, vec!["accents"]);
+        assert_eq!(result.injected_content.chars().count(), size);
+    }
+
+    #[test]
+    fn test_window_budget_stops_injection_until_the_window_resets() {
+        let mission = || {
+            mission_from_parts(
+                10_000,
+                vec![template_json("guide", 1, "g".repeat(100))],
+                vec![search_rule_json("search-guide", &["guide"], "Prepend")],
+            )
+        };
+        let probe = CasialEngine::new();
+        probe.load_mission(mission()).unwrap();
+        let size = probe
+            .coordinate(search_request(None))
+            .unwrap()
+            .injected_content
+            .chars()
+            .count();
+
+        // Room for exactly two coordinations per window
+        let engine = CasialEngine::new();
+        let mut windowed = mission();
+        windowed.budgets.window_budget = Some(WindowBudget {
+            char_limit: 2 * size,
+            window_seconds: 3600,
+        });
+        engine.load_mission(windowed).unwrap();
+
+        let first = engine.coordinate(search_request(None)).unwrap();
+        assert!(first.applied);
+        assert_eq!(first.metadata["window_budget"]["exhausted"], false);
+        let second = engine.coordinate(search_request(None)).unwrap();
+        assert!(second.applied);
+        assert_eq!(second.metadata["window_budget"]["used"], 2 * size);
+        assert_eq!(second.metadata["window_budget"]["exhausted"], true);
+
+        for _ in 0..2 {
+            let starved = engine.coordinate(search_request(None)).unwrap();
+            assert!(!starved.applied);
+            assert!(starved.injected_content.is_empty());
+            assert_eq!(starved.modified_args, search_request(None).tool_args);
+            assert_eq!(starved.metadata["window_budget"]["exhausted"], true);
+        }
+
+        // Once the window has run its course the allowance refills
+        engine.window_usage.get_mut("budgeted").unwrap().started_at -=
+            chrono::Duration::seconds(3600);
+        let refilled = engine.coordinate(search_request(None)).unwrap();
+        assert!(refilled.applied);
+        assert_eq!(refilled.metadata["window_budget"]["used"], size);
+        assert_eq!(refilled.metadata["window_budget"]["exhausted"], false);
+    }
+
+    #[test]
+    fn test_window_is_charged_the_characters_the_budget_counted() {
+        let mut mission = mission_from_parts(
+            10_000,
+            vec![template_json("accents", 1, "é".repeat(50))],
+            vec![search_rule_json("search-accents", &["accents"], "Prepend")],
+        );
+        mission.budgets.injection_markers = Some(InjectionMarkers {
+            begin: "«casial»".to_string(),
+            end: "«/casial»".to_string(),
+        });
+        mission.budgets.window_budget = Some(WindowBudget {
+            char_limit: 10_000,
+            window_seconds: 3600,
+        });
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+        let injected = result.modified_args["query"].as_str().unwrap();
+        let injected_chars = injected.chars().count() - "\n\nrust".len();
+        assert_eq!(result.metadata["window_budget"]["used"], injected_chars);
+    }
+
+    #[test]
+    fn test_missions_coordinating_together_share_the_strictest_limits() {
+        let engine = CasialEngine::new();
+        let mut alpha = mission_from_parts(
+            10_000,
+            vec![template_json("a-guide", 1, "a".repeat(40))],
+            vec![search_rule_json("a-rule", &["a-guide"], "Prepend")],
+        );
+        alpha.id = "alpha".to_string();
+        // Only the later mission sets a tight limit and a window
+        let mut beta = mission_from_parts(
+            60,
+            vec![template_json("b-guide", 2, "b".repeat(40))],
+            vec![search_rule_json("b-rule", &["b-guide"], "Prepend")],
+        );
+        beta.id = "beta".to_string();
+        beta.budgets.window_budget = Some(WindowBudget {
+            char_limit: 10_000,
+            window_seconds: 3600,
+        });
+        engine.load_mission(alpha).unwrap();
+        engine.load_mission(beta).unwrap();
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+
+        // Neither sets a tolerance, so the default holds
+        assert_eq!(
+            result.metadata["paradox_tolerance"].as_f64(),
+            Some(DEFAULT_PARADOX_TOLERANCE)
+        );
+        assert_eq!(result.used_templates, vec!["a-guide"]);
+        assert_eq!(
+            result.metadata["dropped_templates"],
+            serde_json::json!(["b-guide"])
+        );
+        let injected = result.injected_content.chars().count();
+        assert_eq!(result.metadata["window_budget"]["mission_id"], "beta");
+        assert_eq!(result.metadata["window_budget"]["used"], injected);
+        assert_eq!(
+            engine.window_usage.get("beta").unwrap().injected_chars,
+            injected
+        );
+        assert!(engine.window_usage.get("alpha").is_none());
+
+        // The lowest tolerance any mission sets wins over missions that set none
+        let mut gamma = mission_from_parts(10_000, vec![], vec![]);
+        gamma.id = "gamma".to_string();
+        gamma.default_paradox_tolerance = Some(0.8);
+        let mut delta = mission_from_parts(10_000, vec![], vec![]);
+        delta.id = "delta".to_string();
+        delta
+            .per_tool_tolerance
+            .insert("web_search".to_string(), 0.3);
+        engine.load_mission(gamma).unwrap();
+        engine.load_mission(delta).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert_eq!(result.metadata["paradox_tolerance"].as_f64(), Some(0.3));
+    }
+
+    #[test]
+    fn test_transform_types_compose_separate_sections() {
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(mission_from_parts(
+                10_000,
+                vec![
+                    template_json("guardrails", 1, "SYSTEM-ONLY".to_string()),
+                    template_json("hints", 2, "QUERY-ONLY".to_string()),
+                ],
+                vec![
+                    search_rule_json("system", &["guardrails"], "SystemInstruction"),
+                    search_rule_json("prepend", &["hints"], "Prepend"),
+                ],
+            ))
+            .unwrap();
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+        let system_context = result.modified_args["system_context"].as_str().unwrap();
+        let query = result.modified_args["query"].as_str().unwrap();
+
+        assert!(system_context.contains("SYSTEM-ONLY"));
+        assert!(!system_context.contains("QUERY-ONLY"));
+        assert!(query.contains("QUERY-ONLY"));
+        assert!(!query.contains("SYSTEM-ONLY"));
+        assert!(query.ends_with("rust"));
+    }
+
+    #[test]
+    fn test_prepend_without_string_target_creates_fallback_field() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                tool_args: serde_json::json!({ "limit": 5, "query": 42 }),
+                ..search_request(None)
+            })
+            .unwrap();
+
+        let context = result.modified_args["casial_context"].as_str().unwrap();
+        assert!(context.contains(&"a".repeat(40)));
+        assert_eq!(result.modified_args["query"], serde_json::json!(42));
+        assert_eq!(
+            result.metadata.get("fallback_fields"),
+            Some(&serde_json::json!(["casial_context"]))
+        );
+    }
+
+    #[test]
+    fn test_nested_target_field_is_created() {
+        let mut rule = search_rule_json("nested", &["short"], "Append");
+        rule["actions"]["target_field"] = serde_json::json!("options.prompt");
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(mission_from_parts(
+                10_000,
+                vec![template_json("short", 1, "NESTED".to_string())],
+                vec![rule],
+            ))
+            .unwrap();
+
+        let result = engine
+            .coordinate(CoordinationRequest {
+                tool_args: serde_json::json!({}),
+                ..search_request(None)
+            })
+            .unwrap();
+
+        let prompt = result.modified_args["options"]["prompt"].as_str().unwrap();
+        assert!(prompt.contains("NESTED"));
+        assert_eq!(
+            result.metadata.get("fallback_fields"),
+            Some(&serde_json::json!(["options.prompt"]))
+        );
+    }
+
+    #[test]
+    fn test_coordination_history_pages_in_order() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        for _ in 0..3 {
+            engine.coordinate(search_request(None)).unwrap();
+        }
+
+        let first = engine.coordination_history_page(None, 2);
+        let sequences: Vec<u64> = first.iter().map(|(sequence, _)| *sequence).collect();
+        assert_eq!(sequences, vec![1, 2]);
+
+        let rest = engine.coordination_history_page(Some(2), 10);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].0, 3);
+    }
+
+    #[test]
+    fn test_coordination_history_is_returned_in_insertion_order() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        let tools: Vec<String> = (0..20).map(|i| format!("web_search_{}", i)).collect();
+        for tool in &tools {
+            engine
+                .coordinate(CoordinationRequest {
+                    tool_name: tool.clone(),
+                    ..search_request(None)
+                })
+                .unwrap();
+        }
+
+        let history = engine.get_coordination_history();
+        let seen: Vec<&str> = history
+            .iter()
+            .map(|result| result.metadata["tool_name"].as_str().unwrap())
+            .collect();
+        assert_eq!(seen, tools);
+        let sequences: Vec<u64> = history
+            .iter()
+            .map(|result| result.metadata["sequence"].as_u64().unwrap())
+            .collect();
+        assert_eq!(sequences, (1..=20).collect::<Vec<u64>>());
+
+        // Each record's timestamp is the one reported in its result
+        for (_, record) in engine.get_coordination_records() {
+            assert_eq!(
+                record.result.metadata["timestamp"],
+                serde_json::json!(record.timestamp.to_rfc3339())
+            );
+        }
+    }
+
+    #[test]
+    fn test_coordination_records_keep_the_originating_request() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        let before = Utc::now();
+        let request = CoordinationRequest {
+            template_deny: vec!["long".to_string()],
+            ..search_request(Some(500))
+        };
+        let result = engine.coordinate(request.clone()).unwrap();
+
+        let records = engine.get_coordination_records();
+        assert_eq!(records.len(), 1);
+        let (sequence, record) = &records[0];
+        assert_eq!(*sequence, 1);
+        assert_eq!(record.request.tool_name, request.tool_name);
+        assert_eq!(record.request.tool_args, request.tool_args);
+        assert_eq!(record.request.max_context_size, Some(500));
+        assert_eq!(record.request.template_deny, vec!["long"]);
+        assert_eq!(record.result.used_templates, result.used_templates);
+        assert!(record.timestamp >= before);
+        assert_eq!(engine.get_coordination_history().len(), 1);
+    }
+
+    #[test]
+    fn test_coordination_history_keeps_the_latest_entries() {
+        let engine = CasialEngine::new().with_history_limit(3);
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        for _ in 0..5 {
+            engine.coordinate(search_request(None)).unwrap();
+        }
+
+        let sequences: Vec<u64> = engine
+            .get_coordination_records()
+            .iter()
+            .map(|(sequence, _)| *sequence)
+            .collect();
+        assert_eq!(sequences, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_coordination_records_keep_only_referenced_environment() {
+        let mut rule = search_rule_json("staging-only", &["short"], "Prepend");
+        rule["conditions"]["environment_vars"] = serde_json::json!({ "STAGE": "staging" });
+        let mission = mission_from_parts(
+            10_000,
+            vec![template_json("short", 1, "a".repeat(40))],
+            vec![rule],
+        );
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+
+        let mut request = search_request(None);
+        request.environment = [
+            ("STAGE".to_string(), "staging".to_string()),
+            ("API_SECRET".to_string(), "hunter2".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        engine.coordinate(request).unwrap();
+
+        let records = engine.get_coordination_records();
+        let environment = &records[0].1.request.environment;
+        assert_eq!(environment.len(), 1);
+        assert_eq!(
+            environment.get("STAGE").map(String::as_str),
+            Some("staging")
+        );
+    }
+
+    #[test]
+    fn test_coordination_history_filters_by_perception() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+        let watched = PerceptionId::new();
+        let other = PerceptionId::new();
+
+        for active in [vec![watched], vec![other], vec![], vec![other, watched]] {
+            engine
+                .coordinate(CoordinationRequest {
+                    active_perceptions: active,
+                    ..search_request(None)
+                })
+                .unwrap();
+        }
+
+        let history = engine.get_coordination_history_for_perception(&watched);
+        let sequences: Vec<u64> = history.iter().map(|(sequence, _)| *sequence).collect();
+        assert_eq!(sequences, vec![1, 4]);
+        assert!(history
+            .iter()
+            .all(|(_, result)| result.perception_locks.contains(&watched)));
+        assert!(engine
+            .get_coordination_history_for_perception(&PerceptionId::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_flagged_rule_is_inert_until_flag_enabled() {
+        let mut rule = search_rule_json("staging-rule", &["experimental"], "Prepend");
+        rule["required_flags"] = serde_json::json!(["staging"]);
+        let mut mission = mission_from_parts(
+            10_000,
+            vec![template_json("experimental", 1, "TRY THIS".to_string())],
+            vec![rule],
+        );
+        mission.feature_flags.insert("staging".to_string(), false);
+
+        let engine = CasialEngine::new();
+        engine.load_mission(mission.clone()).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert!(result.activated_rules.is_empty());
+        assert!(!result.applied);
+
+        mission.feature_flags.insert("staging".to_string(), true);
+        engine.load_mission(mission).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+        assert_eq!(result.activated_rules, vec!["staging-rule".to_string()]);
+        assert!(result.injected_content.contains("TRY THIS"));
+    }
+
+    #[test]
+    fn test_equal_priority_templates_compose_in_id_order() {
+        let mission = mission_from_parts(
+            10_000,
+            vec![
+                template_json("gamma", 1, "G".to_string()),
+                template_json("alpha", 1, "A".to_string()),
+                template_json("beta", 1, "B".to_string()),
+            ],
+            vec![search_rule_json(
+                "search-context",
+                &["beta", "gamma", "alpha"],
+                "Prepend",
+            )],
+        );
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+
+        for _ in 0..5 {
+            let result = engine.coordinate(search_request(None)).unwrap();
+            assert_eq!(result.used_templates, vec!["alpha", "beta", "gamma"]);
+        }
+    }
+
+    #[test]
+    fn test_redaction_patterns_mask_secrets_in_injected_content() {
+        let mut mission = mission_from_parts(
+            10_000,
+            vec![template_json(
+                "deploy",
+                1,
+                "Use key sk-live-4f9a8b7c6d5e4f3a2b1c when calling the API.".to_string(),
+            )],
+            vec![search_rule_json("search-context", &["deploy"], "Prepend")],
+        );
+        mission.budgets.redaction_patterns = vec![r"sk-live-[A-Za-z0-9]{16,}".to_string()];
+
+        let engine = CasialEngine::new();
+        engine.load_mission(mission.clone()).unwrap();
+        let result = engine.coordinate(search_request(None)).unwrap();
+
+        assert!(result.injected_content.contains("Use key *** when calling"));
+        assert!(!result.injected_content.contains("sk-live"));
+        assert!(!result.modified_args["query"]
+            .as_str()
+            .unwrap()
+            .contains("sk-live"));
+        assert_eq!(result.metadata["redactions"], serde_json::json!(1));
+
+        mission.budgets.redaction_patterns = vec!["(unclosed".to_string()];
+        assert!(engine.load_mission(mission).is_err());
+    }
+
+    #[test]
+    fn test_injected_content_reports_token_estimate() {
+        let engine = CasialEngine::new();
+        engine.load_mission(budgeted_mission(10_000)).unwrap();
+
+        let result = engine.coordinate(search_request(None)).unwrap();
+
+        // 440 template characters plus two short headers: roughly 110-120 tokens
+        let estimate = result.metadata["estimated_tokens"].as_u64().unwrap() as f64;
+        let expected = result.injected_content.len() as f64 / 4.0;
+        assert!((estimate - expected).abs() <= 1.0);
+        assert!((110.0..=125.0).contains(&estimate));
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_unloading_a_mission_drops_only_its_own_perceptions() {
+        let engine = CasialEngine::new();
+        let mut first = mission_from_parts(10_000, vec![], vec![]);
+        first.id = "first".to_string();
+        let mut second = first.clone();
+        second.id = "second".to_string();
+        let shared = Perception {
+            id: PerceptionId::new(),
+            name: "Shared".to_string(),
+            description: String::new(),
+            confidence: 1.0,
+            metadata: AHashMap::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let own = Perception {
+            id: PerceptionId::new(),
+            ..shared.clone()
+        };
+        first.perceptions = vec![shared.clone(), own.clone()];
+        second.perceptions = vec![shared.clone()];
+        engine.load_mission(first).unwrap();
+        engine.load_mission(second).unwrap();
+
+        assert!(engine.unload_mission("first"));
+        assert!(!engine.has_mission("first"));
+        assert!(engine.get_perception(&shared.id).is_some());
+        assert!(engine.get_perception(&own.id).is_none());
+        assert!(!engine.unload_mission("first"));
+    }
+
     #[test]
     fn test_perception_id_generation() {
         let id1 = PerceptionId::new();
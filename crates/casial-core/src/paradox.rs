@@ -3,13 +3,21 @@
 //! Handles contradictory information and conflicting perceptions.
 //! The Casial system thrives on paradox - like hydraulic lime getting stronger under pressure.
 
-use crate::{CasialError, ParadoxStrategy, PerceptionId};
+use crate::{CasialError, IdSource, ParadoxStrategy, PerceptionId, RandomIdSource};
 use ahash::AHashMap;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Paradoxes kept per coordination or detection pass when nothing else is configured
+pub const DEFAULT_MAX_PARADOXES: usize = 32;
+
+/// Template count past which pairwise template conflict detection is skipped
+pub const DEFAULT_MAX_PAIRWISE_TEMPLATES: usize = 256;
+
 /// A detected paradox in the system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Paradox {
@@ -80,6 +88,31 @@ pub struct ParadoxManager {
     resolved_paradoxes: AHashMap<Uuid, Paradox>,
     resolution_history: Vec<ParadoxResolutionEvent>,
     detection_rules: Vec<ParadoxDetectionRule>,
+    limits: DetectionLimits,
+    capped_detections: usize,
+    skipped_template_scans: usize,
+    id_source: Arc<dyn IdSource>,
+}
+
+/// Bounds on a single `detect_paradoxes` pass
+///
+/// Template conflicts are checked pairwise, so a mission with many conflicting templates
+/// could otherwise produce and register a quadratic number of paradoxes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionLimits {
+    /// Paradoxes registered per pass; detection stops once reached
+    pub max_paradoxes: usize,
+    /// Template conflicts are not checked at all for more templates than this
+    pub max_pairwise_templates: usize,
+}
+
+impl Default for DetectionLimits {
+    fn default() -> Self {
+        Self {
+            max_paradoxes: DEFAULT_MAX_PARADOXES,
+            max_pairwise_templates: DEFAULT_MAX_PAIRWISE_TEMPLATES,
+        }
+    }
 }
 
 /// An event in the paradox resolution history
@@ -113,6 +146,71 @@ pub struct ParadoxDetectionRule {
     pub severity_threshold: ParadoxSeverity,
     pub auto_resolve: bool,
     pub preferred_strategy: ParadoxStrategy,
+    /// Synthesis text for paradoxes from this rule, with `{element_a}`, `{element_b}`,
+    /// `{confidence_a}` and `{confidence_b}` placeholders; the built-in wording when unset
+    #[serde(default)]
+    pub synthesis_template: Option<String>,
+    /// Stopwords and synonyms applied before comparing content for similarity
+    #[serde(default)]
+    pub vocabulary: SimilarityVocabulary,
+}
+
+/// Token normalization for content similarity
+///
+/// Tokens are matched case-insensitively. Stopwords are dropped before comparison and
+/// synonyms are replaced by their canonical form, so `"k8s"` and `"kubernetes"` can count
+/// as the same word.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimilarityVocabulary {
+    #[serde(default)]
+    pub stopwords: Vec<String>,
+    /// Token to canonical form
+    #[serde(default)]
+    pub synonyms: AHashMap<String, String>,
+}
+
+impl SimilarityVocabulary {
+    /// Lowercase the vocabulary once, ahead of a detection pass
+    fn normalized(&self) -> NormalizedVocabulary {
+        NormalizedVocabulary {
+            stopwords: self.stopwords.iter().map(|s| s.to_lowercase()).collect(),
+            synonyms: self
+                .synonyms
+                .iter()
+                .map(|(synonym, canonical)| (synonym.to_lowercase(), canonical.to_lowercase()))
+                .collect(),
+        }
+    }
+}
+
+/// A [`SimilarityVocabulary`] with every word lowercased
+struct NormalizedVocabulary {
+    stopwords: HashSet<String>,
+    synonyms: AHashMap<String, String>,
+}
+
+impl NormalizedVocabulary {
+    /// Lowercased whitespace tokens of `content` that take part in a comparison
+    fn tokens(&self, content: &str) -> HashSet<String> {
+        content
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .filter(|token| !self.stopwords.contains(token))
+            .map(|token| self.synonyms.get(&token).cloned().unwrap_or(token))
+            .collect()
+    }
+}
+
+/// Jaccard similarity of two token sets
+fn jaccard(words_a: &HashSet<String>, words_b: &HashSet<String>) -> f64 {
+    let intersection = words_a.intersection(words_b).count();
+    let union = words_a.union(words_b).count();
+
+    if union == 0 {
+        0.0
+    } else {
+        intersection as f64 / union as f64
+    }
 }
 
 /// Patterns for detecting paradoxes
@@ -148,6 +246,10 @@ impl ParadoxManager {
             resolved_paradoxes: AHashMap::new(),
             resolution_history: Vec::new(),
             detection_rules: Vec::new(),
+            limits: DetectionLimits::default(),
+            capped_detections: 0,
+            skipped_template_scans: 0,
+            id_source: Arc::new(RandomIdSource),
         };
 
         // Add default detection rules
@@ -155,6 +257,12 @@ impl ParadoxManager {
         manager
     }
 
+    /// Draw paradox ids from `id_source` instead of random v4 ids
+    pub fn with_id_source(mut self, id_source: Arc<dyn IdSource>) -> Self {
+        self.id_source = id_source;
+        self
+    }
+
     /// Add default paradox detection rules
     fn add_default_detection_rules(&mut self) {
         let rules = vec![
@@ -175,6 +283,8 @@ impl ParadoxManager {
                 severity_threshold: ParadoxSeverity::Medium,
                 auto_resolve: false,
                 preferred_strategy: ParadoxStrategy::Coexist,
+                synthesis_template: None,
+                vocabulary: SimilarityVocabulary::default(),
             },
             ParadoxDetectionRule {
                 id: "perception-conflicts".to_string(),
@@ -187,13 +297,28 @@ impl ParadoxManager {
                 severity_threshold: ParadoxSeverity::High,
                 auto_resolve: true,
                 preferred_strategy: ParadoxStrategy::Synthesize,
+                synthesis_template: None,
+                vocabulary: SimilarityVocabulary::default(),
             },
         ];
 
         self.detection_rules.extend(rules);
     }
 
+    /// Add a detection rule alongside the defaults
+    pub fn add_detection_rule(&mut self, rule: ParadoxDetectionRule) {
+        self.detection_rules.push(rule);
+    }
+
+    /// Replace the bounds applied to each detection pass
+    pub fn set_detection_limits(&mut self, limits: DetectionLimits) {
+        self.limits = limits;
+    }
+
     /// Detect paradoxes in the given context
+    ///
+    /// At most `DetectionLimits::max_paradoxes` are registered; when the cap cuts detection
+    /// short, the paradoxes that were kept carry `detection_capped: true` in their metadata.
     pub fn detect_paradoxes(
         &mut self,
         templates: &[crate::CasialTemplate],
@@ -201,15 +326,30 @@ impl ParadoxManager {
         environment: &AHashMap<String, String>,
     ) -> Result<Vec<Uuid>> {
         let mut detected_paradoxes = Vec::new();
+        let mut capped = false;
+
+        if templates.len() > self.limits.max_pairwise_templates {
+            self.skipped_template_scans += 1;
+        }
 
         for rule in &self.detection_rules {
             if !rule.enabled {
                 continue;
             }
+            let remaining = self.limits.max_paradoxes - detected_paradoxes.len();
+            if remaining == 0 {
+                capped = true;
+                break;
+            }
 
-            let paradoxes = self.apply_detection_rule(rule, templates, perceptions, environment)?;
-            for paradox in paradoxes {
+            let (paradoxes, truncated) =
+                self.apply_detection_rule(rule, templates, perceptions, environment, remaining)?;
+            capped |= truncated;
+            for mut paradox in paradoxes {
                 let paradox_id = paradox.id;
+                paradox
+                    .metadata
+                    .insert("detection_rule".to_string(), serde_json::json!(rule.id));
                 self.active_paradoxes.insert(paradox_id, paradox);
                 detected_paradoxes.push(paradox_id);
 
@@ -226,30 +366,50 @@ impl ParadoxManager {
             }
         }
 
+        if capped {
+            self.capped_detections += 1;
+            for paradox_id in &detected_paradoxes {
+                if let Some(paradox) = self.active_paradoxes.get_mut(paradox_id) {
+                    paradox
+                        .metadata
+                        .insert("detection_capped".to_string(), serde_json::json!(true));
+                }
+            }
+        }
+
         Ok(detected_paradoxes)
     }
 
-    /// Apply a specific detection rule
+    /// Apply a specific detection rule, keeping at most `limit` paradoxes
+    ///
+    /// Also returns whether more would have been found past the limit.
     fn apply_detection_rule(
         &self,
         rule: &ParadoxDetectionRule,
         templates: &[crate::CasialTemplate],
         perceptions: &[crate::Perception],
         environment: &AHashMap<String, String>,
-    ) -> Result<Vec<Paradox>> {
+        limit: usize,
+    ) -> Result<(Vec<Paradox>, bool)> {
         let mut paradoxes = Vec::new();
+        let vocabulary = rule.vocabulary.normalized();
 
         match &rule.detection_pattern {
             DetectionPattern::ConflictingTemplates {
                 similarity_threshold,
                 contradiction_keywords,
             } => {
-                paradoxes.extend(self.detect_template_conflicts(
-                    templates,
-                    *similarity_threshold,
-                    contradiction_keywords,
-                    &rule.preferred_strategy,
-                )?);
+                // Pairwise comparison is quadratic; skip it outright for oversized inputs
+                if templates.len() <= self.limits.max_pairwise_templates {
+                    paradoxes.extend(self.detect_template_conflicts(
+                        templates,
+                        *similarity_threshold,
+                        contradiction_keywords,
+                        &vocabulary,
+                        &rule.preferred_strategy,
+                        limit.saturating_add(1),
+                    )?);
+                }
             }
             DetectionPattern::ConflictingPerceptions {
                 confidence_threshold,
@@ -259,6 +419,7 @@ impl ParadoxManager {
                     perceptions,
                     *confidence_threshold,
                     *overlap_threshold,
+                    &vocabulary,
                     &rule.preferred_strategy,
                 )?);
             }
@@ -282,21 +443,34 @@ impl ParadoxManager {
             }
         }
 
-        Ok(paradoxes)
+        let truncated = paradoxes.len() > limit;
+        paradoxes.truncate(limit);
+        Ok((paradoxes, truncated))
     }
 
-    /// Detect conflicts between templates
+    /// Detect conflicts between templates, stopping once `limit` are found
     fn detect_template_conflicts(
         &self,
         templates: &[crate::CasialTemplate],
         similarity_threshold: f64,
         contradiction_keywords: &[String],
+        vocabulary: &NormalizedVocabulary,
         strategy: &ParadoxStrategy,
+        limit: usize,
     ) -> Result<Vec<Paradox>> {
         let mut conflicts = Vec::new();
+        // Each template is tokenized once, not once per pair
+        let tokens: Vec<HashSet<String>> = templates
+            .iter()
+            .map(|template| vocabulary.tokens(&template.content))
+            .collect();
 
         for i in 0..templates.len() {
             for j in (i + 1)..templates.len() {
+                if conflicts.len() >= limit {
+                    return Ok(conflicts);
+                }
+
                 let template_a = &templates[i];
                 let template_b = &templates[j];
 
@@ -308,12 +482,11 @@ impl ParadoxManager {
                 });
 
                 // Simple similarity check (in practice, use more sophisticated methods)
-                let similarity =
-                    self.calculate_content_similarity(&template_a.content, &template_b.content);
+                let similarity = jaccard(&tokens[i], &tokens[j]);
 
                 if has_contradiction && similarity > similarity_threshold {
                     let paradox = Paradox {
-                        id: Uuid::new_v4(),
+                        id: self.id_source.next_id(),
                         name: format!("Template Conflict: {} vs {}", template_a.name, template_b.name),
                         description: format!(
                             "Templates '{}' and '{}' contain contradictory guidance with high content similarity",
@@ -364,6 +537,7 @@ impl ParadoxManager {
         perceptions: &[crate::Perception],
         confidence_threshold: f64,
         overlap_threshold: f64,
+        vocabulary: &NormalizedVocabulary,
         strategy: &ParadoxStrategy,
     ) -> Result<Vec<Paradox>> {
         let mut conflicts = Vec::new();
@@ -380,11 +554,12 @@ impl ParadoxManager {
                 }
 
                 // Check for conceptual overlap (simplified)
-                let overlap = self.calculate_perception_overlap(perception_a, perception_b);
+                let overlap =
+                    self.calculate_perception_overlap(perception_a, perception_b, vocabulary);
 
                 if overlap > overlap_threshold {
                     let paradox = Paradox {
-                        id: Uuid::new_v4(),
+                        id: self.id_source.next_id(),
                         name: format!("Perception Conflict: {} vs {}", perception_a.name, perception_b.name),
                         description: format!(
                             "High-confidence perceptions '{}' and '{}' have overlapping domains but different conclusions",
@@ -447,7 +622,7 @@ impl ParadoxManager {
                             && environment.values().any(|v| v.contains(conflict_b)))
                     {
                         let paradox = Paradox {
-                            id: Uuid::new_v4(),
+                            id: self.id_source.next_id(),
                             name: "Environmental Conflict".to_string(),
                             description: format!(
                                 "Environment contains conflicting values: '{}' and '{}'",
@@ -498,14 +673,21 @@ impl ParadoxManager {
     /// Resolve a detected paradox
     pub fn resolve_paradox(&mut self, paradox_id: Uuid) -> Result<ParadoxResolution> {
         // First get the paradox immutably to extract needed data
-        let (strategy, conflicting_elements, description) = {
+        let (strategy, conflicting_elements, description, synthesis_template) = {
             let paradox = self.active_paradoxes.get(&paradox_id).ok_or_else(|| {
                 CasialError::ParadoxTimeout(format!("Paradox {} not found", paradox_id))
             })?;
+            let synthesis_template = paradox
+                .metadata
+                .get("detection_rule")
+                .and_then(|rule_id| rule_id.as_str())
+                .and_then(|rule_id| self.detection_rules.iter().find(|r| r.id == rule_id))
+                .and_then(|rule| rule.synthesis_template.clone());
             (
                 paradox.resolution_strategy.clone(),
                 paradox.conflicting_elements.clone(),
                 paradox.description.clone(),
+                synthesis_template,
             )
         };
 
@@ -539,7 +721,10 @@ impl ParadoxManager {
             }
             ParadoxStrategy::Synthesize => {
                 // Attempt to create a higher-order synthesis
-                let synthesis = self.synthesize_paradox_elements(&conflicting_elements);
+                let synthesis = self.synthesize_paradox_elements(
+                    &conflicting_elements,
+                    synthesis_template.as_deref(),
+                );
                 ParadoxResolution {
                     strategy_used: ParadoxStrategy::Synthesize,
                     resolution_time_ms: start_time.elapsed().as_secs_f64() * 1000.0,
@@ -592,7 +777,34 @@ impl ParadoxManager {
     }
 
     /// Synthesize conflicting elements into a higher-order understanding
-    fn synthesize_paradox_elements(&self, conflicting_elements: &[ParadoxElement]) -> String {
+    ///
+    /// A rule's `synthesis_template` replaces the built-in wording, filled from the first
+    /// two elements.
+    fn synthesize_paradox_elements(
+        &self,
+        conflicting_elements: &[ParadoxElement],
+        template: Option<&str>,
+    ) -> String {
+        if let Some(template) = template {
+            let id = |index: usize| {
+                conflicting_elements
+                    .get(index)
+                    .map(|e| e.element_id.clone())
+                    .unwrap_or_default()
+            };
+            let confidence = |index: usize| {
+                conflicting_elements
+                    .get(index)
+                    .map(|e| e.confidence.to_string())
+                    .unwrap_or_default()
+            };
+            return template
+                .replace("{element_a}", &id(0))
+                .replace("{element_b}", &id(1))
+                .replace("{confidence_a}", &confidence(0))
+                .replace("{confidence_b}", &confidence(1));
+        }
+
         // This is a simplified synthesis algorithm
         // In practice, this would use more sophisticated techniques
         match conflicting_elements.len() {
@@ -625,20 +837,14 @@ impl ParadoxManager {
     }
 
     /// Calculate content similarity between two strings
-    fn calculate_content_similarity(&self, content_a: &str, content_b: &str) -> f64 {
-        // Simplified similarity calculation
-        // In practice, use more sophisticated methods like cosine similarity
-        let words_a: std::collections::HashSet<&str> = content_a.split_whitespace().collect();
-        let words_b: std::collections::HashSet<&str> = content_b.split_whitespace().collect();
-
-        let intersection = words_a.intersection(&words_b).count();
-        let union = words_a.union(&words_b).count();
-
-        if union == 0 {
-            0.0
-        } else {
-            intersection as f64 / union as f64
-        }
+    fn calculate_content_similarity(
+        &self,
+        content_a: &str,
+        content_b: &str,
+        vocabulary: &NormalizedVocabulary,
+    ) -> f64 {
+        // Jaccard similarity over normalized whitespace tokens
+        jaccard(&vocabulary.tokens(content_a), &vocabulary.tokens(content_b))
     }
 
     /// Calculate overlap between two perceptions
@@ -646,9 +852,21 @@ impl ParadoxManager {
         &self,
         perception_a: &crate::Perception,
         perception_b: &crate::Perception,
+        vocabulary: &NormalizedVocabulary,
     ) -> f64 {
         // Simplified overlap calculation based on description similarity
-        self.calculate_content_similarity(&perception_a.description, &perception_b.description)
+        self.calculate_content_similarity(
+            &perception_a.description,
+            &perception_b.description,
+            vocabulary,
+        )
+    }
+
+    /// Look up an active or resolved paradox, e.g. by the id of its [`crate::ParadoxReport`]
+    pub fn get_paradox(&self, paradox_id: Uuid) -> Option<&Paradox> {
+        self.active_paradoxes
+            .get(&paradox_id)
+            .or_else(|| self.resolved_paradoxes.get(&paradox_id))
     }
 
     /// Get statistics about paradox detection and resolution
@@ -684,6 +902,8 @@ impl ParadoxManager {
             total_paradoxes: total_count,
             average_resolution_time_ms: avg_resolution_time,
             strategy_distribution,
+            capped_detections: self.capped_detections,
+            skipped_template_scans: self.skipped_template_scans,
         }
     }
 }
@@ -696,6 +916,10 @@ pub struct ParadoxManagerStats {
     pub total_paradoxes: usize,
     pub average_resolution_time_ms: f64,
     pub strategy_distribution: AHashMap<String, usize>,
+    /// Detection passes cut short by `DetectionLimits::max_paradoxes`
+    pub capped_detections: usize,
+    /// Detection passes that skipped template conflicts for having too many templates
+    pub skipped_template_scans: usize,
 }
 
 impl Default for ParadoxManager {
@@ -727,6 +951,46 @@ mod tests {
         assert!(paradox.resolved_at.is_none());
     }
 
+    #[test]
+    fn test_report_from_paradox_carries_severity_and_perceptions() {
+        let (weekly, monthly) = (PerceptionId::new(), PerceptionId::new());
+        let element = |id: &str, confidence: f64, perspective: PerceptionId| ParadoxElement {
+            element_type: ParadoxElementType::Perception,
+            element_id: id.to_string(),
+            confidence,
+            evidence: vec![format!("{} cadence", id)],
+            perspective: Some(perspective),
+        };
+        let paradox = Paradox {
+            id: Uuid::new_v4(),
+            name: "Cadence".to_string(),
+            description: "Weekly and monthly release cadences disagree".to_string(),
+            conflicting_elements: vec![
+                element("weekly", 0.9, weekly),
+                element("monthly", 0.7, monthly),
+            ],
+            severity: ParadoxSeverity::High,
+            resolution_strategy: ParadoxStrategy::Synthesize,
+            created_at: Utc::now(),
+            resolved_at: None,
+            resolution_outcome: None,
+            metadata: AHashMap::new(),
+        };
+
+        let report = crate::ParadoxReport::from(&paradox);
+
+        assert_eq!(report.id, paradox.id);
+        assert_eq!(report.severity, Some(ParadoxSeverity::High));
+        assert_eq!(report.conflicting_perceptions, vec![weekly, monthly]);
+        assert_eq!(report.confidence_impact, 0.9);
+        assert!(report.resolved_at.is_none());
+
+        let mut manager = ParadoxManager::new();
+        manager.active_paradoxes.insert(paradox.id, paradox);
+        let full = manager.get_paradox(report.id).unwrap();
+        assert_eq!(full.conflicting_elements[0].evidence, ["weekly cadence"]);
+    }
+
     #[test]
     fn test_paradox_manager() {
         let manager = ParadoxManager::new();
@@ -735,11 +999,194 @@ mod tests {
         assert!(!manager.detection_rules.is_empty()); // Default rules should be added
     }
 
+    fn perception(name: &str, confidence: f64) -> crate::Perception {
+        crate::Perception {
+            id: PerceptionId::new(),
+            name: name.to_string(),
+            description: "Release cadence for the service".to_string(),
+            confidence,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            metadata: AHashMap::new(),
+        }
+    }
+
+    fn template(id: usize, content: &str) -> crate::CasialTemplate {
+        crate::CasialTemplate {
+            id: format!("t{}", id),
+            name: format!("t{}", id),
+            description: String::new(),
+            categories: Vec::new(),
+            priority: 1,
+            enabled: true,
+            content: content.to_string(),
+            perception_affinity: Vec::new(),
+            paradox_resistance: 0.5,
+            metadata: AHashMap::new(),
+            required_flags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_detection_stops_at_the_paradox_cap() {
+        // Every "never" template conflicts with every plain one: 100 pairs
+        let templates: Vec<_> = (0..20)
+            .map(|i| {
+                let content = if i % 2 == 0 {
+                    "never deploy the release on friday afternoons"
+                } else {
+                    "deploy the release on friday afternoons"
+                };
+                template(i, content)
+            })
+            .collect();
+
+        let mut manager = ParadoxManager::new();
+        let ids = manager
+            .detect_paradoxes(&templates, &[], &AHashMap::new())
+            .unwrap();
+        assert_eq!(ids.len(), DEFAULT_MAX_PARADOXES);
+        assert!(ids.iter().all(|id| {
+            manager.get_paradox(*id).unwrap().metadata["detection_capped"]
+                == serde_json::json!(true)
+        }));
+        assert_eq!(manager.get_statistics().capped_detections, 1);
+
+        manager.set_detection_limits(DetectionLimits {
+            max_paradoxes: 5,
+            max_pairwise_templates: 10,
+        });
+        let ids = manager
+            .detect_paradoxes(&templates, &[], &AHashMap::new())
+            .unwrap();
+        assert!(ids.is_empty());
+        let stats = manager.get_statistics();
+        assert_eq!(stats.skipped_template_scans, 1);
+        assert_eq!(stats.active_paradoxes, DEFAULT_MAX_PARADOXES);
+
+        let ids = manager
+            .detect_paradoxes(&templates[..10], &[], &AHashMap::new())
+            .unwrap();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[test]
+    fn test_seeded_managers_detect_paradoxes_with_identical_ids() {
+        let templates = vec![
+            template(0, "never deploy the release on friday afternoons"),
+            template(1, "deploy the release on friday afternoons"),
+        ];
+        let detect = || {
+            let mut manager =
+                ParadoxManager::new().with_id_source(Arc::new(crate::SeededIdSource::new(3)));
+            manager
+                .detect_paradoxes(&templates, &[], &AHashMap::new())
+                .unwrap()
+        };
+
+        let ids = detect();
+        assert_eq!(ids.len(), 1);
+        assert_eq!(detect(), ids);
+    }
+
+    #[test]
+    fn test_synthesis_uses_rule_template() {
+        let mut manager = ParadoxManager::new();
+        manager.detection_rules.clear();
+        manager.add_detection_rule(ParadoxDetectionRule {
+            id: "localized-perceptions".to_string(),
+            name: "Localized perception conflicts".to_string(),
+            enabled: true,
+            detection_pattern: DetectionPattern::ConflictingPerceptions {
+                confidence_threshold: 0.5,
+                overlap_threshold: 0.5,
+            },
+            severity_threshold: ParadoxSeverity::Medium,
+            auto_resolve: true,
+            preferred_strategy: ParadoxStrategy::Synthesize,
+            synthesis_template: Some(
+                "Síntesis: {element_a} ({confidence_a}) y {element_b} ({confidence_b})".to_string(),
+            ),
+            vocabulary: SimilarityVocabulary::default(),
+        });
+
+        let weekly = perception("weekly", 0.9);
+        let monthly = perception("monthly", 0.8);
+        let ids = manager
+            .detect_paradoxes(&[], &[weekly.clone(), monthly.clone()], &AHashMap::new())
+            .unwrap();
+        assert_eq!(ids.len(), 1);
+
+        let resolution = manager.resolve_paradox(ids[0]).unwrap();
+        assert_eq!(
+            resolution.synthesis_result.unwrap(),
+            format!("Síntesis: {} (0.9) y {} (0.8)", weekly.id.0, monthly.id.0)
+        );
+    }
+
+    #[test]
+    fn test_synthesis_defaults_without_template() {
+        let mut manager = ParadoxManager::new();
+        let ids = manager
+            .detect_paradoxes(
+                &[],
+                &[perception("weekly", 0.9), perception("monthly", 0.85)],
+                &AHashMap::new(),
+            )
+            .unwrap();
+
+        let resolution = manager.resolve_paradox(ids[0]).unwrap();
+        assert!(resolution
+            .synthesis_result
+            .unwrap()
+            .starts_with("SYNTHESIS:"));
+    }
+
     #[test]
     fn test_content_similarity() {
         let manager = ParadoxManager::new();
-        let similarity = manager.calculate_content_similarity("hello world", "hello universe");
+        let similarity = manager.calculate_content_similarity(
+            "hello world",
+            "hello universe",
+            &SimilarityVocabulary::default().normalized(),
+        );
         assert!(similarity > 0.0);
         assert!(similarity < 1.0);
     }
+
+    #[test]
+    fn test_vocabulary_adjusts_similarity() {
+        let manager = ParadoxManager::new();
+        let plain = SimilarityVocabulary::default().normalized();
+        let vocabulary = SimilarityVocabulary {
+            stopwords: ["The", "to", "of", "a"].map(String::from).to_vec(),
+            synonyms: AHashMap::from([
+                ("K8s".to_string(), "kubernetes".to_string()),
+                ("deploy".to_string(), "release".to_string()),
+            ]),
+        }
+        .normalized();
+
+        // Only filler words are shared, so the match is spurious
+        let (a, b) = (
+            "the guide to the setup of a cache",
+            "the notes to the history of a team",
+        );
+        let spurious = manager.calculate_content_similarity(a, b, &plain);
+        let filtered = manager.calculate_content_similarity(a, b, &vocabulary);
+        assert!(spurious >= 0.4, "{}", spurious);
+        assert_eq!(filtered, 0.0);
+
+        // Same guidance in different words
+        let (a, b) = ("deploy services on K8s", "release services on kubernetes");
+        let literal = manager.calculate_content_similarity(a, b, &plain);
+        let normalized = manager.calculate_content_similarity(a, b, &vocabulary);
+        assert!(literal < 0.5, "{}", literal);
+        assert_eq!(normalized, 1.0);
+
+        // Case never matters, with or without a vocabulary
+        let shouted =
+            manager.calculate_content_similarity("Deploy On Friday", "deploy on friday", &plain);
+        assert_eq!(shouted, 1.0);
+    }
 }
@@ -0,0 +1,175 @@
+//! # MCP Transport
+//!
+//! A request/response channel to a remote MCP server, kept free of any async runtime so
+//! the same federation calls run over the server's WebSocket client and over the browser's
+//! `fetch` in the WASM build.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::future::Future;
+use uuid::Uuid;
+
+/// Sends JSON-RPC requests to one remote MCP server
+///
+/// Futures are not required to be `Send`, since browser futures never are.
+pub trait McpTransport {
+    /// Send a JSON-RPC request and resolve to the server's JSON-RPC response
+    fn send(&self, request: Value) -> impl Future<Output = Result<Value>>;
+}
+
+/// The remote server answered with a JSON-RPC error
+#[derive(Debug, thiserror::Error)]
+#[error("{message} (code {code})")]
+pub struct RemoteError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// A JSON-RPC 2.0 request with a fresh string id
+pub fn json_rpc_request(method: &str, params: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": Uuid::new_v4().to_string(),
+        "method": method,
+        "params": params
+    })
+}
+
+/// Send `method` and return the response's `result`, or its error as a [`RemoteError`]
+pub async fn remote_call<T: McpTransport + ?Sized>(
+    transport: &T,
+    method: &str,
+    params: Value,
+) -> Result<Value> {
+    let mut response = transport.send(json_rpc_request(method, params)).await?;
+    if let Some(error) = response.get("error").filter(|error| !error.is_null()) {
+        return Err(RemoteError {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(-32603),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("Unknown error")
+                .to_string(),
+        }
+        .into());
+    }
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .context("Response has neither a result nor an error")
+}
+
+/// The raw `tools/list` entries a remote server advertises
+pub async fn list_remote_tools<T: McpTransport + ?Sized>(transport: &T) -> Result<Vec<Value>> {
+    match remote_call(transport, "tools/list", json!({})).await? {
+        Value::Object(mut result) => match result.remove("tools") {
+            Some(Value::Array(tools)) => Ok(tools),
+            _ => anyhow::bail!("Invalid tools format"),
+        },
+        _ => anyhow::bail!("Invalid tools format"),
+    }
+}
+
+/// Call a tool on a remote server and return its `tools/call` result
+pub async fn call_remote_tool<T: McpTransport + ?Sized>(
+    transport: &T,
+    name: &str,
+    arguments: Value,
+) -> Result<Value> {
+    remote_call(
+        transport,
+        "tools/call",
+        json!({ "name": name, "arguments": arguments }),
+    )
+    .await
+}
+
+/// Merge a remote server's tools into a tool list, tagging each with `source`
+///
+/// Tools from `source` merged earlier are replaced. A remote tool whose name is already
+/// taken by a tool from elsewhere is skipped, so local tools always win. Returns how many
+/// remote tools were added.
+pub fn merge_remote_tools(tools: &mut Vec<Value>, remote: Vec<Value>, source: &str) -> usize {
+    tools.retain(|tool| tool.get("source").and_then(Value::as_str) != Some(source));
+
+    let mut added = 0;
+    for mut tool in remote {
+        let Some(name) = tool.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        if tools.iter().any(|existing| existing["name"] == name) {
+            continue;
+        }
+        tool["source"] = json!(source);
+        tools.push(tool);
+        added += 1;
+    }
+    added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        pin::pin,
+        sync::Arc,
+        task::{Context, Poll, Wake, Waker},
+    };
+
+    /// Answers `tools/list` with a fixed catalog
+    struct CatalogTransport(Value);
+
+    impl McpTransport for CatalogTransport {
+        async fn send(&self, request: Value) -> Result<Value> {
+            Ok(match request["method"].as_str() {
+                Some("tools/list") => {
+                    json!({ "jsonrpc": "2.0", "id": request["id"], "result": { "tools": self.0 } })
+                }
+                _ => json!({
+                    "jsonrpc": "2.0", "id": request["id"],
+                    "error": { "code": -32601, "message": "Method not found" }
+                }),
+            })
+        }
+    }
+
+    /// Drive a future that never waits on anything external
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct NoopWake;
+        impl Wake for NoopWake {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn remote_tools_merge_behind_local_ones() {
+        let transport = CatalogTransport(json!([
+            { "name": "web_search", "description": "remote search" },
+            { "name": "lookup", "description": "remote lookup" }
+        ]));
+        let remote = block_on(list_remote_tools(&transport)).unwrap();
+        assert_eq!(remote.len(), 2);
+
+        let mut tools = vec![json!({ "name": "web_search", "description": "local search" })];
+        assert_eq!(merge_remote_tools(&mut tools, remote.clone(), "remote"), 1);
+        // Merging the same source again replaces its tools instead of duplicating them
+        assert_eq!(merge_remote_tools(&mut tools, remote, "remote"), 1);
+
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0]["description"], "local search");
+        assert_eq!(tools[1]["name"], "lookup");
+        assert_eq!(tools[1]["source"], "remote");
+
+        let error = block_on(call_remote_tool(&transport, "lookup", json!({}))).unwrap_err();
+        let remote_error = error.downcast_ref::<RemoteError>().unwrap();
+        assert_eq!(remote_error.code, -32601);
+    }
+}
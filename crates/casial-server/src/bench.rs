@@ -0,0 +1,222 @@
+//! # Coordination Benchmark
+//!
+//! Drives synthetic coordination requests through a [`CasialEngine`] from several threads
+//! at once and reports throughput and latency percentiles, to catch regressions in the
+//! engine's hot path and its lock contention.
+
+use anyhow::{ensure, Result};
+use casial_core::{CasialEngine, CoordinationRequest};
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Sub-buckets per power of two; bucket bounds are within 12.5% of any recorded value
+const SUB_BUCKETS: u64 = 8;
+const SUB_BUCKET_BITS: u32 = SUB_BUCKETS.trailing_zeros();
+
+/// Log-linear histogram of latencies in nanoseconds
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    counts: Vec<u64>,
+    total: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; Self::bucket(u64::MAX) + 1],
+            total: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn bucket(nanos: u64) -> usize {
+        if nanos < SUB_BUCKETS {
+            return nanos as usize;
+        }
+        let exponent = 63 - nanos.leading_zeros();
+        let sub = (nanos >> (exponent - SUB_BUCKET_BITS)) & (SUB_BUCKETS - 1);
+        (SUB_BUCKETS + (exponent - SUB_BUCKET_BITS) as u64 * SUB_BUCKETS + sub) as usize
+    }
+
+    /// Largest value that falls in `bucket`
+    fn upper_bound(bucket: usize) -> u64 {
+        let bucket = bucket as u64;
+        if bucket < SUB_BUCKETS {
+            return bucket;
+        }
+        let exponent = (bucket - SUB_BUCKETS) / SUB_BUCKETS + SUB_BUCKET_BITS as u64;
+        let sub = bucket % SUB_BUCKETS;
+        let width = 1u64 << (exponent - SUB_BUCKET_BITS as u64);
+        ((SUB_BUCKETS + sub) << (exponent - SUB_BUCKET_BITS as u64)).saturating_add(width - 1)
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[Self::bucket(nanos)] += 1;
+        self.total += 1;
+    }
+
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.total += other.total;
+    }
+
+    /// Latency at or below which `percentile` percent of samples fall
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.total == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((percentile / 100.0) * self.total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_nanos(Self::upper_bound(bucket));
+            }
+        }
+        Duration::from_nanos(u64::MAX)
+    }
+}
+
+/// Outcome of a benchmark run
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub requests: usize,
+    pub concurrency: usize,
+    pub failures: usize,
+    pub elapsed: Duration,
+    /// Completed requests per second
+    pub throughput: f64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} requests ({} failed) at concurrency {} in {:.3?}",
+            self.requests, self.failures, self.concurrency, self.elapsed
+        )?;
+        writeln!(f, "throughput: {:.1} req/s", self.throughput)?;
+        write!(
+            f,
+            "latency: p50 {:.3?}, p95 {:.3?}, p99 {:.3?}",
+            self.p50, self.p95, self.p99
+        )
+    }
+}
+
+/// Coordinate `requests` synthetic `tool` calls across `concurrency` threads
+pub fn run_bench(
+    engine: &CasialEngine,
+    tool: &str,
+    requests: usize,
+    concurrency: usize,
+) -> Result<BenchReport> {
+    ensure!(requests > 0, "--requests must be at least 1");
+    ensure!(concurrency > 0, "--concurrency must be at least 1");
+
+    let next = AtomicUsize::new(0);
+    let failures = AtomicUsize::new(0);
+    let started = Instant::now();
+    let histograms: Vec<LatencyHistogram> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..concurrency.min(requests))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut histogram = LatencyHistogram::default();
+                    loop {
+                        let n = next.fetch_add(1, Ordering::Relaxed);
+                        if n >= requests {
+                            break;
+                        }
+                        let request = synthetic_request(tool, n);
+                        let call_started = Instant::now();
+                        if engine.coordinate(request).is_err() {
+                            failures.fetch_add(1, Ordering::Relaxed);
+                        }
+                        histogram.record(call_started.elapsed());
+                    }
+                    histogram
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().expect("bench worker panicked"))
+            .collect()
+    });
+    let elapsed = started.elapsed();
+
+    let mut latencies = LatencyHistogram::default();
+    for histogram in &histograms {
+        latencies.merge(histogram);
+    }
+
+    Ok(BenchReport {
+        requests,
+        concurrency,
+        failures: failures.into_inner(),
+        elapsed,
+        throughput: requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        p50: latencies.percentile(50.0),
+        p95: latencies.percentile(95.0),
+        p99: latencies.percentile(99.0),
+    })
+}
+
+fn synthetic_request(tool: &str, n: usize) -> CoordinationRequest {
+    CoordinationRequest::new(
+        tool,
+        serde_json::json!({ "query": format!("benchmark query {}", n) }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self, mission_json, rule_json, template_json};
+
+    #[test]
+    fn histogram_bounds_cover_their_values() {
+        for nanos in [0, 7, 8, 9, 100, 1_000, 123_456, 10_000_000_000] {
+            let bucket = LatencyHistogram::bucket(nanos);
+            assert!(LatencyHistogram::upper_bound(bucket) >= nanos, "{}", nanos);
+            if bucket > 0 {
+                assert!(
+                    LatencyHistogram::upper_bound(bucket - 1) < nanos,
+                    "{}",
+                    nanos
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn small_bench_reports_throughput_and_percentiles() {
+        let mission = test_fixtures::mission(mission_json(
+            "bench",
+            vec![template_json("guide", 1, "Prefer primary sources.")],
+            vec![rule_json("search-guide", &["search"], &["guide"])],
+        ));
+        let engine = CasialEngine::new();
+        engine.load_mission(mission).unwrap();
+
+        let report = run_bench(&engine, "web_search", 64, 4).unwrap();
+
+        assert_eq!(report.requests, 64);
+        assert_eq!(report.failures, 0);
+        assert!(report.throughput > 0.0);
+        assert!(report.p50 > Duration::ZERO);
+        assert!(report.p95 >= report.p50);
+        assert!(report.p99 >= report.p95);
+    }
+}
@@ -1,9 +1,11 @@
 //! # MCP Downstream Client
 //!
-//! WebSocket JSON-RPC client for connecting to downstream MCP servers.
+//! WebSocket JSON-RPC client for connecting to downstream MCP servers. It is the server's
+//! [`McpTransport`], so the runtime-neutral federation calls in `casial_core` run over it.
 
 use crate::{config::DownstreamMcpServer, mcp};
 use anyhow::{Context, Result};
+use casial_core::McpTransport;
 use chrono::{DateTime, Utc};
 use futures::{SinkExt, StreamExt};
 use serde_json::Value;
@@ -40,6 +42,11 @@ impl Default for ConnectionState {
     }
 }
 
+/// A request got no response within the server's `timeout_ms`
+#[derive(Debug, thiserror::Error)]
+#[error("Request timeout")]
+pub struct RequestTimeout;
+
 /// Pending request tracking
 struct PendingRequest {
     sender: oneshot::Sender<Result<mcp::JsonRpcResponse>>,
@@ -114,6 +121,10 @@ impl McpClient {
             if self.is_connected().await {
                 return Ok(());
             }
+            // The connection task exits early when the server refuses us
+            if self.handle.as_ref().is_some_and(|h| h.is_finished()) {
+                break;
+            }
             tokio::time::sleep(Duration::from_millis(100)).await;
             attempts += 1;
         }
@@ -155,18 +166,6 @@ impl McpClient {
         self.send_request(request).await
     }
 
-    /// List available tools from downstream server
-    pub async fn list_tools(&self) -> Result<mcp::JsonRpcResponse> {
-        let request = mcp::JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            id: Value::String(Uuid::new_v4().to_string()),
-            method: "tools/list".to_string(),
-            params: Value::Object(serde_json::Map::new()),
-        };
-
-        self.send_request(request).await
-    }
-
     /// Call a tool on downstream server
     pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<mcp::JsonRpcResponse> {
         let request = mcp::JsonRpcRequest {
@@ -376,7 +375,7 @@ impl McpClient {
 
                     for id in timed_out {
                         if let Some(pending) = pending_requests.remove(&id) {
-                            let _ = pending.sender.send(Err(anyhow::anyhow!("Request timeout")));
+                            let _ = pending.sender.send(Err(RequestTimeout.into()));
                             let mut health = health.write().await;
                             health.error_count += 1;
                         }
@@ -432,6 +431,14 @@ impl Drop for McpClient {
     }
 }
 
+impl McpTransport for McpClient {
+    async fn send(&self, request: Value) -> Result<Value> {
+        let request: mcp::JsonRpcRequest =
+            serde_json::from_value(request).context("Invalid JSON-RPC request")?;
+        Ok(serde_json::to_value(self.send_request(request).await?)?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,9 +2,19 @@
 //!
 //! Configuration management for the Casial server.
 
+use crate::mission::TemplateMergePolicy;
 use anyhow::Result;
+use casial_core::FileSignalPolicy;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::time::Interval;
+
+/// Placeholder for secrets in printed configuration
+const REDACTED: &str = "***";
 
 /// Server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +24,43 @@ pub struct ServerConfig {
     pub metrics: MetricsSettings,
     pub logging: LoggingSettings,
     pub federation: FederationSettings,
+    #[serde(default)]
+    pub missions: MissionSettings,
+    #[serde(default)]
+    pub sessions: SessionSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSettings {
+    #[serde(default)]
+    pub backend: SessionBackend,
+    /// Connection URL for the `redis` backend, e.g. `redis://127.0.0.1:6379`
+    #[serde(default)]
+    pub redis_url: Option<String>,
+    /// Seconds without a request before an HTTP session is dropped
+    #[serde(default = "default_session_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+}
+
+/// Where HTTP MCP sessions are kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionBackend {
+    /// Process-local; sessions are lost on restart and not shared between instances
+    #[default]
+    Memory,
+    /// Shared through Redis (requires the `redis` feature)
+    Redis,
+}
+
+impl Default for SessionSettings {
+    fn default() -> Self {
+        Self {
+            backend: SessionBackend::default(),
+            redis_url: None,
+            idle_timeout_secs: default_session_idle_timeout_secs(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +68,173 @@ pub struct ServerSettings {
     pub port: u16,
     pub max_connections: usize,
     pub timeout_seconds: u64,
+    /// MCP protocol revisions accepted during `initialize`
+    #[serde(default = "default_supported_protocol_versions")]
+    pub supported_protocol_versions: Vec<String>,
+    /// Seconds between WebSocket heartbeat pings
+    #[serde(default = "default_ws_heartbeat_secs")]
+    pub ws_heartbeat_secs: u64,
+    /// HTTP tool calls allowed to run at once before new ones get 503
+    #[serde(default = "default_max_concurrent_tool_calls")]
+    pub max_concurrent_tool_calls: usize,
+    /// Keep-alives sent on idle HTTP/SSE streams
+    #[serde(default)]
+    pub sse_keep_alive: SseKeepAliveSettings,
+    /// Extra response headers on SSE streams, e.g. to stop reverse proxies buffering them
+    #[serde(default = "default_sse_headers")]
+    pub sse_headers: BTreeMap<String, String>,
+    /// Events buffered per SSE stream for clients that aren't draining
+    #[serde(default)]
+    pub sse_buffer: SseBufferSettings,
+    /// What sessions using the public demo API key may call
+    #[serde(default)]
+    pub demo_access: DemoAccessSettings,
+    /// Seconds a tool call result is replayed for a repeated idempotency key
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+    /// Chunk or summarize tool results too large to return in one piece
+    #[serde(default)]
+    pub large_results: Option<LargeResultSettings>,
+    /// Let mission rules check files under the client-supplied `project_path`
+    #[serde(default)]
+    pub allow_file_signals: bool,
+    /// Directory file signals are confined to when allowed; defaults to the working directory
+    #[serde(default)]
+    pub file_signal_root: Option<PathBuf>,
+    /// Coordinations kept in history for debugging and replay; older ones are dropped
+    #[serde(default = "default_max_history_entries")]
+    pub max_history_entries: usize,
+}
+
+impl ServerSettings {
+    /// File signal policy for the coordination engine; signals are off unless allowed
+    pub fn file_signal_policy(&self) -> FileSignalPolicy {
+        if !self.allow_file_signals {
+            return FileSignalPolicy::Disabled;
+        }
+        FileSignalPolicy::Sandboxed(
+            self.file_signal_root
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(".")),
+        )
+    }
+}
+
+/// Handling for tool results longer than `max_chars`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeResultSettings {
+    /// Results longer than this many characters are reshaped; also the chunk size
+    pub max_chars: usize,
+    #[serde(default)]
+    pub strategy: LargeResultStrategy,
+    /// Tool called with `{"content", "max_chars"}` by the `summarize` strategy
+    #[serde(default)]
+    pub summarizer_tool: Option<String>,
+}
+
+/// How an oversized tool result is returned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LargeResultStrategy {
+    /// Split the result across several text content sections of at most `max_chars` each
+    #[default]
+    Chunk,
+    /// Replace the result with the summarizer tool's output, chunking if that fails
+    Summarize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SseKeepAliveSettings {
+    /// Seconds between keep-alives on an idle stream
+    #[serde(default = "default_sse_keep_alive_secs")]
+    pub interval_secs: u64,
+    /// Comment text, or the event's data when sent as an event
+    #[serde(default = "default_sse_keep_alive_text")]
+    pub text: String,
+    #[serde(default)]
+    pub mode: SseKeepAliveMode,
+}
+
+/// How SSE keep-alives are framed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SseKeepAliveMode {
+    /// `: <text>` comment lines, ignored by SSE clients
+    #[default]
+    Comment,
+    /// A `keep-alive` event carrying the text as data, for proxies that strip comments
+    Event,
+}
+
+/// Methods and tools open to sessions authenticated with the public demo API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DemoAccessSettings {
+    /// MCP methods the demo key may call; `tools/call` is further limited to `allowed_tools`
+    #[serde(default = "default_demo_allowed_methods")]
+    pub allowed_methods: Vec<String>,
+    /// Tools the demo key may call; federated and admin tools are left out by default
+    #[serde(default = "default_demo_allowed_tools")]
+    pub allowed_tools: Vec<String>,
+}
+
+impl DemoAccessSettings {
+    pub fn permits_method(&self, method: &str) -> bool {
+        self.allowed_methods.iter().any(|allowed| allowed == method)
+    }
+
+    pub fn permits_tool(&self, tool_name: &str) -> bool {
+        self.allowed_tools
+            .iter()
+            .any(|allowed| allowed == tool_name)
+    }
+}
+
+impl Default for DemoAccessSettings {
+    fn default() -> Self {
+        Self {
+            allowed_methods: default_demo_allowed_methods(),
+            allowed_tools: default_demo_allowed_tools(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SseBufferSettings {
+    /// Events held for a session before the overflow policy applies
+    #[serde(default = "default_sse_buffer_capacity")]
+    pub capacity: usize,
+    #[serde(default)]
+    pub overflow: SseOverflowPolicy,
+}
+
+/// What happens to events pushed to a full SSE buffer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SseOverflowPolicy {
+    /// Discard the oldest buffered event to make room
+    #[default]
+    DropOldest,
+    /// Refuse the new event and report the error to the producer
+    Reject,
+}
+
+impl Default for SseBufferSettings {
+    fn default() -> Self {
+        Self {
+            capacity: default_sse_buffer_capacity(),
+            overflow: SseOverflowPolicy::default(),
+        }
+    }
+}
+
+impl Default for SseKeepAliveSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_sse_keep_alive_secs(),
+            text: default_sse_keep_alive_text(),
+            mode: SseKeepAliveMode::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,13 +243,55 @@ pub struct ConsciousnessSettings {
     pub perception_lock_timeout: u64,
     pub paradox_resolution_timeout: u64,
     pub substrate_integration: bool,
+    /// Keep engine-injected fields (e.g. `casial_context`) in tool results for debugging
+    #[serde(default)]
+    pub expose_injected_fields: bool,
+    /// Whether the pitfall shim or mission coordination edits tool arguments first
+    #[serde(default)]
+    pub injection_order: InjectionOrder,
+    /// Perceptions one WebSocket session may register through `casial/perception/add` or
+    /// `import`; they are unregistered when the session closes
+    #[serde(default = "default_max_session_perceptions")]
+    pub max_session_perceptions: usize,
+}
+
+/// Order tool arguments pass through the pitfall shim and mission coordination,
+/// the same on every transport
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionOrder {
+    /// Coordinate first; the shim adds its context to the coordinated arguments
+    #[default]
+    CoordinationBeforeShim,
+    /// Shim first, so coordination sees (and may target) the shim context
+    ShimBeforeCoordination,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsSettings {
     pub enabled: bool,
+    /// Seconds between metrics collection passes
+    #[serde(alias = "interval_secs", default = "default_metrics_interval_secs")]
     pub collection_interval: u64,
     pub retention_hours: u64,
+    /// Require `MOP_ADMIN_TOKEN` before serving `/metrics` (open by default)
+    #[serde(default)]
+    pub require_auth: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MissionSettings {
+    /// How project `templates/` files are merged when their id matches a mission template
+    #[serde(default)]
+    pub template_merge_policy: TemplateMergePolicy,
+    /// Session `mission` names mapped to loaded mission ids, e.g. `exa-orchestration`
+    /// to `exa-mcp-orchestration`
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Refuse tool calls while no mission is loaded instead of passing them through
+    /// uncoordinated, so a missing mission mount fails loudly
+    #[serde(default)]
+    pub require_mission: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +311,9 @@ pub struct FederationSettings {
     pub max_retries: u32,
     #[serde(default = "default_tool_cache_ttl_seconds")]
     pub tool_cache_ttl_seconds: u64,
+    /// File the federated tool catalog is persisted to, so restarts can serve tools before resyncing
+    #[serde(default)]
+    pub tool_cache_path: Option<PathBuf>,
     #[serde(default = "default_circuit_breaker_threshold")]
     pub circuit_breaker_threshold: u32,
     #[serde(default = "default_circuit_breaker_reset_seconds")]
@@ -63,6 +322,42 @@ pub struct FederationSettings {
     pub backoff_initial_ms: u64,
     #[serde(default = "default_backoff_max_ms")]
     pub backoff_max_ms: u64,
+    /// Extra connection rounds at startup for downstreams that are still booting
+    #[serde(default = "default_startup_connect_retries")]
+    pub startup_connect_retries: u32,
+    /// Hold readiness until at least one downstream server connects
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub sync_strategy: SyncStrategy,
+    /// Cache results of idempotent federated tools; off unless configured
+    #[serde(default)]
+    pub result_cache: Option<ResultCacheSettings>,
+    /// How long `reconfigure` waits for in-flight calls to a removed or updated server
+    #[serde(default = "default_drain_timeout_ms")]
+    pub drain_timeout_ms: u64,
+}
+
+/// Result caching for federated tool calls, keyed by tool name and arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultCacheSettings {
+    /// Tools whose results may be reused (must be idempotent reads)
+    pub tools: Vec<String>,
+    #[serde(default = "default_result_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+    #[serde(default = "default_result_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+/// How the periodic catalog sync visits downstream servers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStrategy {
+    /// Sync every server concurrently on each tick
+    #[default]
+    Parallel,
+    /// Sync one server per tick, round-robin, to spread load on shared infrastructure
+    Staggered,
 }
 
 impl Default for FederationSettings {
@@ -75,15 +370,21 @@ impl Default for FederationSettings {
             connection_timeout_ms: 10_000,
             max_retries: 3,
             tool_cache_ttl_seconds: default_tool_cache_ttl_seconds(),
+            tool_cache_path: None,
             circuit_breaker_threshold: default_circuit_breaker_threshold(),
             circuit_breaker_reset_seconds: default_circuit_breaker_reset_seconds(),
             backoff_initial_ms: default_backoff_initial_ms(),
             backoff_max_ms: default_backoff_max_ms(),
+            startup_connect_retries: default_startup_connect_retries(),
+            required: false,
+            sync_strategy: SyncStrategy::default(),
+            result_cache: None,
+            drain_timeout_ms: default_drain_timeout_ms(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DownstreamMcpServer {
     pub id: String,
     pub name: String,
@@ -95,7 +396,7 @@ pub struct DownstreamMcpServer {
     pub auth: Option<McpAuth>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct McpAuth {
     pub auth_type: String, // "header" | "query" | "websocket-subprotocol"
     pub token: Option<String>,
@@ -110,17 +411,33 @@ impl Default for ServerConfig {
                 port: 8000,
                 max_connections: 1000,
                 timeout_seconds: 300,
+                supported_protocol_versions: default_supported_protocol_versions(),
+                ws_heartbeat_secs: default_ws_heartbeat_secs(),
+                max_concurrent_tool_calls: default_max_concurrent_tool_calls(),
+                sse_keep_alive: SseKeepAliveSettings::default(),
+                sse_headers: default_sse_headers(),
+                sse_buffer: SseBufferSettings::default(),
+                demo_access: DemoAccessSettings::default(),
+                idempotency_ttl_secs: default_idempotency_ttl_secs(),
+                large_results: None,
+                allow_file_signals: false,
+                file_signal_root: None,
+                max_history_entries: default_max_history_entries(),
             },
             consciousness: ConsciousnessSettings {
                 enabled: true,
                 perception_lock_timeout: 30,
                 paradox_resolution_timeout: 60,
                 substrate_integration: true,
+                expose_injected_fields: false,
+                injection_order: InjectionOrder::default(),
+                max_session_perceptions: default_max_session_perceptions(),
             },
             metrics: MetricsSettings {
                 enabled: true,
-                collection_interval: 30,
+                collection_interval: default_metrics_interval_secs(),
                 retention_hours: 24,
+                require_auth: false,
             },
             logging: LoggingSettings {
                 level: "info".to_string(),
@@ -128,10 +445,91 @@ impl Default for ServerConfig {
                 file_output: None,
             },
             federation: FederationSettings::default(),
+            missions: MissionSettings::default(),
+            sessions: SessionSettings::default(),
         }
     }
 }
 
+fn default_supported_protocol_versions() -> Vec<String> {
+    vec!["2024-11-05".to_string()]
+}
+
+fn default_ws_heartbeat_secs() -> u64 {
+    30
+}
+
+fn default_sse_keep_alive_secs() -> u64 {
+    30
+}
+
+fn default_sse_keep_alive_text() -> String {
+    "keep-alive".to_string()
+}
+
+fn default_demo_allowed_methods() -> Vec<String> {
+    [
+        "initialize",
+        "notifications/initialized",
+        "ping",
+        "tools/list",
+        "tools/call",
+        "prompts/list",
+        "prompts/get",
+        "resources/list",
+        "resources/read",
+        "resources/templates/list",
+        "completion/complete",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_demo_allowed_tools() -> Vec<String> {
+    [
+        "exa_search_example",
+        "exa_research_example",
+        "casial_validate_mission",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_sse_buffer_capacity() -> usize {
+    100
+}
+
+fn default_sse_headers() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("Cache-Control".to_string(), "no-cache".to_string()),
+        ("X-Accel-Buffering".to_string(), "no".to_string()),
+    ])
+}
+
+fn default_session_idle_timeout_secs() -> u64 {
+    3600
+}
+
+fn default_idempotency_ttl_secs() -> u64 {
+    300
+}
+
+fn default_max_session_perceptions() -> usize {
+    64
+}
+
+fn default_max_history_entries() -> usize {
+    casial_core::DEFAULT_MAX_HISTORY_ENTRIES
+}
+
+fn default_max_concurrent_tool_calls() -> usize {
+    64
+}
+
+fn default_metrics_interval_secs() -> u64 {
+    30
+}
+
 fn default_tool_cache_ttl_seconds() -> u64 {
     300
 }
@@ -152,10 +550,102 @@ fn default_backoff_max_ms() -> u64 {
     5_000
 }
 
+fn default_startup_connect_retries() -> u32 {
+    3
+}
+
+fn default_result_cache_ttl_seconds() -> u64 {
+    60
+}
+
+fn default_result_cache_max_entries() -> usize {
+    256
+}
+
+fn default_drain_timeout_ms() -> u64 {
+    10_000
+}
+
 impl ServerConfig {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = std::fs::read_to_string(&path)?;
         let config: ServerConfig = serde_yaml::from_str(&content)?;
         Ok(config)
     }
+
+    /// Copy with downstream credentials masked, for printing
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        for server in &mut config.federation.downstream_servers {
+            if let Some(auth) = &mut server.auth {
+                for secret in [&mut auth.token, &mut auth.password] {
+                    if secret.is_some() {
+                        *secret = Some(REDACTED.to_string());
+                    }
+                }
+            }
+        }
+        // Redis URLs may embed a password
+        if let Some(url) = &mut config.sessions.redis_url {
+            if let Ok(mut parsed) = url::Url::parse(url) {
+                if parsed.password().is_some() && parsed.set_password(Some(REDACTED)).is_ok() {
+                    *url = parsed.to_string();
+                }
+            }
+        }
+        config
+    }
+
+    /// Timer for the background metrics collection task
+    pub fn metrics_timer(&self) -> Interval {
+        tokio::time::interval(Duration::from_secs(self.metrics.collection_interval.max(1)))
+    }
+
+    /// Timer for WebSocket heartbeat pings
+    pub fn ws_heartbeat_timer(&self) -> Interval {
+        tokio::time::interval(Duration::from_secs(self.server.ws_heartbeat_secs.max(1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timers_use_configured_intervals() {
+        let mut config = ServerConfig::default();
+        assert_eq!(config.metrics_timer().period(), Duration::from_secs(30));
+        assert_eq!(
+            config.ws_heartbeat_timer().period(),
+            Duration::from_secs(30)
+        );
+
+        config.metrics.collection_interval = 120;
+        config.server.ws_heartbeat_secs = 5;
+        assert_eq!(config.metrics_timer().period(), Duration::from_secs(120));
+        assert_eq!(config.ws_heartbeat_timer().period(), Duration::from_secs(5));
+
+        // A zero interval would panic in tokio, so it is clamped to one second
+        config.server.ws_heartbeat_secs = 0;
+        assert_eq!(config.ws_heartbeat_timer().period(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_intervals_parse_from_yaml() {
+        let mut value = serde_yaml::to_value(ServerConfig::default()).unwrap();
+        value["metrics"]
+            .as_mapping_mut()
+            .unwrap()
+            .remove("collection_interval");
+        value["metrics"]["interval_secs"] = serde_yaml::Value::from(10);
+        value["server"]["ws_heartbeat_secs"] = serde_yaml::Value::from(15);
+
+        let config: ServerConfig = serde_yaml::from_value(value).unwrap();
+
+        assert_eq!(config.metrics_timer().period(), Duration::from_secs(10));
+        assert_eq!(
+            config.ws_heartbeat_timer().period(),
+            Duration::from_secs(15)
+        );
+    }
 }
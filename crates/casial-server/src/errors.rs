@@ -0,0 +1,278 @@
+//! # Error Catalog
+//!
+//! Stable codes and messages for the errors clients see. `error.message` stays in English
+//! so existing clients keep matching on it; `error.data` carries the catalog `code` and a
+//! `message` in the language negotiated from the request's `Accept-Language` header,
+//! falling back to English where there is no translation.
+
+use serde_json::{json, Value};
+
+use crate::mcp::{create_error_response, JsonRpcResponse};
+
+/// Language used when the client asks for none we have
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Languages with a full set of translations
+const LANGUAGES: &[&str] = &["en", "es", "fr"];
+
+/// A user-facing error with a stable code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    InvalidApiKey,
+    MissingApiKey,
+    InvalidSession,
+    SessionRequired,
+    MethodNotFound,
+    InvalidParams,
+    UnknownTool,
+    UnknownPrompt,
+    UnknownResource,
+    ServerBusy,
+    DemoKeyDenied,
+    NoMissionLoaded,
+    CircuitOpen,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 13] = [
+        ErrorCode::InvalidApiKey,
+        ErrorCode::MissingApiKey,
+        ErrorCode::InvalidSession,
+        ErrorCode::SessionRequired,
+        ErrorCode::MethodNotFound,
+        ErrorCode::InvalidParams,
+        ErrorCode::UnknownTool,
+        ErrorCode::UnknownPrompt,
+        ErrorCode::UnknownResource,
+        ErrorCode::ServerBusy,
+        ErrorCode::DemoKeyDenied,
+        ErrorCode::NoMissionLoaded,
+        ErrorCode::CircuitOpen,
+    ];
+
+    /// Stable identifier reported as `error.data.code`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidApiKey => "invalid_api_key",
+            ErrorCode::MissingApiKey => "missing_api_key",
+            ErrorCode::InvalidSession => "invalid_session",
+            ErrorCode::SessionRequired => "session_required",
+            ErrorCode::MethodNotFound => "method_not_found",
+            ErrorCode::InvalidParams => "invalid_params",
+            ErrorCode::UnknownTool => "unknown_tool",
+            ErrorCode::UnknownPrompt => "unknown_prompt",
+            ErrorCode::UnknownResource => "unknown_resource",
+            ErrorCode::ServerBusy => "server_busy",
+            ErrorCode::DemoKeyDenied => "demo_key",
+            ErrorCode::NoMissionLoaded => "no_mission_loaded",
+            ErrorCode::CircuitOpen => "circuit_open",
+        }
+    }
+
+    fn from_str(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.as_str() == code)
+    }
+
+    /// JSON-RPC `error.code`
+    pub fn json_rpc_code(self) -> i64 {
+        match self {
+            ErrorCode::MethodNotFound => -32601,
+            ErrorCode::InvalidParams
+            | ErrorCode::UnknownTool
+            | ErrorCode::UnknownPrompt
+            | ErrorCode::UnknownResource => -32602,
+            ErrorCode::InvalidApiKey
+            | ErrorCode::MissingApiKey
+            | ErrorCode::InvalidSession
+            | ErrorCode::SessionRequired
+            | ErrorCode::ServerBusy
+            | ErrorCode::DemoKeyDenied
+            | ErrorCode::NoMissionLoaded => -32000,
+            ErrorCode::CircuitOpen => -32005,
+        }
+    }
+
+    /// Message in `language`, or English when there is no translation
+    pub fn message(self, language: &str) -> &'static str {
+        match language {
+            "es" => self.spanish(),
+            "fr" => self.french(),
+            _ => self.english(),
+        }
+    }
+
+    fn english(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidApiKey => "Invalid API key",
+            ErrorCode::MissingApiKey => "Missing API key",
+            ErrorCode::InvalidSession => "Invalid session ID",
+            ErrorCode::SessionRequired => "Session ID required",
+            ErrorCode::MethodNotFound => "Method not found",
+            ErrorCode::InvalidParams => "Invalid params",
+            ErrorCode::UnknownTool => "Unknown tool",
+            ErrorCode::UnknownPrompt => "Unknown prompt",
+            ErrorCode::UnknownResource => "Unknown resource",
+            ErrorCode::ServerBusy => "Server busy - too many concurrent tool calls",
+            ErrorCode::DemoKeyDenied => {
+                "Not available with the public demo API key; set MOP_API_KEY and use it instead"
+            }
+            ErrorCode::NoMissionLoaded => {
+                "No mission is loaded and missions.require_mission is set; load a mission first"
+            }
+            ErrorCode::CircuitOpen => {
+                "Downstream server is failing; calls to it resume after retry_after_ms"
+            }
+        }
+    }
+
+    fn spanish(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidApiKey => "Clave de API no válida",
+            ErrorCode::MissingApiKey => "Falta la clave de API",
+            ErrorCode::InvalidSession => "ID de sesión no válido",
+            ErrorCode::SessionRequired => "Se requiere un ID de sesión",
+            ErrorCode::MethodNotFound => "Método no encontrado",
+            ErrorCode::InvalidParams => "Parámetros no válidos",
+            ErrorCode::UnknownTool => "Herramienta desconocida",
+            ErrorCode::UnknownPrompt => "Prompt desconocido",
+            ErrorCode::UnknownResource => "Recurso desconocido",
+            ErrorCode::ServerBusy => {
+                "Servidor ocupado: demasiadas llamadas a herramientas simultáneas"
+            }
+            ErrorCode::DemoKeyDenied => {
+                "No disponible con la clave de API de demostración; configure MOP_API_KEY y úsela"
+            }
+            ErrorCode::NoMissionLoaded => {
+                "No hay ninguna misión cargada y missions.require_mission está activado; cargue una misión primero"
+            }
+            ErrorCode::CircuitOpen => {
+                "El servidor remoto está fallando; las llamadas se reanudan tras retry_after_ms"
+            }
+        }
+    }
+
+    fn french(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidApiKey => "Clé API invalide",
+            ErrorCode::MissingApiKey => "Clé API manquante",
+            ErrorCode::InvalidSession => "ID de session invalide",
+            ErrorCode::SessionRequired => "ID de session requis",
+            ErrorCode::MethodNotFound => "Méthode introuvable",
+            ErrorCode::InvalidParams => "Paramètres invalides",
+            ErrorCode::UnknownTool => "Outil inconnu",
+            ErrorCode::UnknownPrompt => "Prompt inconnu",
+            ErrorCode::UnknownResource => "Ressource inconnue",
+            ErrorCode::ServerBusy => "Serveur occupé : trop d'appels d'outils simultanés",
+            ErrorCode::DemoKeyDenied => {
+                "Indisponible avec la clé API de démonstration ; définissez MOP_API_KEY et utilisez-la"
+            }
+            ErrorCode::NoMissionLoaded => {
+                "Aucune mission n'est chargée et missions.require_mission est activé ; chargez d'abord une mission"
+            }
+            ErrorCode::CircuitOpen => {
+                "Le serveur distant est en échec ; les appels reprennent après retry_after_ms"
+            }
+        }
+    }
+
+    /// JSON-RPC error response; fields in `data` are kept next to the catalog ones
+    pub fn response(self, id: Value, data: Option<Value>) -> JsonRpcResponse {
+        let mut data = match data {
+            Some(Value::Object(fields)) => Value::Object(fields),
+            Some(other) => json!({ "detail": other }),
+            None => json!({}),
+        };
+        data["code"] = json!(self.as_str());
+        data["message"] = json!(self.english());
+        create_error_response(id, self.json_rpc_code(), self.english(), Some(data))
+    }
+
+    /// Body for errors raised before a JSON-RPC request is read, like a rejected API key
+    pub fn http_body(self, language: &str) -> Value {
+        json!({
+            "error": self.english(),
+            "code": self.as_str(),
+            "message": self.message(language)
+        })
+    }
+}
+
+/// Best supported language for an `Accept-Language` header
+///
+/// Ranges are tried in order of their `q` weight; region subtags are ignored, so `es-MX`
+/// gets Spanish.
+pub fn negotiate_language(accept_language: Option<&str>) -> &'static str {
+    let Some(header) = accept_language else {
+        return DEFAULT_LANGUAGE;
+    };
+
+    let mut ranges: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let weight = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && weight > 0.0).then_some((tag, weight))
+        })
+        .collect();
+    // Stable, so equal weights keep the client's order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges
+        .into_iter()
+        .find_map(|(tag, _)| {
+            let primary = tag.split('-').next().unwrap_or(tag);
+            LANGUAGES
+                .iter()
+                .find(|language| language.eq_ignore_ascii_case(primary))
+                .copied()
+        })
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+/// Translate the catalog message in a response's `error.data` into `language`
+pub fn localize(response: &mut JsonRpcResponse, language: &str) {
+    let Some(data) = response
+        .error
+        .as_mut()
+        .and_then(|error| error.data.as_mut())
+    else {
+        return;
+    };
+    if let Some(code) = data
+        .get("code")
+        .and_then(Value::as_str)
+        .and_then(ErrorCode::from_str)
+    {
+        data["message"] = json!(code.message(language));
+        data["lang"] = json!(language);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiation_follows_weights_and_ignores_regions() {
+        assert_eq!(negotiate_language(None), "en");
+        assert_eq!(negotiate_language(Some("es-MX")), "es");
+        assert_eq!(negotiate_language(Some("de, fr;q=0.5, es;q=0.8")), "es");
+        assert_eq!(negotiate_language(Some("ja, *;q=0.1")), "en");
+        assert_eq!(negotiate_language(Some("es;q=0")), "en");
+    }
+
+    #[test]
+    fn every_code_has_every_translation() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_str(code.as_str()), Some(code));
+            for language in LANGUAGES {
+                assert!(!code.message(language).is_empty());
+            }
+            assert_ne!(code.message("es"), code.message("en"), "{:?}", code);
+        }
+    }
+}
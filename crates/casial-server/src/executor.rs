@@ -0,0 +1,1279 @@
+//! # Tool Executors
+//!
+//! Pluggable backends that actually run tool calls once context coordination is done.
+//! Executors are registered per tool name; unregistered tools fall back to a default.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+use tokio::sync::{mpsc, RwLock};
+use uuid::Uuid;
+
+use crate::{
+    federation::{ExecutionMode, McpFederationManager},
+    mission::{self, MissionValidation},
+    pitfall_shim::{PitfallAvoidanceShim, ShimConfig},
+    registry::ToolRegistry,
+    sse::SseChannel,
+};
+use casial_core::CasialMission;
+
+/// Argument keys added by context coordination, the pitfall shim and orchestration, hidden
+/// from tool results
+pub const INJECTED_FIELDS: [&str; 6] = [
+    "casial_context",
+    "_shim_context",
+    "system_context",
+    "perception_context",
+    "_context",
+    "_swarm_instructions",
+];
+
+/// Arguments an executor can't use, reported to the client as JSON-RPC invalid params
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct InvalidToolArguments(pub String);
+
+/// Remove engine-injected keys from a tool result, at any depth, so echoed args don't leak them
+pub fn strip_injected_fields(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for field in INJECTED_FIELDS {
+                map.remove(field);
+            }
+            map.values_mut().for_each(strip_injected_fields);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_injected_fields),
+        _ => {}
+    }
+}
+
+/// Emits MCP `notifications/progress` for one tool call over its session's outbound channel
+///
+/// Inert when the caller sent no `_meta.progressToken`, so executors can report unconditionally.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    token: Option<Value>,
+    outbound: Option<ProgressOutbound>,
+}
+
+/// Where progress notifications are delivered
+#[derive(Debug, Clone)]
+enum ProgressOutbound {
+    /// A WebSocket session's outbound messages
+    WebSocket(mpsc::Sender<String>),
+    /// An HTTP session's SSE stream
+    Sse(Arc<SseChannel>),
+}
+
+impl ProgressReporter {
+    pub fn new(token: Option<Value>, outbound: mpsc::Sender<String>) -> Self {
+        Self {
+            token,
+            outbound: Some(ProgressOutbound::WebSocket(outbound)),
+        }
+    }
+
+    /// Report progress on an HTTP session's SSE stream
+    pub fn over_sse(token: Option<Value>, channel: Arc<SseChannel>) -> Self {
+        Self {
+            token,
+            outbound: Some(ProgressOutbound::Sse(channel)),
+        }
+    }
+
+    /// Progress token from tools/call params (`_meta.progressToken`), if the client asked for one
+    pub fn token_from_params(params: &Value) -> Option<Value> {
+        params
+            .get("_meta")
+            .and_then(|meta| meta.get("progressToken"))
+            .filter(|token| token.is_string() || token.is_number())
+            .cloned()
+    }
+
+    /// Send one progress update; dropped rather than blocking when the channel is full
+    pub fn report(&self, progress: f64, total: Option<f64>, message: Option<&str>) {
+        let (Some(token), Some(outbound)) = (&self.token, &self.outbound) else {
+            return;
+        };
+
+        let mut params = json!({ "progressToken": token, "progress": progress });
+        if let Some(total) = total {
+            params["total"] = json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = json!(message);
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": params
+        });
+        let delivered = match outbound {
+            ProgressOutbound::WebSocket(sender) => {
+                sender.try_send(notification.to_string()).is_ok()
+            }
+            ProgressOutbound::Sse(channel) => channel.push(notification).is_ok(),
+        };
+        if !delivered {
+            tracing::warn!("Dropping progress notification: session channel unavailable");
+        }
+    }
+}
+
+/// Backend capable of executing a tool call with (already augmented) arguments
+#[async_trait]
+pub trait ToolExecutor: Send + Sync {
+    async fn execute(&self, tool_name: &str, args: Value) -> Result<Value>;
+
+    /// Execute while reporting progress; executors without progress ignore the reporter
+    async fn execute_with_progress(
+        &self,
+        tool_name: &str,
+        args: Value,
+        _progress: &ProgressReporter,
+    ) -> Result<Value> {
+        self.execute(tool_name, args).await
+    }
+}
+
+/// Tool name to executor mapping with a default for unregistered tools
+pub struct ToolExecutorRegistry {
+    executors: DashMap<String, Arc<dyn ToolExecutor>>,
+    default_executor: Arc<dyn ToolExecutor>,
+}
+
+impl ToolExecutorRegistry {
+    pub fn new(default_executor: Arc<dyn ToolExecutor>) -> Self {
+        Self {
+            executors: DashMap::new(),
+            default_executor,
+        }
+    }
+
+    /// Route a tool name to an executor, replacing any previous registration
+    pub fn register(&self, tool_name: impl Into<String>, executor: Arc<dyn ToolExecutor>) {
+        self.executors.insert(tool_name.into(), executor);
+    }
+
+    /// Executor explicitly registered for a tool, if any
+    pub fn executor_for(&self, tool_name: &str) -> Option<Arc<dyn ToolExecutor>> {
+        self.executors
+            .get(tool_name)
+            .map(|entry| Arc::clone(entry.value()))
+    }
+
+    /// Names of all explicitly registered tools, sorted
+    pub fn registered_tools(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .executors
+            .iter()
+            .map(|entry| entry.key().clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Execute a tool with its registered executor, or the default one
+    pub async fn execute(
+        &self,
+        tool_name: &str,
+        args: Value,
+        progress: &ProgressReporter,
+    ) -> Result<Value> {
+        let executor = self
+            .executor_for(tool_name)
+            .unwrap_or_else(|| Arc::clone(&self.default_executor));
+        executor
+            .execute_with_progress(tool_name, args, progress)
+            .await
+    }
+}
+
+/// Stages a simulated research task moves through, one per poll
+const RESEARCH_STAGES: [(&str, &str); 5] = [
+    (
+        "planner",
+        "Decomposed the instructions into research questions",
+    ),
+    ("websets", "Collected candidate sources for each question"),
+    (
+        "crawlers",
+        "Extracted content from the highest-ranked sources",
+    ),
+    (
+        "synthesizer",
+        "Drafted findings across the extracted content",
+    ),
+    ("verifier", "Checked findings against their cited sources"),
+];
+
+/// How long a completed research task's report can still be polled
+const COMPLETED_RESEARCH_TASK_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a research task that is never polled to completion is kept
+const IDLE_RESEARCH_TASK_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A `deep_researcher_start` task tracked until it has been polled to completion
+struct ResearchTask {
+    instructions: Value,
+    model: Value,
+    completed_stages: usize,
+    last_polled: Instant,
+}
+
+impl ResearchTask {
+    fn is_expired(&self, now: Instant) -> bool {
+        let ttl = if self.completed_stages == RESEARCH_STAGES.len() {
+            COMPLETED_RESEARCH_TASK_TTL
+        } else {
+            IDLE_RESEARCH_TASK_TTL
+        };
+        now.duration_since(self.last_polled) > ttl
+    }
+}
+
+/// Built-in executor returning simulated results for the example tool catalog
+pub struct SimulatedExecutor {
+    tool_registry: Arc<ToolRegistry>,
+    research_tasks: DashMap<String, ResearchTask>,
+    proxy_forwarder: Option<Arc<dyn ProxyForwarder>>,
+}
+
+impl SimulatedExecutor {
+    /// Tools with dedicated simulated responses
+    pub const TOOLS: [&'static str; 10] = [
+        "exa_search_example",
+        "exa_research_example",
+        "orchestrate_mcp_proxy",
+        "discover_mcp_tools",
+        "web_search_exa",
+        "deep_researcher_start",
+        "deep_researcher_poll",
+        "crawling_exa",
+        "linkedin_search_exa",
+        "company_research_exa",
+    ];
+
+    pub fn new(tool_registry: Arc<ToolRegistry>) -> Self {
+        Self {
+            tool_registry,
+            research_tasks: DashMap::new(),
+            proxy_forwarder: None,
+        }
+    }
+
+    /// Forward non-dry-run `orchestrate_mcp_proxy` calls through `forwarder`
+    pub fn with_proxy_forwarder(mut self, forwarder: Arc<dyn ProxyForwarder>) -> Self {
+        self.proxy_forwarder = Some(forwarder);
+        self
+    }
+
+    /// Augment a proxy call and forward it, or only preview it when `dry_run` is set
+    async fn execute_orchestrate_mcp_proxy(&self, args: &Value) -> Result<Value> {
+        let call = augment_proxy_call(args)?;
+        let dry_run = args
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if dry_run {
+            let resolved_target = match &self.proxy_forwarder {
+                Some(forwarder) => forwarder.resolve(&call.target_server).await.ok(),
+                None => None,
+            };
+            return Ok(json!({
+                "status": "success",
+                "tool": "orchestrate_mcp_proxy",
+                "dry_run": true,
+                "forwarded_to": call.target_server,
+                "resolved_target": resolved_target,
+                "tool_called": call.tool_name,
+                "augmented_params": call.augmented_params,
+                "augmentation_applied": call.augmentation_config,
+                "result": {
+                    "message": "Dry run: the augmented request was not sent",
+                    "would_call": format!("{}/{}", call.target_server, call.tool_name)
+                },
+                "metadata": {
+                    "augmented": true,
+                    "timestamp": chrono::Utc::now().to_rfc3339()
+                }
+            }));
+        }
+
+        let forwarder = self.proxy_forwarder.as_ref().ok_or_else(|| {
+            anyhow!(
+                "No downstream forwarding configured for orchestrate_mcp_proxy; use dry_run to preview"
+            )
+        })?;
+        let server_id = forwarder.resolve(&call.target_server).await?;
+        let result = forwarder
+            .forward(&server_id, &call.tool_name, call.augmented_params.clone())
+            .await?;
+        Ok(json!({
+            "status": "success",
+            "tool": "orchestrate_mcp_proxy",
+            "dry_run": false,
+            "forwarded_to": call.target_server,
+            "resolved_target": server_id,
+            "tool_called": call.tool_name,
+            "augmented_params": call.augmented_params,
+            "augmentation_applied": call.augmentation_config,
+            "result": result,
+            "metadata": {
+                "augmented": true,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            }
+        }))
+    }
+
+    /// Drop completed tasks past their grace period and tasks abandoned mid-research
+    fn expire_research_tasks(&self) {
+        let now = Instant::now();
+        self.research_tasks.retain(|_, task| !task.is_expired(now));
+    }
+
+    /// Register a research task and return its id
+    fn start_research_task(&self, args: &Value) -> Value {
+        self.expire_research_tasks();
+        let task_id = Uuid::new_v4().to_string();
+        let instructions = args.get("instructions").cloned().unwrap_or(Value::Null);
+        let model = args
+            .get("model")
+            .cloned()
+            .unwrap_or_else(|| json!("exa-research"));
+        self.research_tasks.insert(
+            task_id.clone(),
+            ResearchTask {
+                instructions: instructions.clone(),
+                model: model.clone(),
+                completed_stages: 0,
+                last_polled: Instant::now(),
+            },
+        );
+
+        json!({
+            "status": "success",
+            "tool": "deep_researcher_start",
+            "instructions": instructions,
+            "model": model,
+            "task_id": task_id,
+            "next_step": "Poll for progress with deep_researcher_poll using the task_id",
+            "consciousness_enhanced": true,
+            "simulation": true
+        })
+    }
+
+    /// Advance a research task by one stage and report progress with partial results
+    fn poll_research_task(&self, args: &Value) -> Result<Value> {
+        let task_id = args
+            .get("task_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("deep_researcher_poll requires a 'task_id'"))?;
+        self.expire_research_tasks();
+        let mut task = self
+            .research_tasks
+            .get_mut(task_id)
+            .ok_or_else(|| anyhow!("Unknown research task: {}", task_id))?;
+
+        task.completed_stages = (task.completed_stages + 1).min(RESEARCH_STAGES.len());
+        task.last_polled = Instant::now();
+        let completed = task.completed_stages == RESEARCH_STAGES.len();
+        let partial_results: Vec<Value> = RESEARCH_STAGES[..task.completed_stages]
+            .iter()
+            .map(|(stage, summary)| json!({ "stage": stage, "summary": summary }))
+            .collect();
+
+        let mut result = json!({
+            "status": if completed { "completed" } else { "running" },
+            "tool": "deep_researcher_poll",
+            "task_id": task_id,
+            "model": task.model,
+            "progress": task.completed_stages * 100 / RESEARCH_STAGES.len(),
+            "current_stage": RESEARCH_STAGES[task.completed_stages - 1].0,
+            "partial_results": partial_results,
+            "simulation": true
+        });
+        if completed {
+            result["report"] = json!({
+                "instructions": task.instructions,
+                "summary": "Research complete. In a real implementation, this would contain the synthesized report."
+            });
+        }
+        Ok(result)
+    }
+
+    /// Build a registry that routes every simulated tool here and uses this as the default
+    pub fn into_registry(self) -> ToolExecutorRegistry {
+        let executor: Arc<dyn ToolExecutor> = Arc::new(self);
+        let registry = ToolExecutorRegistry::new(Arc::clone(&executor));
+        for tool_name in Self::TOOLS {
+            registry.register(tool_name, Arc::clone(&executor));
+        }
+        registry
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for SimulatedExecutor {
+    async fn execute_with_progress(
+        &self,
+        tool_name: &str,
+        args: Value,
+        progress: &ProgressReporter,
+    ) -> Result<Value> {
+        let result = self.execute(tool_name, args).await?;
+        if tool_name == "deep_researcher_poll" {
+            progress.report(
+                result["progress"].as_f64().unwrap_or_default(),
+                Some(100.0),
+                result["current_stage"].as_str(),
+            );
+        }
+        Ok(result)
+    }
+
+    async fn execute(&self, tool_name: &str, args: Value) -> Result<Value> {
+        // This simulates tool execution with the context-modified arguments
+        // In a real implementation, this would call actual external APIs
+        let result = match tool_name {
+            "exa_search_example" => simulate_exa_search_example(&args),
+            "exa_research_example" => simulate_exa_research_example(&args),
+            "orchestrate_mcp_proxy" => self.execute_orchestrate_mcp_proxy(&args).await?,
+            "discover_mcp_tools" => self.simulate_discover_mcp_tools(&args),
+            "web_search_exa" => json!({
+                "status": "success",
+                "tool": "web_search_exa",
+                "query": args.get("query").unwrap_or(&Value::Null),
+                "results_count": args.get("numResults").and_then(|n| n.as_u64()).unwrap_or(5),
+                "context_enhanced": true,
+                "simulation": true
+            }),
+            "deep_researcher_start" => self.start_research_task(&args),
+            "deep_researcher_poll" => self.poll_research_task(&args)?,
+            "crawling_exa" => json!({
+                "status": "success",
+                "tool": "crawling_exa",
+                "url": args.get("url").unwrap_or(&Value::Null),
+                "max_chars": args.get("maxCharacters").and_then(|n| n.as_u64()).unwrap_or(3000),
+                "context_aware": true,
+                "simulation": true
+            }),
+            "linkedin_search_exa" => json!({
+                "status": "success",
+                "tool": "linkedin_search_exa",
+                "query": args.get("query").unwrap_or(&Value::Null),
+                "searchType": args.get("searchType").unwrap_or(&json!("all")),
+                "results_count": args.get("numResults").and_then(|n| n.as_u64()).unwrap_or(5),
+                "context_enhanced": true,
+                "professional_network_focus": true,
+                "sample_profile": "breyden-taylor",
+                "simulation": true
+            }),
+            "company_research_exa" => json!({
+                "status": "success",
+                "tool": "company_research_exa",
+                "companyName": args.get("companyName").unwrap_or(&Value::Null),
+                "results_count": args.get("numResults").and_then(|n| n.as_u64()).unwrap_or(5),
+                "context_enhanced": true,
+                "mission_driven": true,
+                "research_depth": "comprehensive",
+                "simulation": true
+            }),
+            _ => json!({
+                "status": "success",
+                "tool": tool_name,
+                "args": args,
+                "consciousness_coordinated": true,
+                "simulation": true
+            }),
+        };
+
+        Ok(result)
+    }
+}
+
+impl SimulatedExecutor {
+    fn simulate_discover_mcp_tools(&self, args: &Value) -> Value {
+        let server_url = args
+            .get("server_url")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let analyze_for_orchestration = args
+            .get("analyze_for_orchestration")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+
+        // Get tools from our registry as an example
+        let tools = self.tool_registry.get_all_tools();
+
+        let discovered_tools: Vec<Value> = tools
+            .into_iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "source": tool.source.label(),
+                    "input_schema": tool.input_schema,
+                    "orchestration_hints": if analyze_for_orchestration {
+                        Some(json!({
+                            "supports_consciousness": true,
+                            "paradox_tolerant": true,
+                            "federation_ready": true
+                        }))
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect();
+
+        json!({
+            "status": "success",
+            "tool": "discover_mcp_tools",
+            "server_url": server_url,
+            "discovered_tools": discovered_tools,
+            "total_tools": discovered_tools.len(),
+            "analysis": {
+                "orchestration_compatible": true,
+                "consciousness_features": ["temporal_awareness", "context_injection", "paradox_handling"],
+                "recommended_patterns": ["saga", "event_driven", "federation"]
+            },
+            "metadata": {
+                "augmented": true,
+                "timestamp": chrono::Utc::now().to_rfc3339()
+            }
+        })
+    }
+}
+
+fn simulate_exa_search_example(args: &Value) -> Value {
+    // Extract parameters
+    let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+    let num_results = args.get("numResults").and_then(|v| v.as_u64()).unwrap_or(5);
+
+    // Since this is an example tool, we'll return a simulated response
+    // In a real implementation, this would call the actual Exa API
+    json!({
+        "status": "success",
+        "tool": "exa_search_example",
+        "query": query,
+        "results": [
+            {
+                "title": "AI Orchestration Best Practices 2025",
+                "url": "https://example.com/ai-orchestration",
+                "snippet": "Latest developments in AI orchestration for microservices...",
+                "score": 0.95
+            },
+            {
+                "title": "MCP Federation Architecture Guide",
+                "url": "https://example.com/mcp-federation",
+                "snippet": "How to build federated MCP systems with consciousness-aware features...",
+                "score": 0.92
+            }
+        ],
+        "metadata": {
+            "num_results_requested": num_results,
+            "augmented": true,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }
+    })
+}
+
+fn simulate_exa_research_example(args: &Value) -> Value {
+    let instructions = args
+        .get("instructions")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let model = args
+        .get("model")
+        .and_then(|v| v.as_str())
+        .unwrap_or("exa-research");
+
+    // Simulated research task response
+    json!({
+        "status": "success",
+        "tool": "exa_research_example",
+        "task_id": Uuid::new_v4().to_string(),
+        "instructions": instructions,
+        "model": model,
+        "result": {
+            "summary": "Research task initiated. In a real implementation, this would start an async research process.",
+            "next_step": "Poll for results using the task_id"
+        },
+        "metadata": {
+            "augmented": true,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }
+    })
+}
+
+/// Maximum number of chained `orchestrate_mcp_proxy` hops before a call is rejected
+pub const MAX_ORCHESTRATION_DEPTH: u64 = 4;
+
+/// Hop count and visited servers for a proxy call, rejecting loops before anything is forwarded
+///
+/// Incoming `_orchestration_depth`/`_visited` come from an upstream proxy; nested
+/// `orchestrate_mcp_proxy` calls in `original_params` are walked so a self-referential
+/// chain is caught without a round trip.
+fn orchestration_route(args: &Value) -> Result<(u64, Vec<String>)> {
+    let mut depth = args
+        .get("_orchestration_depth")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    let mut visited: Vec<String> = args
+        .get("_visited")
+        .and_then(|v| v.as_array())
+        .map(|servers| {
+            servers
+                .iter()
+                .filter_map(|s| s.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut route = Vec::new();
+
+    let mut hop = args;
+    loop {
+        let target = hop
+            .get("target_server")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if visited.iter().any(|server| server == target) {
+            return Err(anyhow!(
+                "Orchestration loop detected: '{}' was already visited ({})",
+                target,
+                visited.join(" -> ")
+            ));
+        }
+        depth += 1;
+        if depth > MAX_ORCHESTRATION_DEPTH {
+            return Err(anyhow!(
+                "Orchestration depth {} exceeds maximum of {} ({} -> {})",
+                depth,
+                MAX_ORCHESTRATION_DEPTH,
+                visited.join(" -> "),
+                target
+            ));
+        }
+        visited.push(target.to_string());
+        route.push((depth, visited.clone()));
+
+        match hop.get("original_params") {
+            Some(next)
+                if hop.get("tool_name").and_then(|v| v.as_str())
+                    == Some("orchestrate_mcp_proxy") =>
+            {
+                hop = next
+            }
+            _ => break,
+        }
+    }
+
+    Ok(route.swap_remove(0))
+}
+
+/// A proxy call with its augmentation applied, ready to forward
+struct ProxyCall {
+    target_server: String,
+    tool_name: String,
+    augmented_params: Value,
+    augmentation_config: Value,
+}
+
+fn augment_proxy_call(args: &Value) -> Result<ProxyCall> {
+    let required = |field: &str| {
+        args.get(field)
+            .and_then(|v| v.as_str())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| anyhow!("orchestrate_mcp_proxy requires a non-empty '{}'", field))
+    };
+    let target_server = required("target_server")?;
+    let tool_name = required("tool_name")?;
+    let (depth, visited) = orchestration_route(args)?;
+    let original_params = args.get("original_params").cloned().unwrap_or(json!({}));
+    let augmentation_config = args
+        .get("augmentation_config")
+        .cloned()
+        .unwrap_or(json!({}));
+
+    // Apply augmentation based on config
+    let mut augmented_params = original_params.clone();
+    let hop = augmented_params.as_object_mut().ok_or_else(|| {
+        InvalidToolArguments("orchestrate_mcp_proxy 'original_params' must be an object".into())
+    })?;
+    hop.insert("_orchestration_depth".to_string(), json!(depth));
+    hop.insert("_visited".to_string(), json!(visited));
+
+    if augmentation_config
+        .get("inject_context")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        augmented_params["_context"] = json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "orchestration_source": "mop",
+            "consciousness_aware": true
+        });
+    }
+
+    if let Some(instructions) = augmentation_config
+        .get("add_swarm_instructions")
+        .and_then(|v| v.as_array())
+    {
+        augmented_params["_swarm_instructions"] = Value::Array(instructions.clone());
+    }
+
+    Ok(ProxyCall {
+        target_server: target_server.to_string(),
+        tool_name: tool_name.to_string(),
+        augmented_params,
+        augmentation_config,
+    })
+}
+
+/// Downstream that `orchestrate_mcp_proxy` forwards augmented calls to
+#[async_trait]
+pub trait ProxyForwarder: Send + Sync {
+    /// Configured server id for a `target_server` given as an id or URL
+    async fn resolve(&self, target_server: &str) -> Result<String>;
+
+    async fn forward(&self, server_id: &str, tool_name: &str, params: Value) -> Result<Value>;
+}
+
+/// Executor that routes calls to downstream MCP servers via federation
+pub struct FederationExecutor {
+    federation_manager: Arc<RwLock<Option<McpFederationManager>>>,
+}
+
+impl FederationExecutor {
+    pub fn new(federation_manager: Arc<RwLock<Option<McpFederationManager>>>) -> Self {
+        Self { federation_manager }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for FederationExecutor {
+    async fn execute(&self, tool_name: &str, args: Value) -> Result<Value> {
+        match self.federation_manager.read().await.as_ref() {
+            Some(manager) => {
+                manager
+                    .route_tool_call(tool_name, args, ExecutionMode::Execute)
+                    .await
+            }
+            None => Err(anyhow!("Unknown tool: {}", tool_name)),
+        }
+    }
+}
+
+#[async_trait]
+impl ProxyForwarder for FederationExecutor {
+    async fn resolve(&self, target_server: &str) -> Result<String> {
+        let federation = self.federation_manager.read().await;
+        let manager = federation.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Federation is disabled; cannot proxy to '{}'",
+                target_server
+            )
+        })?;
+        manager
+            .resolve_server(target_server)
+            .ok_or_else(|| anyhow!("Unknown downstream server: {}", target_server))
+    }
+
+    async fn forward(&self, server_id: &str, tool_name: &str, params: Value) -> Result<Value> {
+        match self.federation_manager.read().await.as_ref() {
+            Some(manager) => manager.call_server_tool(server_id, tool_name, params).await,
+            None => Err(anyhow!(
+                "Federation is disabled; cannot proxy to '{}'",
+                server_id
+            )),
+        }
+    }
+}
+
+/// Executor for the mission authoring tools; never touches the server's loaded missions
+pub struct MissionToolExecutor;
+
+impl MissionToolExecutor {
+    pub const TOOLS: [&'static str; 2] = ["casial_validate_mission", "casial_preview_coordination"];
+
+    /// Route the mission tools in `registry` to this executor
+    pub fn register(registry: &ToolExecutorRegistry) {
+        let executor: Arc<dyn ToolExecutor> = Arc::new(Self);
+        for tool_name in Self::TOOLS {
+            registry.register(tool_name, Arc::clone(&executor));
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for MissionToolExecutor {
+    async fn execute(&self, tool_name: &str, args: Value) -> Result<Value> {
+        match tool_name {
+            "casial_validate_mission" => {
+                let validation = match mission_from_args(&args) {
+                    Ok(mission) => mission::validate_mission(&mission),
+                    Err(e) => MissionValidation {
+                        valid: false,
+                        errors: vec![e.to_string()],
+                        warnings: Vec::new(),
+                    },
+                };
+                Ok(serde_json::to_value(validation)?)
+            }
+            "casial_preview_coordination" => {
+                let mission = mission_from_args(&args)?;
+                let tool = args
+                    .get("tool_name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| anyhow!("casial_preview_coordination requires a 'tool_name'"))?;
+                let tool_args = args.get("tool_args").cloned().unwrap_or_else(|| json!({}));
+                let environment = match args.get("environment") {
+                    Some(value) => serde_json::from_value(value.clone())
+                        .map_err(|e| anyhow!("Invalid environment: {}", e))?,
+                    None => Default::default(),
+                };
+                let result =
+                    mission::preview_coordination(mission, tool, tool_args, environment, None)?;
+                Ok(serde_json::to_value(result)?)
+            }
+            _ => Err(anyhow!("Unknown mission tool: {}", tool_name)),
+        }
+    }
+}
+
+/// Argument carrying an admin tool's credential
+const ADMIN_TOKEN_ARG: &str = "admin_token";
+
+/// Take the `admin_token` argument out of a tool call's arguments
+///
+/// The credential is only for the executor, so it is kept away from the pitfall shim,
+/// mission coordination and coordination history.
+pub fn take_admin_token(args: &mut Value) -> Option<Value> {
+    args.as_object_mut()?.remove(ADMIN_TOKEN_ARG)
+}
+
+/// Hand an `admin_token` taken with [`take_admin_token`] back for the executor
+pub fn restore_admin_token(mut args: Value, token: Option<Value>) -> Value {
+    if let (Some(token), Some(fields)) = (token, args.as_object_mut()) {
+        fields.insert(ADMIN_TOKEN_ARG.to_string(), token);
+    }
+    args
+}
+
+/// Whether a provided secret matches the expected one, in time independent of where they differ
+pub fn secret_matches(provided: &str, expected: &str) -> bool {
+    provided.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Executor for the admin tools that read and replace the pitfall shim configuration
+///
+/// Mirrors `/debug/shim`: calls must carry an `admin_token` matching `MOP_ADMIN_TOKEN`, and
+/// both tools are refused while no token is configured.
+pub struct ShimToolExecutor {
+    shim: Arc<RwLock<PitfallAvoidanceShim>>,
+    admin_token: Option<String>,
+}
+
+impl ShimToolExecutor {
+    pub const TOOLS: [&'static str; 2] = ["casial_get_shim_config", "casial_update_shim_config"];
+
+    pub fn new(shim: Arc<RwLock<PitfallAvoidanceShim>>, admin_token: Option<String>) -> Self {
+        Self {
+            shim,
+            admin_token: admin_token.filter(|token| !token.is_empty()),
+        }
+    }
+
+    /// Route the shim tools in `registry` to this executor
+    pub fn register(self, registry: &ToolExecutorRegistry) {
+        let executor: Arc<dyn ToolExecutor> = Arc::new(self);
+        for tool_name in Self::TOOLS {
+            registry.register(tool_name, Arc::clone(&executor));
+        }
+    }
+
+    fn authorize(&self, tool_name: &str, args: &Value) -> Result<()> {
+        let expected = self
+            .admin_token
+            .as_deref()
+            .ok_or_else(|| anyhow!("Set MOP_ADMIN_TOKEN to enable {}", tool_name))?;
+        match args.get(ADMIN_TOKEN_ARG).and_then(Value::as_str) {
+            Some(token) if secret_matches(token, expected) => Ok(()),
+            _ => Err(anyhow!("{} requires a valid 'admin_token'", tool_name)),
+        }
+    }
+}
+
+#[async_trait]
+impl ToolExecutor for ShimToolExecutor {
+    async fn execute(&self, tool_name: &str, args: Value) -> Result<Value> {
+        self.authorize(tool_name, &args)?;
+        match tool_name {
+            "casial_get_shim_config" => {
+                let shim = self.shim.read().await;
+                Ok(serde_json::to_value(shim.get_config())?)
+            }
+            "casial_update_shim_config" => {
+                let config: ShimConfig = serde_json::from_value(
+                    args.get("config")
+                        .cloned()
+                        .ok_or_else(|| anyhow!("Missing 'config' argument"))?,
+                )
+                .map_err(|e| anyhow!("Invalid shim config: {}", e))?;
+                let mut shim = self.shim.write().await;
+                shim.update_config(config);
+                Ok(serde_json::to_value(shim.get_config())?)
+            }
+            _ => Err(anyhow!("Unknown shim tool: {}", tool_name)),
+        }
+    }
+}
+
+/// The `mission` argument, given as an object or a JSON string
+fn mission_from_args(args: &Value) -> Result<CasialMission> {
+    let mission = match args.get("mission") {
+        Some(Value::String(text)) => serde_json::from_str(text),
+        Some(value) => serde_json::from_value(value.clone()),
+        None => return Err(anyhow!("Missing 'mission' argument")),
+    };
+    mission.map_err(|e| anyhow!("Invalid mission: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unregistered_tools_use_default_executor() {
+        let registry = SimulatedExecutor::new(Arc::new(ToolRegistry::new())).into_registry();
+
+        let result = registry
+            .execute(
+                "unlisted_tool",
+                json!({ "query": "rust" }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("simulated execution succeeds");
+
+        assert_eq!(result["tool"], json!("unlisted_tool"));
+        assert_eq!(result["simulation"], json!(true));
+        assert!(registry.executor_for("unlisted_tool").is_none());
+        assert!(registry
+            .registered_tools()
+            .contains(&"web_search_exa".to_string()));
+    }
+
+    #[tokio::test]
+    async fn federation_executor_rejects_tools_without_federation() {
+        let executor = FederationExecutor::new(Arc::new(RwLock::new(None)));
+
+        let error = executor
+            .execute("remote_tool", json!({}))
+            .await
+            .expect_err("federation disabled");
+
+        assert!(error.to_string().contains("Unknown tool"));
+    }
+
+    #[tokio::test]
+    async fn research_task_progresses_when_polled() {
+        let registry = SimulatedExecutor::new(Arc::new(ToolRegistry::new())).into_registry();
+
+        let started = registry
+            .execute(
+                "deep_researcher_start",
+                json!({ "instructions": "Survey Rust async runtimes" }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("task starts");
+        let task_id = started["task_id"].clone();
+
+        let first = registry
+            .execute(
+                "deep_researcher_poll",
+                json!({ "task_id": task_id }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("first poll");
+        assert_eq!(first["status"], json!("running"));
+        assert_eq!(first["current_stage"], json!("planner"));
+        assert_eq!(first["partial_results"].as_array().unwrap().len(), 1);
+
+        let second = registry
+            .execute(
+                "deep_researcher_poll",
+                json!({ "task_id": task_id }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("second poll");
+        assert!(second["progress"].as_u64() > first["progress"].as_u64());
+        assert_eq!(second["partial_results"].as_array().unwrap().len(), 2);
+
+        let mut last = second;
+        for _ in 0..3 {
+            last = registry
+                .execute(
+                    "deep_researcher_poll",
+                    json!({ "task_id": task_id }),
+                    &ProgressReporter::default(),
+                )
+                .await
+                .expect("poll");
+        }
+        assert_eq!(last["status"], json!("completed"));
+        assert_eq!(last["progress"], json!(100));
+        assert!(last["report"].is_object());
+
+        let error = registry
+            .execute(
+                "deep_researcher_poll",
+                json!({ "task_id": "missing" }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect_err("unknown task");
+        assert!(error.to_string().contains("Unknown research task"));
+    }
+
+    #[tokio::test]
+    async fn finished_and_abandoned_research_tasks_expire() {
+        let executor = SimulatedExecutor::new(Arc::new(ToolRegistry::new()));
+        let start = |executor: &SimulatedExecutor| {
+            executor.start_research_task(&json!({ "instructions": "Survey" }))["task_id"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        let (finished, abandoned, recent) = (start(&executor), start(&executor), start(&executor));
+        for _ in 0..RESEARCH_STAGES.len() {
+            executor
+                .poll_research_task(&json!({ "task_id": finished }))
+                .unwrap();
+        }
+        let now = Instant::now();
+        executor
+            .research_tasks
+            .get_mut(&finished)
+            .unwrap()
+            .last_polled = now - COMPLETED_RESEARCH_TASK_TTL - Duration::from_secs(1);
+        executor
+            .research_tasks
+            .get_mut(&abandoned)
+            .unwrap()
+            .last_polled = now - IDLE_RESEARCH_TASK_TTL - Duration::from_secs(1);
+        executor
+            .research_tasks
+            .get_mut(&recent)
+            .unwrap()
+            .last_polled = now - COMPLETED_RESEARCH_TASK_TTL - Duration::from_secs(1);
+
+        let error = executor
+            .poll_research_task(&json!({ "task_id": finished }))
+            .expect_err("finished task expired");
+        assert!(error.to_string().contains("Unknown research task"));
+        assert!(!executor.research_tasks.contains_key(&abandoned));
+        // Unfinished tasks get the longer idle window
+        assert!(executor
+            .poll_research_task(&json!({ "task_id": recent }))
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn proxy_propagates_hop_count_and_visited_servers() {
+        let registry = SimulatedExecutor::new(Arc::new(ToolRegistry::new())).into_registry();
+
+        let result = registry
+            .execute(
+                "orchestrate_mcp_proxy",
+                json!({
+                    "target_server": "https://b.example/mcp",
+                    "tool_name": "web_search_exa",
+                    "original_params": { "query": "rust" },
+                    "dry_run": true,
+                    "_orchestration_depth": 1,
+                    "_visited": ["https://a.example/mcp"]
+                }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("acyclic proxy call succeeds");
+
+        assert_eq!(result["augmented_params"]["_orchestration_depth"], json!(2));
+        assert_eq!(
+            result["augmented_params"]["_visited"],
+            json!(["https://a.example/mcp", "https://b.example/mcp"])
+        );
+    }
+
+    #[tokio::test]
+    async fn proxy_rejects_non_object_params() {
+        let registry = SimulatedExecutor::new(Arc::new(ToolRegistry::new())).into_registry();
+
+        let error = registry
+            .execute(
+                "orchestrate_mcp_proxy",
+                json!({
+                    "target_server": "https://b.example/mcp",
+                    "tool_name": "web_search_exa",
+                    "original_params": ["rust"],
+                    "dry_run": true
+                }),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect_err("array params are rejected");
+        assert!(error.is::<InvalidToolArguments>(), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn self_referential_proxy_call_is_rejected() {
+        let registry = SimulatedExecutor::new(Arc::new(ToolRegistry::new())).into_registry();
+        let proxy_to_self = |params: Value| {
+            json!({
+                "target_server": "https://mop.example/mcp",
+                "tool_name": "orchestrate_mcp_proxy",
+                "original_params": params
+            })
+        };
+
+        let error = registry
+            .execute(
+                "orchestrate_mcp_proxy",
+                proxy_to_self(proxy_to_self(json!({}))),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect_err("loop must be rejected");
+        assert!(error.to_string().contains("Orchestration loop detected"));
+        assert!(error.to_string().contains("https://mop.example/mcp"));
+
+        let mut hops = json!({ "target_server": "https://last.example/mcp" });
+        for server in ["d", "c", "b", "a"] {
+            hops = json!({
+                "target_server": format!("https://{}.example/mcp", server),
+                "tool_name": "orchestrate_mcp_proxy",
+                "original_params": hops
+            });
+        }
+        let error = registry
+            .execute("orchestrate_mcp_proxy", hops, &ProgressReporter::default())
+            .await
+            .expect_err("depth must be capped");
+        assert!(error.to_string().contains("exceeds maximum of 4"));
+    }
+
+    /// Forwarder recording each call it receives
+    #[derive(Default)]
+    struct RecordingForwarder {
+        calls: std::sync::Mutex<Vec<(String, String, Value)>>,
+    }
+
+    #[async_trait]
+    impl ProxyForwarder for RecordingForwarder {
+        async fn resolve(&self, target_server: &str) -> Result<String> {
+            match target_server {
+                "https://exa.example/mcp" => Ok("exa".to_string()),
+                other => Err(anyhow!("Unknown downstream server: {}", other)),
+            }
+        }
+
+        async fn forward(&self, server_id: &str, tool_name: &str, params: Value) -> Result<Value> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((server_id.to_string(), tool_name.to_string(), params));
+            Ok(json!({ "content": [{ "type": "text", "text": "downstream result" }] }))
+        }
+    }
+
+    #[tokio::test]
+    async fn proxy_dry_run_previews_without_contacting_downstream() {
+        let forwarder = Arc::new(RecordingForwarder::default());
+        let registry = SimulatedExecutor::new(Arc::new(ToolRegistry::new()))
+            .with_proxy_forwarder(forwarder.clone())
+            .into_registry();
+        let call = |dry_run: bool| {
+            json!({
+                "target_server": "https://exa.example/mcp",
+                "tool_name": "web_search_exa",
+                "original_params": { "query": "rust" },
+                "augmentation_config": { "add_swarm_instructions": ["cite sources"] },
+                "dry_run": dry_run
+            })
+        };
+
+        let preview = registry
+            .execute(
+                "orchestrate_mcp_proxy",
+                call(true),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("dry run succeeds");
+        assert!(forwarder.calls.lock().unwrap().is_empty());
+        assert_eq!(preview["dry_run"], json!(true));
+        assert_eq!(preview["resolved_target"], json!("exa"));
+        assert_eq!(
+            preview["augmented_params"]["_swarm_instructions"],
+            json!(["cite sources"])
+        );
+
+        let forwarded = registry
+            .execute(
+                "orchestrate_mcp_proxy",
+                call(false),
+                &ProgressReporter::default(),
+            )
+            .await
+            .expect("forwarded call succeeds");
+        let calls = forwarder.calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        let (server_id, tool_name, params) = &calls[0];
+        assert_eq!(
+            (server_id.as_str(), tool_name.as_str()),
+            ("exa", "web_search_exa")
+        );
+        assert_eq!(params, &preview["augmented_params"]);
+        assert_eq!(
+            forwarded["result"]["content"][0]["text"],
+            json!("downstream result")
+        );
+    }
+
+    #[tokio::test]
+    async fn preview_rules_cannot_see_server_environment() {
+        use crate::test_fixtures::{mission_json, rule_json, template_json};
+
+        std::env::set_var("CASIAL_PREVIEW_PROBE_SECRET", "s3cret");
+        let mut rule = rule_json("probe", &[], &["leak"]);
+        rule["conditions"]["environment_vars"] = json!({ "CASIAL_PREVIEW_PROBE_SECRET": "s" });
+        let mission = mission_json("probe", vec![template_json("leak", 1, "hit")], vec![rule]);
+        let preview = |environment: Value| {
+            MissionToolExecutor.execute(
+                "casial_preview_coordination",
+                json!({
+                    "mission": mission, "tool_name": "web_search", "environment": environment
+                }),
+            )
+        };
+
+        let probed = preview(json!({})).await.expect("preview runs");
+        assert_eq!(probed["activated_rules"], json!([]));
+
+        let supplied = preview(json!({ "CASIAL_PREVIEW_PROBE_SECRET": "supplied" }))
+            .await
+            .expect("preview runs");
+        assert_eq!(supplied["activated_rules"], json!(["probe"]));
+    }
+}
@@ -3,17 +3,23 @@
 //! Manages federation of multiple downstream MCP servers, tool aggregation, and intelligent routing.
 
 use crate::{
-    client::McpClient,
-    config::FederationSettings,
-    registry::{ToolRegistry, ToolSource, ToolSpec},
+    client::{McpClient, RequestTimeout},
+    config::{DownstreamMcpServer, FederationSettings, ResultCacheSettings, SyncStrategy},
+    errors::ErrorCode,
+    executor::strip_injected_fields,
+    mcp::{create_error_response, JsonRpcResponse},
+    registry::{RegistryChangeEvent, ToolRegistry, ToolSource, ToolSpec},
 };
 use anyhow::{Context, Result};
+use casial_core::{list_remote_tools, RemoteError};
 use chrono::{DateTime, Utc};
-use dashmap::{mapref::entry::Entry, DashMap};
+use dashmap::{mapref::entry::Entry, DashMap, DashSet};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::{
     collections::HashMap,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -23,7 +29,7 @@ use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 /// Federation metrics and status
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct FederationMetrics {
     pub active_connections: usize,
     pub total_servers: usize,
@@ -34,6 +40,114 @@ pub struct FederationMetrics {
     pub server_failures: HashMap<String, u64>,
     pub open_circuits: usize,
     pub circuit_open_skips: u64,
+    /// Forwarded calls re-sent after a failed attempt
+    pub federation_retries_total: u64,
+}
+
+impl FederationMetrics {
+    /// Prometheus exposition of the federation counters
+    pub fn export_prometheus(&self) -> String {
+        format!(
+            r#"# HELP casial_federation_retries_total Forwarded tool calls retried after a failed attempt
+# TYPE casial_federation_retries_total counter
+casial_federation_retries_total {}
+"#,
+            self.federation_retries_total
+        )
+    }
+}
+
+/// Jittered exponential backoff as applied, after the floors on the configured values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct BackoffPolicy {
+    pub initial_ms: u64,
+    pub max_ms: u64,
+    /// Random delay of up to this much is added to every backoff
+    pub max_jitter_ms: u64,
+    /// Retries of a forwarded call after its first attempt
+    pub max_retries: u32,
+}
+
+impl BackoffPolicy {
+    pub fn from_settings(settings: &FederationSettings) -> Self {
+        let initial_ms = settings.backoff_initial_ms.max(10);
+        let max_ms = settings.backoff_max_ms.max(initial_ms);
+        Self {
+            initial_ms,
+            max_ms,
+            max_jitter_ms: initial_ms.min(max_ms),
+            max_retries: settings.max_retries.max(1),
+        }
+    }
+}
+
+/// Why a call forwarded to a downstream server failed
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FederationError {
+    /// Too many recent failures; calls are refused until the circuit resets
+    #[error("Circuit open for server '{server_id}' (retry in {retry_after:?})")]
+    CircuitOpen {
+        server_id: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("Server '{0}' is not connected")]
+    NotConnected(String),
+    /// The downstream server answered with a JSON-RPC error
+    #[error("Downstream error {code}: {message}")]
+    Downstream { code: i64, message: String },
+    /// The request or its response was lost on the way
+    #[error("Failed to forward to {server_id}: {message}")]
+    Transport { server_id: String, message: String },
+    #[error("Request to server '{0}' timed out")]
+    Timeout(String),
+}
+
+impl FederationError {
+    /// JSON-RPC error code reported upstream; downstream errors keep their own code
+    pub fn json_rpc_code(&self) -> i64 {
+        match self {
+            FederationError::CircuitOpen { .. } => ErrorCode::CircuitOpen.json_rpc_code(),
+            FederationError::NotConnected(_) => -32002,
+            FederationError::Downstream { code, .. } => *code,
+            FederationError::Transport { .. } => -32003,
+            FederationError::Timeout(_) => -32004,
+        }
+    }
+
+    /// Whether the same call may succeed if retried later
+    pub fn retryable(&self) -> bool {
+        !matches!(self, FederationError::Downstream { .. })
+    }
+
+    /// JSON-RPC error response for a failed call to `tool_name`
+    pub fn to_response(&self, id: serde_json::Value, tool_name: &str) -> JsonRpcResponse {
+        let mut data = self.to_error_data();
+        data["tool"] = serde_json::json!(tool_name);
+        match self {
+            FederationError::CircuitOpen { .. } => ErrorCode::CircuitOpen.response(id, Some(data)),
+            _ => create_error_response(id, self.json_rpc_code(), &self.to_string(), Some(data)),
+        }
+    }
+
+    /// Error data for a JSON-RPC error response
+    pub fn to_error_data(&self) -> serde_json::Value {
+        let kind = match self {
+            FederationError::CircuitOpen { .. } => "circuit_open",
+            FederationError::NotConnected(_) => "not_connected",
+            FederationError::Downstream { .. } => "downstream",
+            FederationError::Transport { .. } => "transport",
+            FederationError::Timeout(_) => "timeout",
+        };
+        let mut data = serde_json::json!({ "kind": kind, "retryable": self.retryable() });
+        if let FederationError::CircuitOpen {
+            retry_after: Some(retry_after),
+            ..
+        } = self
+        {
+            data["retry_after_ms"] = serde_json::json!(retry_after.as_millis() as u64);
+        }
+        data
+    }
 }
 
 /// Execution mode for tool calls
@@ -67,6 +181,13 @@ pub struct McpFederationManager {
     sync_handle: Option<tokio::task::JoinHandle<()>>,
     failure_tracker: Arc<DashMap<String, CircuitState>>,
     tool_cache: Arc<DashMap<String, ToolCacheEntry>>,
+    result_cache: Option<Arc<ToolResultCache>>,
+    /// Definition of each configured downstream, kept current by `reconfigure`
+    server_configs: Arc<DashMap<String, DownstreamMcpServer>>,
+    /// Calls currently being forwarded, per server
+    in_flight: Arc<DashMap<String, usize>>,
+    /// Servers being drained by `reconfigure`; new calls to them are refused
+    draining: Arc<DashSet<String>>,
 }
 
 /// Federation events for notifications
@@ -84,6 +205,47 @@ struct ToolCacheEntry {
     spec_hash: String,
     expires_at: Instant,
     tool_count: usize,
+    tools: Vec<serde_json::Value>,
+}
+
+/// On-disk snapshot of the federated tool catalog, keyed by server id
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedToolCatalog {
+    servers: HashMap<String, PersistedServerTools>,
+}
+
+/// Raw `tools/list` entries for one server, tagged with their spec hash
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedServerTools {
+    spec_hash: String,
+    tools: Vec<serde_json::Value>,
+}
+
+/// How often a drain checks whether a server's in-flight calls have finished
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Counts a forwarded call against its server until dropped
+struct InFlightCall {
+    counts: Arc<DashMap<String, usize>>,
+    server_id: String,
+}
+
+impl InFlightCall {
+    fn start(counts: &Arc<DashMap<String, usize>>, server_id: &str) -> Self {
+        *counts.entry(server_id.to_string()).or_insert(0) += 1;
+        Self {
+            counts: Arc::clone(counts),
+            server_id: server_id.to_string(),
+        }
+    }
+}
+
+impl Drop for InFlightCall {
+    fn drop(&mut self) {
+        if let Some(mut count) = self.counts.get_mut(&self.server_id) {
+            *count = count.saturating_sub(1);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +256,137 @@ struct CircuitState {
     reset_after: Duration,
 }
 
+/// LRU of federated tool results for allow-listed idempotent tools
+///
+/// Entries are dropped when the registry reports the tool or its server's catalog changed.
+struct ToolResultCache {
+    settings: ResultCacheSettings,
+    entries: std::sync::Mutex<ResultCacheEntries>,
+    invalidations: std::sync::Mutex<mpsc::UnboundedReceiver<RegistryChangeEvent>>,
+}
+
+#[derive(Default)]
+struct ResultCacheEntries {
+    by_key: HashMap<String, CachedToolResult>,
+    clock: u64,
+}
+
+struct CachedToolResult {
+    server_id: String,
+    tool_name: String,
+    result: serde_json::Value,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+impl ToolResultCache {
+    fn new(
+        settings: ResultCacheSettings,
+        invalidations: mpsc::UnboundedReceiver<RegistryChangeEvent>,
+    ) -> Self {
+        Self {
+            settings,
+            entries: std::sync::Mutex::new(ResultCacheEntries::default()),
+            invalidations: std::sync::Mutex::new(invalidations),
+        }
+    }
+
+    fn caches(&self, tool_name: &str) -> bool {
+        self.settings.max_entries > 0 && self.settings.tools.iter().any(|t| t == tool_name)
+    }
+
+    /// Hash of the tool name and the caller's arguments
+    ///
+    /// Coordinated context and the shim's `_shim_context` differ between otherwise identical
+    /// calls (the shim stamps the current time), so they are left out of the key.
+    fn key(tool_name: &str, arguments: &serde_json::Value) -> String {
+        let mut arguments = arguments.clone();
+        strip_injected_fields(&mut arguments);
+        let mut hasher = Sha256::new();
+        hasher.update(tool_name.as_bytes());
+        hasher.update([0]);
+        hasher.update(arguments.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Apply registry changes seen since the last lookup
+    fn apply_invalidations(&self, entries: &mut ResultCacheEntries) {
+        let mut invalidations = self.invalidations.lock().unwrap();
+        while let Ok(event) = invalidations.try_recv() {
+            match event {
+                RegistryChangeEvent::SourceRemoved(server_id) => {
+                    entries.by_key.retain(|_, e| e.server_id != server_id)
+                }
+                RegistryChangeEvent::ToolUpdated(name) | RegistryChangeEvent::ToolRemoved(name) => {
+                    entries.by_key.retain(|_, e| e.tool_name != name)
+                }
+                RegistryChangeEvent::ToolAdded(_) | RegistryChangeEvent::SourceAdded(_) => {}
+            }
+        }
+    }
+
+    fn get(&self, tool_name: &str, arguments: &serde_json::Value) -> Option<serde_json::Value> {
+        if !self.caches(tool_name) {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        self.apply_invalidations(&mut entries);
+
+        let key = Self::key(tool_name, arguments);
+        let now = Instant::now();
+        if entries.by_key.get(&key)?.expires_at <= now {
+            entries.by_key.remove(&key);
+            return None;
+        }
+        entries.clock += 1;
+        let clock = entries.clock;
+        let entry = entries.by_key.get_mut(&key)?;
+        entry.last_used = clock;
+        Some(entry.result.clone())
+    }
+
+    fn insert(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+        result: &serde_json::Value,
+    ) {
+        if !self.caches(tool_name) {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        self.apply_invalidations(&mut entries);
+
+        let now = Instant::now();
+        entries.by_key.retain(|_, e| e.expires_at > now);
+        let key = Self::key(tool_name, arguments);
+        if !entries.by_key.contains_key(&key) && entries.by_key.len() >= self.settings.max_entries {
+            let least_recent = entries
+                .by_key
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(least_recent) = least_recent {
+                entries.by_key.remove(&least_recent);
+            }
+        }
+
+        entries.clock += 1;
+        let last_used = entries.clock;
+        entries.by_key.insert(
+            key,
+            CachedToolResult {
+                server_id: server_id.to_string(),
+                tool_name: tool_name.to_string(),
+                result: result.clone(),
+                expires_at: now + Duration::from_secs(self.settings.ttl_seconds),
+                last_used,
+            },
+        );
+    }
+}
+
 impl CircuitState {
     fn new(reset_seconds: u64) -> Self {
         Self {
@@ -160,21 +453,39 @@ impl CircuitState {
     }
 }
 
+/// Server ids the periodic sync visits on a tick (`server_ids` must be in a stable order)
+fn servers_for_tick(strategy: SyncStrategy, server_ids: &[String], tick: usize) -> Vec<String> {
+    match strategy {
+        SyncStrategy::Parallel => server_ids.to_vec(),
+        SyncStrategy::Staggered if server_ids.is_empty() => Vec::new(),
+        SyncStrategy::Staggered => vec![server_ids[tick % server_ids.len()].clone()],
+    }
+}
+
 fn compute_backoff_duration(settings: &FederationSettings, attempt: u32) -> Duration {
-    let base = settings.backoff_initial_ms.max(10);
-    let max_backoff = settings.backoff_max_ms.max(base);
+    let policy = BackoffPolicy::from_settings(settings);
     let power = attempt.min(16);
     let multiplier = 1u64.checked_shl(power.into()).unwrap_or(u64::MAX);
-    let mut backoff_ms = base.saturating_mul(multiplier);
-    if backoff_ms > max_backoff {
-        backoff_ms = max_backoff;
-    }
-    let jitter_max = base.min(max_backoff);
-    let jitter = rand::thread_rng().gen_range(0..=jitter_max);
+    let backoff_ms = policy
+        .initial_ms
+        .saturating_mul(multiplier)
+        .min(policy.max_ms);
+    let jitter = rand::thread_rng().gen_range(0..=policy.max_jitter_ms);
     let total_ms = backoff_ms.saturating_add(jitter).max(1);
     Duration::from_millis(total_ms)
 }
 
+/// Write the catalog through a temp file so readers never see a partial write
+async fn write_catalog_file(path: &Path, catalog: &PersistedToolCatalog) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let temp_path = path.with_extension("tmp");
+    tokio::fs::write(&temp_path, serde_json::to_vec_pretty(catalog)?).await?;
+    tokio::fs::rename(&temp_path, path).await?;
+    Ok(())
+}
+
 async fn record_failure_shared(
     failure_tracker: &DashMap<String, CircuitState>,
     metrics: &Arc<RwLock<FederationMetrics>>,
@@ -256,6 +567,10 @@ impl McpFederationManager {
             sync_handle: None,
             failure_tracker: Arc::new(DashMap::new()),
             tool_cache: Arc::new(DashMap::new()),
+            result_cache: None,
+            server_configs: Arc::new(DashMap::new()),
+            in_flight: Arc::new(DashMap::new()),
+            draining: Arc::new(DashSet::new()),
         }
     }
 
@@ -283,21 +598,52 @@ impl McpFederationManager {
             );
             let client = Arc::new(RwLock::new(McpClient::new(server_config.clone())));
             self.clients.insert(server_config.id.clone(), client);
+            self.server_configs
+                .insert(server_config.id.clone(), server_config.clone());
+        }
+
+        if let Some(cache_settings) = self.settings.result_cache.clone() {
+            let (invalidation_tx, invalidation_rx) = mpsc::unbounded_channel();
+            self.tool_registry
+                .add_change_listener(invalidation_tx)
+                .await;
+            self.result_cache = Some(Arc::new(ToolResultCache::new(
+                cache_settings,
+                invalidation_rx,
+            )));
         }
 
+        let restored_tools = match self.load_persisted_catalog().await {
+            Ok(count) => count,
+            Err(e) => {
+                warn!("⚠️ Ignoring persisted tool catalog: {}", e);
+                0
+            }
+        };
+
         // Start periodic sync task
         if self.settings.catalog_refresh_interval > 0 {
             self.start_sync_task().await?;
         }
 
-        // Perform initial sync
-        self.sync_all_servers().await?;
+        // Perform initial sync, unless restored tools can be served while the
+        // periodic task's first tick refreshes them in the background
+        if restored_tools > 0 && self.sync_handle.is_some() {
+            info!(
+                "📦 Serving {} cached federated tools until the first sync completes",
+                restored_tools
+            );
+        } else {
+            self.sync_all_servers().await?;
+        }
 
         info!("✅ MCP Federation initialized successfully");
         Ok(())
     }
 
     /// Connect to all enabled downstream servers
+    ///
+    /// Clients that are already connected are left as they are.
     pub async fn connect_all(&self) -> Result<()> {
         let mut connection_tasks = Vec::new();
 
@@ -308,6 +654,9 @@ impl McpFederationManager {
             let task = tokio::spawn(async move {
                 let result = {
                     let mut client = client.write().await;
+                    if client.is_connected().await {
+                        return Ok(server_id);
+                    }
                     client.connect().await
                 };
                 match result {
@@ -357,6 +706,24 @@ impl McpFederationManager {
         }
     }
 
+    /// Connect at startup, retrying with backoff while some downstreams are unreachable
+    ///
+    /// Returns the number of connected servers once all connect or the retries run out.
+    pub async fn connect_with_retries(&self) -> usize {
+        let mut attempt = 0;
+        loop {
+            if let Err(e) = self.connect_all().await {
+                warn!("Startup connection attempt {} failed: {}", attempt + 1, e);
+            }
+            let connected = self.metrics.read().await.active_connections;
+            if connected == self.clients.len() || attempt >= self.settings.startup_connect_retries {
+                return connected;
+            }
+            tokio::time::sleep(compute_backoff_duration(&self.settings, attempt)).await;
+            attempt += 1;
+        }
+    }
+
     /// Sync tools from all connected servers
     pub async fn sync_all_servers(&self) -> Result<()> {
         let sync_start = std::time::Instant::now();
@@ -413,6 +780,8 @@ impl McpFederationManager {
             }
         }
 
+        Self::persist_tool_cache(&self.settings, &self.tool_cache).await;
+
         let sync_duration = sync_start.elapsed();
 
         {
@@ -483,30 +852,29 @@ impl McpFederationManager {
                 Err(e) => warn!("⚠️ Failed to initialize {}: {}", server_id, e),
             }
 
-            client_guard
-                .list_tools()
-                .await
-                .with_context(|| format!("Failed to list tools from server {}", server_id))
-        }?;
-
-        if let Some(error) = tools_response.error {
-            let message = format!("Server {} returned error: {}", server_id, error.message);
-            record_failure_shared(&failure_tracker, &metrics, &server_id, &settings, &message)
-                .await;
-            return Err(anyhow::anyhow!(message));
-        }
-
-        let tools_data = tools_response
-            .result
-            .ok_or_else(|| anyhow::anyhow!("No tools data from server {}", server_id))?;
-
-        let tools = tools_data
-            .get("tools")
-            .and_then(|t| t.as_array())
-            .ok_or_else(|| anyhow::anyhow!("Invalid tools format from server {}", server_id))?;
+            list_remote_tools(&*client_guard).await
+        };
+        let tools = match tools_response {
+            Ok(tools) => tools,
+            Err(e) => {
+                if let Some(error) = e.downcast_ref::<RemoteError>() {
+                    let message = format!("Server {} returned error: {}", server_id, error.message);
+                    record_failure_shared(
+                        &failure_tracker,
+                        &metrics,
+                        &server_id,
+                        &settings,
+                        &message,
+                    )
+                    .await;
+                    return Err(anyhow::anyhow!(message));
+                }
+                return Err(e.context(format!("Failed to list tools from server {}", server_id)));
+            }
+        };
 
         let spec_hash = {
-            let serialized = serde_json::to_vec(tools).map_err(|e| {
+            let serialized = serde_json::to_vec(&tools).map_err(|e| {
                 anyhow::anyhow!("Failed to serialize tools from {}: {}", server_id, e)
             })?;
             let mut hasher = Sha256::new();
@@ -532,7 +900,7 @@ impl McpFederationManager {
         registry.remove_tools_from_source(&server_id).await;
 
         let mut registered_count = 0;
-        for tool_data in tools {
+        for tool_data in &tools {
             if let Ok(tool_spec) = Self::parse_tool_spec(tool_data, &server_id) {
                 if registry.register_tool(tool_spec).await.is_ok() {
                     registered_count += 1;
@@ -540,7 +908,7 @@ impl McpFederationManager {
             }
         }
 
-        if settings.tool_cache_ttl_seconds > 0 {
+        if settings.tool_cache_ttl_seconds > 0 || settings.tool_cache_path.is_some() {
             tool_cache.insert(
                 server_id.clone(),
                 ToolCacheEntry {
@@ -548,6 +916,7 @@ impl McpFederationManager {
                     expires_at: Instant::now()
                         + Duration::from_secs(settings.tool_cache_ttl_seconds.max(1)),
                     tool_count: registered_count,
+                    tools: tools.clone(),
                 },
             );
         }
@@ -561,6 +930,93 @@ impl McpFederationManager {
         Ok(registered_count)
     }
 
+    /// Register tools from the persisted catalog for configured servers
+    ///
+    /// Restored entries seed the in-memory cache, so a live sync that sees the same
+    /// spec hash leaves the registry untouched.
+    async fn load_persisted_catalog(&self) -> Result<usize> {
+        let Some(path) = &self.settings.tool_cache_path else {
+            return Ok(0);
+        };
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read tool cache {}", path.display()))
+            }
+        };
+        let catalog: PersistedToolCatalog = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid tool cache {}", path.display()))?;
+
+        let mut restored = 0;
+        for (server_id, persisted) in catalog.servers {
+            if !self.clients.contains_key(&server_id) {
+                continue;
+            }
+
+            let mut registered_count = 0;
+            for tool_data in &persisted.tools {
+                if let Ok(tool_spec) = Self::parse_tool_spec(tool_data, &server_id) {
+                    if self.tool_registry.register_tool(tool_spec).await.is_ok() {
+                        registered_count += 1;
+                    }
+                }
+            }
+
+            debug!(
+                "Restored {} cached tools for server: {}",
+                registered_count, server_id
+            );
+            self.tool_cache.insert(
+                server_id,
+                ToolCacheEntry {
+                    spec_hash: persisted.spec_hash,
+                    expires_at: Instant::now()
+                        + Duration::from_secs(self.settings.tool_cache_ttl_seconds.max(1)),
+                    tool_count: registered_count,
+                    tools: persisted.tools,
+                },
+            );
+            restored += registered_count;
+        }
+
+        Ok(restored)
+    }
+
+    /// Write the cached catalog to `tool_cache_path`, if configured
+    async fn persist_tool_cache(
+        settings: &FederationSettings,
+        tool_cache: &DashMap<String, ToolCacheEntry>,
+    ) {
+        let Some(path) = &settings.tool_cache_path else {
+            return;
+        };
+
+        let catalog = PersistedToolCatalog {
+            servers: tool_cache
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.key().clone(),
+                        PersistedServerTools {
+                            spec_hash: entry.spec_hash.clone(),
+                            tools: entry.tools.clone(),
+                        },
+                    )
+                })
+                .collect(),
+        };
+
+        if let Err(e) = write_catalog_file(path, &catalog).await {
+            warn!(
+                "⚠️ Failed to persist tool cache to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
     /// Parse tool specification from JSON
     fn parse_tool_spec(tool_data: &serde_json::Value, server_id: &str) -> Result<ToolSpec> {
         let name = tool_data
@@ -682,13 +1138,54 @@ impl McpFederationManager {
         }
     }
 
+    /// Configured downstream id for a proxy target given as a server id or URL
+    pub fn resolve_server(&self, target: &str) -> Option<String> {
+        if self.server_configs.contains_key(target) {
+            return Some(target.to_string());
+        }
+        let target = target.trim_end_matches('/');
+        self.server_configs
+            .iter()
+            .find(|entry| entry.value().url.trim_end_matches('/') == target)
+            .map(|entry| entry.key().clone())
+    }
+
+    /// Forward a tool call to a specific downstream server, bypassing catalog routing
+    pub async fn call_server_tool(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        arguments: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.forward_to_downstream(server_id, tool_name, arguments)
+            .await
+    }
+
     /// Forward tool call to downstream server
+    ///
+    /// Failures reaching the server are returned as a [`FederationError`] inside the
+    /// `anyhow::Error`, so callers can downcast to tell retryable from permanent ones.
     async fn forward_to_downstream(
         &self,
         server_id: &str,
         tool_name: &str,
         arguments: serde_json::Value,
     ) -> Result<serde_json::Value> {
+        if let Some(cached) = self
+            .result_cache
+            .as_ref()
+            .and_then(|cache| cache.get(tool_name, &arguments))
+        {
+            debug!("Result cache hit for '{}'", tool_name);
+            return Ok(cached);
+        }
+
+        // Counted before the drain check so a drain never misses a call it let through
+        let _in_flight = InFlightCall::start(&self.in_flight, server_id);
+        if self.draining.contains(server_id) {
+            return Err(anyhow::anyhow!("Server '{}' is draining", server_id));
+        }
+
         let client = self
             .clients
             .get(server_id)
@@ -717,10 +1214,11 @@ impl McpFederationManager {
                 metrics_guard.circuit_open_skips =
                     metrics_guard.circuit_open_skips.saturating_add(1);
             }
-            return Err(anyhow::anyhow!(format!(
-                "Circuit open for server '{}' (retry in {:?})",
-                server_id, retry_after
-            )));
+            return Err(FederationError::CircuitOpen {
+                server_id: server_id.to_string(),
+                retry_after,
+            }
+            .into());
         }
 
         debug!(
@@ -728,80 +1226,82 @@ impl McpFederationManager {
             tool_name, server_id
         );
 
-        let max_attempts = std::cmp::max(1, self.settings.max_retries) as u32;
+        let max_attempts = BackoffPolicy::from_settings(&self.settings).max_retries;
         let mut attempt = 0u32;
-        let mut last_error: Option<anyhow::Error> = None;
+        let mut last_error: Option<FederationError> = None;
 
         while attempt <= max_attempts {
             let call_result = {
                 let client_guard = downstream.read().await;
                 if !client_guard.is_connected().await {
-                    let message = format!("Server '{}' is not connected", server_id);
+                    // A retry finding the connection gone reports what broke it
+                    let error = last_error
+                        .take()
+                        .unwrap_or_else(|| FederationError::NotConnected(server_id.to_string()));
                     record_failure_shared(
                         &self.failure_tracker,
                         &self.metrics,
                         server_id,
                         &self.settings,
-                        &message,
+                        &error.to_string(),
                     )
                     .await;
-                    return Err(anyhow::anyhow!(message));
+                    return Err(error.into());
                 }
                 client_guard.call_tool(tool_name, arguments.clone()).await
             };
 
-            match call_result {
-                Ok(response) => {
-                    if let Some(error) = response.error {
-                        let message = format!("Downstream error: {}", error.message);
-                        let circuit_duration = record_failure_shared(
-                            &self.failure_tracker,
-                            &self.metrics,
-                            server_id,
-                            &self.settings,
-                            &message,
-                        )
-                        .await;
-                        last_error = Some(anyhow::anyhow!(message.clone()));
-
-                        if let Some(duration) = circuit_duration {
-                            return Err(anyhow::anyhow!(format!(
-                                "Circuit opened for server '{}' ({:?}) after downstream error",
-                                server_id, duration
-                            )));
-                        }
-                    } else {
+            let error = match call_result {
+                Ok(response) => match response.error {
+                    Some(error) => FederationError::Downstream {
+                        code: error.code,
+                        message: error.message,
+                    },
+                    None => {
                         record_success_shared(&self.failure_tracker, &self.metrics, server_id)
                             .await;
                         {
                             let mut metrics = self.metrics.write().await;
                             metrics.tool_calls_forwarded += 1;
                         }
-                        return Ok(response
+                        let result = response
                             .result
-                            .unwrap_or(serde_json::json!({"status": "success"})));
+                            .unwrap_or(serde_json::json!({"status": "success"}));
+                        if let Some(cache) = &self.result_cache {
+                            cache.insert(server_id, tool_name, &arguments, &result);
+                        }
+                        return Ok(result);
                     }
+                },
+                Err(err) if err.downcast_ref::<RequestTimeout>().is_some() => {
+                    FederationError::Timeout(server_id.to_string())
                 }
-                Err(err) => {
-                    let message = format!("Failed to forward to {}: {}", server_id, err);
-                    let circuit_duration = record_failure_shared(
-                        &self.failure_tracker,
-                        &self.metrics,
-                        server_id,
-                        &self.settings,
-                        &message,
-                    )
-                    .await;
-                    last_error = Some(anyhow::anyhow!(message.clone()));
+                Err(err) => FederationError::Transport {
+                    server_id: server_id.to_string(),
+                    message: err.to_string(),
+                },
+            };
 
-                    if let Some(duration) = circuit_duration {
-                        return Err(anyhow::anyhow!(format!(
-                            "Circuit opened for server '{}' ({:?}) after transport error",
-                            server_id, duration
-                        )));
-                    }
+            let circuit_duration = record_failure_shared(
+                &self.failure_tracker,
+                &self.metrics,
+                server_id,
+                &self.settings,
+                &error.to_string(),
+            )
+            .await;
+            if let Some(duration) = circuit_duration {
+                warn!(
+                    "Circuit opened for server '{}' ({:?}) after: {}",
+                    server_id, duration, error
+                );
+                return Err(FederationError::CircuitOpen {
+                    server_id: server_id.to_string(),
+                    retry_after: Some(duration),
                 }
+                .into());
             }
+            last_error = Some(error);
 
             attempt = attempt.saturating_add(1);
             if attempt > max_attempts {
@@ -809,15 +1309,22 @@ impl McpFederationManager {
             }
 
             let backoff = compute_backoff_duration(&self.settings, attempt);
+            {
+                let mut metrics = self.metrics.write().await;
+                metrics.federation_retries_total =
+                    metrics.federation_retries_total.saturating_add(1);
+            }
             tokio::time::sleep(backoff).await;
         }
 
-        Err(last_error.unwrap_or_else(|| {
-            anyhow::anyhow!(format!(
+        Err(match last_error {
+            Some(error) => error.into(),
+            None => anyhow::anyhow!(
                 "Unknown downstream error while calling '{}' on {}",
-                tool_name, server_id
-            ))
-        }))
+                tool_name,
+                server_id
+            ),
+        })
     }
 
     /// Start periodic sync task
@@ -832,22 +1339,27 @@ impl McpFederationManager {
 
         let sync_task = tokio::spawn(async move {
             let mut interval_timer = tokio::time::interval(interval);
+            let mut tick = 0usize;
 
             loop {
                 interval_timer.tick().await;
 
                 debug!("🔄 Periodic federation sync starting...");
 
-                // Sync all servers
+                let mut server_ids: Vec<String> =
+                    clients.iter().map(|entry| entry.key().clone()).collect();
+                server_ids.sort();
+                let due = servers_for_tick(settings.sync_strategy, &server_ids, tick);
+                tick = tick.wrapping_add(1);
+
+                // Sync the servers due on this tick
                 let sync_start = std::time::Instant::now();
                 let mut total_tools = 0;
                 let mut errors = 0;
 
-                for client_entry in clients.iter() {
-                    let server_id = client_entry.key().clone();
-                    let client = Arc::clone(client_entry.value());
-
-                    match Self::sync_server_tools(
+                let syncs = due.into_iter().filter_map(|server_id| {
+                    let client = clients.get(&server_id).map(|c| Arc::clone(c.value()))?;
+                    Some(Self::sync_server_tools(
                         server_id,
                         client,
                         Arc::clone(&registry),
@@ -855,9 +1367,10 @@ impl McpFederationManager {
                         Arc::clone(&metrics),
                         Arc::clone(&failure_tracker),
                         settings.clone(),
-                    )
-                    .await
-                    {
+                    ))
+                });
+                for result in futures::future::join_all(syncs).await {
+                    match result {
                         Ok(count) => total_tools += count,
                         Err(e) => {
                             error!("Periodic sync error: {}", e);
@@ -866,6 +1379,8 @@ impl McpFederationManager {
                     }
                 }
 
+                Self::persist_tool_cache(&settings, &tool_cache).await;
+
                 let sync_duration = sync_start.elapsed();
 
                 {
@@ -892,6 +1407,16 @@ impl McpFederationManager {
         self.metrics.read().await.clone()
     }
 
+    /// Backoff applied between retries of forwarded calls
+    pub fn backoff_policy(&self) -> BackoffPolicy {
+        BackoffPolicy::from_settings(&self.settings)
+    }
+
+    /// Jittered delay before reconnect attempt `attempt + 1`
+    pub fn reconnect_backoff(&self, attempt: u32) -> Duration {
+        compute_backoff_duration(&self.settings, attempt)
+    }
+
     /// Get connection health for all servers
     pub async fn get_connection_health(&self) -> HashMap<String, crate::client::ConnectionHealth> {
         let mut health_map = HashMap::new();
@@ -916,11 +1441,9 @@ impl McpFederationManager {
 
             // Find the config for this server
             let config = self
-                .settings
-                .downstream_servers
-                .iter()
-                .find(|s| s.id == server_id)
-                .cloned();
+                .server_configs
+                .get(&server_id)
+                .map(|entry| entry.value().clone());
 
             if let Some(cfg) = config {
                 servers.push(serde_json::json!({
@@ -937,6 +1460,141 @@ impl McpFederationManager {
         servers
     }
 
+    /// Apply a new downstream server list without tearing down unchanged connections
+    ///
+    /// Added servers are connected and synced; removed servers are drained and disconnected;
+    /// changed servers are drained, then replaced. Other settings keep their startup values.
+    pub async fn reconfigure(&self, settings: FederationSettings) -> Result<()> {
+        let enabled = settings.enabled;
+        let desired: HashMap<String, DownstreamMcpServer> = settings
+            .downstream_servers
+            .into_iter()
+            .filter(|server| enabled && server.enabled)
+            .map(|server| (server.id.clone(), server))
+            .collect();
+
+        let removed: Vec<String> = self
+            .clients
+            .iter()
+            .map(|entry| entry.key().clone())
+            .filter(|server_id| !desired.contains_key(server_id))
+            .collect();
+        let mut changed = Vec::new();
+        let mut added = Vec::new();
+        for server in desired.into_values() {
+            match self.server_configs.get(&server.id).map(|c| c.clone()) {
+                Some(current) if current == server => {}
+                Some(_) => changed.push(server),
+                None => added.push(server),
+            }
+        }
+
+        info!(
+            "🔧 Reconfiguring federation: {} added, {} removed, {} updated",
+            added.len(),
+            removed.len(),
+            changed.len()
+        );
+
+        for server_id in &removed {
+            self.tool_registry.remove_tools_from_source(server_id).await;
+            self.tool_cache.remove(server_id);
+            self.retire_server(server_id).await;
+        }
+        for server in &changed {
+            self.retire_server(&server.id).await;
+        }
+
+        let mut replacements = Vec::new();
+        for server in changed.into_iter().chain(added) {
+            let mut client = McpClient::new(server.clone());
+            if let Err(e) = client.connect().await {
+                warn!(
+                    "Failed to connect to {} after reconfigure: {}",
+                    server.id, e
+                );
+            }
+            let client = Arc::new(RwLock::new(client));
+            self.clients.insert(server.id.clone(), Arc::clone(&client));
+            replacements.push((server.id.clone(), client));
+            self.server_configs.insert(server.id.clone(), server);
+        }
+
+        let syncs = replacements.into_iter().map(|(server_id, client)| {
+            Self::sync_server_tools(
+                server_id,
+                client,
+                Arc::clone(&self.tool_registry),
+                Arc::clone(&self.tool_cache),
+                Arc::clone(&self.metrics),
+                Arc::clone(&self.failure_tracker),
+                self.settings.clone(),
+            )
+        });
+        for result in futures::future::join_all(syncs).await {
+            if let Err(e) = result {
+                warn!("Sync after reconfigure failed: {}", e);
+            }
+        }
+        Self::persist_tool_cache(&self.settings, &self.tool_cache).await;
+
+        let mut active_connections = 0;
+        for entry in self.clients.iter() {
+            if entry.value().read().await.is_connected().await {
+                active_connections += 1;
+            }
+        }
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.active_connections = active_connections;
+            metrics.total_servers = self.clients.len();
+        }
+
+        Ok(())
+    }
+
+    /// Stop routing to a server, wait for its in-flight calls, then disconnect it
+    async fn retire_server(&self, server_id: &str) {
+        self.draining.insert(server_id.to_string());
+        let drained = self.wait_for_in_flight(server_id).await;
+
+        if let Some((_, client)) = self.clients.remove(server_id) {
+            let id = server_id.to_string();
+            let disconnect = async move {
+                if let Err(e) = client.write().await.disconnect().await {
+                    warn!("Error disconnecting from {}: {}", id, e);
+                }
+            };
+            if drained {
+                disconnect.await;
+            } else {
+                warn!(
+                    "⏱️ Drain of {} timed out; disconnecting once remaining calls finish",
+                    server_id
+                );
+                tokio::spawn(disconnect);
+            }
+        }
+
+        self.server_configs.remove(server_id);
+        self.failure_tracker.remove(server_id);
+        self.draining.remove(server_id);
+    }
+
+    /// Wait until no calls to `server_id` are in flight; false if the drain timeout passed first
+    async fn wait_for_in_flight(&self, server_id: &str) -> bool {
+        let deadline = Instant::now() + Duration::from_millis(self.settings.drain_timeout_ms);
+        loop {
+            if self.in_flight.get(server_id).map_or(0, |count| *count) == 0 {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    }
+
     /// Shutdown federation manager
     pub async fn shutdown(&mut self) -> Result<()> {
         info!("🛑 Shutting down MCP Federation...");
@@ -1010,4 +1668,512 @@ mod tests {
         assert_eq!(spec.description, "A test tool");
         assert!(matches!(spec.source, ToolSource::Federated { .. }));
     }
+
+    #[tokio::test]
+    async fn test_persisted_catalog_serves_tools_before_live_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = FederationSettings {
+            enabled: true,
+            tool_cache_path: Some(dir.path().join("cache/tools.json")),
+            downstream_servers: vec![crate::config::DownstreamMcpServer {
+                id: "alpha".to_string(),
+                name: "Alpha".to_string(),
+                url: "ws://127.0.0.1:9".to_string(),
+                connection_type: "websocket".to_string(),
+                enabled: true,
+                timeout_ms: 100,
+                priority: 1,
+                auth: None,
+            }],
+            ..Default::default()
+        };
+
+        // First process: a completed sync leaves the catalog on disk
+        let first = McpFederationManager::new(settings.clone(), Arc::new(ToolRegistry::new()));
+        first.tool_cache.insert(
+            "alpha".to_string(),
+            ToolCacheEntry {
+                spec_hash: "abc123".to_string(),
+                expires_at: Instant::now(),
+                tool_count: 1,
+                tools: vec![serde_json::json!({
+                    "name": "alpha_search",
+                    "description": "Search via alpha",
+                    "inputSchema": {"type": "object"}
+                })],
+            },
+        );
+        McpFederationManager::persist_tool_cache(&first.settings, &first.tool_cache).await;
+        drop(first);
+
+        // Restart: alpha is unreachable, so only the persisted catalog can supply tools
+        let registry = Arc::new(ToolRegistry::new());
+        let mut restarted = McpFederationManager::new(settings, Arc::clone(&registry));
+        restarted.initialize().await.unwrap();
+
+        let tool = registry
+            .get_tool("alpha_search")
+            .expect("cached tool is served");
+        assert!(matches!(
+            &tool.source,
+            ToolSource::Federated { server_id, .. } if server_id == "alpha"
+        ));
+        assert_eq!(
+            restarted
+                .tool_cache
+                .get("alpha")
+                .map(|e| e.spec_hash.clone()),
+            Some("abc123".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeated_federated_call_is_served_from_result_cache() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Downstream that answers every request and counts the tool calls it sees
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let tool_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server = tokio::spawn({
+            let tool_calls = Arc::clone(&tool_calls);
+            async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if request["method"] == "tools/call" {
+                        tool_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": { "content": [{ "type": "text", "text": "fresh" }] }
+                    });
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let settings = FederationSettings {
+            enabled: true,
+            catalog_refresh_interval: 0,
+            result_cache: Some(ResultCacheSettings {
+                tools: vec!["cached_search".to_string()],
+                ttl_seconds: 60,
+                max_entries: 8,
+            }),
+            downstream_servers: vec![crate::config::DownstreamMcpServer {
+                id: "reader".to_string(),
+                name: "Reader".to_string(),
+                url: format!("ws://{}", addr),
+                connection_type: "websocket".to_string(),
+                enabled: true,
+                timeout_ms: 1_000,
+                priority: 1,
+                auth: None,
+            }],
+            ..Default::default()
+        };
+        let registry = Arc::new(ToolRegistry::new());
+        let mut manager = McpFederationManager::new(settings, Arc::clone(&registry));
+        manager.initialize().await.unwrap();
+        manager.connect_all().await.unwrap();
+        let tool_data = serde_json::json!({
+            "name": "cached_search",
+            "description": "Idempotent search",
+            "inputSchema": { "type": "object" }
+        });
+        registry
+            .register_tool(McpFederationManager::parse_tool_spec(&tool_data, "reader").unwrap())
+            .await
+            .unwrap();
+
+        let call = |query: &str| {
+            manager.route_tool_call(
+                "cached_search",
+                serde_json::json!({ "query": query }),
+                ExecutionMode::Execute,
+            )
+        };
+        let first = call("rust").await.unwrap();
+        let second = call("rust").await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(tool_calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        call("tokio").await.unwrap();
+        assert_eq!(tool_calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Injected context changes on every call but doesn't make the call different
+        for now in ["2025-01-01T00:00:00Z", "2025-01-01T00:00:01Z"] {
+            manager
+                .route_tool_call(
+                    "cached_search",
+                    serde_json::json!({
+                        "query": "rust",
+                        "casial_context": "Cite sources.",
+                        "_shim_context": { "current_date": now }
+                    }),
+                    ExecutionMode::Execute,
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(tool_calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_drains_in_flight_call_before_removing_server() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Downstream that signals when a tool call arrives, then answers it slowly
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let call_started = Arc::new(tokio::sync::Notify::new());
+        let server = tokio::spawn({
+            let call_started = Arc::clone(&call_started);
+            async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if request["method"] == "tools/call" {
+                        call_started.notify_one();
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                    }
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": { "content": [{ "type": "text", "text": "slow answer" }] }
+                    });
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let settings = FederationSettings {
+            enabled: true,
+            catalog_refresh_interval: 0,
+            downstream_servers: vec![DownstreamMcpServer {
+                id: "slow".to_string(),
+                name: "Slow".to_string(),
+                url: format!("ws://{}", addr),
+                connection_type: "websocket".to_string(),
+                enabled: true,
+                timeout_ms: 2_000,
+                priority: 1,
+                auth: None,
+            }],
+            ..Default::default()
+        };
+        let registry = Arc::new(ToolRegistry::new());
+        let mut manager = McpFederationManager::new(settings.clone(), Arc::clone(&registry));
+        manager.initialize().await.unwrap();
+        manager.connect_all().await.unwrap();
+        let tool_data = serde_json::json!({
+            "name": "slow_search",
+            "description": "Slow search",
+            "inputSchema": { "type": "object" }
+        });
+        registry
+            .register_tool(McpFederationManager::parse_tool_spec(&tool_data, "slow").unwrap())
+            .await
+            .unwrap();
+        let manager = Arc::new(manager);
+
+        let call = tokio::spawn({
+            let manager = Arc::clone(&manager);
+            async move {
+                manager
+                    .route_tool_call(
+                        "slow_search",
+                        serde_json::json!({ "query": "rust" }),
+                        ExecutionMode::Execute,
+                    )
+                    .await
+            }
+        });
+        call_started.notified().await;
+
+        let reconfigure_start = Instant::now();
+        manager
+            .reconfigure(FederationSettings {
+                downstream_servers: vec![],
+                ..settings
+            })
+            .await
+            .unwrap();
+
+        assert!(reconfigure_start.elapsed() >= Duration::from_millis(100));
+        let result = call.await.unwrap().unwrap();
+        assert_eq!(result["content"][0]["text"], "slow answer");
+        assert!(manager.clients.is_empty());
+        assert!(registry.get_tool("slow_search").is_none());
+        assert_eq!(manager.get_metrics().await.total_servers, 0);
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_forwarding_failures_map_to_federation_errors() {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        // Downstream whose behaviour for a tool call depends on the query
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                let response = match request["params"]["arguments"]["query"].as_str() {
+                    Some("fails") => serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": { "code": -32042, "message": "quota exceeded" }
+                    }),
+                    Some("hangs") => continue,
+                    Some("drops") => break,
+                    _ => serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": {} }),
+                };
+                ws.send(Message::Text(response.to_string())).await.unwrap();
+            }
+        });
+
+        let settings = FederationSettings {
+            enabled: true,
+            catalog_refresh_interval: 0,
+            max_retries: 0,
+            circuit_breaker_threshold: 100,
+            backoff_initial_ms: 10,
+            downstream_servers: vec![DownstreamMcpServer {
+                id: "flaky".to_string(),
+                name: "Flaky".to_string(),
+                url: format!("ws://{}", addr),
+                connection_type: "websocket".to_string(),
+                enabled: true,
+                timeout_ms: 100,
+                priority: 1,
+                auth: None,
+            }],
+            ..Default::default()
+        };
+        let mut manager = McpFederationManager::new(settings, Arc::new(ToolRegistry::new()));
+        manager.initialize().await.unwrap();
+        manager.connect_all().await.unwrap();
+
+        let call = |query: &'static str| {
+            let manager = &manager;
+            async move {
+                let err = manager
+                    .call_server_tool("flaky", "search", serde_json::json!({ "query": query }))
+                    .await
+                    .unwrap_err();
+                err.downcast::<FederationError>().unwrap()
+            }
+        };
+
+        let downstream = call("fails").await;
+        assert_eq!(
+            downstream,
+            FederationError::Downstream {
+                code: -32042,
+                message: "quota exceeded".to_string()
+            }
+        );
+        assert_eq!(downstream.json_rpc_code(), -32042);
+        assert!(!downstream.retryable());
+
+        let timeout = call("hangs").await;
+        assert_eq!(timeout, FederationError::Timeout("flaky".to_string()));
+        assert_eq!(timeout.json_rpc_code(), -32004);
+
+        assert!(matches!(
+            call("drops").await,
+            FederationError::Transport { ref server_id, .. } if server_id == "flaky"
+        ));
+        assert_eq!(
+            call("rust").await,
+            FederationError::NotConnected("flaky".to_string())
+        );
+
+        manager.failure_tracker.insert(
+            "flaky".to_string(),
+            CircuitState {
+                failure_count: 100,
+                last_failure: Some(Instant::now()),
+                open_until: Some(Instant::now() + Duration::from_secs(30)),
+                reset_after: Duration::from_secs(30),
+            },
+        );
+        let circuit_open = call("rust").await;
+        assert!(matches!(
+            circuit_open,
+            FederationError::CircuitOpen {
+                retry_after: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(circuit_open.json_rpc_code(), -32005);
+        assert!(
+            circuit_open.to_error_data()["retry_after_ms"]
+                .as_u64()
+                .unwrap()
+                > 0
+        );
+        let error = circuit_open
+            .to_response(serde_json::json!(7), "rust_search")
+            .error
+            .unwrap();
+        assert_eq!(error.code, -32005);
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], "circuit_open");
+        assert_eq!(data["kind"], "circuit_open");
+        assert_eq!(data["tool"], "rust_search");
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_counted_and_backoff_is_reported() {
+        use futures::StreamExt;
+
+        // Downstream that accepts calls and never answers them
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = ws.next().await {}
+        });
+
+        let settings = FederationSettings {
+            enabled: true,
+            catalog_refresh_interval: 0,
+            max_retries: 2,
+            circuit_breaker_threshold: 100,
+            backoff_initial_ms: 1,
+            backoff_max_ms: 40,
+            downstream_servers: vec![DownstreamMcpServer {
+                id: "silent".to_string(),
+                name: "Silent".to_string(),
+                url: format!("ws://{}", addr),
+                connection_type: "websocket".to_string(),
+                enabled: true,
+                timeout_ms: 50,
+                priority: 1,
+                auth: None,
+            }],
+            ..Default::default()
+        };
+        let mut manager = McpFederationManager::new(settings, Arc::new(ToolRegistry::new()));
+        manager.initialize().await.unwrap();
+        manager.connect_all().await.unwrap();
+
+        let err = manager
+            .call_server_tool("silent", "search", serde_json::json!({ "query": "rust" }))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<FederationError>().unwrap(),
+            FederationError::Timeout("silent".to_string())
+        );
+
+        let metrics = manager.get_metrics().await;
+        assert_eq!(metrics.federation_retries_total, 2);
+        assert!(metrics
+            .export_prometheus()
+            .contains("casial_federation_retries_total 2"));
+
+        // The 1ms initial backoff is raised to the 10ms floor
+        assert_eq!(
+            manager.backoff_policy(),
+            BackoffPolicy {
+                initial_ms: 10,
+                max_ms: 40,
+                max_jitter_ms: 10,
+                max_retries: 2
+            }
+        );
+        server.abort();
+    }
+
+    #[test]
+    fn test_staggered_sync_visits_one_server_per_tick() {
+        let server_ids: Vec<String> = ["alpha", "beta", "gamma"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+
+        assert_eq!(
+            servers_for_tick(SyncStrategy::Parallel, &server_ids, 0),
+            server_ids
+        );
+
+        let ticks: Vec<Vec<String>> = (0..4)
+            .map(|tick| servers_for_tick(SyncStrategy::Staggered, &server_ids, tick))
+            .collect();
+        assert!(ticks.iter().all(|due| due.len() == 1));
+        assert_eq!(
+            ticks.concat(),
+            vec!["alpha", "beta", "gamma", "alpha"]
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        );
+        assert!(servers_for_tick(SyncStrategy::Staggered, &[], 5).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_startup_retries_until_slow_downstream_accepts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        // Still booting on the first attempt: the socket is dropped before the handshake
+        let server = tokio::spawn({
+            let accepted = Arc::clone(&accepted);
+            async move {
+                let (refused, _) = listener.accept().await.unwrap();
+                accepted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                drop(refused);
+
+                let (stream, _) = listener.accept().await.unwrap();
+                accepted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let _ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                std::future::pending::<()>().await;
+            }
+        });
+
+        let settings = FederationSettings {
+            enabled: true,
+            catalog_refresh_interval: 0,
+            backoff_initial_ms: 10,
+            backoff_max_ms: 20,
+            startup_connect_retries: 3,
+            downstream_servers: vec![crate::config::DownstreamMcpServer {
+                id: "slow".to_string(),
+                name: "Slow".to_string(),
+                url: format!("ws://{}", addr),
+                connection_type: "websocket".to_string(),
+                enabled: true,
+                timeout_ms: 100,
+                priority: 1,
+                auth: None,
+            }],
+            ..Default::default()
+        };
+        let mut manager = McpFederationManager::new(settings, Arc::new(ToolRegistry::new()));
+        manager.initialize().await.unwrap();
+
+        assert_eq!(manager.connect_with_retries().await, 1);
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // A connected client is not torn down and dialled again
+        manager.connect_all().await.unwrap();
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 2);
+        server.abort();
+    }
 }
@@ -4,27 +4,35 @@
 //! to enable registration and operation with Smithery.ai and other MCP clients.
 
 use anyhow::Result;
+use arc_swap::ArcSwap;
 use axum::{
     extract::{Query, State},
     http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
-    response::{sse::Event, IntoResponse, Response, Sse},
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
     Json,
 };
-use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use dashmap::DashMap;
+use base64::{
+    engine::general_purpose::{
+        GeneralPurpose, STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD,
+    },
+    Engine,
+};
+use casial_core::{sanitize_paradox_tolerance, CoordinationRequest, CoordinationResult};
+use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{convert::Infallible, sync::Arc};
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, error, info, warn};
 
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowCredentials, AllowOrigin, CorsLayer};
 
 const ALLOWED_METHODS: &str = "GET, POST, DELETE, HEAD, OPTIONS";
 const ALLOWED_HEADERS: &str =
-    "Content-Type, Authorization, Accept, Cache-Control, Mcp-Session-Id, Mcp-Protocol-Version";
+    "Content-Type, Authorization, Accept, Cache-Control, Mcp-Session-Id, Mcp-Protocol-Version, Idempotency-Key";
 const EXPOSED_HEADERS: &str = "Mcp-Session-Id, Mcp-Protocol-Version";
 
 /// Global CORS policy shared across manual responses
@@ -37,12 +45,29 @@ pub struct CorsPolicy {
 #[derive(Debug, Clone)]
 enum OriginPolicy {
     Any,
-    List(Vec<HeaderValue>),
+    List(Vec<AllowedOrigin>),
+}
+
+/// A listed origin and whether responses to it may carry credentials
+#[derive(Debug, Clone)]
+struct AllowedOrigin {
+    origin: HeaderValue,
+    credentials: bool,
 }
 
+/// `ALLOWED_ORIGINS` suffix granting an origin credentials, e.g. `https://internal.app|creds`
+const CREDENTIALS_FLAG: &str = "creds";
+
 impl CorsPolicy {
     fn from_env() -> Self {
-        let allowed_origins = std::env::var("ALLOWED_ORIGINS").unwrap_or_default();
+        Self::parse(&std::env::var("ALLOWED_ORIGINS").unwrap_or_default())
+    }
+
+    /// Parse an `ALLOWED_ORIGINS` value
+    ///
+    /// Once any origin is marked `|creds`, only marked origins get credentials; a list
+    /// without markers allows credentials for every listed origin.
+    fn parse(allowed_origins: &str) -> Self {
         let allowed_origins = allowed_origins.trim();
 
         if allowed_origins.is_empty() {
@@ -63,15 +88,34 @@ impl CorsPolicy {
             };
         }
 
-        let origins: Vec<HeaderValue> = allowed_origins
+        let entries: Vec<(&str, Option<&str>)> = allowed_origins
             .split(',')
             .map(str::trim)
             .filter(|s| !s.is_empty())
-            .filter_map(|origin| match origin.parse::<HeaderValue>() {
-                Ok(value) => Some(value),
-                Err(e) => {
-                    tracing::error!("Failed to parse allowed origin '{}': {}", origin, e);
-                    None
+            .map(|entry| match entry.split_once('|') {
+                Some((origin, flag)) => (origin.trim(), Some(flag.trim())),
+                None => (entry, None),
+            })
+            .collect();
+        let flagged = entries
+            .iter()
+            .any(|(_, flag)| *flag == Some(CREDENTIALS_FLAG));
+
+        let origins: Vec<AllowedOrigin> = entries
+            .into_iter()
+            .filter_map(|(origin, flag)| {
+                if let Some(flag) = flag.filter(|flag| *flag != CREDENTIALS_FLAG) {
+                    tracing::warn!("Ignoring unknown flag '{}' for origin '{}'", flag, origin);
+                }
+                match origin.parse::<HeaderValue>() {
+                    Ok(value) => Some(AllowedOrigin {
+                        origin: value,
+                        credentials: !flagged || flag == Some(CREDENTIALS_FLAG),
+                    }),
+                    Err(e) => {
+                        tracing::error!("Failed to parse allowed origin '{}': {}", origin, e);
+                        None
+                    }
                 }
             })
             .collect();
@@ -86,47 +130,96 @@ impl CorsPolicy {
             }
         } else {
             Self {
+                allow_credentials: origins.iter().any(|origin| origin.credentials),
                 origin_policy: OriginPolicy::List(origins),
-                allow_credentials: true,
             }
         }
     }
 
-    fn resolve_origin(&self, request_headers: &HeaderMap) -> Option<HeaderValue> {
+    /// The `Access-Control-Allow-Origin` value for a request, and whether it may carry credentials
+    fn resolve_origin(&self, request_headers: &HeaderMap) -> Option<(HeaderValue, bool)> {
         match &self.origin_policy {
-            OriginPolicy::Any => Some(HeaderValue::from_static("*")),
+            OriginPolicy::Any => Some((HeaderValue::from_static("*"), false)),
             OriginPolicy::List(allowed) => {
-                if let Some(request_origin) = request_headers
-                    .get(header::ORIGIN)
-                    .and_then(|value| value.to_str().ok())
-                {
-                    if let Some(matching) = allowed
-                        .iter()
-                        .find(|origin| origin.as_bytes() == request_origin.as_bytes())
-                    {
-                        return Some(matching.clone());
-                    }
-                }
-                None
+                let request_origin = request_headers.get(header::ORIGIN)?;
+                allowed
+                    .iter()
+                    .find(|allowed| allowed.origin.as_bytes() == request_origin.as_bytes())
+                    .map(|allowed| (allowed.origin.clone(), allowed.credentials))
             }
         }
     }
 
-    fn allow_credentials(&self) -> bool {
-        self.allow_credentials
+    /// Allowed origins and their credential flags, for the admin reload endpoint
+    pub fn summary(&self) -> Value {
+        match &self.origin_policy {
+            OriginPolicy::Any => json!({ "origins": "*", "allow_credentials": false }),
+            OriginPolicy::List(origins) => json!({
+                "origins": origins
+                    .iter()
+                    .map(|allowed| json!({
+                        "origin": allowed.origin.to_str().unwrap_or_default(),
+                        "credentials": allowed.credentials
+                    }))
+                    .collect::<Vec<_>>(),
+                "allow_credentials": self.allow_credentials
+            }),
+        }
+    }
+
+    fn apply_headers(&self, headers: &mut HeaderMap, request_headers: &HeaderMap) {
+        if let Some((origin, credentials)) = self.resolve_origin(request_headers) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+
+            if credentials {
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
+                );
+            } else {
+                headers.remove(header::ACCESS_CONTROL_ALLOW_CREDENTIALS);
+            }
+        } else {
+            headers.remove(header::ACCESS_CONTROL_ALLOW_ORIGIN);
+            headers.remove(header::ACCESS_CONTROL_ALLOW_CREDENTIALS);
+        }
+
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static(ALLOWED_METHODS),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static(ALLOWED_HEADERS),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static(EXPOSED_HEADERS),
+        );
+        headers.insert(header::VARY, HeaderValue::from_static("Origin"));
     }
 }
 
-static CORS_POLICY: Lazy<CorsPolicy> = Lazy::new(CorsPolicy::from_env);
+static CORS_POLICY: Lazy<ArcSwap<CorsPolicy>> =
+    Lazy::new(|| ArcSwap::from_pointee(CorsPolicy::from_env()));
 
-/// Access the configured global CORS policy
-pub fn cors_policy() -> &'static CorsPolicy {
-    &CORS_POLICY
+/// Access the current global CORS policy
+pub fn cors_policy() -> Arc<CorsPolicy> {
+    CORS_POLICY.load_full()
+}
+
+/// Re-read `ALLOWED_ORIGINS` and swap the new policy in for subsequent requests
+pub fn reload_cors_policy() -> Arc<CorsPolicy> {
+    let policy = Arc::new(CorsPolicy::from_env());
+    CORS_POLICY.store(Arc::clone(&policy));
+    policy
 }
 
 /// Build a [`CorsLayer`] that mirrors the manual headers emitted elsewhere
+///
+/// Origins and credentials are checked against the current policy on every request,
+/// so a reload applies without rebuilding the router.
 pub fn build_cors_layer() -> CorsLayer {
-    let policy = cors_policy().clone();
     let allow_headers = vec![
         header::CONTENT_TYPE,
         header::AUTHORIZATION,
@@ -134,6 +227,7 @@ pub fn build_cors_layer() -> CorsLayer {
         header::CACHE_CONTROL,
         HeaderName::from_static("mcp-session-id"),
         HeaderName::from_static("mcp-protocol-version"),
+        HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
     ];
 
     let methods = [
@@ -144,235 +238,2007 @@ pub fn build_cors_layer() -> CorsLayer {
         Method::OPTIONS,
     ];
 
-    match &policy.origin_policy {
-        OriginPolicy::Any => CorsLayer::new()
-            .allow_origin(Any)
-            .allow_methods(methods)
-            .allow_headers(allow_headers)
-            .expose_headers([
-                HeaderName::from_static("mcp-session-id"),
-                HeaderName::from_static("mcp-protocol-version"),
-            ]),
-        OriginPolicy::List(origins) => {
-            let mut layer = CorsLayer::new()
-                .allow_origin(origins.clone())
-                .allow_methods(methods)
-                .allow_headers(allow_headers)
-                .expose_headers([
-                    HeaderName::from_static("mcp-session-id"),
-                    HeaderName::from_static("mcp-protocol-version"),
-                ]);
-
-            if policy.allow_credentials() {
-                layer = layer.allow_credentials(true);
-            }
+    let origin_request = |origin: &HeaderValue| {
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::ORIGIN, origin.clone());
+        cors_policy().resolve_origin(&request_headers)
+    };
 
-            layer
-        }
-    }
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(move |origin, _| {
+            origin_request(origin).is_some()
+        }))
+        .allow_credentials(AllowCredentials::predicate(move |origin, _| {
+            origin_request(origin).is_some_and(|(_, credentials)| credentials)
+        }))
+        .allow_methods(methods)
+        .allow_headers(allow_headers)
+        .expose_headers([
+            HeaderName::from_static("mcp-session-id"),
+            HeaderName::from_static("mcp-protocol-version"),
+        ])
 }
 
 /// Apply manual CORS headers to a response
 pub fn apply_cors_headers(headers: &mut HeaderMap, request_headers: &HeaderMap) {
-    let policy = cors_policy();
-    if let Some(origin) = policy.resolve_origin(request_headers) {
-        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
-
-        if policy.allow_credentials() {
-            headers.insert(
-                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
-                HeaderValue::from_static("true"),
-            );
-        } else {
-            headers.remove(header::ACCESS_CONTROL_ALLOW_CREDENTIALS);
-        }
-    } else {
-        headers.remove(header::ACCESS_CONTROL_ALLOW_ORIGIN);
-        headers.remove(header::ACCESS_CONTROL_ALLOW_CREDENTIALS);
-    }
-
-    headers.insert(
-        header::ACCESS_CONTROL_ALLOW_METHODS,
-        HeaderValue::from_static(ALLOWED_METHODS),
-    );
-    headers.insert(
-        header::ACCESS_CONTROL_ALLOW_HEADERS,
-        HeaderValue::from_static(ALLOWED_HEADERS),
-    );
-    headers.insert(
-        header::ACCESS_CONTROL_EXPOSE_HEADERS,
-        HeaderValue::from_static(EXPOSED_HEADERS),
-    );
-    headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+    cors_policy().apply_headers(headers, request_headers);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        config::ServerConfig, mcp::JsonRpcRequest, pitfall_shim::PitfallAvoidanceShim, AppState,
+        config::{LargeResultSettings, LargeResultStrategy, ServerConfig},
+        mcp::JsonRpcRequest,
+        pitfall_shim::PitfallAvoidanceShim,
+        session_store::SessionStore,
+        test_fixtures, AppState,
     };
     use serde_json::json;
 
-    fn reset_env() {
-        std::env::remove_var("ALLOWED_ORIGINS");
+    /// Serializes tests that set `ALLOWED_ORIGINS` or swap the global CORS policy
+    ///
+    /// A tokio mutex, so async tests can hold it across the awaits that read the policy.
+    static CORS_ENV: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    fn reset_env() {
+        std::env::remove_var("ALLOWED_ORIGINS");
+    }
+
+    fn reset_sampling_flag() {
+        std::env::remove_var("MOP_ENABLE_SAMPLING");
+    }
+
+    #[test]
+    fn cors_policy_defaults_to_any_when_env_missing() {
+        let _env = CORS_ENV.blocking_lock();
+        reset_env();
+        let policy = CorsPolicy::from_env();
+        let origin = policy.resolve_origin(&HeaderMap::new());
+
+        assert_eq!(origin, Some((HeaderValue::from_static("*"), false)));
+        assert!(!policy.allow_credentials);
+    }
+
+    #[test]
+    fn cors_policy_matches_listed_origin() {
+        let _env = CORS_ENV.blocking_lock();
+        std::env::set_var("ALLOWED_ORIGINS", "https://example.com,https://other.test");
+        let policy = CorsPolicy::from_env();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ORIGIN,
+            HeaderValue::from_static("https://other.test"),
+        );
+
+        let origin = policy.resolve_origin(&headers);
+        assert_eq!(
+            origin,
+            Some((HeaderValue::from_static("https://other.test"), true))
+        );
+        assert!(policy.allow_credentials);
+        reset_env();
+    }
+
+    #[test]
+    fn reloaded_cors_policy_applies_without_restart() {
+        let _env = CORS_ENV.blocking_lock();
+        let response_origin = |origin: &'static str| {
+            let mut request = HeaderMap::new();
+            request.insert(header::ORIGIN, HeaderValue::from_static(origin));
+            let mut headers = HeaderMap::new();
+            apply_cors_headers(&mut headers, &request);
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).cloned()
+        };
+
+        std::env::set_var("ALLOWED_ORIGINS", "https://first.app");
+        reload_cors_policy();
+        assert_eq!(
+            response_origin("https://first.app"),
+            Some(HeaderValue::from_static("https://first.app"))
+        );
+        assert_eq!(response_origin("https://second.app"), None);
+
+        std::env::set_var("ALLOWED_ORIGINS", "https://second.app");
+        let reloaded = reload_cors_policy();
+        assert_eq!(
+            reloaded.summary()["origins"][0]["origin"],
+            "https://second.app"
+        );
+        assert_eq!(response_origin("https://first.app"), None);
+        assert_eq!(
+            response_origin("https://second.app"),
+            Some(HeaderValue::from_static("https://second.app"))
+        );
+
+        reset_env();
+        reload_cors_policy();
+    }
+
+    #[test]
+    fn cors_credentials_only_for_origins_marked_creds() {
+        let policy = CorsPolicy::parse("https://internal.app|creds, https://public.app");
+        let response_headers = |origin: &'static str| {
+            let mut request = HeaderMap::new();
+            request.insert(header::ORIGIN, HeaderValue::from_static(origin));
+            let mut headers = HeaderMap::new();
+            policy.apply_headers(&mut headers, &request);
+            headers
+        };
+
+        let internal = response_headers("https://internal.app");
+        assert_eq!(
+            internal[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://internal.app"
+        );
+        assert_eq!(internal[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+
+        let public = response_headers("https://public.app");
+        assert_eq!(
+            public[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://public.app"
+        );
+        assert!(public
+            .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+            .is_none());
+
+        let unknown = response_headers("https://evil.example");
+        assert!(unknown.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[test]
+    fn cors_context_suppresses_credentials_for_wildcard() {
+        let _env = CORS_ENV.blocking_lock();
+        std::env::set_var("ALLOWED_ORIGINS", "*");
+        let policy = CorsPolicy::from_env();
+        let origin = policy.resolve_origin(&HeaderMap::new());
+
+        let mut headers = HeaderMap::new();
+        policy.apply_headers(&mut headers, &HeaderMap::new());
+
+        assert_eq!(origin, Some((HeaderValue::from_static("*"), false)));
+        assert!(!policy.allow_credentials);
+        assert!(headers
+            .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+            .is_none());
+        reset_env();
+    }
+
+    #[test]
+    fn sampling_disabled_by_default() {
+        reset_sampling_flag();
+        assert!(!super::sampling_feature_enabled());
+    }
+
+    #[test]
+    fn sampling_enabled_for_truthy_values() {
+        for value in ["true", "1", "yes"] {
+            std::env::set_var("MOP_ENABLE_SAMPLING", value);
+            assert!(
+                super::sampling_feature_enabled(),
+                "value {:?} should enable sampling",
+                value
+            );
+        }
+        reset_sampling_flag();
+    }
+
+    #[test]
+    fn decodes_url_safe_config_without_padding() {
+        let raw = r#"{"apiKey":"DEMO_KEY_PUBLIC","agent_role":"researcher","max_context_size":5000,"mission":"research?>"}"#;
+        let encoded = URL_SAFE_NO_PAD.encode(raw);
+        assert!(encoded.contains('-') || encoded.contains('_'));
+        assert!(STANDARD.decode(&encoded).is_err());
+
+        let decoded = super::decode_base64_config(&encoded).expect("url-safe config decodes");
+        let config: SessionConfig = serde_json::from_slice(&decoded).expect("valid config json");
+
+        assert_eq!(config.api_key.as_deref(), Some("DEMO_KEY_PUBLIC"));
+        assert_eq!(config.agent_role.as_deref(), Some("researcher"));
+        assert_eq!(config.max_context_size, Some(5000));
+        assert_eq!(config.mission.as_deref(), Some("research?>"));
+    }
+
+    fn build_state() -> AppState {
+        let config = ServerConfig::default();
+        let shim = PitfallAvoidanceShim::default();
+        AppState::new(config, shim)
+    }
+
+    #[tokio::test]
+    async fn sse_stream_sends_configured_keep_alive() {
+        use futures::StreamExt;
+
+        let mut config = ServerConfig::default();
+        config.server.sse_keep_alive = crate::config::SseKeepAliveSettings {
+            interval_secs: 1,
+            text: "ping".to_string(),
+            mode: SseKeepAliveMode::Event,
+        };
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let session_id = "mop-sse-keep-alive".to_string();
+        state
+            .session_store
+            .insert(SessionData {
+                id: session_id.clone(),
+                config: SessionConfig::default(),
+                created_at: chrono::Utc::now(),
+                last_accessed: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let response = handle_get_sse(state, SessionConfig::default(), Some(session_id))
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+        let frame = tokio::time::timeout(std::time::Duration::from_secs(3), body.next())
+            .await
+            .expect("keep-alive within the configured interval")
+            .expect("stream stays open")
+            .unwrap();
+
+        assert_eq!(&frame[..], b"event: keep-alive\ndata: ping\n\n");
+    }
+
+    #[tokio::test]
+    async fn sse_response_carries_proxy_friendly_headers() {
+        let mut config = ServerConfig::default();
+        config
+            .server
+            .sse_headers
+            .insert("X-Smithery-Stream".to_string(), "1".to_string());
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let session_id = "mop-sse-headers".to_string();
+        state
+            .session_store
+            .insert(SessionData {
+                id: session_id.clone(),
+                config: SessionConfig::default(),
+                created_at: chrono::Utc::now(),
+                last_accessed: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let response = handle_get_sse(state, SessionConfig::default(), Some(session_id))
+            .await
+            .unwrap();
+        let headers = response.headers();
+        assert_eq!(headers["x-accel-buffering"], "no");
+        assert_eq!(headers["cache-control"], "no-cache");
+        assert_eq!(headers["x-smithery-stream"], "1");
+        assert_eq!(headers["content-type"], "text/event-stream");
+    }
+
+    #[tokio::test]
+    async fn resources_list_includes_tool_catalog() {
+        let state = build_state();
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: "resources/list".to_string(),
+            params: json!({}),
+        };
+
+        let response = super::handle_resources_list(&state, request).await;
+        let result = response.result.expect("expected resources payload");
+        let resources = result
+            .get("resources")
+            .and_then(|value| value.as_array())
+            .expect("resources should be an array");
+
+        assert!(resources
+            .iter()
+            .any(|entry| entry.get("uri") == Some(&json!("mop://tools/catalog"))));
+    }
+
+    fn list_request(method: &str, params: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    async fn build_state_with_tools(count: usize) -> AppState {
+        let mut state = build_state();
+        state.tool_registry = Arc::new(crate::registry::ToolRegistry::new());
+        for index in 0..count {
+            state
+                .tool_registry
+                .register_tool(crate::registry::ToolSpec {
+                    name: format!("tool_{:02}", index),
+                    description: format!("Test tool {}", index),
+                    input_schema: json!({"type": "object"}),
+                    output_schema: None,
+                    source: crate::registry::ToolSource::Local,
+                    spec_version: "1.0.0".to_string(),
+                    spec_hash: String::new(),
+                    last_updated: chrono::Utc::now(),
+                    metadata: json!({}),
+                })
+                .await
+                .unwrap();
+        }
+        state
+    }
+
+    #[tokio::test]
+    async fn tools_list_pages_through_registry_with_cursor() {
+        let state = build_state_with_tools(50).await;
+        let mut cursor: Option<String> = None;
+        let mut names = Vec::new();
+        let mut pages = 0;
+
+        loop {
+            let mut params = json!({ "limit": 10 });
+            if let Some(cursor) = &cursor {
+                params["cursor"] = json!(cursor);
+            }
+            let response =
+                super::handle_tools_list(&state, list_request("tools/list", params)).await;
+            let result = response.result.expect("expected tools page");
+            let tools = result["tools"].as_array().expect("tools array");
+            assert_eq!(tools.len(), 10);
+            names.extend(
+                tools
+                    .iter()
+                    .map(|tool| tool["name"].as_str().unwrap().to_string()),
+            );
+            pages += 1;
+
+            match result.get("nextCursor").and_then(Value::as_str) {
+                Some(next) => cursor = Some(next.to_string()),
+                None => break,
+            }
+        }
+
+        let expected: Vec<String> = (0..50).map(|index| format!("tool_{:02}", index)).collect();
+        assert_eq!(pages, 5);
+        assert_eq!(names, expected);
+    }
+
+    #[tokio::test]
+    async fn tools_list_returns_full_catalog_without_cursor() {
+        let state = build_state_with_tools(50).await;
+
+        let response =
+            super::handle_tools_list(&state, list_request("tools/list", json!({}))).await;
+        let result = response.result.expect("expected tools list");
+
+        assert_eq!(result["tools"].as_array().map(Vec::len), Some(50));
+        assert!(result.get("nextCursor").is_none());
+    }
+
+    #[tokio::test]
+    async fn list_methods_reject_malformed_cursor() {
+        let state = build_state();
+        let params = json!({ "cursor": "%%%" });
+
+        let tools =
+            super::handle_tools_list(&state, list_request("tools/list", params.clone())).await;
+        let resources =
+            super::handle_resources_list(&state, list_request("resources/list", params)).await;
+
+        assert_eq!(tools.error.map(|e| e.code), Some(-32602));
+        assert_eq!(resources.error.map(|e| e.code), Some(-32602));
+    }
+
+    #[tokio::test]
+    async fn resources_list_pages_with_limit() {
+        let state = build_state();
+
+        let first = super::handle_resources_list(
+            &state,
+            list_request("resources/list", json!({ "limit": 4 })),
+        )
+        .await
+        .result
+        .expect("expected first page");
+        let cursor = first["nextCursor"].as_str().expect("more resources remain");
+        let second = super::handle_resources_list(
+            &state,
+            list_request("resources/list", json!({ "limit": 4, "cursor": cursor })),
+        )
+        .await
+        .result
+        .expect("expected second page");
+
+        assert_eq!(first["resources"].as_array().map(Vec::len), Some(4));
+        assert_eq!(second["resources"].as_array().map(Vec::len), Some(2));
+        assert!(second.get("nextCursor").is_none());
+    }
+
+    #[tokio::test]
+    async fn resources_read_returns_catalog_contents() {
+        let state = build_state();
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(2),
+            method: "resources/read".to_string(),
+            params: json!({ "uri": "mop://tools/catalog" }),
+        };
+
+        let response = super::handle_resources_read(&state, request).await;
+        let result = response.result.expect("expected catalog result");
+        let contents = result
+            .get("contents")
+            .and_then(|value| value.as_array())
+            .expect("contents should be an array");
+        let catalog_entry = contents.first().expect("catalog entry missing");
+
+        assert_eq!(
+            catalog_entry.get("uri"),
+            Some(&json!("mop://tools/catalog"))
+        );
+
+        let payload = catalog_entry
+            .get("text")
+            .and_then(|value| value.as_str())
+            .map(|text| {
+                serde_json::from_str::<serde_json::Value>(text).expect("valid catalog json")
+            })
+            .expect("catalog text payload");
+
+        let tools = payload
+            .get("tools")
+            .and_then(|value| value.as_array())
+            .expect("tools array");
+        let total_tools = payload
+            .get("totalTools")
+            .and_then(|value| value.as_u64())
+            .expect("totalTools count");
+
+        assert_eq!(tools.len() as u64, total_tools);
+        assert!(total_tools > 0, "expected seeded tools to be advertised");
+    }
+
+    #[tokio::test]
+    async fn templated_history_resource_binds_tool_variable() {
+        let state = build_state();
+        state
+            .casial_engine
+            .load_mission(test_fixtures::empty_mission("history"))
+            .unwrap();
+        for tool_name in ["web_search", "crawl", "web_search"] {
+            state
+                .casial_engine
+                .coordinate(CoordinationRequest::new(tool_name, json!({})))
+                .unwrap();
+        }
+
+        let response = super::handle_resources_templates_list(
+            &state,
+            list_request("resources/templates/list", json!({})),
+        )
+        .await;
+        let templates = response.result.expect("templates listed")["resourceTemplates"].clone();
+        assert_eq!(
+            templates[0]["uriTemplate"],
+            json!("mop://orchestration/history/{tool}")
+        );
+
+        let read = |uri: &str| JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(3),
+            method: "resources/read".to_string(),
+            params: json!({ "uri": uri }),
+        };
+        let response =
+            super::handle_resources_read(&state, read("mop://orchestration/history/web_search"))
+                .await;
+        let entry = response.result.expect("templated resource read")["contents"][0].clone();
+        assert_eq!(
+            entry["uri"],
+            json!("mop://orchestration/history/web_search")
+        );
+        let payload: Value = serde_json::from_str(entry["text"].as_str().unwrap()).unwrap();
+        assert_eq!(payload["totalEvents"], json!(2));
+        for event in payload["recentSample"].as_array().unwrap() {
+            assert_eq!(event["metadata"]["tool_name"], json!("web_search"));
+        }
+
+        let response =
+            super::handle_resources_read(&state, read("mop://orchestration/history/web_search/x"))
+                .await;
+        assert!(response.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn resources_read_returns_binary_blob() {
+        let state = build_state();
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(2),
+            method: "resources/read".to_string(),
+            params: json!({ "uri": "mop://tools/catalog.sha256" }),
+        };
+
+        let response = super::handle_resources_read(&state, request).await;
+        let result = response.result.expect("expected blob result");
+        let entry = &result["contents"][0];
+
+        assert_eq!(entry["mimeType"], json!("application/octet-stream"));
+        assert!(entry.get("text").is_none());
+
+        let blob = entry["blob"].as_str().expect("blob payload");
+        let decoded = STANDARD.decode(blob).expect("valid base64");
+        assert_eq!(decoded.len(), 32);
+        assert_eq!(
+            decoded,
+            super::tool_catalog_fingerprint(&state.tool_registry)
+        );
+    }
+
+    async fn load_search_mission(
+        state: &AppState,
+        mission_id: &str,
+        template_chars: usize,
+        perception_affinity: Vec<casial_core::PerceptionId>,
+    ) {
+        let mission = search_mission_json(mission_id, template_chars, perception_affinity);
+        load_mission_json(state, mission).await;
+    }
+
+    fn search_mission_json(
+        mission_id: &str,
+        template_chars: usize,
+        perception_affinity: Vec<casial_core::PerceptionId>,
+    ) -> Value {
+        let mut template = test_fixtures::template_json("guide", 1, "g".repeat(template_chars));
+        template["perception_affinity"] = json!(perception_affinity);
+        let mut mission = test_fixtures::mission_json(
+            mission_id,
+            vec![template],
+            vec![test_fixtures::rule_json(
+                "search-guide",
+                &["search"],
+                &["guide"],
+            )],
+        );
+        mission["budgets"]["global_char_limit"] = json!(100000);
+        mission
+    }
+
+    async fn load_mission_json(state: &AppState, mission: Value) {
+        state
+            .casial_engine
+            .load_mission(test_fixtures::mission(mission))
+            .expect("mission loads");
+    }
+
+    #[tokio::test]
+    async fn session_max_context_size_caps_injected_context() {
+        let state = build_state();
+        load_search_mission(&state, "search", 400, vec![]).await;
+        let args = json!({ "query": "rust" });
+
+        let uncapped = super::coordinate_tool_args(
+            &state,
+            "exa_search_example",
+            &args,
+            &SessionConfig::default(),
+            None,
+        )
+        .await
+        .expect("coordination succeeds")
+        .expect("full mode coordinates");
+        assert_eq!(uncapped.used_templates, vec!["guide"]);
+        assert!(uncapped.injected_content.len() > 400);
+
+        let capped_config = SessionConfig {
+            max_context_size: Some(100),
+            ..SessionConfig::default()
+        };
+        let capped =
+            super::coordinate_tool_args(&state, "exa_search_example", &args, &capped_config, None)
+                .await
+                .expect("coordination succeeds")
+                .expect("full mode coordinates");
+        assert!(capped.used_templates.is_empty());
+        assert!(capped.injected_content.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sessions_coordinate_against_their_selected_mission() {
+        let state = build_state();
+        for mission_id in ["research", "monitoring"] {
+            let template_id = format!("{}-guide", mission_id);
+            let mut mission = search_mission_json(mission_id, 40, vec![]);
+            mission["templates"][0]["id"] = json!(template_id);
+            mission["rules"][0]["actions"]["template_ids"] = json!([template_id]);
+            load_mission_json(&state, mission).await;
+        }
+        let args = json!({ "query": "rust" });
+
+        for mission_id in ["research", "monitoring"] {
+            let config = SessionConfig {
+                mission: Some(mission_id.to_string()),
+                ..SessionConfig::default()
+            };
+            let result =
+                super::coordinate_tool_args(&state, "exa_search_example", &args, &config, None)
+                    .await
+                    .expect("coordination succeeds")
+                    .expect("full mode coordinates");
+            assert_eq!(result.used_templates, vec![format!("{}-guide", mission_id)]);
+        }
+
+        // A mission that isn't loaded falls back to every loaded mission
+        let config = SessionConfig {
+            mission: Some("general".to_string()),
+            ..SessionConfig::default()
+        };
+        let mut used =
+            super::coordinate_tool_args(&state, "exa_search_example", &args, &config, None)
+                .await
+                .expect("coordination succeeds")
+                .expect("full mode coordinates")
+                .used_templates;
+        used.sort();
+        assert_eq!(used, vec!["monitoring-guide", "research-guide"]);
+    }
+
+    /// Two missions share the `guide` template with conflicting perception affinities
+    async fn build_paradox_state() -> AppState {
+        let state = build_state();
+        for mission_id in ["search-a", "search-b"] {
+            let affinity = vec![casial_core::PerceptionId::new()];
+            load_search_mission(&state, mission_id, 40, affinity).await;
+        }
+        state
+    }
+
+    fn mode_config(mode: &str) -> SessionConfig {
+        SessionConfig {
+            consciousness_mode: Some(mode.to_string()),
+            ..SessionConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn full_consciousness_mode_injects_context_and_detects_paradoxes() {
+        let state = build_paradox_state().await;
+        let args = json!({ "query": "rust" });
+
+        let result = super::coordinate_tool_args(
+            &state,
+            "exa_search_example",
+            &args,
+            &mode_config("full"),
+            None,
+        )
+        .await
+        .expect("coordination succeeds")
+        .expect("full mode coordinates");
+
+        assert!(result.applied);
+        assert!(!result.paradoxes_detected.is_empty());
+        assert!(result.paradoxes_detected[0].evidence.is_none());
+        assert_ne!(result.modified_args, args);
+
+        let args = json!({ "query": "rust", "includeParadoxEvidence": true });
+        let result = super::coordinate_tool_args(
+            &state,
+            "exa_search_example",
+            &args,
+            &mode_config("full"),
+            None,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(result.paradoxes_detected[0].evidence.is_some());
+    }
+
+    #[tokio::test]
+    async fn partial_consciousness_mode_skips_paradox_detection() {
+        let state = build_paradox_state().await;
+        let args = json!({ "query": "rust" });
+
+        let result = super::coordinate_tool_args(
+            &state,
+            "exa_search_example",
+            &args,
+            &mode_config("partial"),
+            None,
+        )
+        .await
+        .expect("coordination succeeds")
+        .expect("partial mode coordinates");
+
+        assert!(result.applied);
+        assert!(result.paradoxes_detected.is_empty());
+        assert_ne!(result.modified_args, args);
+    }
+
+    #[tokio::test]
+    async fn seeded_id_source_yields_stable_session_ids() {
+        let mut session_ids = Vec::new();
+        for _ in 0..2 {
+            let state = AppState::with_id_source(
+                ServerConfig::default(),
+                PitfallAvoidanceShim::default(),
+                Arc::new(casial_core::SeededIdSource::new(7)),
+            );
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(1),
+                method: "initialize".to_string(),
+                params: json!({ "protocolVersion": "2024-11-05", "capabilities": {} }),
+            };
+
+            let response =
+                super::handle_initialize(&state, request, &SessionConfig::default()).await;
+            let session_id = response.result.expect("initialize result")["sessionId"]
+                .as_str()
+                .expect("session id")
+                .to_string();
+            session_ids.push(session_id);
+        }
+
+        assert_eq!(session_ids[0], session_ids[1]);
+    }
+
+    fn initialize_request(protocol_version: &str) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: "initialize".to_string(),
+            params: json!({ "protocolVersion": protocol_version, "capabilities": {} }),
+        }
+    }
+
+    #[tokio::test]
+    async fn initialize_negotiates_configured_protocol_versions() {
+        let mut config = ServerConfig::default();
+        config.server.supported_protocol_versions =
+            vec!["2024-11-05".to_string(), "2025-06-18".to_string()];
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+
+        let response = super::handle_initialize(
+            &state,
+            initialize_request("2025-06-18"),
+            &SessionConfig::default(),
+        )
+        .await;
+        let result = response.result.expect("initialize result");
+        assert_eq!(result["protocolVersion"], json!("2025-06-18"));
+
+        let mut config = ServerConfig::default();
+        config.server.supported_protocol_versions = vec!["2025-06-18".to_string()];
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+
+        let response = super::handle_initialize(
+            &state,
+            initialize_request("2024-11-05"),
+            &SessionConfig::default(),
+        )
+        .await;
+        assert!(response.result.is_none());
+        assert_eq!(response.error.expect("rejected").code, -32602);
+    }
+
+    #[tokio::test]
+    async fn initialize_advertises_casial_strategies_and_transforms() {
+        let state = build_state();
+        let response = super::handle_initialize(
+            &state,
+            initialize_request("2024-11-05"),
+            &SessionConfig::default(),
+        )
+        .await;
+        let casial =
+            &response.result.expect("initialize result")["capabilities"]["experimental"]["casial"];
+
+        assert_eq!(
+            casial["paradox_strategies"],
+            json!(["Ignore", "Coexist", "Synthesize", "Expose"])
+        );
+        assert_eq!(
+            casial["transform_types"],
+            json!([
+                "Prepend",
+                "Append",
+                "InjectField",
+                "SystemInstruction",
+                "PerceptionLayer"
+            ])
+        );
+        assert_eq!(casial["preview"], true);
+        assert_eq!(casial["explain"], false);
+    }
+
+    struct RecordingExecutor {
+        calls: std::sync::Mutex<Vec<(String, Value)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for RecordingExecutor {
+        async fn execute(&self, tool_name: &str, args: Value) -> Result<Value> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((tool_name.to_string(), args));
+            Ok(json!({ "status": "success", "backend": "recording" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn tool_call_dispatches_to_registered_executor() {
+        let state = build_state();
+        let executor = Arc::new(RecordingExecutor {
+            calls: std::sync::Mutex::new(Vec::new()),
+        });
+        state
+            .tool_executors
+            .register("custom_tool", executor.clone());
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(3),
+            method: "tools/call".to_string(),
+            params: json!({ "name": "custom_tool", "arguments": { "query": "rust" } }),
+        };
+        let response =
+            super::handle_tool_call(&state, request, &SessionConfig::default(), None).await;
+
+        let calls = executor.calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].0, "custom_tool");
+        assert_eq!(calls[0].1["query"], json!("rust"));
+
+        let text = response.result.expect("tool result")["content"][0]["text"]
+            .as_str()
+            .expect("text content")
+            .to_string();
+        assert!(text.contains("recording"));
+    }
+
+    #[tokio::test]
+    async fn shim_and_coordination_apply_in_the_configured_order() {
+        // Coordination writes into the shim's context, which only survives when the shim
+        // ran first; otherwise the shim replaces the whole field
+        for (order, note_survives) in [
+            (InjectionOrder::CoordinationBeforeShim, false),
+            (InjectionOrder::ShimBeforeCoordination, true),
+        ] {
+            let mut config = ServerConfig::default();
+            config.consciousness.injection_order = order;
+            let state = AppState::new(config, PitfallAvoidanceShim::default());
+            let mut mission = search_mission_json("search", 40, vec![]);
+            mission["rules"][0]["actions"]["transform_type"] = json!("InjectField");
+            mission["rules"][0]["actions"]["target_field"] = json!("_shim_context.casial_note");
+            load_mission_json(&state, mission).await;
+            let executor = Arc::new(RecordingExecutor {
+                calls: std::sync::Mutex::new(Vec::new()),
+            });
+            state
+                .tool_executors
+                .register("exa_search_recorded", executor.clone());
+
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(4),
+                method: "tools/call".to_string(),
+                params: json!({ "name": "exa_search_recorded", "arguments": { "query": "rust" } }),
+            };
+            super::handle_tool_call(&state, request, &SessionConfig::default(), None).await;
+
+            let calls = executor.calls.lock().unwrap();
+            let shim_context = &calls[0].1["_shim_context"];
+            assert!(shim_context["current_date"].is_string(), "{:?}", order);
+            assert_eq!(
+                shim_context.get("casial_note").is_some(),
+                note_survives,
+                "{:?}",
+                order
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn bypass_shim_passes_raw_arguments_for_one_call() {
+        let state = build_state();
+        let executor = Arc::new(RecordingExecutor {
+            calls: std::sync::Mutex::new(Vec::new()),
+        });
+        state
+            .tool_executors
+            .register("custom_tool", executor.clone());
+
+        let mut results = Vec::new();
+        for (id, params) in [
+            json!({ "name": "custom_tool", "arguments": { "query": "rust" } }),
+            json!({
+                "name": "custom_tool", "arguments": { "query": "rust" },
+                "_meta": { "bypassShim": true }
+            }),
+            json!({ "name": "custom_tool", "arguments": { "query": "rust" }, "bypass_shim": true }),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(id),
+                method: "tools/call".to_string(),
+                params,
+            };
+            let response =
+                super::handle_tool_call(&state, request, &SessionConfig::default(), None).await;
+            results.push(response.result.unwrap());
+        }
+
+        let calls = executor.calls.lock().unwrap();
+        assert!(calls[0].1["_shim_context"]["current_date"].is_string());
+        assert!(results[0].get("_meta").is_none());
+        for (call, result) in calls[1..].iter().zip(&results[1..]) {
+            assert_eq!(call.1, json!({ "query": "rust" }));
+            assert_eq!(result["_meta"]["casial_shim_bypassed"], true);
+        }
+    }
+
+    #[tokio::test]
+    async fn repeated_idempotency_key_replays_without_executing_again() {
+        let state = build_state();
+        let executor = Arc::new(RecordingExecutor {
+            calls: std::sync::Mutex::new(Vec::new()),
+        });
+        state
+            .tool_executors
+            .register("custom_tool", executor.clone());
+
+        let call = |arguments: Value| JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(3),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "custom_tool",
+                "arguments": arguments,
+                "_meta": { "idempotencyKey": "retry-1" }
+            }),
+        };
+        let config = SessionConfig::default();
+        let first =
+            super::handle_tool_call(&state, call(json!({ "query": "rust" })), &config, None).await;
+        let retry =
+            super::handle_tool_call(&state, call(json!({ "query": "rust" })), &config, None).await;
+
+        assert_eq!(executor.calls.lock().unwrap().len(), 1);
+        assert_eq!(retry.result, first.result);
+
+        let reused =
+            super::handle_tool_call(&state, call(json!({ "query": "go" })), &config, None).await;
+        assert_eq!(reused.error.expect("key conflict").code, -32602);
+        assert_eq!(executor.calls.lock().unwrap().len(), 1);
+
+        // The same key from another session is a different call
+        let other_session = super::handle_tool_call(
+            &state,
+            call(json!({ "query": "rust" })),
+            &config,
+            Some("mop-other"),
+        )
+        .await;
+        assert!(other_session.error.is_none());
+        assert_eq!(executor.calls.lock().unwrap().len(), 2);
+    }
+
+    async fn call_proxy_with(state: &AppState, arguments: Value) -> JsonRpcResponse {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(4),
+            method: "tools/call".to_string(),
+            params: json!({ "name": "orchestrate_mcp_proxy", "arguments": arguments }),
+        };
+        super::handle_tool_call(state, request, &SessionConfig::default(), None).await
+    }
+
+    fn validation_errors(response: JsonRpcResponse) -> String {
+        let error = response.error.expect("arguments rejected");
+        assert_eq!(error.code, -32602);
+        error.data.expect("error data")["validation_errors"].to_string()
+    }
+
+    #[tokio::test]
+    async fn proxy_call_without_target_server_is_rejected() {
+        let state = build_state();
+        let response = call_proxy_with(
+            &state,
+            json!({ "tool_name": "search", "original_params": {} }),
+        )
+        .await;
+
+        let errors = validation_errors(response);
+        assert!(errors.contains("target_server"), "{}", errors);
+        assert!(!errors.contains("tool_name"), "{}", errors);
+    }
+
+    #[tokio::test]
+    async fn proxy_call_without_tool_name_is_rejected() {
+        let state = build_state();
+        let response = call_proxy_with(
+            &state,
+            json!({ "target_server": "https://example-mcp.com", "original_params": {} }),
+        )
+        .await;
+
+        let errors = validation_errors(response);
+        assert!(errors.contains("tool_name"), "{}", errors);
+        assert!(!errors.contains("target_server"), "{}", errors);
+    }
+
+    struct EchoExecutor;
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for EchoExecutor {
+        async fn execute(&self, tool_name: &str, args: Value) -> Result<Value> {
+            Ok(json!({ "tool": tool_name, "echo": args }))
+        }
+    }
+
+    /// Reports more progress than a small SSE buffer holds
+    struct ChattyExecutor;
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for ChattyExecutor {
+        async fn execute(&self, _tool_name: &str, _args: Value) -> Result<Value> {
+            Ok(json!({ "done": true }))
+        }
+
+        async fn execute_with_progress(
+            &self,
+            tool_name: &str,
+            args: Value,
+            progress: &ProgressReporter,
+        ) -> Result<Value> {
+            for step in 1..=10 {
+                progress.report(step as f64, Some(10.0), None);
+            }
+            self.execute(tool_name, args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn undrained_sse_stream_drops_progress_without_blocking_the_tool() {
+        use futures::StreamExt;
+
+        let mut config = ServerConfig::default();
+        config.server.sse_buffer.capacity = 2;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        state
+            .tool_executors
+            .register("chatty", Arc::new(ChattyExecutor));
+        let session_id = "mop-sse-backpressure".to_string();
+        state
+            .session_store
+            .insert(SessionData {
+                id: session_id.clone(),
+                config: SessionConfig::default(),
+                created_at: chrono::Utc::now(),
+                last_accessed: chrono::Utc::now(),
+            })
+            .await
+            .unwrap();
+        let stream = handle_get_sse(
+            state.clone(),
+            SessionConfig::default(),
+            Some(session_id.clone()),
+        )
+        .await
+        .unwrap();
+
+        // Nothing reads the stream while the tool runs
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(9),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "chatty", "arguments": {},
+                "_meta": { "progressToken": "chatty-1" }
+            }),
+        };
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            super::handle_tool_call(
+                &state,
+                request,
+                &SessionConfig::default(),
+                Some(&session_id),
+            ),
+        )
+        .await
+        .expect("tool call isn't held up by the full buffer");
+        assert!(response.result.is_some());
+
+        let mut body = stream.into_body().into_data_stream();
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            let frame = tokio::time::timeout(std::time::Duration::from_secs(1), body.next())
+                .await
+                .expect("buffered event")
+                .expect("stream stays open")
+                .unwrap();
+            let frame = std::str::from_utf8(&frame).unwrap();
+            let data = frame.trim().strip_prefix("data: ").expect("data event");
+            events.push(serde_json::from_str::<Value>(data).unwrap());
+        }
+
+        assert_eq!(events[0]["method"], "notifications/message");
+        assert_eq!(events[0]["params"]["level"], "warning");
+        assert_eq!(events[0]["params"]["data"]["dropped"], 8);
+        assert_eq!(events[1]["params"]["progress"], 9.0);
+        assert_eq!(events[2]["params"]["progress"], 10.0);
+    }
+
+    /// Simulated crawl returning a page far larger than a context budget
+    struct OversizedCrawlExecutor;
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for OversizedCrawlExecutor {
+        async fn execute(&self, _tool_name: &str, args: Value) -> Result<Value> {
+            Ok(json!({ "url": args["url"], "content": "crawled text ".repeat(400) }))
+        }
+    }
+
+    struct SummarizerExecutor;
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for SummarizerExecutor {
+        async fn execute(&self, _tool_name: &str, args: Value) -> Result<Value> {
+            let length = args["content"].as_str().unwrap_or_default().len();
+            Ok(json!({ "summary": format!("summary of {} bytes", length) }))
+        }
+    }
+
+    async fn oversized_crawl_result(strategy: LargeResultStrategy) -> Value {
+        let mut config = ServerConfig::default();
+        config.server.large_results = Some(LargeResultSettings {
+            max_chars: 1000,
+            strategy,
+            summarizer_tool: Some("summarize_text".to_string()),
+        });
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        state
+            .tool_executors
+            .register("crawling_exa", Arc::new(OversizedCrawlExecutor));
+        state
+            .tool_executors
+            .register("summarize_text", Arc::new(SummarizerExecutor));
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(6),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "crawling_exa",
+                "arguments": { "url": "https://example.com/long" }
+            }),
+        };
+        let response =
+            super::handle_tool_call(&state, request, &SessionConfig::default(), None).await;
+        response.result.expect("tool result")
+    }
+
+    #[tokio::test]
+    async fn oversized_crawl_result_is_chunked_instead_of_dumped_whole() {
+        let result = oversized_crawl_result(LargeResultStrategy::Chunk).await;
+        let sections = result["content"].as_array().unwrap();
+        assert!(sections.len() > 1);
+        let texts: Vec<&str> = sections
+            .iter()
+            .map(|section| section["text"].as_str().unwrap())
+            .collect();
+        assert!(texts.iter().all(|text| text.chars().count() <= 1000));
+        // Chunks end between members, or between words of the long crawled text
+        assert!(texts[..texts.len() - 1]
+            .iter()
+            .all(|text| text.ends_with('\n') || text.ends_with(' ')));
+
+        let crawl: Value = serde_json::from_str(&texts.concat()).unwrap();
+        assert_eq!(crawl["content"], "crawled text ".repeat(400));
+        let metadata = &result["_meta"]["casial_large_result"];
+        assert_eq!(metadata["strategy"], "chunk");
+        assert_eq!(metadata["chunks"], sections.len());
+        assert!(metadata["original_chars"].as_u64().unwrap() > 5200);
+
+        let summarized = oversized_crawl_result(LargeResultStrategy::Summarize).await;
+        assert_eq!(summarized["content"].as_array().unwrap().len(), 1);
+        assert!(summarized["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("summary of "));
+        assert_eq!(
+            summarized["_meta"]["casial_large_result"]["summarizer_tool"],
+            "summarize_text"
+        );
+    }
+
+    async fn echo_tool_call_text(state: &AppState) -> String {
+        let mut mission = search_mission_json("inject", 40, vec![]);
+        mission["rules"][0]["actions"]["transform_type"] = json!("InjectField");
+        load_mission_json(state, mission).await;
+        state
+            .tool_executors
+            .register("echo_search", Arc::new(EchoExecutor));
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(4),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "echo_search",
+                "arguments": { "query": "rust", "_swarm_instructions": ["fan out"] }
+            }),
+        };
+        let response =
+            super::handle_tool_call(state, request, &SessionConfig::default(), None).await;
+
+        response.result.expect("tool result")["content"][0]["text"]
+            .as_str()
+            .expect("text content")
+            .to_string()
+    }
+
+    async fn call_tool_json(state: &AppState, name: &str, arguments: Value) -> Value {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(5),
+            method: "tools/call".to_string(),
+            params: json!({ "name": name, "arguments": arguments }),
+        };
+        let response =
+            super::handle_tool_call(state, request, &SessionConfig::default(), None).await;
+        let text = response.result.expect("tool result")["content"][0]["text"]
+            .as_str()
+            .expect("text content")
+            .to_string();
+        serde_json::from_str(&text).expect("JSON tool output")
+    }
+
+    #[tokio::test]
+    async fn rule_cooldown_holds_across_calls_in_an_http_session() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let mut rule = test_fixtures::rule_json("guide-rule", &["search"], &["guide"]);
+        rule["cooldown_secs"] = json!(60);
+        let cooldown = test_fixtures::mission_json(
+            "cooldown",
+            vec![test_fixtures::template_json("guide", 1, "Cite sources.")],
+            vec![rule],
+        );
+        state
+            .casial_engine
+            .load_mission(test_fixtures::mission(cooldown))
+            .unwrap();
+
+        let call = |session_id: &'static str| {
+            let state = state.clone();
+            async move {
+                let request = JsonRpcRequest {
+                    jsonrpc: "2.0".to_string(),
+                    id: json!(5),
+                    method: "tools/call".to_string(),
+                    params: json!({ "name": "exa_search_example", "arguments": { "query": "rust" } }),
+                };
+                super::handle_tool_call(
+                    &state,
+                    request,
+                    &SessionConfig::default(),
+                    Some(session_id),
+                )
+                .await;
+                state
+                    .casial_engine
+                    .coordination_history_page(None, usize::MAX)
+                    .pop()
+                    .unwrap()
+                    .1
+                    .activated_rules
+            }
+        };
+
+        assert_eq!(call("mop-a").await, vec!["guide-rule"]);
+        assert!(call("mop-a").await.is_empty());
+        assert_eq!(call("mop-b").await, vec!["guide-rule"]);
+    }
+
+    #[tokio::test]
+    async fn template_allow_and_deny_arguments_filter_injected_templates() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let filtered = test_fixtures::mission_json(
+            "filtered",
+            vec![
+                test_fixtures::template_json("citations", 1, "Cite sources."),
+                test_fixtures::template_json("background", 2, "Add background."),
+                test_fixtures::template_json("tone", 3, "Stay neutral."),
+            ],
+            vec![test_fixtures::rule_json(
+                "all",
+                &["search"],
+                &["citations", "background", "tone"],
+            )],
+        );
+        state
+            .casial_engine
+            .load_mission(test_fixtures::mission(filtered))
+            .unwrap();
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(5),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "exa_search_example",
+                "arguments": {
+                    "query": "rust",
+                    "templateAllow": ["citations", "background"],
+                    "templateDeny": ["background"]
+                }
+            }),
+        };
+        super::handle_tool_call(&state, request, &SessionConfig::default(), None).await;
+
+        let history = state.casial_engine.get_coordination_history();
+        assert_eq!(history[0].used_templates, vec!["citations"]);
+    }
+
+    #[tokio::test]
+    async fn require_mission_refuses_tool_calls_until_a_mission_loads() {
+        let call = |state: AppState| async move {
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: json!(5),
+                method: "tools/call".to_string(),
+                params: json!({ "name": "exa_search_example", "arguments": { "query": "rust" } }),
+            };
+            super::handle_tool_call(&state, request, &SessionConfig::default(), None).await
+        };
+
+        // Permissive by default: the call passes through uncoordinated
+        let permissive = call(build_state()).await;
+        assert!(permissive.error.is_none());
+        assert!(permissive.result.is_some());
+
+        let mut config = ServerConfig::default();
+        config.missions.require_mission = true;
+        let strict = AppState::new(config, PitfallAvoidanceShim::default());
+        let refused = call(strict.clone()).await;
+        let error = refused.error.expect("no mission loaded");
+        assert_eq!(error.code, -32000);
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], "no_mission_loaded");
+        assert_eq!(data["tool"], "exa_search_example");
+
+        // Authoring a mission doesn't need one loaded
+        let validate = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(6),
+            method: "tools/call".to_string(),
+            params: json!({
+                "name": "casial_validate_mission",
+                "arguments": { "mission": test_fixtures::mission_json("draft", vec![], vec![]) }
+            }),
+        };
+        let validated =
+            super::handle_tool_call(&strict, validate, &SessionConfig::default(), None).await;
+        assert!(validated.error.is_none());
+
+        load_search_mission(&strict, "search", 10, vec![]).await;
+        let accepted = call(strict).await;
+        assert!(accepted.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn shim_config_round_trips_through_the_admin_tools() {
+        let state = build_state();
+        crate::executor::ShimToolExecutor::new(
+            Arc::clone(&state.pitfall_shim),
+            Some("admin-secret".to_string()),
+        )
+        .register(&state.tool_executors);
+        // The shim stamps its own results; only the config itself should round-trip
+        let shim_config = |mut result: Value| {
+            result.as_object_mut().unwrap().remove("_response_metadata");
+            result
+        };
+
+        let mut config = shim_config(
+            call_tool_json(
+                &state,
+                "casial_get_shim_config",
+                json!({ "admin_token": "admin-secret" }),
+            )
+            .await,
+        );
+        assert_eq!(config["enabled"], true);
+
+        config["inject_datetime"] = json!(false);
+        config["custom_extension"] = json!("Prefer primary sources");
+        config["features"]["pitfall_warnings"] = json!(false);
+        let updated = call_tool_json(
+            &state,
+            "casial_update_shim_config",
+            json!({ "admin_token": "admin-secret", "config": config }),
+        )
+        .await;
+        assert_eq!(shim_config(updated), config);
+
+        let reread = call_tool_json(
+            &state,
+            "casial_get_shim_config",
+            json!({ "admin_token": "admin-secret" }),
+        )
+        .await;
+        assert_eq!(shim_config(reread), config);
+        assert_eq!(
+            state
+                .pitfall_shim
+                .read()
+                .await
+                .get_config()
+                .custom_extension
+                .as_deref(),
+            Some("Prefer primary sources")
+        );
+
+        let denied = call_tool_json(
+            &state,
+            "casial_get_shim_config",
+            json!({ "admin_token": "wrong" }),
+        )
+        .await;
+        assert!(denied["error"]
+            .as_str()
+            .unwrap()
+            .contains("requires a valid 'admin_token'"));
+        assert!(!denied.to_string().contains("wrong"), "{}", denied);
     }
 
-    fn reset_sampling_flag() {
-        std::env::remove_var("MOP_ENABLE_SAMPLING");
+    #[tokio::test]
+    async fn admin_tokens_stay_out_of_coordination_history() {
+        let state = build_state();
+        state
+            .casial_engine
+            .load_mission(test_fixtures::empty_mission("recorded"))
+            .unwrap();
+        crate::executor::ShimToolExecutor::new(
+            Arc::clone(&state.pitfall_shim),
+            Some("admin-secret".to_string()),
+        )
+        .register(&state.tool_executors);
+
+        let config = call_tool_json(
+            &state,
+            "casial_get_shim_config",
+            json!({ "admin_token": "admin-secret" }),
+        )
+        .await;
+        assert_eq!(config["enabled"], true);
+
+        let records = state.casial_engine.get_coordination_records();
+        assert_eq!(records.len(), 1);
+        let recorded = serde_json::to_string(&records[0].1).unwrap();
+        assert!(!recorded.contains("admin-secret"), "{}", recorded);
     }
 
-    #[test]
-    fn cors_policy_defaults_to_any_when_env_missing() {
-        reset_env();
-        let policy = CorsPolicy::from_env();
-        let origin = policy.resolve_origin(&HeaderMap::new());
+    #[tokio::test]
+    async fn mission_tools_validate_and_preview_without_loading() {
+        let state = build_state();
 
-        assert_eq!(origin, Some(HeaderValue::from_static("*")));
-        assert!(!policy.allow_credentials());
-    }
+        let mut broken = search_mission_json("broken", 10, vec![]);
+        broken["budgets"]["redaction_patterns"] = json!(["("]);
+        broken["rules"][0]["actions"]["template_ids"] = json!(["guide", "missing"]);
+        let validation = call_tool_json(
+            &state,
+            "casial_validate_mission",
+            json!({ "mission": broken }),
+        )
+        .await;
+        assert_eq!(validation["valid"], false);
+        assert!(validation["errors"][0]
+            .as_str()
+            .unwrap()
+            .contains("Invalid redaction pattern '('"));
+        assert_eq!(
+            validation["warnings"],
+            json!(["rule 'search-guide' references unknown template 'missing'"])
+        );
 
-    #[test]
-    fn cors_policy_matches_listed_origin() {
-        std::env::set_var("ALLOWED_ORIGINS", "https://example.com,https://other.test");
-        let policy = CorsPolicy::from_env();
+        let unparseable =
+            call_tool_json(&state, "casial_validate_mission", json!({ "mission": "{" })).await;
+        assert_eq!(unparseable["valid"], false);
+        assert!(unparseable["errors"][0]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid mission"));
+
+        let preview = call_tool_json(
+            &state,
+            "casial_preview_coordination",
+            json!({
+                "mission": search_mission_json("preview", 12, vec![]),
+                "tool_name": "web_search",
+                "tool_args": { "query": "rust" }
+            }),
+        )
+        .await;
+        assert_eq!(preview["applied"], true);
+        assert_eq!(preview["activated_rules"], json!(["search-guide"]));
+        assert!(preview["injected_content"]
+            .as_str()
+            .unwrap()
+            .contains(&"g".repeat(12)));
+
+        // Previews run in a throwaway engine
+        assert!(state.casial_engine.get_coordination_history().is_empty());
+        assert!(state.casial_engine.get_paradox_registry().is_empty());
+    }
 
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            header::ORIGIN,
-            HeaderValue::from_static("https://other.test"),
-        );
+    #[tokio::test]
+    async fn tool_results_hide_injected_fields() {
+        let state = build_state();
+        let text = echo_tool_call_text(&state).await;
 
-        let origin = policy.resolve_origin(&headers);
-        assert_eq!(origin, Some(HeaderValue::from_static("https://other.test")));
-        assert!(policy.allow_credentials());
-        reset_env();
+        assert!(text.contains("\"query\""));
+        assert!(!text.contains("casial_context"));
+        assert!(!text.contains("_swarm_instructions"));
     }
 
-    #[test]
-    fn cors_context_suppresses_credentials_for_wildcard() {
-        std::env::set_var("ALLOWED_ORIGINS", "*");
-        let policy = CorsPolicy::from_env();
-        let origin = policy.resolve_origin(&HeaderMap::new());
+    #[tokio::test]
+    async fn tool_results_keep_injected_fields_when_exposed() {
+        let mut config = ServerConfig::default();
+        config.consciousness.expose_injected_fields = true;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let text = echo_tool_call_text(&state).await;
+
+        assert!(text.contains("casial_context"));
+        assert!(text.contains("_swarm_instructions"));
+    }
 
-        let mut headers = HeaderMap::new();
-        apply_cors_headers(&mut headers, &HeaderMap::new());
+    #[tokio::test]
+    async fn disabled_consciousness_mode_bypasses_coordination() {
+        let state = build_paradox_state().await;
+        let args = json!({ "query": "rust" });
+
+        let result = super::coordinate_tool_args(
+            &state,
+            "exa_search_example",
+            &args,
+            &mode_config("disabled"),
+            None,
+        )
+        .await
+        .expect("coordination succeeds");
+
+        assert!(result.is_none());
+        assert!(state.casial_engine.get_coordination_history().is_empty());
+    }
 
-        assert_eq!(origin, Some(HeaderValue::from_static("*")));
-        assert!(!policy.allow_credentials());
-        assert!(headers
-            .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
-            .is_none());
-        reset_env();
+    /// Session store recording each operation, backed by the in-memory store
+    #[derive(Default)]
+    struct RecordingSessionStore {
+        inner: crate::session_store::InMemorySessionStore,
+        operations: std::sync::Mutex<Vec<String>>,
     }
 
-    #[test]
-    fn sampling_disabled_by_default() {
-        reset_sampling_flag();
-        assert!(!super::sampling_feature_enabled());
+    impl RecordingSessionStore {
+        fn record(&self, operation: &str, id: &str) {
+            self.operations
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", operation, id));
+        }
     }
 
-    #[test]
-    fn sampling_enabled_for_truthy_values() {
-        for value in ["true", "1", "yes"] {
-            std::env::set_var("MOP_ENABLE_SAMPLING", value);
-            assert!(
-                super::sampling_feature_enabled(),
-                "value {:?} should enable sampling",
-                value
-            );
+    #[async_trait::async_trait]
+    impl SessionStore for RecordingSessionStore {
+        async fn get(&self, id: &str) -> Result<Option<SessionData>> {
+            self.record("get", id);
+            self.inner.get(id).await
+        }
+
+        async fn touch(&self, id: &str) -> Result<Option<SessionData>> {
+            self.record("touch", id);
+            self.inner.touch(id).await
+        }
+
+        async fn insert(&self, session: SessionData) -> Result<()> {
+            self.record("insert", &session.id);
+            self.inner.insert(session).await
+        }
+
+        async fn remove(&self, id: &str) -> Result<bool> {
+            self.record("remove", id);
+            self.inner.remove(id).await
+        }
+
+        async fn reap(&self, idle_timeout: std::time::Duration) -> Result<usize> {
+            self.record("reap", "*");
+            self.inner.reap(idle_timeout).await
         }
-        reset_sampling_flag();
     }
 
-    fn build_state() -> AppState {
-        let config = ServerConfig::default();
-        let shim = PitfallAvoidanceShim::default();
-        AppState::new(config, shim)
+    #[tokio::test]
+    async fn demo_key_reaches_example_tools_but_not_federated_or_admin_ones() {
+        let state = build_state();
+        let demo = SessionConfig {
+            api_key: Some(DEMO_API_KEY.to_string()),
+            ..SessionConfig::default()
+        };
+        let init_body = serde_json::to_string(&initialize_request("2024-11-05")).unwrap();
+        let init = super::handle_post(
+            state.clone(),
+            demo.clone(),
+            Some(init_body),
+            None,
+            None,
+            "en",
+        )
+        .await
+        .unwrap();
+        let session_id = init.headers()["Mcp-Session-Id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let post = |method: &str, params: Value| {
+            let body = json!({ "jsonrpc": "2.0", "id": 2, "method": method, "params": params });
+            super::handle_post(
+                state.clone(),
+                SessionConfig::default(),
+                Some(body.to_string()),
+                Some(session_id.clone()),
+                None,
+                "en",
+            )
+        };
+
+        let example = post(
+            "tools/call",
+            json!({ "name": "exa_search_example", "arguments": { "query": "rust" } }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(example.status(), StatusCode::OK);
+
+        for (method, params) in [
+            (
+                "tools/call",
+                json!({ "name": "orchestrate_mcp_proxy", "arguments": {} }),
+            ),
+            (
+                "tools/call",
+                json!({ "name": "casial_get_shim_config", "arguments": {} }),
+            ),
+            ("sampling/createMessage", json!({})),
+        ] {
+            let denied = post(method, params).await.unwrap();
+            assert_eq!(denied.status(), StatusCode::FORBIDDEN, "{}", method);
+            let body = axum::body::to_bytes(denied.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["error"]["data"]["reason"], "demo_key");
+        }
     }
 
     #[tokio::test]
-    async fn resources_list_includes_tool_catalog() {
+    async fn catalog_errors_carry_a_stable_code_and_a_localized_message() {
         let state = build_state();
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            id: json!(1),
-            method: "resources/list".to_string(),
-            params: json!({}),
+        let body = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }).to_string();
+        let error_for = |language: &'static str| {
+            let state = state.clone();
+            let body = body.clone();
+            async move {
+                let response = super::handle_post(
+                    state,
+                    SessionConfig::default(),
+                    Some(body),
+                    None,
+                    None,
+                    negotiate_language(Some(language)),
+                )
+                .await
+                .unwrap();
+                assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&body).unwrap()["error"].clone()
+            }
         };
 
-        let response = super::handle_resources_list(&state, request).await;
-        let result = response.result.expect("expected resources payload");
-        let resources = result
-            .get("resources")
-            .and_then(|value| value.as_array())
-            .expect("resources should be an array");
+        let english = error_for("en-US").await;
+        let spanish = error_for("es-ES,es;q=0.9,en;q=0.8").await;
+        for error in [&english, &spanish] {
+            assert_eq!(error["code"], -32000);
+            assert_eq!(error["message"], "Session ID required");
+            assert_eq!(error["data"]["code"], "session_required");
+        }
+        assert_eq!(english["data"]["message"], "Session ID required");
+        assert_eq!(spanish["data"]["message"], "Se requiere un ID de sesión");
+        assert_eq!(spanish["data"]["lang"], "es");
+    }
 
-        assert!(resources
+    #[tokio::test]
+    async fn http_sessions_go_through_the_configured_store() {
+        let store = Arc::new(RecordingSessionStore::default());
+        let mut state = build_state();
+        state.session_store = store.clone();
+
+        let init_body = serde_json::to_string(&initialize_request("2024-11-05")).unwrap();
+        let init = super::handle_post(
+            state.clone(),
+            SessionConfig::default(),
+            Some(init_body),
+            None,
+            None,
+            "en",
+        )
+        .await
+        .unwrap();
+        let session_id = init.headers()["Mcp-Session-Id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let ping = json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" }).to_string();
+        let pong = super::handle_post(
+            state.clone(),
+            SessionConfig::default(),
+            Some(ping),
+            Some(session_id.clone()),
+            None,
+            "en",
+        )
+        .await
+        .unwrap();
+        assert_eq!(pong.status(), StatusCode::OK);
+
+        let deleted = super::handle_delete_session(state.clone(), Some(session_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(deleted.status(), StatusCode::OK);
+        let missing = super::handle_delete_session(state, Some(session_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        let expected: Vec<String> = ["reap *", "insert", "touch", "remove", "remove"]
             .iter()
-            .any(|entry| entry.get("uri") == Some(&json!("mop://tools/catalog"))));
+            .map(|op| match *op {
+                "reap *" => op.to_string(),
+                op => format!("{} {}", op, session_id),
+            })
+            .collect();
+        assert_eq!(*store.operations.lock().unwrap(), expected);
     }
 
     #[tokio::test]
-    async fn resources_read_returns_catalog_contents() {
-        let state = build_state();
-        let request = JsonRpcRequest {
-            jsonrpc: "2.0".to_string(),
-            id: json!(2),
-            method: "resources/read".to_string(),
-            params: json!({ "uri": "mop://tools/catalog" }),
-        };
+    async fn saturated_tool_call_limiter_returns_503() {
+        let mut config = ServerConfig::default();
+        config.server.max_concurrent_tool_calls = 1;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+
+        let init_body = serde_json::to_string(&initialize_request("2024-11-05")).unwrap();
+        let init = super::handle_post(
+            state.clone(),
+            SessionConfig::default(),
+            Some(init_body),
+            None,
+            None,
+            "en",
+        )
+        .await
+        .expect("initialize succeeds");
+        let session_id = init.headers()["Mcp-Session-Id"]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let call_body = json!({
+            "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": { "name": "exa_search_example", "arguments": { "query": "rust" } }
+        })
+        .to_string();
+
+        // Hold the only permit, as an in-flight call would
+        let held = state.tool_call_limiter.try_acquire().unwrap();
+        let busy = super::handle_post(
+            state.clone(),
+            SessionConfig::default(),
+            Some(call_body.clone()),
+            Some(session_id.clone()),
+            None,
+            "en",
+        )
+        .await
+        .expect("busy response");
+        assert_eq!(busy.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(busy.headers()[header::RETRY_AFTER], "1");
+
+        drop(held);
+        let ok = super::handle_post(
+            state,
+            SessionConfig::default(),
+            Some(call_body),
+            Some(session_id),
+            None,
+            "en",
+        )
+        .await
+        .expect("tool call response");
+        assert_eq!(ok.status(), StatusCode::OK);
+    }
 
-        let response = super::handle_resources_read(&state, request).await;
-        let result = response.result.expect("expected catalog result");
-        let contents = result
-            .get("contents")
-            .and_then(|value| value.as_array())
-            .expect("contents should be an array");
-        let catalog_entry = contents.first().expect("catalog entry missing");
+    #[tokio::test]
+    async fn well_known_config_revalidates_with_etag() {
+        let response =
+            well_known_config_handler(Method::GET, State(build_state()), HeaderMap::new(), None)
+                .await
+                .expect("config response");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(header::ETAG));
+
+        // Fixed config value so the sampling env tests can't change it mid-test
+        let config = build_mcp_config();
+        let first = mcp_config_response(config.clone(), &HeaderMap::new());
+        let etag = first.headers()[header::ETAG].clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag.clone());
+        let cached = mcp_config_response(config.clone(), &headers);
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(cached.headers()[header::ETAG], etag);
+
+        let mut changed = config;
+        changed["version"] = json!("0.0.0-changed");
+        let refreshed = mcp_config_response(changed, &headers);
+        assert_eq!(refreshed.status(), StatusCode::OK);
+        assert_ne!(refreshed.headers()[header::ETAG], etag);
+    }
+
+    #[tokio::test]
+    async fn well_known_config_answers_head_and_options() {
+        let _env = CORS_ENV.lock().await;
+        std::env::set_var("ALLOWED_ORIGINS", "https://app.example.com");
+        reload_cors_policy();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ORIGIN,
+            HeaderValue::from_static("https://app.example.com"),
+        );
 
+        let head =
+            well_known_config_handler(Method::HEAD, State(build_state()), headers.clone(), None)
+                .await
+                .expect("head response");
+        let get = mcp_config_response(build_mcp_config(), &HeaderMap::new());
+        assert_eq!(head.status(), StatusCode::OK);
+        assert_eq!(head.headers()[header::ETAG], get.headers()[header::ETAG]);
+        assert_eq!(head.headers()[header::CONTENT_TYPE], "application/json");
         assert_eq!(
-            catalog_entry.get("uri"),
-            Some(&json!("mop://tools/catalog"))
+            head.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        let body = axum::body::to_bytes(head.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+
+        let options =
+            well_known_config_handler(Method::OPTIONS, State(build_state()), headers, None)
+                .await
+                .expect("options response");
+        assert_eq!(options.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            options.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(
+            options.headers()[header::ACCESS_CONTROL_ALLOW_METHODS],
+            ALLOWED_METHODS
+        );
+        assert_eq!(
+            options.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS],
+            ALLOWED_HEADERS
         );
 
-        let payload = catalog_entry
-            .get("text")
-            .and_then(|value| value.as_str())
-            .map(|text| {
-                serde_json::from_str::<serde_json::Value>(text).expect("valid catalog json")
-            })
-            .expect("catalog text payload");
+        reset_env();
+        reload_cors_policy();
+    }
 
-        let tools = payload
-            .get("tools")
-            .and_then(|value| value.as_array())
-            .expect("tools array");
-        let total_tools = payload
-            .get("totalTools")
-            .and_then(|value| value.as_u64())
-            .expect("totalTools count");
+    #[cfg(feature = "otlp")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn tool_call_span_is_exported_over_otlp() {
+        use axum::{body::Bytes, extract::State, routing::post, Router};
+        use std::sync::Mutex;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        // Mock collector recording every OTLP trace export
+        let received: Arc<Mutex<Vec<Bytes>>> = Arc::default();
+        let collector = Router::new()
+            .route(
+                "/v1/traces",
+                post(
+                    |State(received): State<Arc<Mutex<Vec<Bytes>>>>, body: Bytes| async move {
+                        received.lock().unwrap().push(body);
+                    },
+                ),
+            )
+            .route("/v1/metrics", post(|| async {}))
+            .with_state(Arc::clone(&received));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, collector).await });
+
+        let telemetry = crate::telemetry::OtlpTelemetry::new(&endpoint).unwrap();
+        let _subscriber = tracing::subscriber::set_default(
+            tracing_subscriber::registry().with(telemetry.layer()),
+        );
 
-        assert_eq!(tools.len() as u64, total_tools);
-        assert!(total_tools > 0, "expected seeded tools to be advertised");
+        let state = build_state();
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: json!(1),
+            method: "tools/call".to_string(),
+            params: json!({ "name": "exa_search_example", "arguments": { "query": "rust" } }),
+        };
+        let response =
+            super::handle_tool_call(&state, request, &SessionConfig::default(), None).await;
+        assert!(response.result.is_some());
+
+        // Shutdown flushes the batch exporter synchronously
+        tokio::task::spawn_blocking(move || telemetry.shutdown())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let batches = received.lock().unwrap();
+        assert!(batches
+            .iter()
+            .any(|body| body.windows(b"tool_call".len()).any(|w| w == b"tool_call")));
     }
 }
 
-use crate::{mcp::*, AppState};
+use crate::{
+    config::{DemoAccessSettings, InjectionOrder, SseKeepAliveMode, SseKeepAliveSettings},
+    errors::{localize, negotiate_language, ErrorCode, DEFAULT_LANGUAGE},
+    executor::{
+        restore_admin_token, strip_injected_fields, take_admin_token, FederationExecutor,
+        InvalidToolArguments, ProgressReporter,
+    },
+    federation::FederationError,
+    idempotency::IdempotentLookup,
+    large_results::shape_tool_result,
+    mcp::*,
+    pitfall_shim::PitfallAvoidanceShim,
+    AppState,
+};
 
-/// Active session storage
-#[derive(Debug, Clone)]
+/// An HTTP MCP session, kept in the configured [`SessionStore`](crate::session_store::SessionStore)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionData {
     pub id: String,
     pub config: SessionConfig,
-    pub created_at: std::time::Instant,
-    pub last_accessed: std::time::Instant,
+    pub created_at: DateTime<Utc>,
+    pub last_accessed: DateTime<Utc>,
 }
 
-/// Global session storage shared across requests
-static SESSIONS: Lazy<Arc<DashMap<String, SessionData>>> = Lazy::new(|| Arc::new(DashMap::new()));
+pub(crate) const DEMO_API_KEY: &str = "DEMO_KEY_PUBLIC";
+
+/// Seconds clients are asked to wait after a 503 from the tool call limiter
+const TOOL_CALL_RETRY_AFTER_SECS: u64 = 1;
 
-const DEMO_API_KEY: &str = "DEMO_KEY_PUBLIC";
+/// Header carrying a tool call's idempotency key
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Event name used when SSE keep-alives are sent as events rather than comments
+const SSE_KEEP_ALIVE_EVENT: &str = "keep-alive";
 
 static EXPECTED_API_KEY: Lazy<String> = Lazy::new(|| {
     let value = std::env::var("MOP_API_KEY").unwrap_or_else(|_| DEMO_API_KEY.to_string());
@@ -384,7 +2250,7 @@ static EXPECTED_API_KEY: Lazy<String> = Lazy::new(|| {
     value
 });
 
-fn expected_api_key() -> &'static str {
+pub(crate) fn expected_api_key() -> &'static str {
     EXPECTED_API_KEY.as_str()
 }
 
@@ -395,18 +2261,52 @@ fn sampling_feature_enabled() -> bool {
 }
 
 /// Session configuration from query parameters
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct SessionConfig {
     #[serde(rename = "apiKey")]
     pub api_key: Option<String>,
     pub debug: Option<bool>,
     pub consciousness_mode: Option<String>,
     pub max_context_size: Option<i32>,
+    /// Skip templates with a higher priority number (less important) instead of relying on
+    /// budget truncation
+    pub max_template_priority: Option<u32>,
     pub agent_role: Option<String>,
     pub mission: Option<String>,
     pub shim_enabled: Option<bool>,
 }
 
+/// How much of the coordination pipeline a session opts into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsciousnessMode {
+    /// Inject mission context and detect paradoxes
+    Full,
+    /// Inject mission context without paradox detection
+    Partial,
+    /// Pass tool calls through without coordination
+    Disabled,
+}
+
+impl SessionConfig {
+    /// Resolve the session's consciousness mode, defaulting to full
+    pub fn resolved_consciousness_mode(&self) -> ConsciousnessMode {
+        let mode = self
+            .consciousness_mode
+            .as_deref()
+            .map(|mode| mode.trim().to_ascii_lowercase());
+
+        match mode.as_deref() {
+            None | Some("full") => ConsciousnessMode::Full,
+            Some("partial") => ConsciousnessMode::Partial,
+            Some("disabled") => ConsciousnessMode::Disabled,
+            Some(other) => {
+                warn!("Unknown consciousness_mode '{}', using full", other);
+                ConsciousnessMode::Full
+            }
+        }
+    }
+}
+
 /// Query parameters that may include base64 encoded config
 #[derive(Debug, Deserialize, Default)]
 pub struct QueryParams {
@@ -415,6 +2315,31 @@ pub struct QueryParams {
     pub config: Option<String>, // Base64-encoded JSON config
 }
 
+/// Base64 variants accepted for the `config` query parameter, in the order they are tried
+const BASE64_CONFIG_VARIANTS: [(&str, GeneralPurpose); 4] = [
+    ("standard", STANDARD),
+    ("url-safe", URL_SAFE),
+    ("standard (unpadded)", STANDARD_NO_PAD),
+    ("url-safe (unpadded)", URL_SAFE_NO_PAD),
+];
+
+/// Decode a base64 `config` parameter, falling back to URL-safe and unpadded encodings
+fn decode_base64_config(encoded: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    let mut last_error = None;
+
+    for (variant, engine) in BASE64_CONFIG_VARIANTS.iter() {
+        match engine.decode(encoded) {
+            Ok(decoded) => {
+                debug!("Decoded config using {} base64", variant);
+                return Ok(decoded);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.expect("at least one base64 variant is attempted"))
+}
+
 /// MCP HTTP handler - supports both POST for JSON-RPC and GET for SSE
 pub async fn mcp_handler(
     method: Method,
@@ -426,7 +2351,7 @@ pub async fn mcp_handler(
     // Extract config from base64 if provided, otherwise use direct params
     let mut config = if let Some(encoded_config) = params.config {
         // Decode base64 config like Python implementation
-        match BASE64.decode(&encoded_config) {
+        match decode_base64_config(&encoded_config) {
             Ok(decoded) => match serde_json::from_slice::<SessionConfig>(&decoded) {
                 Ok(parsed_config) => {
                     debug!("Decoded config from base64: {:?}", parsed_config);
@@ -446,24 +2371,17 @@ pub async fn mcp_handler(
         params.direct_params
     };
 
-    // Check for Bearer token authentication in headers (Smithery style)
-    let mut api_key_from_header: Option<String> = None;
-    if let Some(auth_header) = headers.get(header::AUTHORIZATION) {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some((scheme, token)) = auth_str.split_once(' ') {
-                let token = token.trim();
-                if scheme.eq_ignore_ascii_case("bearer") && !token.is_empty() {
-                    api_key_from_header = Some(token.to_string());
-                    debug!("Found Bearer token in Authorization header");
-                }
-            }
-        }
+    // Use a Bearer token (Smithery style) if no API key in query params
+    if config.api_key.is_none() {
+        config.api_key = bearer_token(&headers);
     }
 
-    // Use Bearer token if no API key in query params
-    if config.api_key.is_none() && api_key_from_header.is_some() {
-        config.api_key = api_key_from_header;
-    }
+    // Catalog error messages follow the client's preferred language
+    let language = negotiate_language(
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|h| h.to_str().ok()),
+    );
 
     // Extract session ID from headers
     let session_id = headers
@@ -472,10 +2390,9 @@ pub async fn mcp_handler(
         .map(|s| s.to_string());
 
     // Check if we have a valid session (bypass API key check if so)
-    let has_valid_session = if let Some(sid) = &session_id {
-        SESSIONS.contains_key(sid)
-    } else {
-        false
+    let has_valid_session = match &session_id {
+        Some(sid) => lookup_session(&state, sid, false).await.is_some(),
+        None => false,
     };
 
     // Validate API key only if no valid session
@@ -488,12 +2405,9 @@ pub async fn mcp_handler(
                     .status(StatusCode::UNAUTHORIZED)
                     .header(header::CONTENT_TYPE, "application/json")
                     .body(
-                        Json(json!({
-                            "error": "Invalid API key",
-                            "message": "Please provide a valid API key in the configuration"
-                        }))
-                        .into_response()
-                        .into_body(),
+                        Json(ErrorCode::InvalidApiKey.http_body(language))
+                            .into_response()
+                            .into_body(),
                     )
                     .unwrap();
 
@@ -505,12 +2419,9 @@ pub async fn mcp_handler(
                 .status(StatusCode::UNAUTHORIZED)
                 .header(header::CONTENT_TYPE, "application/json")
                 .body(
-                    Json(json!({
-                        "error": "Missing API key",
-                        "message": "API key is required. Please configure with apiKey parameter."
-                    }))
-                    .into_response()
-                    .into_body(),
+                    Json(ErrorCode::MissingApiKey.http_body(language))
+                        .into_response()
+                        .into_body(),
                 )
                 .unwrap();
 
@@ -527,9 +2438,15 @@ pub async fn mcp_handler(
     }
 
     let response = match method {
-        Method::POST => handle_post(state, config, body, session_id).await,
+        Method::POST => {
+            let idempotency_key = headers
+                .get(IDEMPOTENCY_KEY_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+            handle_post(state, config, body, session_id, idempotency_key, language).await
+        }
         Method::GET => handle_get_sse(state, config, session_id).await,
-        Method::DELETE => handle_delete_session(session_id).await,
+        Method::DELETE => handle_delete_session(state, session_id).await,
         Method::HEAD => {
             // Return OK for HEAD requests (used by Smithery for health checks)
             Ok(Response::builder()
@@ -556,15 +2473,25 @@ async fn handle_post(
     mut config: SessionConfig,
     body: Option<String>,
     session_id: Option<String>,
+    idempotency_key: Option<String>,
+    language: &str,
 ) -> Result<Response, StatusCode> {
     let body = body.ok_or(StatusCode::BAD_REQUEST)?;
 
     // Parse JSON-RPC request
-    let request: JsonRpcRequest = serde_json::from_str(&body).map_err(|e| {
+    let mut request: JsonRpcRequest = serde_json::from_str(&body).map_err(|e| {
         error!("Failed to parse JSON-RPC request: {}", e);
         StatusCode::BAD_REQUEST
     })?;
 
+    // The Idempotency-Key header is the transport form of `_meta.idempotencyKey`
+    if let (Some(key), Some(params)) = (idempotency_key, request.params.as_object_mut()) {
+        let meta = params.entry("_meta").or_insert_with(|| json!({}));
+        if let Some(meta) = meta.as_object_mut() {
+            meta.entry("idempotencyKey").or_insert(Value::String(key));
+        }
+    }
+
     debug!(
         "Received MCP request: method={}, id={:?}",
         request.method, request.id
@@ -573,49 +2500,42 @@ async fn handle_post(
     // For non-initialize requests, validate session
     if request.method != "initialize" {
         if let Some(sid) = &session_id {
-            if let Some(mut session) = SESSIONS.get_mut(sid) {
-                // Update last accessed time
-                session.last_accessed = std::time::Instant::now();
+            if let Some(session) = lookup_session(&state, sid, true).await {
                 // Use session's config
-                config = session.config.clone();
+                config = session.config;
                 info!("Using existing session: {}", sid);
             } else {
                 warn!("Invalid session ID: {}", sid);
+                let mut error = ErrorCode::InvalidSession.response(request.id, None);
+                localize(&mut error, language);
                 return Ok(Response::builder()
                     .status(StatusCode::BAD_REQUEST)
                     .header(header::CONTENT_TYPE, "application/json")
-                    .body(
-                        Json(json!({
-                            "jsonrpc": "2.0",
-                            "error": {
-                                "code": -32000,
-                                "message": "Invalid session ID"
-                            },
-                            "id": request.id
-                        }))
-                        .into_response()
-                        .into_body(),
-                    )
+                    .body(Json(error).into_response().into_body())
                     .unwrap());
             }
         } else if request.method != "notifications/initialized" {
             // Session ID required for non-initialize, non-notification requests
             warn!("Missing session ID for method: {}", request.method);
+            let mut error = ErrorCode::SessionRequired.response(request.id, None);
+            localize(&mut error, language);
             return Ok(Response::builder()
                 .status(StatusCode::BAD_REQUEST)
                 .header(header::CONTENT_TYPE, "application/json")
-                .body(
-                    Json(json!({
-                        "jsonrpc": "2.0",
-                        "error": {
-                            "code": -32000,
-                            "message": "Session ID required"
-                        },
-                        "id": request.id
-                    }))
-                    .into_response()
-                    .into_body(),
-                )
+                .body(Json(error).into_response().into_body())
+                .unwrap());
+        }
+    }
+
+    // The public demo key only reaches read-only methods and example tools
+    if config.api_key.as_deref() == Some(DEMO_API_KEY) {
+        if let Some(mut denied) = demo_access_denial(&state.config.server.demo_access, &request) {
+            localize(&mut denied, language);
+            warn!("Demo API key denied {}", request.method);
+            return Ok(Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Json(denied).into_response().into_body())
                 .unwrap());
         }
     }
@@ -628,11 +2548,18 @@ async fn handle_post(
         "initialize" => handle_initialize(&state, request, &config).await,
         "notifications/initialized" => handle_initialized(&state, request).await,
         "tools/list" => handle_tools_list(&state, request).await,
-        "tools/call" => handle_tool_call(&state, request, config.agent_role.as_deref()).await,
+        "tools/call" => {
+            let Ok(_permit) = state.tool_call_limiter.try_acquire() else {
+                warn!("Tool call concurrency limit reached, rejecting request");
+                return Ok(server_busy_response(request.id, language));
+            };
+            handle_tool_call(&state, request, &config, session_id.as_deref()).await
+        }
         "prompts/list" => handle_prompts_list(&state, request).await,
         "prompts/get" => handle_prompts_get(&state, request).await,
         "resources/list" => handle_resources_list(&state, request).await,
         "resources/read" => handle_resources_read(&state, request).await,
+        "resources/templates/list" => handle_resources_templates_list(&state, request).await,
         "resources/subscribe" => handle_resources_subscribe(&state, request).await,
         "resources/unsubscribe" => handle_resources_unsubscribe(&state, request).await,
         "sampling/createMessage" => handle_sampling_create(&state, request).await,
@@ -640,15 +2567,14 @@ async fn handle_post(
         "ping" => handle_ping(request).await,
         _ => {
             warn!("Unknown MCP method: {}", request.method);
-            create_error_response(
-                request.id,
-                -32601,
-                "Method not found",
-                Some(json!({ "method": request.method })),
-            )
+            ErrorCode::MethodNotFound
+                .response(request.id, Some(json!({ "method": request.method })))
         }
     };
 
+    let mut response = response;
+    localize(&mut response, language);
+
     // Check if this is an initialize response that includes a sessionId
     let mut session_id: Option<String> = None;
     if method == "initialize" {
@@ -669,8 +2595,16 @@ async fn handle_post(
         response_builder = response_builder.header("Mcp-Session-Id", sid);
     }
 
-    // Add protocol version header
-    response_builder = response_builder.header("Mcp-Protocol-Version", "2024-11-05");
+    // Add protocol version header (the negotiated one for initialize responses)
+    let protocol_version = response
+        .result
+        .as_ref()
+        .and_then(|result| result.get("protocolVersion"))
+        .and_then(|v| v.as_str())
+        .or_else(|| latest_protocol_version(&state.config.server.supported_protocol_versions))
+        .unwrap_or("2024-11-05")
+        .to_string();
+    response_builder = response_builder.header("Mcp-Protocol-Version", protocol_version);
 
     let response = response_builder
         .body(Json(response).into_response().into_body())
@@ -679,56 +2613,162 @@ async fn handle_post(
     Ok(response)
 }
 
+/// Token from an `Authorization: Bearer` header, if one is present
+pub(crate) fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    let (scheme, token) = headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then(|| token.to_string())
+}
+
+/// Error for a request the demo API key isn't allowed to make, if it isn't
+pub(crate) fn demo_access_denial(
+    access: &DemoAccessSettings,
+    request: &JsonRpcRequest,
+) -> Option<JsonRpcResponse> {
+    let tool = (request.method == "tools/call")
+        .then(|| request.params.get("name").and_then(Value::as_str))
+        .flatten();
+    let permitted = access.permits_method(&request.method)
+        && tool.map_or(request.method != "tools/call", |tool| {
+            access.permits_tool(tool)
+        });
+    if permitted {
+        return None;
+    }
+
+    let mut data = json!({ "reason": "demo_key", "method": request.method });
+    if let Some(tool) = tool {
+        data["tool"] = json!(tool);
+    }
+    Some(ErrorCode::DemoKeyDenied.response(request.id.clone(), Some(data)))
+}
+
+/// 503 response for tool calls rejected by the concurrency limiter
+fn server_busy_response(id: Value, language: &str) -> Response {
+    let mut error = ErrorCode::ServerBusy.response(id, Some(json!({ "reason": "backpressure" })));
+    localize(&mut error, language);
+
+    Response::builder()
+        .status(StatusCode::SERVICE_UNAVAILABLE)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::RETRY_AFTER, TOOL_CALL_RETRY_AFTER_SECS.to_string())
+        .body(Json(error).into_response().into_body())
+        .unwrap()
+}
+
 /// Handle GET requests for SSE stream
 async fn handle_get_sse(
-    _state: AppState,
+    state: AppState,
     _config: SessionConfig,
     session_id: Option<String>,
 ) -> Result<Response, StatusCode> {
     // Validate session for GET requests
-    if let Some(sid) = &session_id {
-        if let Some(mut session) = SESSIONS.get_mut(sid) {
-            // Update last accessed time
-            session.last_accessed = std::time::Instant::now();
-            info!("SSE stream for session: {}", sid);
-        } else {
-            warn!("Invalid session ID for SSE: {}", sid);
-            return Ok(Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(axum::body::Body::from("Invalid session ID"))
-                .unwrap());
-        }
-    } else {
+    let Some(sid) = session_id.as_deref() else {
         warn!("Missing session ID for SSE stream");
         return Ok(Response::builder()
             .status(StatusCode::BAD_REQUEST)
-            .body(axum::body::Body::from("Session ID required"))
+            .body(axum::body::Body::from(
+                ErrorCode::SessionRequired.message(DEFAULT_LANGUAGE),
+            ))
+            .unwrap());
+    };
+    if lookup_session(&state, sid, true).await.is_some() {
+        info!("SSE stream for session: {}", sid);
+    } else {
+        warn!("Invalid session ID for SSE: {}", sid);
+        return Ok(Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(axum::body::Body::from(
+                ErrorCode::InvalidSession.message(DEFAULT_LANGUAGE),
+            ))
             .unwrap());
     }
     // For Smithery's Streamable HTTP, we need to return a simple SSE stream
     // that will handle JSON-RPC messages sent as events
-    let (_tx, rx) = mpsc::channel::<Result<Event, Infallible>>(100);
+    let subscription = state
+        .sse_channels
+        .open(sid, &state.config.server.sse_buffer);
+
+    // Don't send any initial events - let the client initiate
+    // This matches the Streamable HTTP specification
+
+    // The stream (and its keep-alives) lasts until the client disconnects or the
+    // session is deleted; dropping it unregisters the session's channel
+    let stream = futures::stream::unfold(subscription, |subscription| async move {
+        let message = subscription.recv().await?;
+        let event = Event::default().data(message.to_string());
+        Some((Ok::<_, Infallible>(event), subscription))
+    });
+
+    // Set up SSE response with appropriate headers
+    let mut response = Sse::new(stream)
+        .keep_alive(sse_keep_alive(&state.config.server.sse_keep_alive))
+        .into_response();
+    apply_sse_headers(response.headers_mut(), &state.config.server.sse_headers);
 
-    // Don't send any initial events - let the client initiate
-    // This matches the Streamable HTTP specification
+    Ok(response)
+}
 
-    // Convert receiver to stream
-    let stream = ReceiverStream::new(rx);
+/// Add the configured extra SSE headers, replacing defaults of the same name
+fn apply_sse_headers(headers: &mut HeaderMap, extra: &std::collections::BTreeMap<String, String>) {
+    for (name, value) in extra {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => warn!("Skipping invalid SSE header '{}: {}'", name, value),
+        }
+    }
+}
 
-    // Set up SSE response with appropriate headers
-    let response = Sse::new(stream).keep_alive(
-        axum::response::sse::KeepAlive::new()
-            .interval(std::time::Duration::from_secs(30))
-            .text("keep-alive"),
-    );
+/// Keep-alive framing and interval for SSE streams, as configured
+fn sse_keep_alive(settings: &SseKeepAliveSettings) -> KeepAlive {
+    let keep_alive = KeepAlive::new().interval(std::time::Duration::from_secs(
+        settings.interval_secs.max(1),
+    ));
+    match settings.mode {
+        SseKeepAliveMode::Comment => keep_alive.text(settings.text.as_str()),
+        SseKeepAliveMode::Event => keep_alive.event(
+            Event::default()
+                .event(SSE_KEEP_ALIVE_EVENT)
+                .data(settings.text.as_str()),
+        ),
+    }
+}
 
-    Ok(response.into_response())
+/// Look up a session, marking it accessed when `touch` is set; store errors count as missing
+async fn lookup_session(state: &AppState, id: &str, touch: bool) -> Option<SessionData> {
+    let result = if touch {
+        state.session_store.touch(id).await
+    } else {
+        state.session_store.get(id).await
+    };
+    result.unwrap_or_else(|e| {
+        error!("Session store lookup for {} failed: {:#}", id, e);
+        None
+    })
 }
 
 /// Handle DELETE requests for session termination
-async fn handle_delete_session(session_id: Option<String>) -> Result<Response, StatusCode> {
+async fn handle_delete_session(
+    state: AppState,
+    session_id: Option<String>,
+) -> Result<Response, StatusCode> {
     if let Some(sid) = session_id {
-        if let Some(_) = SESSIONS.remove(&sid) {
+        let removed = state.session_store.remove(&sid).await.map_err(|e| {
+            error!("Session store removal for {} failed: {:#}", sid, e);
+            StatusCode::SERVICE_UNAVAILABLE
+        })?;
+        if removed {
+            state.sse_channels.close(&sid);
+            state.rule_activations.remove_session(&sid);
             info!("Session terminated: {}", sid);
             Ok(Response::builder()
                 .status(StatusCode::OK)
@@ -744,14 +2784,16 @@ async fn handle_delete_session(session_id: Option<String>) -> Result<Response, S
     } else {
         Ok(Response::builder()
             .status(StatusCode::BAD_REQUEST)
-            .body(axum::body::Body::from("Session ID required"))
+            .body(axum::body::Body::from(
+                ErrorCode::SessionRequired.message(DEFAULT_LANGUAGE),
+            ))
             .unwrap())
     }
 }
 
 /// Handle initialize request
 async fn handle_initialize(
-    _state: &AppState,
+    state: &AppState,
     request: JsonRpcRequest,
     config: &SessionConfig,
 ) -> JsonRpcResponse {
@@ -768,12 +2810,8 @@ async fn handle_initialize(
     let params: InitializeParams = match serde_json::from_value(request.params) {
         Ok(p) => p,
         Err(e) => {
-            return create_error_response(
-                request.id,
-                -32602,
-                "Invalid params",
-                Some(json!({ "error": e.to_string() })),
-            );
+            return ErrorCode::InvalidParams
+                .response(request.id, Some(json!({ "error": e.to_string() })));
         }
     };
 
@@ -782,18 +2820,27 @@ async fn handle_initialize(
         params.protocol_version, params.client_info
     );
 
-    // Check protocol version compatibility
-    let supported_version = "2024-11-05";
-    let negotiated_version = if params.protocol_version == supported_version {
-        supported_version
-    } else {
-        // For now, we only support one version
-        warn!(
-            "Client requested unsupported protocol version: {}",
-            params.protocol_version
-        );
-        supported_version
-    };
+    // Negotiate the newest protocol version both sides speak
+    let supported_versions = &state.config.server.supported_protocol_versions;
+    let negotiated_version =
+        match negotiate_protocol_version(&params.protocol_version, supported_versions) {
+            Some(version) => version,
+            None => {
+                warn!(
+                    "Client requested unsupported protocol version: {}",
+                    params.protocol_version
+                );
+                return create_error_response(
+                    request.id,
+                    -32602,
+                    "Unsupported protocol version",
+                    Some(json!({
+                        "requested": params.protocol_version,
+                        "supported": supported_versions
+                    })),
+                );
+            }
+        };
 
     let sampling_enabled = sampling_feature_enabled();
 
@@ -815,7 +2862,8 @@ async fn handle_initialize(
         },
         "experimental": {
             "consciousness": true,
-            "paradox_handling": true
+            "paradox_handling": true,
+            "casial": casial_capabilities()
         }
     });
 
@@ -844,16 +2892,34 @@ async fn handle_initialize(
     });
 
     // Generate a session ID for streamable-http transport
-    let session_id = format!("mop-{}", uuid::Uuid::new_v4());
-
-    // Store the session
+    let session_id = format!("mop-{}", state.id_source.next_id());
+
+    // Store the session, dropping idle ones on the way
+    let idle_timeout = std::time::Duration::from_secs(state.config.sessions.idle_timeout_secs);
+    match state.session_store.reap(idle_timeout).await {
+        Ok(0) => {}
+        Ok(reaped) => debug!("Reaped {} idle sessions", reaped),
+        Err(e) => warn!("Failed to reap idle sessions: {:#}", e),
+    }
+    if let Ok(idle_timeout) = chrono::Duration::from_std(idle_timeout) {
+        state.rule_activations.reap(idle_timeout);
+    }
+    let now = Utc::now();
     let session_data = SessionData {
         id: session_id.clone(),
         config: config.clone(),
-        created_at: std::time::Instant::now(),
-        last_accessed: std::time::Instant::now(),
+        created_at: now,
+        last_accessed: now,
     };
-    SESSIONS.insert(session_id.clone(), session_data);
+    if let Err(e) = state.session_store.insert(session_data).await {
+        error!("Failed to store session {}: {:#}", session_id, e);
+        return create_error_response(
+            request.id,
+            -32000,
+            "Failed to store session",
+            Some(json!({ "error": e.to_string() })),
+        );
+    }
     info!("Created new session: {}", session_id);
 
     // Store session ID in the result for HTTP transport
@@ -880,8 +2946,19 @@ async fn handle_initialized(_state: &AppState, request: JsonRpcRequest) -> JsonR
 async fn handle_tools_list(state: &AppState, request: JsonRpcRequest) -> JsonRpcResponse {
     info!("Listing MCP tools");
 
-    // Get tools from registry
-    let tools = state.tool_registry.get_all_tools();
+    let params = match ListParams::from_params(&request.params) {
+        Ok(params) => params,
+        Err(e) => return invalid_list_params(request.id, e),
+    };
+
+    // Get tools from registry, sorted so cursors stay stable between calls
+    let mut tools = state.tool_registry.get_all_tools();
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let (tools, next_cursor) = match paginate(tools, &params) {
+        Ok(page) => page,
+        Err(e) => return invalid_list_params(request.id, e),
+    };
 
     // Convert to MCP tool format
     let mcp_tools: Vec<Value> = tools
@@ -896,89 +2973,196 @@ async fn handle_tools_list(state: &AppState, request: JsonRpcRequest) -> JsonRpc
         })
         .collect();
 
-    let result = json!({
+    let mut result = json!({
         "tools": mcp_tools
     });
+    if let Some(cursor) = next_cursor {
+        result["nextCursor"] = json!(cursor);
+    }
 
     create_success_response(request.id, result)
 }
 
 /// Handle tools/call request
+#[tracing::instrument(name = "tool_call", skip_all, fields(tool))]
 async fn handle_tool_call(
     state: &AppState,
     request: JsonRpcRequest,
-    agent_role: Option<&str>,
+    config: &SessionConfig,
+    session_id: Option<&str>,
 ) -> JsonRpcResponse {
     #[derive(Deserialize)]
     struct ToolCallParams {
         name: String,
         arguments: Option<Value>,
+        #[serde(rename = "_meta", default)]
+        meta: Option<ToolCallMeta>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ToolCallMeta {
+        idempotency_key: Option<String>,
     }
 
+    let progress_token = ProgressReporter::token_from_params(&request.params);
+    let bypass_shim = PitfallAvoidanceShim::bypass_requested(&request.params);
     let params: ToolCallParams = match serde_json::from_value(request.params) {
         Ok(p) => p,
         Err(e) => {
-            return create_error_response(
-                request.id,
-                -32602,
-                "Invalid params",
-                Some(json!({ "error": e.to_string() })),
-            );
+            return ErrorCode::InvalidParams
+                .response(request.id, Some(json!({ "error": e.to_string() })));
         }
     };
 
+    tracing::Span::current().record("tool", params.name.as_str());
     info!("Calling tool: {}", params.name);
 
-    // Apply pitfall avoidance shim to augment the request
-    let augmented_args = {
-        let shim = state.pitfall_shim.read().await;
-        let args = params.arguments.unwrap_or(json!({}));
-        match shim.augment_request(&params.name, &args, agent_role) {
-            Ok(augmented) => augmented,
-            Err(e) => {
-                warn!("Failed to augment request with shim: {}", e);
-                args
+    if state.missing_required_mission(&params.name) {
+        warn!("Refusing {}: no mission is loaded", params.name);
+        return ErrorCode::NoMissionLoaded
+            .response(request.id, Some(json!({ "tool": params.name })));
+    }
+
+    // A repeated idempotency key replays the first result instead of executing again. Keys
+    // are scoped to the session, or the API key for calls outside one.
+    let idempotency_key = params.meta.and_then(|meta| meta.idempotency_key);
+    let original_args = params.arguments.unwrap_or(json!({}));
+    let idempotency_scope = session_id.or(config.api_key.as_deref()).unwrap_or_default();
+    let mut idempotency_reservation = None;
+    if let Some(key) = &idempotency_key {
+        match state
+            .idempotency_cache
+            .begin(idempotency_scope, key, &params.name, &original_args)
+        {
+            IdempotentLookup::Hit(result) => {
+                info!(
+                    "Replaying {} result for idempotency key {}",
+                    params.name, key
+                );
+                return create_success_response(request.id, result);
+            }
+            IdempotentLookup::Conflict => {
+                return create_error_response(
+                    request.id,
+                    -32602,
+                    "Idempotency key already used for a different tool call",
+                    Some(json!({ "idempotencyKey": key })),
+                );
             }
+            IdempotentLookup::InProgress => {
+                return create_error_response(
+                    request.id,
+                    -32000,
+                    "A tool call with this idempotency key is still running",
+                    Some(json!({ "idempotencyKey": key })),
+                );
+            }
+            IdempotentLookup::Miss(reservation) => idempotency_reservation = Some(reservation),
+        }
+    }
+
+    // Omitted optional arguments take their schema defaults
+    let mut args = original_args.clone();
+    state
+        .tool_registry
+        .apply_schema_defaults(&params.name, &mut args);
+
+    // Registered tools must match their input schema before anything runs
+    if state.tool_registry.get_tool(&params.name).is_some() {
+        if let Err(validation_errors) = state
+            .tool_registry
+            .validate_tool_arguments(&params.name, &args)
+            .await
+        {
+            return ErrorCode::InvalidParams.response(
+                request.id,
+                Some(json!({
+                    "tool": params.name,
+                    "validation_errors": validation_errors
+                })),
+            );
         }
+    }
+
+    // The pitfall shim and mission context are applied in the configured order, unless the
+    // caller asked for the raw arguments. Admin credentials skip both.
+    let admin_token = take_admin_token(&mut args);
+    let agent_role = config.agent_role.as_deref();
+    let shim_first =
+        state.config.consciousness.injection_order == InjectionOrder::ShimBeforeCoordination;
+    if bypass_shim {
+        info!("Bypassing the pitfall shim for {}", params.name);
+    }
+    let args = if shim_first && !bypass_shim {
+        state.apply_shim(&params.name, args, agent_role).await
+    } else {
+        args
     };
 
-    // Execute the tool based on its name
-    let tool_response = match params.name.as_str() {
-        "exa_search_example" => execute_exa_search_example(state, augmented_args).await,
-        "exa_research_example" => execute_exa_research_example(state, augmented_args).await,
-        "orchestrate_mcp_proxy" => execute_orchestrate_mcp_proxy(state, augmented_args).await,
-        "discover_mcp_tools" => execute_discover_mcp_tools(state, augmented_args).await,
-        _ => {
-            // Check if it's a federated tool
-            if let Some(federation_manager) = state.federation_manager.read().await.as_ref() {
-                match federation_manager
-                    .route_tool_call(
-                        &params.name,
-                        augmented_args.clone(),
-                        crate::federation::ExecutionMode::Execute,
-                    )
-                    .await
-                {
-                    Ok(result) => result,
-                    Err(e) => {
-                        json!({
-                            "error": format!("Tool execution failed: {}", e),
-                            "tool": params.name,
-                            "augmented_arguments": augmented_args
-                        })
-                    }
-                }
-            } else {
-                json!({
-                    "error": format!("Unknown tool: {}", params.name),
-                    "available_tools": ["exa_search_example", "exa_research_example", "orchestrate_mcp_proxy", "discover_mcp_tools"]
-                })
+    // Inject mission context according to the session's consciousness mode
+    let args = match coordinate_tool_args(state, &params.name, &args, config, session_id).await {
+        Ok(Some(result)) => result.modified_args,
+        Ok(None) => args,
+        Err(e) => {
+            warn!("Context coordination failed for {}: {}", params.name, e);
+            args
+        }
+    };
+
+    let augmented_args = if shim_first || bypass_shim {
+        args
+    } else {
+        state.apply_shim(&params.name, args, agent_role).await
+    };
+
+    // Execute with the registered backend, falling back to federation
+    let executor = state
+        .tool_executors
+        .executor_for(&params.name)
+        .unwrap_or_else(|| {
+            Arc::new(FederationExecutor::new(Arc::clone(
+                &state.federation_manager,
+            )))
+        });
+    let mut executed = true;
+    // Progress goes out on the session's SSE stream, when it has one open
+    let progress = session_id
+        .and_then(|sid| state.sse_channels.get(sid))
+        .map(|channel| ProgressReporter::over_sse(progress_token.clone(), channel))
+        .unwrap_or_default();
+    let tool_response = match executor
+        .execute_with_progress(
+            &params.name,
+            restore_admin_token(augmented_args.clone(), admin_token),
+            &progress,
+        )
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            // Downstream failures surface as JSON-RPC errors so clients can decide to retry
+            if let Some(error) = e.downcast_ref::<FederationError>() {
+                return error.to_response(request.id, &params.name);
             }
+            if e.is::<InvalidToolArguments>() {
+                return ErrorCode::InvalidParams.response(
+                    request.id,
+                    Some(json!({ "tool": params.name, "detail": e.to_string() })),
+                );
+            }
+            executed = false;
+            json!({
+                "error": format!("Tool execution failed: {}", e),
+                "tool": params.name,
+                "augmented_arguments": augmented_args,
+                "available_tools": state.tool_executors.registered_tools()
+            })
         }
     };
 
     // Process the response through the shim
-    let processed_response = {
+    let mut processed_response = {
         let shim = state.pitfall_shim.read().await;
         match shim.process_response(&params.name, &tool_response) {
             Ok(processed) => processed,
@@ -989,18 +3173,126 @@ async fn handle_tool_call(
         }
     };
 
-    create_success_response(
-        request.id,
-        json!({
-            "content": [{
-                "type": "text",
-                "text": serde_json::to_string_pretty(&processed_response).unwrap_or_default()
-            }],
-            "isError": false
-        }),
+    if !state.config.consciousness.expose_injected_fields {
+        strip_injected_fields(&mut processed_response);
+    }
+
+    let mut result = shape_tool_result(
+        &params.name,
+        serde_json::to_string_pretty(&processed_response).unwrap_or_default(),
+        state.config.server.large_results.as_ref(),
+        &state.tool_executors,
+    )
+    .await;
+    result["isError"] = json!(false);
+    if bypass_shim {
+        result["_meta"]["casial_shim_bypassed"] = json!(true);
+    }
+
+    // Failed executions aren't remembered, so a retry with the same key runs again
+    if let (Some(reservation), true) = (idempotency_reservation, executed) {
+        reservation.complete(result.clone());
+    }
+
+    create_success_response(request.id, result)
+}
+
+/// Run mission coordination for a tool call within the session's context budget
+///
+/// Returns `None` when the session has disabled consciousness coordination.
+async fn coordinate_tool_args(
+    state: &AppState,
+    tool_name: &str,
+    args: &Value,
+    config: &SessionConfig,
+    session_id: Option<&str>,
+) -> Result<Option<CoordinationResult>> {
+    let mode = config.resolved_consciousness_mode();
+    if mode == ConsciousnessMode::Disabled {
+        debug!(
+            "Consciousness disabled for session, passing {} through",
+            tool_name
+        );
+        return Ok(None);
+    }
+
+    let coordination_request = CoordinationRequest {
+        tool_name: tool_name.to_string(),
+        tool_args: args.clone(),
+        environment: std::env::vars().collect(),
+        project_path: args
+            .get("projectPath")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        paradox_tolerance: args
+            .get("paradoxTolerance")
+            .and_then(|v| v.as_f64())
+            .and_then(sanitize_paradox_tolerance),
+        // Negative sizes are meaningless, so they fall back to the mission budget
+        max_context_size: config
+            .max_context_size
+            .and_then(|size| usize::try_from(size).ok()),
+        detect_paradoxes: mode == ConsciousnessMode::Full,
+        max_template_priority: config.max_template_priority,
+        mission_id: session_mission_id(state, config),
+        include_provenance: args
+            .get("includeProvenance")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        include_paradox_evidence: args
+            .get("includeParadoxEvidence")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        template_allow: template_ids_arg(args, "templateAllow"),
+        template_deny: template_ids_arg(args, "templateDeny").unwrap_or_default(),
+        ..Default::default()
+    };
+
+    // Rule cooldowns need a session to remember activations in
+    match session_id {
+        Some(session_id) => state.rule_activations.coordinate(
+            &state.casial_engine,
+            session_id,
+            coordination_request,
+        ),
+        None => state.casial_engine.coordinate(coordination_request),
+    }
+    .map(Some)
+}
+
+/// Template ids listed under `key` in tool call arguments, for `templateAllow`/`templateDeny`
+pub(crate) fn template_ids_arg(args: &Value, key: &str) -> Option<Vec<String>> {
+    let ids = args.get(key)?.as_array()?;
+    Some(
+        ids.iter()
+            .filter_map(|id| id.as_str().map(str::to_string))
+            .collect(),
     )
 }
 
+/// Loaded mission the session selected, after resolving configured aliases
+///
+/// Sessions naming no mission, or one that isn't loaded, coordinate against every
+/// loaded mission.
+fn session_mission_id(state: &AppState, config: &SessionConfig) -> Option<String> {
+    let requested = config.mission.as_deref()?.trim();
+    let mission_id = state
+        .config
+        .missions
+        .aliases
+        .get(requested)
+        .map_or(requested, String::as_str);
+    if state.casial_engine.has_mission(mission_id) {
+        Some(mission_id.to_string())
+    } else {
+        debug!(
+            "Session mission '{}' is not loaded, using all missions",
+            requested
+        );
+        None
+    }
+}
+
 /// Handle completion request
 async fn handle_completion(_state: &AppState, request: JsonRpcRequest) -> JsonRpcResponse {
     #[derive(Deserialize)]
@@ -1026,12 +3318,8 @@ async fn handle_completion(_state: &AppState, request: JsonRpcRequest) -> JsonRp
     let _params: CompletionParams = match serde_json::from_value(request.params) {
         Ok(p) => p,
         Err(e) => {
-            return create_error_response(
-                request.id,
-                -32602,
-                "Invalid params",
-                Some(json!({ "error": e.to_string() })),
-            );
+            return ErrorCode::InvalidParams
+                .response(request.id, Some(json!({ "error": e.to_string() })));
         }
     };
 
@@ -1117,6 +3405,12 @@ fn build_mcp_config() -> serde_json::Value {
                     "maximum": 1000000,
                     "default": 100000
                 },
+                "max_template_priority": {
+                    "type": "integer",
+                    "title": "Maximum Template Priority",
+                    "description": "Skip templates with a higher priority number (less important) for cheap/fast calls",
+                    "minimum": 0
+                },
                 "mission": {
                     "type": "string",
                     "title": "Mission Profile",
@@ -1141,6 +3435,39 @@ fn build_mcp_config() -> serde_json::Value {
     })
 }
 
+/// Quoted SHA-256 of the serialized config, stable for as long as the config is unchanged
+fn mcp_config_etag(config: &Value) -> String {
+    use sha2::{Digest, Sha256};
+
+    let bytes = serde_json::to_vec(config).unwrap_or_default();
+    format!("\"{:x}\"", Sha256::digest(&bytes))
+}
+
+/// Config response with an `ETag`, or a bare 304 when `If-None-Match` already has it
+fn mcp_config_response(config: Value, headers: &HeaderMap) -> Response {
+    let etag = mcp_config_etag(&config);
+    let not_modified = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag);
+
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        Json(config).into_response()
+    };
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
 pub async fn well_known_config_handler(
     method: Method,
     State(state): State<AppState>,
@@ -1148,10 +3475,7 @@ pub async fn well_known_config_handler(
     body: Option<String>,
 ) -> Result<Response, StatusCode> {
     match method {
-        Method::GET => {
-            let config = build_mcp_config();
-            Ok(Json(config).into_response())
-        }
+        Method::GET => Ok(mcp_config_response(build_mcp_config(), &headers)),
         Method::POST => {
             // For POST requests, handle as JSON-RPC (Smithery might be sending JSON-RPC to this endpoint)
             if let Some(body) = body {
@@ -1170,196 +3494,24 @@ pub async fn well_known_config_handler(
             }
 
             // If not JSON-RPC, return the same config as GET
-            let config = build_mcp_config();
-            Ok(Json(config).into_response())
+            Ok(mcp_config_response(build_mcp_config(), &headers))
         }
-        _ => Ok(StatusCode::METHOD_NOT_ALLOWED.into_response()),
-    }
-}
-
-// Tool execution implementations
-
-async fn execute_exa_search_example(
-    _state: &AppState,
-    args: serde_json::Value,
-) -> serde_json::Value {
-    // Extract parameters
-    let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
-    let num_results = args.get("numResults").and_then(|v| v.as_u64()).unwrap_or(5);
-
-    // Since this is an example tool, we'll return a simulated response
-    // In a real implementation, this would call the actual Exa API
-    json!({
-        "status": "success",
-        "tool": "exa_search_example",
-        "query": query,
-        "results": [
-            {
-                "title": "AI Orchestration Best Practices 2025",
-                "url": "https://example.com/ai-orchestration",
-                "snippet": "Latest developments in AI orchestration for microservices...",
-                "score": 0.95
-            },
-            {
-                "title": "MCP Federation Architecture Guide",
-                "url": "https://example.com/mcp-federation",
-                "snippet": "How to build federated MCP systems with consciousness-aware features...",
-                "score": 0.92
-            }
-        ],
-        "metadata": {
-            "num_results_requested": num_results,
-            "augmented": true,
-            "timestamp": chrono::Utc::now().to_rfc3339()
+        Method::HEAD => {
+            // Same status and headers as GET, so discovery clients can check the ETag cheaply
+            let (mut parts, _) = mcp_config_response(build_mcp_config(), &headers).into_parts();
+            apply_cors_headers(&mut parts.headers, &headers);
+            Ok(Response::from_parts(parts, axum::body::Body::empty()))
         }
-    })
-}
-
-async fn execute_exa_research_example(
-    _state: &AppState,
-    args: serde_json::Value,
-) -> serde_json::Value {
-    let instructions = args
-        .get("instructions")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let model = args
-        .get("model")
-        .and_then(|v| v.as_str())
-        .unwrap_or("exa-research");
-
-    // Simulated research task response
-    json!({
-        "status": "success",
-        "tool": "exa_research_example",
-        "task_id": uuid::Uuid::new_v4().to_string(),
-        "instructions": instructions,
-        "model": model,
-        "result": {
-            "summary": "Research task initiated. In a real implementation, this would start an async research process.",
-            "next_step": "Poll for results using the task_id"
-        },
-        "metadata": {
-            "augmented": true,
-            "timestamp": chrono::Utc::now().to_rfc3339()
+        Method::OPTIONS => {
+            let mut response = StatusCode::NO_CONTENT.into_response();
+            apply_cors_headers(response.headers_mut(), &headers);
+            Ok(response)
         }
-    })
-}
-
-async fn execute_orchestrate_mcp_proxy(
-    _state: &AppState,
-    args: serde_json::Value,
-) -> serde_json::Value {
-    let target_server = args
-        .get("target_server")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let tool_name = args.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
-    let original_params = args.get("original_params").cloned().unwrap_or(json!({}));
-    let augmentation_config = args
-        .get("augmentation_config")
-        .cloned()
-        .unwrap_or(json!({}));
-
-    // Apply augmentation based on config
-    let mut augmented_params = original_params.clone();
-
-    if augmentation_config
-        .get("inject_context")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false)
-    {
-        augmented_params["_context"] = json!({
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "orchestration_source": "mop",
-            "consciousness_aware": true
-        });
-    }
-
-    if let Some(instructions) = augmentation_config
-        .get("add_swarm_instructions")
-        .and_then(|v| v.as_array())
-    {
-        augmented_params["_swarm_instructions"] = serde_json::Value::Array(instructions.clone());
+        _ => Ok(StatusCode::METHOD_NOT_ALLOWED.into_response()),
     }
-
-    // In a real implementation, this would forward to the actual target server
-    // For now, return a response showing what would be sent
-    json!({
-        "status": "success",
-        "tool": "orchestrate_mcp_proxy",
-        "forwarded_to": target_server,
-        "tool_called": tool_name,
-        "augmented_params": augmented_params,
-        "augmentation_applied": augmentation_config,
-        "result": {
-            "message": "In production, this would forward the augmented request to the target MCP server",
-            "would_call": format!("{}/{}", target_server, tool_name)
-        },
-        "metadata": {
-            "augmented": true,
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        }
-    })
 }
 
-async fn execute_discover_mcp_tools(
-    state: &AppState,
-    args: serde_json::Value,
-) -> serde_json::Value {
-    let server_url = args
-        .get("server_url")
-        .and_then(|v| v.as_str())
-        .unwrap_or("");
-    let analyze_for_orchestration = args
-        .get("analyze_for_orchestration")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(true);
-
-    // Get tools from our registry as an example
-    let tools = state.tool_registry.get_all_tools();
-
-    let discovered_tools: Vec<serde_json::Value> = tools
-        .into_iter()
-        .map(|tool| {
-            json!({
-                "name": tool.name,
-                "description": tool.description,
-                "source": match &tool.source {
-                    crate::registry::ToolSource::Local => "local",
-                    crate::registry::ToolSource::Federated { server_id, .. } => server_id
-                },
-                "input_schema": tool.input_schema,
-                "orchestration_hints": if analyze_for_orchestration {
-                    Some(json!({
-                        "supports_consciousness": true,
-                        "paradox_tolerant": true,
-                        "federation_ready": true
-                    }))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect();
-
-    json!({
-        "status": "success",
-        "tool": "discover_mcp_tools",
-        "server_url": server_url,
-        "discovered_tools": discovered_tools,
-        "total_tools": discovered_tools.len(),
-        "analysis": {
-            "orchestration_compatible": true,
-            "consciousness_features": ["temporal_awareness", "context_injection", "paradox_handling"],
-            "recommended_patterns": ["saga", "event_driven", "federation"]
-        },
-        "metadata": {
-            "augmented": true,
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        }
-    })
-}
+// Tool execution implementations
 
 // Prompts handlers
 
@@ -1417,12 +3569,8 @@ async fn handle_prompts_get(_state: &AppState, request: JsonRpcRequest) -> JsonR
     let params: PromptsGetParams = match serde_json::from_value(request.params) {
         Ok(p) => p,
         Err(e) => {
-            return create_error_response(
-                request.id,
-                -32602,
-                "Invalid params",
-                Some(json!({ "error": e.to_string() })),
-            );
+            return ErrorCode::InvalidParams
+                .response(request.id, Some(json!({ "error": e.to_string() })));
         }
     };
 
@@ -1512,12 +3660,8 @@ async fn handle_prompts_get(_state: &AppState, request: JsonRpcRequest) -> JsonR
             })]
         }
         _ => {
-            return create_error_response(
-                request.id,
-                -32602,
-                &format!("Unknown prompt: {}", params.name),
-                None,
-            );
+            return ErrorCode::UnknownPrompt
+                .response(request.id, Some(json!({ "prompt": params.name })));
         }
     };
 
@@ -1529,6 +3673,11 @@ async fn handle_prompts_get(_state: &AppState, request: JsonRpcRequest) -> JsonR
 async fn handle_resources_list(_state: &AppState, request: JsonRpcRequest) -> JsonRpcResponse {
     info!("Listing MCP resources");
 
+    let params = match ListParams::from_params(&request.params) {
+        Ok(params) => params,
+        Err(e) => return invalid_list_params(request.id, e),
+    };
+
     let resources = vec![
         json!({
             "uri": "mop://tools/catalog",
@@ -1537,6 +3686,13 @@ async fn handle_resources_list(_state: &AppState, request: JsonRpcRequest) -> Js
             "description": "Complete catalog of tools registered with the server, including federation metadata",
             "mimeType": "application/json"
         }),
+        json!({
+            "uri": "mop://tools/catalog.sha256",
+            "name": "Tool Catalog Fingerprint",
+            "title": "Tool Catalog SHA-256",
+            "description": "Raw SHA-256 digest of tool names and spec hashes, for cheap change detection",
+            "mimeType": "application/octet-stream"
+        }),
         json!({
             "uri": "mop://orchestration/context",
             "name": "Current Orchestration Context",
@@ -1567,7 +3723,70 @@ async fn handle_resources_list(_state: &AppState, request: JsonRpcRequest) -> Js
         }),
     ];
 
-    create_success_response(request.id, json!({ "resources": resources }))
+    let (resources, next_cursor) = match paginate(resources, &params) {
+        Ok(page) => page,
+        Err(e) => return invalid_list_params(request.id, e),
+    };
+
+    let mut result = json!({ "resources": resources });
+    if let Some(cursor) = next_cursor {
+        result["nextCursor"] = json!(cursor);
+    }
+
+    create_success_response(request.id, result)
+}
+
+/// Coordination history narrowed to calls of one tool
+const HISTORY_BY_TOOL_TEMPLATE: &str = "mop://orchestration/history/{tool}";
+
+async fn handle_resources_templates_list(
+    _state: &AppState,
+    request: JsonRpcRequest,
+) -> JsonRpcResponse {
+    let params = match ListParams::from_params(&request.params) {
+        Ok(params) => params,
+        Err(e) => return invalid_list_params(request.id, e),
+    };
+
+    let templates = vec![json!({
+        "uriTemplate": HISTORY_BY_TOOL_TEMPLATE,
+        "name": "Tool Orchestration History",
+        "title": "Orchestration History for One Tool",
+        "description": "Past orchestrations recorded for calls to the named tool",
+        "mimeType": "application/json"
+    })];
+
+    let (templates, next_cursor) = match paginate(templates, &params) {
+        Ok(page) => page,
+        Err(e) => return invalid_list_params(request.id, e),
+    };
+
+    let mut result = json!({ "resourceTemplates": templates });
+    if let Some(cursor) = next_cursor {
+        result["nextCursor"] = json!(cursor);
+    }
+
+    create_success_response(request.id, result)
+}
+
+/// History resource body: totals plus a sample of up to 10 events
+fn orchestration_history_contents(uri: &str, history: &[CoordinationResult]) -> Vec<Value> {
+    let applied_events = history.iter().filter(|entry| entry.applied).count();
+    let recent_sample: Vec<_> = history.iter().take(10).cloned().collect();
+
+    let payload = json!({
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+        "totalEvents": history.len(),
+        "appliedEvents": applied_events,
+        "recentSample": recent_sample,
+        "notes": "Sample includes up to 10 of the most recently recorded coordination events",
+    });
+
+    vec![json!({
+        "uri": uri,
+        "mimeType": "application/json",
+        "text": serde_json::to_string_pretty(&payload).unwrap(),
+    })]
 }
 
 async fn handle_resources_read(state: &AppState, request: JsonRpcRequest) -> JsonRpcResponse {
@@ -1579,12 +3798,8 @@ async fn handle_resources_read(state: &AppState, request: JsonRpcRequest) -> Jso
     let params: ResourcesReadParams = match serde_json::from_value(request.params) {
         Ok(p) => p,
         Err(e) => {
-            return create_error_response(
-                request.id,
-                -32602,
-                "Invalid params",
-                Some(json!({ "error": e.to_string() })),
-            );
+            return ErrorCode::InvalidParams
+                .response(request.id, Some(json!({ "error": e.to_string() })));
         }
     };
 
@@ -1636,6 +3851,14 @@ async fn handle_resources_read(state: &AppState, request: JsonRpcRequest) -> Jso
                 "text": serde_json::to_string_pretty(&payload).unwrap(),
             })]
         }
+        "mop://tools/catalog.sha256" => {
+            let fingerprint = tool_catalog_fingerprint(&state.tool_registry);
+            vec![blob_resource_content(
+                &params.uri,
+                "application/octet-stream",
+                &fingerprint,
+            )]
+        }
         "mop://orchestration/context" => {
             let metrics = state.metrics_collector.read().await.get_current_metrics();
             vec![json!({
@@ -1657,26 +3880,8 @@ async fn handle_resources_read(state: &AppState, request: JsonRpcRequest) -> Jso
             })]
         }
         "mop://orchestration/history" => {
-            let engine = state.casial_engine.read().await;
-            let history = engine.get_coordination_history();
-            drop(engine);
-
-            let applied_events = history.iter().filter(|entry| entry.applied).count();
-            let recent_sample: Vec<_> = history.iter().take(10).cloned().collect();
-
-            let payload = json!({
-                "generatedAt": chrono::Utc::now().to_rfc3339(),
-                "totalEvents": history.len(),
-                "appliedEvents": applied_events,
-                "recentSample": recent_sample,
-                "notes": "Sample includes up to 10 of the most recently recorded coordination events",
-            });
-
-            vec![json!({
-                "uri": params.uri.clone(),
-                "mimeType": "application/json",
-                "text": serde_json::to_string_pretty(&payload).unwrap(),
-            })]
+            let history = state.casial_engine.get_coordination_history();
+            orchestration_history_contents(&params.uri, &history)
         }
         "mop://consciousness/state" => {
             let metrics = state.metrics_collector.read().await.get_current_metrics();
@@ -1718,19 +3923,54 @@ async fn handle_resources_read(state: &AppState, request: JsonRpcRequest) -> Jso
                 "text": serde_json::to_string_pretty(&federation_info).unwrap()
             })]
         }
-        _ => {
-            return create_error_response(
-                request.id,
-                -32602,
-                &format!("Unknown resource: {}", params.uri),
-                None,
-            );
-        }
+        uri => match match_uri_template(HISTORY_BY_TOOL_TEMPLATE, uri) {
+            Some(variables) => {
+                let tool = json!(variables["tool"]);
+                let history: Vec<CoordinationResult> = state
+                    .casial_engine
+                    .get_coordination_history()
+                    .into_iter()
+                    .filter(|entry| entry.metadata.get("tool_name") == Some(&tool))
+                    .collect();
+                orchestration_history_contents(uri, &history)
+            }
+            None => {
+                return ErrorCode::UnknownResource
+                    .response(request.id, Some(json!({ "uri": params.uri })));
+            }
+        },
     };
 
     create_success_response(request.id, json!({ "contents": contents }))
 }
 
+/// Binary resource content in the MCP `blob` form (base64 payload)
+fn blob_resource_content(uri: &str, mime_type: &str, bytes: &[u8]) -> Value {
+    json!({
+        "uri": uri,
+        "mimeType": mime_type,
+        "blob": STANDARD.encode(bytes),
+    })
+}
+
+/// SHA-256 over the sorted `name:spec_hash` lines of every registered tool
+fn tool_catalog_fingerprint(registry: &crate::registry::ToolRegistry) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+
+    let mut entries: Vec<String> = registry
+        .get_all_tools()
+        .iter()
+        .map(|tool| format!("{}:{}\n", tool.name, tool.spec_hash))
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for entry in &entries {
+        hasher.update(entry.as_bytes());
+    }
+    hasher.finalize().to_vec()
+}
+
 async fn handle_resources_subscribe(_state: &AppState, request: JsonRpcRequest) -> JsonRpcResponse {
     // For now, acknowledge subscription but don't implement real-time updates
     info!("Resource subscription requested");
@@ -1760,12 +4000,8 @@ async fn handle_sampling_create(_state: &AppState, request: JsonRpcRequest) -> J
     let params: SamplingCreateParams = match serde_json::from_value(request.params) {
         Ok(p) => p,
         Err(e) => {
-            return create_error_response(
-                request.id,
-                -32602,
-                "Invalid params",
-                Some(json!({ "error": e.to_string() })),
-            );
+            return ErrorCode::InvalidParams
+                .response(request.id, Some(json!({ "error": e.to_string() })));
         }
     };
 
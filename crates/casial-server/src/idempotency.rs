@@ -0,0 +1,194 @@
+//! # Idempotency Keys
+//!
+//! Remembers tool call results by client-supplied idempotency key so a retried call
+//! returns the first result instead of executing the tool again.
+
+use dashmap::{mapref::entry::Entry, DashMap};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+/// Outcome of looking up an idempotency key
+#[derive(Debug)]
+pub enum IdempotentLookup<'a> {
+    /// Unseen (or expired) key, now reserved for this call; the call should run
+    Miss(IdempotencyReservation<'a>),
+    /// The key was used for the same call within the TTL
+    Hit(Value),
+    /// The key was used for a different tool or different arguments
+    Conflict,
+    /// The same call is still running under this key
+    InProgress,
+}
+
+struct IdempotentEntry {
+    fingerprint: String,
+    /// `None` while the call that reserved the key is still running
+    result: Option<Value>,
+    expires_at: Instant,
+}
+
+/// Tool call results by idempotency key, kept for a fixed TTL
+///
+/// Keys are scoped by the caller (session or API key), so clients can't replay or block
+/// each other's calls by guessing keys.
+pub struct IdempotencyCache {
+    ttl: Duration,
+    entries: DashMap<String, IdempotentEntry>,
+}
+
+/// A key reserved for a running call; released unless the call completes
+#[derive(Debug)]
+pub struct IdempotencyReservation<'a> {
+    cache: &'a IdempotencyCache,
+    key: String,
+    completed: bool,
+}
+
+impl IdempotencyReservation<'_> {
+    /// Remember the call's result for repeats of the key
+    pub fn complete(mut self, result: Value) {
+        if let Some(mut entry) = self.cache.entries.get_mut(&self.key) {
+            entry.result = Some(result);
+            entry.expires_at = Instant::now() + self.cache.ttl;
+        }
+        self.completed = true;
+    }
+}
+
+impl Drop for IdempotencyReservation<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.cache
+                .entries
+                .remove_if(&self.key, |_, entry| entry.result.is_none());
+        }
+    }
+}
+
+impl std::fmt::Debug for IdempotencyCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyCache")
+            .field("ttl", &self.ttl)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: DashMap::new(),
+        }
+    }
+
+    /// Look up `scope`'s key for a call to `tool_name` with `arguments`, reserving it if unseen
+    pub fn begin(
+        &self,
+        scope: &str,
+        key: &str,
+        tool_name: &str,
+        arguments: &Value,
+    ) -> IdempotentLookup<'_> {
+        let now = Instant::now();
+        self.entries.retain(|_, e| e.expires_at > now);
+
+        let fingerprint = Self::fingerprint(tool_name, arguments);
+        let scoped_key = format!("{}\0{}", scope, key);
+        match self.entries.entry(scoped_key.clone()) {
+            Entry::Occupied(entry) => {
+                let entry = entry.get();
+                if entry.fingerprint != fingerprint {
+                    IdempotentLookup::Conflict
+                } else {
+                    match &entry.result {
+                        Some(result) => IdempotentLookup::Hit(result.clone()),
+                        None => IdempotentLookup::InProgress,
+                    }
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(IdempotentEntry {
+                    fingerprint,
+                    result: None,
+                    expires_at: now + self.ttl,
+                });
+                IdempotentLookup::Miss(IdempotencyReservation {
+                    cache: self,
+                    key: scoped_key,
+                    completed: false,
+                })
+            }
+        }
+    }
+
+    fn fingerprint(tool_name: &str, arguments: &Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(tool_name.as_bytes());
+        hasher.update([0]);
+        hasher.update(arguments.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_entries_expire_and_conflict_on_different_arguments() {
+        let cache = IdempotencyCache::new(Duration::from_millis(20));
+        let args = json!({ "query": "rust" });
+        match cache.begin("session-a", "retry-1", "search", &args) {
+            IdempotentLookup::Miss(reservation) => reservation.complete(json!({ "hits": 3 })),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(matches!(
+            cache.begin("session-a", "retry-1", "search", &args),
+            IdempotentLookup::Hit(result) if result == json!({ "hits": 3 })
+        ));
+        assert!(matches!(
+            cache.begin("session-a", "retry-1", "search", &json!({ "query": "go" })),
+            IdempotentLookup::Conflict
+        ));
+        assert!(matches!(
+            cache.begin("session-a", "other", "search", &args),
+            IdempotentLookup::Miss(_)
+        ));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(matches!(
+            cache.begin("session-a", "retry-1", "search", &args),
+            IdempotentLookup::Miss(_)
+        ));
+    }
+
+    #[test]
+    fn test_keys_are_reserved_per_scope_until_the_call_finishes() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let args = json!({ "query": "rust" });
+        let IdempotentLookup::Miss(running) = cache.begin("session-a", "k", "search", &args) else {
+            panic!("first use reserves the key");
+        };
+
+        assert!(matches!(
+            cache.begin("session-a", "k", "search", &args),
+            IdempotentLookup::InProgress
+        ));
+        // Another caller's identical key is unrelated
+        assert!(matches!(
+            cache.begin("session-b", "k", "search", &args),
+            IdempotentLookup::Miss(_)
+        ));
+
+        // A call that fails releases its key so a retry runs
+        drop(running);
+        assert!(matches!(
+            cache.begin("session-a", "k", "search", &args),
+            IdempotentLookup::Miss(_)
+        ));
+    }
+}
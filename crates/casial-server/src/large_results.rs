@@ -0,0 +1,192 @@
+//! # Large Tool Results
+//!
+//! Post-execution hook for tool results too large to hand back in one piece. Oversized
+//! results are split across several text content sections, or replaced by the output of
+//! a configured summarizer tool. The strategy used is recorded under
+//! `_meta.casial_large_result`.
+
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::{
+    config::{LargeResultSettings, LargeResultStrategy},
+    executor::{ProgressReporter, ToolExecutorRegistry},
+};
+
+/// Build the MCP tool result content for a tool's rendered output
+///
+/// Output within `max_chars`, or with no settings, is returned as a single text section.
+pub async fn shape_tool_result(
+    tool_name: &str,
+    text: String,
+    settings: Option<&LargeResultSettings>,
+    executors: &ToolExecutorRegistry,
+) -> Value {
+    let original_chars = text.chars().count();
+    let settings = match settings {
+        Some(settings) if settings.max_chars > 0 && original_chars > settings.max_chars => settings,
+        _ => return json!({ "content": [text_section(text)] }),
+    };
+
+    let mut summarizer_error = None;
+    if settings.strategy == LargeResultStrategy::Summarize {
+        match summarize(tool_name, &text, settings, executors).await {
+            Ok((summarizer, summary)) => {
+                return json!({
+                    "content": [text_section(summary)],
+                    "_meta": { "casial_large_result": {
+                        "strategy": "summarize",
+                        "original_chars": original_chars,
+                        "summarizer_tool": summarizer
+                    }}
+                });
+            }
+            Err(e) => {
+                warn!(
+                    "Summarizing the {} result failed, chunking: {}",
+                    tool_name, e
+                );
+                summarizer_error = Some(e.to_string());
+            }
+        }
+    }
+
+    let chunks = chunk_text(&text, settings.max_chars);
+    let mut metadata = json!({
+        "strategy": "chunk",
+        "original_chars": original_chars,
+        "chunks": chunks.len()
+    });
+    if let Some(error) = summarizer_error {
+        metadata["summarizer_error"] = json!(error);
+    }
+    json!({
+        "content": chunks.into_iter().map(text_section).collect::<Vec<_>>(),
+        "_meta": { "casial_large_result": metadata }
+    })
+}
+
+/// Run the configured summarizer tool, returning its name and the summary text
+async fn summarize(
+    tool_name: &str,
+    text: &str,
+    settings: &LargeResultSettings,
+    executors: &ToolExecutorRegistry,
+) -> anyhow::Result<(String, String)> {
+    let summarizer = settings
+        .summarizer_tool
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("no summarizer_tool is configured"))?;
+    if summarizer == tool_name {
+        anyhow::bail!("'{}' cannot summarize its own results", summarizer);
+    }
+
+    let output = executors
+        .execute(
+            summarizer,
+            json!({ "content": text, "max_chars": settings.max_chars }),
+            &ProgressReporter::default(),
+        )
+        .await?;
+    let summary = match output {
+        Value::String(summary) => summary,
+        Value::Object(ref map) if map.get("summary").is_some_and(Value::is_string) => {
+            map["summary"].as_str().unwrap_or_default().to_string()
+        }
+        other => serde_json::to_string_pretty(&other)?,
+    };
+    Ok((summarizer.to_string(), summary))
+}
+
+/// Split text into pieces of at most `max_chars` characters that concatenate back to it
+///
+/// Pieces end after a complete line where one fits, so pretty-printed JSON breaks between
+/// members. A line longer than `max_chars` breaks after a space, or failing that anywhere
+/// outside a JSON escape sequence.
+fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let end = if chars.len() - start <= max_chars {
+            chars.len()
+        } else {
+            start + chunk_len(&chars[start..start + max_chars])
+        };
+        chunks.push(chars[start..end].iter().collect());
+        start = end;
+    }
+    chunks
+}
+
+/// How much of a full `window` to take as one chunk
+fn chunk_len(window: &[char]) -> usize {
+    let after = |boundary: char| window.iter().rposition(|c| *c == boundary).map(|at| at + 1);
+    if let Some(len) = after('\n').or_else(|| after(' ')) {
+        return len;
+    }
+
+    // Back off so an escape like `\"` or `\u00e9` isn't split from its backslash
+    let mut len = window.len();
+    if let Some(escape) = window[window.len().saturating_sub(5)..]
+        .windows(2)
+        .rposition(|pair| pair == ['\\', 'u'])
+    {
+        len = window.len().saturating_sub(5) + escape;
+    }
+    while len > 0 && window[len - 1] == '\\' {
+        len -= 1;
+    }
+    // A window of nothing but backslashes can't be split cleanly; take it whole
+    if len == 0 {
+        window.len()
+    } else {
+        len
+    }
+}
+
+fn text_section(text: String) -> Value {
+    json!({ "type": "text", "text": text })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_split_on_character_boundaries() {
+        let chunks = chunk_text("héllowörld", 4);
+        assert_eq!(chunks, vec!["héll", "owör", "ld"]);
+        assert_eq!(chunks.concat(), "héllowörld");
+    }
+
+    #[test]
+    fn chunks_break_between_json_members() {
+        let text = serde_json::to_string_pretty(&json!({
+            "title": "Rust async runtimes",
+            "url": "https://example.com/runtimes",
+            "summary": "Tokio and smol compared"
+        }))
+        .unwrap();
+
+        let chunks = chunk_text(&text, 45);
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.chars().count() <= 45);
+            assert!(chunk.ends_with('\n'), "split inside a member: {:?}", chunk);
+        }
+    }
+
+    #[test]
+    fn long_lines_break_at_spaces_and_keep_escapes_whole() {
+        let chunks = chunk_text("\"crawled text crawled text\"", 16);
+        assert_eq!(chunks, vec!["\"crawled text ", "crawled text\""]);
+
+        let escaped = r#"abcdefgh\u00e9ij"#;
+        let chunks = chunk_text(escaped, 10);
+        assert_eq!(chunks, vec!["abcdefgh", r#"\u00e9ij"#]);
+        let chunks = chunk_text(r#"abcdefghi\"j"#, 10);
+        assert_eq!(chunks, vec!["abcdefghi", r#"\"j"#]);
+    }
+}
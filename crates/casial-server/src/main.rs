@@ -3,45 +3,79 @@
 //! High-performance WebSocket MCP server with consciousness-aware context coordination.
 //! Part of the Ubiquity OS ecosystem - where paradoxes make the system stronger.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axum::{
     body::Body,
     extract::{ws::WebSocketUpgrade, Query, State},
     http::{self, header, HeaderMap, HeaderValue, Method, Request, StatusCode},
     middleware::{from_fn_with_state, Next},
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use dashmap::DashMap;
-use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
 use tokio::signal;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 use tower_http::trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tracing::{info, warn, Level};
+use tracing_subscriber::{
+    filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
 use uuid::Uuid;
 
 use serde_json::json;
 
+mod bench;
 mod client;
 mod config;
+mod errors;
+mod executor;
 mod federation;
 mod http_mcp;
+mod idempotency;
+mod large_results;
 mod mcp;
 mod metrics;
 mod mission;
+mod mission_diff;
 mod pitfall_shim;
 mod registry;
+mod rule_activations;
+mod session_store;
+mod sse;
+#[cfg(feature = "otlp")]
+mod telemetry;
+#[cfg(test)]
+mod test_fixtures;
 mod websocket;
 
-use casial_core::CasialEngine;
-use config::ServerConfig;
-use federation::McpFederationManager;
-use metrics::MetricsCollector;
+use casial_core::{
+    CasialEngine, CoordinationResult, IdSource, PerceptionId, RandomIdSource, SubstrateManager,
+};
+use config::{FederationSettings, ServerConfig, SessionBackend, SessionSettings};
+use errors::{ErrorCode, DEFAULT_LANGUAGE};
+use executor::{
+    FederationExecutor, MissionToolExecutor, ShimToolExecutor, SimulatedExecutor,
+    ToolExecutorRegistry,
+};
+use federation::{BackoffPolicy, McpFederationManager};
+use idempotency::IdempotencyCache;
+use metrics::{ContextSprawl, MetricsCollector};
 use mission::MissionManager;
 use pitfall_shim::{PitfallAvoidanceShim, ShimConfig};
 use registry::ToolRegistry;
+use rule_activations::RuleActivations;
+use session_store::{InMemorySessionStore, SessionStore};
+use sse::SseChannels;
 use websocket::WebSocketHandler;
 
 /// Meta-Orchestration Protocol (MOP): Consciousness-aware context coordination for AI systems
@@ -54,48 +88,114 @@ struct Cli {
     command: Commands,
 }
 
-#[derive(Subcommand)]
-enum Commands {
-    /// Start the Casial server
-    Start {
-        /// Configuration file path
-        #[arg(short, long, value_name = "FILE")]
-        config: Option<PathBuf>,
+/// Flags for `casial-server start`
+#[derive(Args)]
+struct StartArgs {
+    /// Configuration file path
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<PathBuf>,
 
-        /// Server port
-        #[arg(short, long, default_value = "8000")]
-        port: u16,
+    /// Server port
+    #[arg(short, long, default_value = "8000")]
+    port: u16,
 
-        /// Mission configuration file
-        #[arg(short, long, value_name = "FILE")]
-        mission: Option<PathBuf>,
+    /// Mission configuration file; repeat to load several missions sessions can choose from
+    #[arg(short, long, value_name = "FILE")]
+    mission: Vec<PathBuf>,
 
-        /// Enable debug mode
-        #[arg(short, long)]
-        debug: bool,
+    /// Enable debug mode
+    #[arg(short, long)]
+    debug: bool,
 
-        /// Enable global pitfall avoidance shim (enabled by default)
-        #[arg(long, default_value = "true")]
-        shim: bool,
+    /// Enable global pitfall avoidance shim (enabled by default)
+    #[arg(long, default_value = "true")]
+    shim: bool,
 
-        /// Disable global pitfall avoidance shim
-        #[arg(long, conflicts_with = "shim")]
-        no_shim: bool,
+    /// Disable global pitfall avoidance shim
+    #[arg(long, conflicts_with = "shim")]
+    no_shim: bool,
 
-        /// Extend shim with custom string
-        #[arg(long, value_name = "STRING")]
-        shim_extend: Option<String>,
+    /// Extend shim with custom string
+    #[arg(long, value_name = "STRING")]
+    shim_extend: Option<String>,
 
-        /// Path to custom shim configuration
-        #[arg(long, value_name = "FILE")]
-        shim_config: Option<PathBuf>,
-    },
+    /// Path to custom shim configuration
+    #[arg(long, value_name = "FILE")]
+    shim_config: Option<PathBuf>,
+
+    /// OTLP/HTTP collector to export traces and metrics to (requires the `otlp` feature)
+    #[arg(long, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+
+    /// Print the effective configuration as JSON (secrets redacted) and exit
+    #[arg(long)]
+    print_config: bool,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Start the Casial server
+    Start(StartArgs),
     /// Validate mission configuration
     Validate {
         /// Mission file to validate
         #[arg(value_name = "MISSION_FILE")]
         mission_file: PathBuf,
     },
+    /// Run a single coordination against a mission and print the result
+    Coordinate {
+        /// Mission file to load
+        #[arg(short, long, value_name = "FILE")]
+        mission: PathBuf,
+
+        /// Tool name to coordinate for
+        #[arg(short, long)]
+        tool: String,
+
+        /// Tool arguments as a JSON object
+        #[arg(short, long, value_name = "JSON", default_value = "{}")]
+        args: String,
+
+        /// Project path passed to the engine for file signals
+        #[arg(long, value_name = "DIR")]
+        project_path: Option<String>,
+    },
+    /// Compare two mission versions by template, rule and perception id
+    Diff {
+        /// Original mission file
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Updated mission file
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Print the diff as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Benchmark coordination throughput and latency against a mission
+    Bench {
+        /// Mission file to load
+        #[arg(short, long, value_name = "FILE")]
+        mission: PathBuf,
+
+        /// Number of coordination requests to run
+        #[arg(short, long, default_value = "1000")]
+        requests: usize,
+
+        /// Number of requests in flight at once
+        #[arg(short, long, default_value = "4")]
+        concurrency: usize,
+
+        /// Tool name the synthetic requests coordinate for
+        #[arg(short, long, default_value = "web_search")]
+        tool: String,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Show server status and metrics
     Status {
         /// Server endpoint
@@ -104,21 +204,73 @@ enum Commands {
     },
 }
 
+/// How long shutdown waits for in-flight coordinations
+const COORDINATION_QUIESCE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
-    casial_engine: Arc<RwLock<CasialEngine>>,
+    casial_engine: Arc<CasialEngine>,
     mission_manager: Arc<RwLock<MissionManager>>,
+    substrate_manager: Arc<RwLock<SubstrateManager>>,
     metrics_collector: Arc<RwLock<MetricsCollector>>,
     active_sessions: Arc<DashMap<Uuid, websocket::WebSocketSession>>,
     tool_registry: Arc<ToolRegistry>,
     federation_manager: Arc<RwLock<Option<McpFederationManager>>>,
+    tool_executors: Arc<ToolExecutorRegistry>,
+    /// Caps concurrent HTTP tool calls; overflow is rejected with 503
+    tool_call_limiter: Arc<Semaphore>,
     config: Arc<ServerConfig>,
     pitfall_shim: Arc<RwLock<PitfallAvoidanceShim>>,
+    id_source: Arc<dyn IdSource>,
+    /// Reported by `/ready`; held false while required federation has no downstream
+    ready: Arc<AtomicBool>,
+    /// Background metrics collection, aborted on shutdown
+    metrics_task: Arc<Mutex<Option<MetricsTask>>>,
+    /// Tool call results replayed for repeated `Idempotency-Key`s
+    idempotency_cache: Arc<IdempotencyCache>,
+    /// When each rule last activated, per HTTP or WebSocket session
+    rule_activations: Arc<RuleActivations>,
+    /// HTTP MCP sessions by `mcp-session-id`
+    session_store: Arc<dyn SessionStore>,
+    /// Open SSE streams by `mcp-session-id`, for events pushed to HTTP clients
+    sse_channels: Arc<SseChannels>,
+    /// When the state was built, for uptime reporting
+    started_at: std::time::Instant,
+}
+
+/// Handle to the background metrics task; aborts it when dropped
+struct MetricsTask(Option<tokio::task::JoinHandle<()>>);
+
+impl MetricsTask {
+    /// Abort the task and wait until it has stopped
+    async fn stop(mut self) {
+        if let Some(handle) = self.0.take() {
+            handle.abort();
+            let _ = handle.await;
+        }
+    }
+}
+
+impl Drop for MetricsTask {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            handle.abort();
+        }
+    }
 }
 
 impl AppState {
     fn new(config: ServerConfig, shim: PitfallAvoidanceShim) -> Self {
+        Self::with_id_source(config, shim, Arc::new(RandomIdSource))
+    }
+
+    /// Build state whose engine and session ids come from `id_source` (seeded in tests)
+    fn with_id_source(
+        config: ServerConfig,
+        shim: PitfallAvoidanceShim,
+        id_source: Arc<dyn IdSource>,
+    ) -> Self {
         // Initialize tool registry with local tools
         let tool_registry = Arc::new(ToolRegistry::new());
         if let Err(e) = tool_registry.seed_with_local_tools() {
@@ -134,15 +286,109 @@ impl AppState {
             None
         };
 
+        let federation_manager = Arc::new(RwLock::new(federation_manager));
+
+        // Simulated backends until real executors are registered; proxy calls go through federation
+        let tool_executors = Arc::new(
+            SimulatedExecutor::new(Arc::clone(&tool_registry))
+                .with_proxy_forwarder(Arc::new(FederationExecutor::new(Arc::clone(
+                    &federation_manager,
+                ))))
+                .into_registry(),
+        );
+        MissionToolExecutor::register(&tool_executors);
+        let pitfall_shim = Arc::new(RwLock::new(shim));
+        ShimToolExecutor::new(
+            Arc::clone(&pitfall_shim),
+            std::env::var("MOP_ADMIN_TOKEN").ok(),
+        )
+        .register(&tool_executors);
+
+        let ready = !(config.federation.enabled && config.federation.required);
+        let idempotency_cache = Arc::new(IdempotencyCache::new(std::time::Duration::from_secs(
+            config.server.idempotency_ttl_secs,
+        )));
+
         Self {
-            casial_engine: Arc::new(RwLock::new(CasialEngine::new())),
+            casial_engine: Arc::new(
+                CasialEngine::with_id_source(Arc::clone(&id_source))
+                    .with_file_signal_policy(config.server.file_signal_policy())
+                    .with_history_limit(config.server.max_history_entries),
+            ),
             mission_manager: Arc::new(RwLock::new(MissionManager::new())),
+            substrate_manager: Arc::new(RwLock::new(SubstrateManager::new())),
             metrics_collector: Arc::new(RwLock::new(MetricsCollector::new())),
             active_sessions: Arc::new(DashMap::new()),
             tool_registry,
-            federation_manager: Arc::new(RwLock::new(federation_manager)),
+            federation_manager,
+            tool_executors,
+            tool_call_limiter: Arc::new(Semaphore::new(
+                config.server.max_concurrent_tool_calls.max(1),
+            )),
             config: Arc::new(config),
-            pitfall_shim: Arc::new(RwLock::new(shim)),
+            pitfall_shim,
+            id_source,
+            ready: Arc::new(AtomicBool::new(ready)),
+            metrics_task: Arc::new(Mutex::new(None)),
+            idempotency_cache,
+            rule_activations: Arc::new(RuleActivations::new()),
+            session_store: Arc::new(InMemorySessionStore::new()),
+            sse_channels: Arc::new(SseChannels::default()),
+            started_at: std::time::Instant::now(),
+        }
+    }
+
+    /// Add the pitfall shim's context to tool arguments, keeping them as-is if that fails
+    async fn apply_shim(
+        &self,
+        tool_name: &str,
+        args: serde_json::Value,
+        agent_role: Option<&str>,
+    ) -> serde_json::Value {
+        let shim = self.pitfall_shim.read().await;
+        match shim.augment_request(tool_name, &args, agent_role) {
+            Ok(augmented) => augmented,
+            Err(e) => {
+                warn!("Failed to augment request with shim: {}", e);
+                args
+            }
+        }
+    }
+
+    /// Whether a call to `tool_name` is refused because `missions.require_mission` is set
+    /// and no mission is loaded
+    ///
+    /// Mission authoring and admin tools don't need a loaded mission, so they always run.
+    fn missing_required_mission(&self, tool_name: &str) -> bool {
+        let exempt = MissionToolExecutor::TOOLS.contains(&tool_name)
+            || ShimToolExecutor::TOOLS.contains(&tool_name);
+        !exempt && self.config.missions.require_mission && self.casial_engine.mission_count() == 0
+    }
+
+    /// Stop background tasks so nothing runs after the server is torn down
+    ///
+    /// Coordinations still running get up to [`COORDINATION_QUIESCE_TIMEOUT`] to finish
+    /// writing their history first.
+    async fn shutdown(&self) {
+        let engine = Arc::clone(&self.casial_engine);
+        let idle =
+            tokio::task::spawn_blocking(move || engine.quiesce(COORDINATION_QUIESCE_TIMEOUT))
+                .await
+                .unwrap_or(false);
+        if !idle {
+            warn!(
+                "Coordinations still running after {:?}; shutting down anyway",
+                COORDINATION_QUIESCE_TIMEOUT
+            );
+        }
+
+        let metrics_task = self
+            .metrics_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(task) = metrics_task {
+            task.stop().await;
         }
     }
 }
@@ -152,62 +398,66 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Start {
-            config,
-            port,
-            mission,
-            debug,
-            shim,
-            no_shim,
-            shim_extend,
-            shim_config,
-        } => {
-            start_server(
-                config,
-                port,
-                mission,
-                debug,
-                shim,
-                no_shim,
-                shim_extend,
-                shim_config,
-            )
-            .await
+        Commands::Start(args) => {
+            if args.print_config {
+                return print_effective_config(args.config.as_deref(), args.port);
+            }
+            start_server(args).await
         }
         Commands::Validate { mission_file } => validate_mission(mission_file).await,
+        Commands::Coordinate {
+            mission,
+            tool,
+            args,
+            project_path,
+        } => coordinate_once(mission, tool, args, project_path),
+        Commands::Diff { old, new, json } => diff_mission_files(&old, &new, json),
+        Commands::Bench {
+            mission,
+            requests,
+            concurrency,
+            tool,
+            json,
+        } => bench_mission(&mission, &tool, requests, concurrency, json),
         Commands::Status { endpoint } => show_status(endpoint).await,
     }
 }
 
-async fn start_server(
-    config_path: Option<PathBuf>,
-    port: u16,
-    mission_path: Option<PathBuf>,
-    debug: bool,
-    shim: bool,
-    no_shim: bool,
-    shim_extend: Option<String>,
-    shim_config_path: Option<PathBuf>,
-) -> Result<()> {
-    // Initialize tracing
-    init_tracing(debug);
+async fn start_server(args: StartArgs) -> Result<()> {
+    let StartArgs {
+        config: config_path,
+        port,
+        mission: mission_paths,
+        debug,
+        shim,
+        no_shim,
+        shim_extend,
+        shim_config: shim_config_path,
+        otlp_endpoint,
+        print_config: _,
+    } = args;
+
+    // Initialize tracing, exporting spans over OTLP when requested
+    #[cfg(feature = "otlp")]
+    let otlp = otlp_endpoint
+        .as_deref()
+        .map(telemetry::OtlpTelemetry::install)
+        .transpose()?;
+    #[cfg(feature = "otlp")]
+    init_tracing(debug, otlp.as_ref().map(|otlp| otlp.layer().boxed()));
+    #[cfg(not(feature = "otlp"))]
+    {
+        if otlp_endpoint.is_some() {
+            anyhow::bail!("--otlp-endpoint requires casial-server built with the `otlp` feature");
+        }
+        init_tracing(debug, None);
+    }
 
     info!("🚀 Starting Meta-Orchestration Protocol (MOP) Server");
     info!("    Consciousness-aware context coordination for AI systems");
     info!("    Part of Ubiquity OS - Like hydraulic lime, stronger under pressure");
 
-    // Load configuration
-    let config = if let Some(path) = config_path {
-        ServerConfig::from_file(&path)?
-    } else {
-        ServerConfig::default()
-    };
-
-    // Override port if specified
-    let mut config = config;
-    if port != 8000 {
-        config.server.port = port;
-    }
+    let config = effective_config(config_path.as_deref(), port)?;
 
     info!("📋 Server configuration loaded");
     info!("    Port: {}", config.server.port);
@@ -255,10 +505,11 @@ async fn start_server(
     }
 
     // Initialize application state
-    let state = AppState::new(config.clone(), shim);
+    let mut state = AppState::new(config.clone(), shim);
+    state.session_store = build_session_store(&config.sessions).await?;
 
-    // Load mission if provided
-    if let Some(mission_path) = mission_path {
+    // Load missions if provided
+    for mission_path in mission_paths {
         match load_mission(&state, mission_path).await {
             Ok(_) => info!("✅ Mission loaded successfully"),
             Err(e) => {
@@ -295,6 +546,7 @@ async fn start_server(
     );
     info!("    Metrics endpoint: http://{}/metrics", addr);
     info!("    Health endpoint: http://{}/health", addr);
+    info!("    Readiness endpoint: http://{}/ready", addr);
 
     // Start the server with graceful shutdown
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -303,49 +555,112 @@ async fn start_server(
         .with_graceful_shutdown(shutdown_signal())
         .await?;
 
+    state.shutdown().await;
+
+    #[cfg(feature = "otlp")]
+    if let Some(otlp) = otlp {
+        otlp.shutdown()?;
+    }
+
     info!("🛑 Server shutdown complete");
     Ok(())
 }
 
-fn init_tracing(debug: bool) {
-    let level = if debug { Level::DEBUG } else { Level::INFO };
-
-    tracing_subscriber::fmt()
-        .with_max_level(level)
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_file(debug)
-        .with_line_number(debug)
+/// Install the global subscriber, with an optional extra layer (e.g. OTLP export)
+fn init_tracing(debug: bool, export_layer: Option<Box<dyn Layer<Registry> + Send + Sync>>) {
+    let filter = log_filter(debug, std::env::var("RUST_LOG").ok().as_deref());
+
+    tracing_subscriber::registry()
+        .with(export_layer)
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(true)
+                .with_thread_ids(true)
+                .with_file(debug)
+                .with_line_number(debug),
+        )
+        .with(filter)
         .init();
 }
 
+/// Filter from `RUST_LOG`-style directives, e.g. `info,casial_server::federation=debug`
+///
+/// Targets without a directive log at `debug` with `--debug` and `info` otherwise; directives
+/// that fail to parse are skipped.
+fn log_filter(debug: bool, directives: Option<&str>) -> EnvFilter {
+    let default = if debug {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+    EnvFilter::builder()
+        .with_default_directive(default.into())
+        .parse_lossy(directives.unwrap_or_default())
+}
+
+/// Session store for the configured backend
+async fn build_session_store(settings: &SessionSettings) -> Result<Arc<dyn SessionStore>> {
+    match settings.backend {
+        SessionBackend::Memory => Ok(Arc::new(InMemorySessionStore::new())),
+        #[cfg(feature = "redis")]
+        SessionBackend::Redis => {
+            let url = settings
+                .redis_url
+                .as_deref()
+                .context("sessions.redis_url is required for the redis backend")?;
+            info!("🗄️  Storing sessions in Redis");
+            let store = session_store::RedisSessionStore::connect(
+                url,
+                std::time::Duration::from_secs(settings.idle_timeout_secs),
+            )
+            .await?;
+            Ok(Arc::new(store))
+        }
+        #[cfg(not(feature = "redis"))]
+        SessionBackend::Redis => {
+            anyhow::bail!(
+                "sessions.backend is redis, but the server was built without the `redis` feature"
+            )
+        }
+    }
+}
+
 async fn load_mission(state: &AppState, mission_path: PathBuf) -> Result<()> {
     info!(
         "📖 Loading mission configuration: {}",
         mission_path.display()
     );
 
-    let mission = mission::load_mission_from_file(&mission_path)?;
+    let (mission, warnings) = mission::load_mission_from_file(&mission_path)?;
+    for warning in &warnings {
+        warn!("⚠️  Mission {}: {}", mission.id, warning);
+    }
 
     // Load mission with project templates
     {
-        let engine = state.casial_engine.write().await;
         let mut enhanced_mission = mission.clone();
 
         // Try to find project root and load templates
         if let Some(project_root) = mission_path.parent().and_then(|p| p.to_str()) {
-            if let Err(e) = mission::merge_templates_from_dir(&mut enhanced_mission, project_root) {
-                tracing::warn!("Failed to load project templates: {}", e);
-            }
+            mission::merge_templates_from_dir(
+                &mut enhanced_mission,
+                project_root,
+                state.config.missions.template_merge_policy,
+            )
+            .map_err(|e| anyhow::anyhow!("Failed to load project templates: {}", e))?;
         }
 
-        engine.load_mission(enhanced_mission)?;
+        state.casial_engine.load_mission(enhanced_mission)?;
     }
 
-    // Register with mission manager
+    // Register with mission manager, retiring any id this file used to define
     {
         let mut manager = state.mission_manager.write().await;
-        manager.add_mission(mission)?;
+        let mission_id = mission.id.clone();
+        for replaced in manager.add_mission_from_file(mission, &mission_path) {
+            state.casial_engine.unload_mission(&replaced);
+            info!("🔁 Mission {} is now loaded as {}", replaced, mission_id);
+        }
     }
 
     info!("✅ Mission configuration loaded successfully");
@@ -356,49 +671,128 @@ async fn start_federation(state: &AppState) -> Result<()> {
     info!("🌐 Starting MCP Federation...");
 
     // Initialize federation manager
-    {
+    let connected = {
         let mut federation_opt = state.federation_manager.write().await;
-        if let Some(ref mut manager) = federation_opt.as_mut() {
-            manager.initialize().await?;
-            manager.connect_all().await.unwrap_or_else(|e| {
-                tracing::warn!("Some federation connections failed: {}", e);
-            });
+        match federation_opt.as_mut() {
+            Some(manager) => {
+                manager.initialize().await?;
+                let connected = manager.connect_with_retries().await;
+                if connected > 0 {
+                    // The sync in initialize ran before anything was connected
+                    if let Err(e) = manager.sync_all_servers().await {
+                        warn!("Post-connect federation sync failed: {}", e);
+                    }
+                }
+                connected
+            }
+            None => 0,
         }
+    };
+
+    if connected > 0 {
+        state.ready.store(true, Ordering::Release);
+    } else if state.config.federation.required {
+        warn!("⚠️  No downstream server connected; holding readiness until one does");
+        tokio::spawn(await_required_downstream(state.clone()));
     }
 
     info!("✅ MCP Federation started successfully");
     Ok(())
 }
 
+/// Keep retrying downstream connections in the background, then mark the server ready
+async fn await_required_downstream(state: AppState) {
+    let mut attempt: u32 = 0;
+    loop {
+        let backoff = match state.federation_manager.read().await.as_ref() {
+            Some(manager) => manager.reconnect_backoff(attempt),
+            None => return,
+        };
+        tokio::time::sleep(backoff).await;
+        attempt = attempt.saturating_add(1);
+
+        let federation_opt = state.federation_manager.read().await;
+        let Some(manager) = federation_opt.as_ref() else {
+            return;
+        };
+        match manager.connect_all().await {
+            Ok(()) => {
+                if let Err(e) = manager.sync_all_servers().await {
+                    warn!("Post-connect federation sync failed: {}", e);
+                }
+                state.ready.store(true, Ordering::Release);
+                info!("✅ Downstream server connected; server is ready");
+                return;
+            }
+            Err(e) => warn!(
+                "Required downstream still unreachable after reconnect attempt {}: {}",
+                attempt, e
+            ),
+        }
+    }
+}
+
 async fn start_metrics_collection(state: &AppState) -> Result<()> {
     info!("📊 Starting metrics collection");
 
     let metrics_collector = state.metrics_collector.clone();
     let casial_engine = state.casial_engine.clone();
     let active_sessions = state.active_sessions.clone();
+    let mission_manager = state.mission_manager.clone();
+    #[cfg(feature = "otlp")]
+    let federation_manager = state.federation_manager.clone();
+    let mut interval = state.config.metrics_timer();
 
     // Spawn metrics collection task
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
-
+    let handle = tokio::spawn(async move {
         loop {
             interval.tick().await;
 
+            // Context sprawl of each mission, as reported by /debug/sprawl?mission_id=
+            let sprawl: Vec<(String, ContextSprawl)> = {
+                let manager = mission_manager.read().await;
+                manager
+                    .get_all_missions()
+                    .into_iter()
+                    .map(|mission| {
+                        let sprawl = ContextSprawl::measure([mission], active_sessions.len());
+                        (mission.id.clone(), sprawl)
+                    })
+                    .collect()
+            };
+
             // Collect metrics from various sources
             let mut collector = metrics_collector.write().await;
 
             // Engine metrics
-            let coordination_history = casial_engine.read().await.get_coordination_history();
+            let coordination_history = casial_engine.get_coordination_history();
             collector.record_coordination_events(coordination_history.len());
 
             // Session metrics
             collector.record_active_sessions(active_sessions.len());
+            collector.record_context_sprawl(sprawl);
+
+            #[cfg(feature = "otlp")]
+            {
+                let federation = match federation_manager.read().await.as_ref() {
+                    Some(manager) => Some(manager.get_metrics().await),
+                    None => None,
+                };
+                telemetry::record_metrics(
+                    coordination_history.len(),
+                    active_sessions.len(),
+                    federation.as_ref(),
+                );
+            }
 
             // Report metrics
             collector.log_summary();
         }
     });
 
+    // Replacing a previous task drops, and so aborts, it
+    *state.metrics_task.lock().unwrap_or_else(|e| e.into_inner()) = Some(MetricsTask(Some(handle)));
+
     Ok(())
 }
 
@@ -471,7 +865,7 @@ fn validate_admin_token(expected: Option<&str>, headers: &HeaderMap) -> Result<(
         });
 
     match provided {
-        Some(token) if token == expected => Ok(()),
+        Some(token) if executor::secret_matches(&token, expected) => Ok(()),
         _ => {
             tracing::warn!("Unauthorized attempt to access /debug endpoints");
             Err(StatusCode::UNAUTHORIZED)
@@ -483,13 +877,30 @@ async fn build_router(state: AppState) -> Result<Router> {
     let debug_routes = Router::new()
         .route("/status", get(debug_status))
         .route("/missions", get(debug_missions))
+        .route("/missions/reload", post(reload_missions))
         .route("/sessions", get(debug_sessions))
+        .route("/history.ndjson", get(debug_history_ndjson))
         .route("/perceptions", get(debug_perceptions))
+        .route("/perceptions/:id/history", get(debug_perception_history))
+        .route("/history/replay", post(replay_history))
         .route("/sprawl", get(debug_sprawl))
+        .route("/substrate", get(debug_substrate))
         .route("/shim", get(debug_shim).post(update_shim))
+        .route(
+            "/federation",
+            get(debug_federation).post(reconfigure_federation),
+        )
+        .route("/cors/reload", post(reload_cors))
         .route_layer(from_fn_with_state(state.clone(), require_admin_token))
         .with_state(state.clone());
 
+    // Metrics stay public unless the operator opts into the admin token guard
+    let mut metrics_routes = Router::new().route("/metrics", get(metrics_handler));
+    if state.config.metrics.require_auth {
+        metrics_routes =
+            metrics_routes.route_layer(from_fn_with_state(state.clone(), require_admin_token));
+    }
+
     let router = Router::new()
         // WebSocket endpoint for MCP communication
         .route("/ws", get(websocket_handler))
@@ -505,13 +916,17 @@ async fn build_router(state: AppState) -> Result<Router> {
         // Well-known MCP configuration endpoint
         .route(
             "/.well-known/mcp-config",
-            get(well_known_get_handler).post(well_known_post_handler),
+            get(well_known_get_handler)
+                .post(well_known_post_handler)
+                .head(well_known_head_handler)
+                .options(well_known_options_handler),
         )
         // Health check endpoint
         .route("/", get(health_check))
         .route("/health", get(health_check))
-        // Metrics endpoint (if enabled)
-        .route("/metrics", get(metrics_handler))
+        .route("/ready", get(readiness_check))
+        // Metrics endpoint (optionally guarded by MOP_ADMIN_TOKEN)
+        .merge(metrics_routes)
         .nest("/debug", debug_routes)
         // State management
         .with_state(state)
@@ -527,11 +942,32 @@ async fn build_router(state: AppState) -> Result<Router> {
 }
 
 /// WebSocket handler for MCP communication
+///
+/// Takes the API key like `/mcp` does: `apiKey` in the query or a Bearer token. A wrong key
+/// is refused; the public demo key, or no key, limits tool calls to `demo_access`.
 async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| WebSocketHandler::new(state).handle_connection(socket))
+    headers: http::HeaderMap,
+    Query(params): Query<http_mcp::QueryParams>,
+) -> Response {
+    let api_key = params
+        .direct_params
+        .api_key
+        .or_else(|| http_mcp::bearer_token(&headers));
+    if api_key
+        .as_deref()
+        .is_some_and(|key| key != http_mcp::expected_api_key())
+    {
+        return (
+            axum::http::StatusCode::UNAUTHORIZED,
+            axum::Json(ErrorCode::InvalidApiKey.http_body(DEFAULT_LANGUAGE)),
+        )
+            .into_response();
+    }
+    let demo_access = api_key.map_or(true, |key| key == http_mcp::DEMO_API_KEY);
+
+    ws.on_upgrade(move |socket| WebSocketHandler::new(state).handle_connection(socket, demo_access))
 }
 
 /// MCP HTTP GET handler (for SSE)
@@ -618,15 +1054,26 @@ async fn well_known_post_handler(
     http_mcp::well_known_config_handler(Method::POST, State(state), headers, Some(body)).await
 }
 
+/// Well-known configuration HEAD handler
+async fn well_known_head_handler(
+    State(state): State<AppState>,
+    headers: http::HeaderMap,
+) -> impl IntoResponse {
+    http_mcp::well_known_config_handler(Method::HEAD, State(state), headers, None).await
+}
+
+/// Well-known configuration OPTIONS handler
+async fn well_known_options_handler(
+    State(state): State<AppState>,
+    headers: http::HeaderMap,
+) -> impl IntoResponse {
+    http_mcp::well_known_config_handler(Method::OPTIONS, State(state), headers, None).await
+}
+
 /// Health check endpoint
 async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     let session_count = state.active_sessions.len();
-    let engine_stats = state
-        .casial_engine
-        .read()
-        .await
-        .get_coordination_history()
-        .len();
+    let engine_stats = state.casial_engine.get_coordination_history().len();
 
     axum::Json(serde_json::json!({
         "status": "healthy",
@@ -641,27 +1088,44 @@ async fn health_check(State(state): State<AppState>) -> impl IntoResponse {
     }))
 }
 
+/// Readiness probe: 503 until startup dependencies such as required federation are up
+async fn readiness_check(State(state): State<AppState>) -> impl IntoResponse {
+    if state.ready.load(Ordering::Acquire) {
+        (StatusCode::OK, axum::Json(json!({ "status": "ready" })))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            axum::Json(json!({ "status": "starting" })),
+        )
+    }
+}
+
 /// Prometheus metrics endpoint
 async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let metrics = state.metrics_collector.read().await.export_prometheus();
+    let mut metrics = state.metrics_collector.read().await.export_prometheus();
+    if let Some(manager) = state.federation_manager.read().await.as_ref() {
+        metrics.push('\n');
+        metrics.push_str(&manager.get_metrics().await.export_prometheus());
+    }
     ([("content-type", "text/plain; version=0.0.4")], metrics)
 }
 
 /// Debug status endpoint
 async fn debug_status(State(state): State<AppState>) -> impl IntoResponse {
-    let casial_engine = state.casial_engine.read().await;
+    let casial_engine = &state.casial_engine;
     let coordination_history = casial_engine.get_coordination_history();
     let paradox_registry = casial_engine.get_paradox_registry();
+    let active_missions = state.mission_manager.read().await.get_all_missions().len();
 
     axum::Json(serde_json::json!({
         "casial_engine": {
             "coordination_events": coordination_history.len(),
             "paradoxes_detected": paradox_registry.len(),
-            "active_missions": 1 // Simplified
+            "active_missions": active_missions
         },
         "server": {
             "active_sessions": state.active_sessions.len(),
-            "uptime_info": "runtime_info_placeholder"
+            "uptime_seconds": state.started_at.elapsed().as_secs_f64()
         },
         "consciousness": {
             "substrate_active": true,
@@ -671,22 +1135,51 @@ async fn debug_status(State(state): State<AppState>) -> impl IntoResponse {
     }))
 }
 
+/// Debug substrate endpoint: layers, primitives, network topology and performance metrics
+async fn debug_substrate(State(state): State<AppState>) -> impl IntoResponse {
+    axum::Json(state.substrate_manager.read().await.get_statistics())
+}
+
 /// Debug missions endpoint
 async fn debug_missions(State(state): State<AppState>) -> impl IntoResponse {
     let manager = state.mission_manager.read().await;
     let missions = manager.get_all_missions();
 
     axum::Json(serde_json::json!({
-        "missions": missions.iter().map(|m| serde_json::json!({
-            "id": m.id,
-            "name": m.name,
-            "templates": m.templates.len(),
-            "rules": m.rules.len(),
-            "perceptions": m.perceptions.len()
-        })).collect::<Vec<_>>()
+        "missions": missions.iter().map(|m| {
+            let status = manager.status(&m.id);
+            serde_json::json!({
+                "id": m.id,
+                "name": m.name,
+                "templates": m.templates.len(),
+                "rules": m.rules.len(),
+                "perceptions": m.perceptions.len(),
+                "source_path": status.and_then(|s| s.source_path.as_ref()),
+                "loaded_at": status.map(|s| s.loaded_at),
+                "last_reload_result": status.map(|s| &s.last_reload_result),
+                "warning_count": status.map(|s| s.warning_count)
+            })
+        }).collect::<Vec<_>>()
     }))
 }
 
+/// Reload file-backed missions; a mission that fails keeps its previous definition
+async fn reload_missions(State(state): State<AppState>) -> Response {
+    let sources = state.mission_manager.read().await.file_sources();
+    for (id, path) in sources {
+        if let Err(e) = load_mission(&state, path).await {
+            warn!("⚠️  Reloading mission {} failed: {:#}", id, e);
+            state
+                .mission_manager
+                .write()
+                .await
+                .record_reload_failure(&id, format!("{:#}", e));
+        }
+    }
+
+    debug_missions(State(state)).await.into_response()
+}
+
 /// Debug sessions endpoint  
 async fn debug_sessions(State(state): State<AppState>) -> impl IntoResponse {
     let sessions: Vec<_> = state
@@ -711,7 +1204,7 @@ async fn debug_sessions(State(state): State<AppState>) -> impl IntoResponse {
 
 /// Debug paradoxes endpoint
 async fn debug_paradoxes(State(state): State<AppState>) -> impl IntoResponse {
-    let paradoxes = state.casial_engine.read().await.get_paradox_registry();
+    let paradoxes = state.casial_engine.get_paradox_registry();
 
     axum::Json(serde_json::json!({
         "paradoxes": paradoxes.iter().map(|p| serde_json::json!({
@@ -724,37 +1217,103 @@ async fn debug_paradoxes(State(state): State<AppState>) -> impl IntoResponse {
     }))
 }
 
-/// Debug perceptions endpoint
+/// Entries fetched from the engine per chunk of the history export
+const HISTORY_EXPORT_PAGE_SIZE: usize = 100;
+
+/// Cursor parameters for the coordination history export
+#[derive(Debug, Default, serde::Deserialize)]
+struct HistoryExportParams {
+    /// Only export entries with a sequence number greater than this
+    after: Option<u64>,
+    /// Maximum number of entries to export
+    limit: Option<usize>,
+}
+
+/// Debug coordination history export, streamed as NDJSON one page at a time
+async fn debug_history_ndjson(
+    State(state): State<AppState>,
+    Query(params): Query<HistoryExportParams>,
+) -> impl IntoResponse {
+    let remaining = params.limit.unwrap_or(usize::MAX);
+
+    let pages = futures::stream::unfold(
+        (state, params.after, remaining),
+        |(state, after, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+
+            let page = state
+                .casial_engine
+                .coordination_history_page(after, remaining.min(HISTORY_EXPORT_PAGE_SIZE));
+            let last_sequence = page.last()?.0;
+
+            let mut chunk = String::new();
+            for (sequence, result) in &page {
+                let line = json!({ "sequence": sequence, "result": result });
+                chunk.push_str(&line.to_string());
+                chunk.push('\n');
+            }
+
+            let remaining = remaining - page.len();
+            Some((
+                Ok::<_, std::convert::Infallible>(chunk),
+                (state, Some(last_sequence), remaining),
+            ))
+        },
+    );
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(pages),
+    )
+}
+
+/// Debug perceptions endpoint: every perception from loaded missions and sessions
 async fn debug_perceptions(
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, (axum::http::StatusCode, String)> {
-    let _engine = state.casial_engine.read().await;
     let manager = state.mission_manager.read().await;
-    let missions = manager.get_all_missions();
-
-    // Get perceptions from all loaded missions
-    let mut total_perceptions = 0;
-    let mut total_confidence = 0.0;
+    let missions_with_perceptions = manager
+        .get_all_missions()
+        .iter()
+        .filter(|mission| !mission.perceptions.is_empty())
+        .count();
 
-    for mission in &missions {
-        total_perceptions += mission.perceptions.len();
-        for perception in &mission.perceptions {
-            // Note: locked field not available, count all perceptions
-            total_confidence += perception.confidence;
-        }
-    }
+    let perceptions = state.casial_engine.get_perceptions();
+    let session_active: std::collections::HashSet<casial_core::PerceptionId> = state
+        .active_sessions
+        .iter()
+        .flat_map(|session| session.active_perceptions.clone())
+        .collect();
 
-    let avg_confidence = if total_perceptions > 0 {
-        total_confidence / total_perceptions as f64
-    } else {
+    let avg_confidence = if perceptions.is_empty() {
         0.0
+    } else {
+        perceptions.iter().map(|p| p.confidence).sum::<f64>() / perceptions.len() as f64
     };
+    let items: Vec<serde_json::Value> = perceptions
+        .iter()
+        .map(|perception| {
+            serde_json::json!({
+                "id": perception.id,
+                "name": perception.name,
+                "description": perception.description,
+                "confidence": perception.confidence,
+                "created_at": perception.created_at,
+                "updated_at": perception.updated_at,
+                "metadata": perception.metadata,
+                "session_active": session_active.contains(&perception.id)
+            })
+        })
+        .collect();
 
     let debug_info = serde_json::json!({
         "perceptions": {
-            "total_count": total_perceptions,
+            "total_count": items.len(),
             "avg_confidence": avg_confidence,
-            "missions_with_perceptions": missions.len()
+            "missions_with_perceptions": missions_with_perceptions,
+            "items": items
         },
         "consciousness_metrics": {
             "perception_coordination_active": true,
@@ -766,63 +1325,113 @@ async fn debug_perceptions(
     Ok(axum::Json(debug_info))
 }
 
-/// Debug endpoint for context sprawl monitoring
-async fn debug_sprawl(
+/// Replay recorded coordination history against a candidate mission
+///
+/// Takes `{"mission": ...}`; reports per-request rule, template and content changes.
+async fn replay_history(
     State(state): State<AppState>,
-) -> Result<impl IntoResponse, (axum::http::StatusCode, String)> {
-    let mut total_chars = 0usize;
-    let mut template_count = 0usize;
-    let mut largest_templates: Vec<(String, usize)> = Vec::new();
-    let mut categories = std::collections::HashMap::new();
-
-    // Analyze templates from the casial engine
-    {
-        let _engine = state.casial_engine.read().await;
-        let manager = state.mission_manager.read().await;
-        let missions = manager.get_all_missions();
-
-        // Get all loaded missions
-        if let Some(mission) = missions.first() {
-            template_count = mission.templates.len();
-
-            for template in &mission.templates {
-                let content_length = template.content.len();
-                total_chars += content_length;
-
-                // Track largest templates
-                largest_templates.push((template.id.clone(), content_length));
-
-                // Track categories
-                for category in &template.categories {
-                    *categories.entry(category.clone()).or_insert(0) += 1;
-                }
-            }
+    axum::Json(body): axum::Json<serde_json::Value>,
+) -> impl IntoResponse {
+    let replay = serde_json::from_value::<casial_core::CasialMission>(
+        body.get("mission").cloned().unwrap_or_default(),
+    )
+    .map_err(|e| anyhow::anyhow!("Invalid mission: {}", e))
+    .and_then(|mission| {
+        mission::replay_coordinations(
+            mission,
+            state.casial_engine.get_coordination_records(),
+            state.casial_engine.file_signal_policy().clone(),
+        )
+    });
 
-            // Sort by size, keep top 10
-            largest_templates.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
-            largest_templates.truncate(10);
+    match replay {
+        Ok(diffs) => {
+            let changed = diffs.iter().filter(|diff| !diff.is_unchanged()).count();
+            (
+                StatusCode::OK,
+                axum::Json(json!({
+                    "replayed": diffs.len(),
+                    "changed": changed,
+                    "diffs": diffs
+                })),
+            )
         }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            axum::Json(json!({
+                "status": "error",
+                "message": format!("{:#}", e)
+            })),
+        ),
     }
+}
 
-    // Active session context analysis
-    let active_sessions = state.active_sessions.len();
-    let avg_context_per_session = if active_sessions > 0 {
-        total_chars / active_sessions
-    } else {
-        0
+/// Debug coordination history recorded while one perception was active
+async fn debug_perception_history(
+    State(state): State<AppState>,
+    axum::extract::Path(perception_id): axum::extract::Path<PerceptionId>,
+) -> impl IntoResponse {
+    let history = state
+        .casial_engine
+        .get_coordination_history_for_perception(&perception_id);
+
+    axum::Json(json!({
+        "perception_id": perception_id,
+        "count": history.len(),
+        "history": history
+            .iter()
+            .map(|(sequence, result)| json!({ "sequence": sequence, "result": result }))
+            .collect::<Vec<_>>()
+    }))
+}
+
+/// Narrows `/debug/sprawl` to one mission
+#[derive(Debug, Default, serde::Deserialize)]
+struct SprawlParams {
+    mission_id: Option<String>,
+}
+
+/// Debug endpoint for context sprawl monitoring, over all missions unless one is named
+async fn debug_sprawl(
+    State(state): State<AppState>,
+    Query(params): Query<SprawlParams>,
+) -> Result<impl IntoResponse, (axum::http::StatusCode, String)> {
+    let sprawl = {
+        let manager = state.mission_manager.read().await;
+        let missions = match &params.mission_id {
+            Some(id) => vec![manager.get_mission(id).ok_or_else(|| {
+                (
+                    axum::http::StatusCode::NOT_FOUND,
+                    format!("Unknown mission '{}'", id),
+                )
+            })?],
+            None => manager.get_all_missions(),
+        };
+        ContextSprawl::measure(missions, state.active_sessions.len())
     };
+    let ContextSprawl {
+        template_count,
+        total_chars,
+        total_tokens,
+        active_sessions,
+        avg_context_per_session,
+        largest_templates,
+        categories,
+    } = sprawl;
 
     let sprawl_info = serde_json::json!({
         "context_sprawl_analysis": {
             "templates_total": template_count,
             "injected_characters_total": total_chars,
+            "injected_tokens_estimate": total_tokens,
             "active_sessions": active_sessions,
             "avg_context_per_session": avg_context_per_session,
             "largest_templates_top10": largest_templates
                 .into_iter()
-                .map(|(id, chars)| serde_json::json!({
+                .map(|(id, chars, tokens)| serde_json::json!({
                     "template_id": id,
                     "character_count": chars,
+                    "estimated_tokens": tokens,
                     "size_category": if chars > 5000 { "large" } else if chars > 1000 { "medium" } else { "small" }
                 }))
                 .collect::<Vec<_>>(),
@@ -894,6 +1503,63 @@ async fn update_shim(
     )
 }
 
+/// Re-read ALLOWED_ORIGINS via POST so CORS changes apply without a restart
+async fn reload_cors() -> impl IntoResponse {
+    let policy = http_mcp::reload_cors_policy();
+    axum::Json(json!({
+        "status": "success",
+        "cors": policy.summary()
+    }))
+}
+
+/// Effective retry backoff and federation counters, for tuning retry storms
+async fn debug_federation(State(state): State<AppState>) -> impl IntoResponse {
+    let federation = state.federation_manager.read().await;
+    let (backoff, metrics) = match federation.as_ref() {
+        Some(manager) => (manager.backoff_policy(), Some(manager.get_metrics().await)),
+        None => (BackoffPolicy::from_settings(&state.config.federation), None),
+    };
+    axum::Json(json!({
+        "enabled": federation.is_some(),
+        "backoff": backoff,
+        "metrics": metrics
+    }))
+}
+
+/// Apply a new federation server list via POST, draining removed or changed servers
+async fn reconfigure_federation(
+    State(state): State<AppState>,
+    axum::Json(settings): axum::Json<FederationSettings>,
+) -> impl IntoResponse {
+    let federation = state.federation_manager.read().await;
+    let Some(manager) = federation.as_ref() else {
+        return (
+            StatusCode::CONFLICT,
+            axum::Json(json!({
+                "status": "error",
+                "message": "Federation is not enabled"
+            })),
+        );
+    };
+
+    match manager.reconfigure(settings).await {
+        Ok(()) => (
+            StatusCode::OK,
+            axum::Json(json!({
+                "status": "success",
+                "servers": manager.get_active_servers().await
+            })),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            axum::Json(json!({
+                "status": "error",
+                "message": e.to_string()
+            })),
+        ),
+    }
+}
+
 /// Graceful shutdown signal handler
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -930,13 +1596,19 @@ async fn validate_mission(mission_file: PathBuf) -> Result<()> {
     );
 
     match mission::load_mission_from_file(&mission_file) {
-        Ok(mission) => {
+        Ok((mission, warnings)) => {
             info!("✅ Mission configuration is valid");
             info!("    ID: {}", mission.id);
             info!("    Name: {}", mission.name);
             info!("    Templates: {}", mission.templates.len());
             info!("    Rules: {}", mission.rules.len());
             info!("    Perceptions: {}", mission.perceptions.len());
+            if !warnings.is_empty() {
+                warn!("⚠️  {} warning(s):", warnings.len());
+                for warning in &warnings {
+                    warn!("    - {}", warning);
+                }
+            }
             Ok(())
         }
         Err(e) => {
@@ -946,6 +1618,103 @@ async fn validate_mission(mission_file: PathBuf) -> Result<()> {
     }
 }
 
+fn coordinate_once(
+    mission_file: PathBuf,
+    tool: String,
+    args: String,
+    project_path: Option<String>,
+) -> Result<()> {
+    let result = run_coordination(&mission_file, &tool, &args, project_path)?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+/// Load a mission into a fresh engine and coordinate one tool call against it
+fn run_coordination(
+    mission_file: &Path,
+    tool: &str,
+    args: &str,
+    project_path: Option<String>,
+) -> Result<CoordinationResult> {
+    let (mission, warnings) = mission::load_mission_from_file(mission_file)?;
+    for warning in &warnings {
+        warn!("⚠️  Mission {}: {}", mission.id, warning);
+    }
+
+    let tool_args: serde_json::Value =
+        serde_json::from_str(args).context("--args must be valid JSON")?;
+
+    // The CLI runs as the operator, so rules see the operator's own environment
+    mission::preview_coordination(
+        mission,
+        tool,
+        tool_args,
+        std::env::vars().collect(),
+        project_path,
+    )
+}
+
+fn bench_mission(
+    mission_file: &Path,
+    tool: &str,
+    requests: usize,
+    concurrency: usize,
+    as_json: bool,
+) -> Result<()> {
+    let (mission, _) = mission::load_mission_from_file(mission_file)
+        .with_context(|| format!("Failed to load {}", mission_file.display()))?;
+    let engine = CasialEngine::new();
+    engine.load_mission(mission)?;
+
+    let report = bench::run_bench(&engine, tool, requests, concurrency)?;
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
+/// Load the config file (or defaults) and apply CLI overrides
+fn effective_config(config_path: Option<&Path>, port: u16) -> Result<ServerConfig> {
+    let mut config = match config_path {
+        Some(path) => ServerConfig::from_file(path)
+            .with_context(|| format!("Failed to load {}", path.display()))?,
+        None => ServerConfig::default(),
+    };
+
+    // Override port if specified
+    if port != 8000 {
+        config.server.port = port;
+    }
+    Ok(config)
+}
+
+fn render_effective_config(config_path: Option<&Path>, port: u16) -> Result<String> {
+    let config = effective_config(config_path, port)?.redacted();
+    Ok(serde_json::to_string_pretty(&config)?)
+}
+
+fn print_effective_config(config_path: Option<&Path>, port: u16) -> Result<()> {
+    println!("{}", render_effective_config(config_path, port)?);
+    Ok(())
+}
+
+fn diff_mission_files(old: &Path, new: &Path, as_json: bool) -> Result<()> {
+    let (old_mission, _) = mission::load_mission_from_file(old)
+        .with_context(|| format!("Failed to load {}", old.display()))?;
+    let (new_mission, _) = mission::load_mission_from_file(new)
+        .with_context(|| format!("Failed to load {}", new.display()))?;
+
+    let diff = mission_diff::diff_missions(&old_mission, &new_mission)?;
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
 async fn show_status(endpoint: String) -> Result<()> {
     info!("📊 Checking server status at: {}", endpoint);
 
@@ -966,6 +1735,7 @@ async fn show_status(endpoint: String) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::{empty_mission, mission_json, rule_json, template_json};
     use axum::http::{HeaderValue, StatusCode};
     use std::env;
 
@@ -997,6 +1767,30 @@ mod tests {
         // Should create layer with specific origins without panicking
     }
 
+    #[test]
+    fn log_filter_applies_per_module_directives() {
+        let levels = |filter: EnvFilter| {
+            let subscriber = tracing_subscriber::registry().with(filter);
+            tracing::subscriber::with_default(subscriber, || {
+                (
+                    tracing::enabled!(target: "casial_server::federation", Level::DEBUG),
+                    tracing::enabled!(target: "casial_server::http_mcp", Level::DEBUG),
+                    tracing::enabled!(target: "casial_server::http_mcp", Level::INFO),
+                    tracing::enabled!(target: "hyper", Level::INFO),
+                )
+            })
+        };
+
+        let directives = "info,casial_server::federation=debug,hyper=warn";
+        assert_eq!(
+            levels(log_filter(false, Some(directives))),
+            (true, false, true, false)
+        );
+        // Without directives every target follows --debug
+        assert_eq!(levels(log_filter(true, None)), (true, true, true, true));
+        assert_eq!(levels(log_filter(false, None)), (false, false, true, true));
+    }
+
     #[test]
     fn test_cors_layer_invalid_origin() {
         env::set_var("ALLOWED_ORIGINS", "invalid@url");
@@ -1055,4 +1849,696 @@ mod tests {
         let result = validate_admin_token(Some("secret"), &headers);
         assert!(result.is_ok());
     }
+
+    fn metrics_request() -> Request<Body> {
+        Request::builder()
+            .uri("/metrics")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_open_by_default() {
+        use tower::ServiceExt;
+
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let router = build_router(state).await.unwrap();
+
+        let response = router.oneshot(metrics_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn metrics_task_stops_on_shutdown() {
+        let mut config = ServerConfig::default();
+        config.metrics.collection_interval = 1;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let add_session = |state: &AppState| {
+            let id = uuid::Uuid::new_v4();
+            state
+                .active_sessions
+                .insert(id, websocket::WebSocketSession::new(id));
+        };
+        async fn active_sessions_gauge(state: &AppState) -> Option<String> {
+            let exported = state.metrics_collector.read().await.export_prometheus();
+            exported
+                .lines()
+                .find_map(|line| line.strip_prefix("casial_active_sessions "))
+                .map(str::to_string)
+        }
+
+        start_metrics_collection(&state).await.unwrap();
+        add_session(&state);
+        tokio::time::sleep(std::time::Duration::from_millis(1_500)).await;
+        assert_eq!(active_sessions_gauge(&state).await.as_deref(), Some("1"));
+
+        state.shutdown().await;
+        assert!(state.metrics_task.lock().unwrap().is_none());
+
+        // Several intervals pass without a collection pass picking up the new session
+        add_session(&state);
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        assert_eq!(active_sessions_gauge(&state).await.as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn sprawl_gauges_match_debug_sprawl() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let mission = test_fixtures::mission(mission_json(
+            "sprawl",
+            vec![
+                template_json("small", 1, "s".repeat(120)),
+                template_json("large", 1, "l".repeat(3_000)),
+            ],
+            vec![],
+        ));
+        state
+            .mission_manager
+            .write()
+            .await
+            .add_mission_from_file(mission, std::path::Path::new("missions/test.yaml"));
+        for _ in 0..2 {
+            let id = uuid::Uuid::new_v4();
+            state
+                .active_sessions
+                .insert(id, websocket::WebSocketSession::new(id));
+        }
+
+        start_metrics_collection(&state).await.unwrap();
+        // The first tick fires immediately
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let exported = state.metrics_collector.read().await.export_prometheus();
+        state.shutdown().await;
+
+        let response = debug_sprawl(State(state), Query(SprawlParams::default()))
+            .await
+            .unwrap()
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let analysis = &value["context_sprawl_analysis"];
+        assert_eq!(analysis["injected_characters_total"], 3_120);
+        assert_eq!(analysis["avg_context_per_session"], 1_560);
+
+        for (gauge, field) in [
+            ("casial_context_total_chars", "injected_characters_total"),
+            ("casial_template_count", "templates_total"),
+            ("casial_avg_context_per_session", "avg_context_per_session"),
+        ] {
+            let line = format!("\n{}{{mission_id=\"sprawl\"}} {}\n", gauge, analysis[field]);
+            assert!(
+                exported.contains(&line),
+                "missing {:?} in {}",
+                line,
+                exported
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn debug_sprawl_covers_every_mission_unless_one_is_named() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        for (id, size) in [("alpha", 100), ("beta", 250)] {
+            let mission = test_fixtures::mission(mission_json(
+                id,
+                vec![template_json(&format!("{}-guide", id), 1, "x".repeat(size))],
+                vec![],
+            ));
+            state
+                .mission_manager
+                .write()
+                .await
+                .add_mission_from_file(mission, Path::new(&format!("missions/{}.yaml", id)));
+        }
+        let sprawl = |mission_id: Option<&str>| {
+            let state = state.clone();
+            let params = SprawlParams {
+                mission_id: mission_id.map(String::from),
+            };
+            async move {
+                match debug_sprawl(State(state), Query(params)).await {
+                    Ok(response) => {
+                        let body =
+                            axum::body::to_bytes(response.into_response().into_body(), usize::MAX)
+                                .await
+                                .unwrap();
+                        let value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        Ok(value["context_sprawl_analysis"].clone())
+                    }
+                    Err((status, _)) => Err(status),
+                }
+            }
+        };
+
+        let all = sprawl(None).await.unwrap();
+        assert_eq!(all["templates_total"], 2);
+        assert_eq!(all["injected_characters_total"], 350);
+        assert_eq!(
+            all["largest_templates_top10"][0]["template_id"],
+            "beta-guide"
+        );
+
+        let alpha = sprawl(Some("alpha")).await.unwrap();
+        assert_eq!(alpha["templates_total"], 1);
+        assert_eq!(alpha["injected_characters_total"], 100);
+
+        assert_eq!(
+            sprawl(Some("gamma")).await.unwrap_err(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn debug_status_reports_loaded_missions_and_uptime() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        for id in ["alpha", "beta"] {
+            let mission = empty_mission(id);
+            state
+                .mission_manager
+                .write()
+                .await
+                .add_mission_from_file(mission, Path::new(&format!("missions/{}.yaml", id)));
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        let response = debug_status(State(state)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(value["casial_engine"]["active_missions"], 2);
+        assert!(value["server"]["uptime_seconds"].as_f64().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn file_signals_are_off_by_default_and_sandboxed_when_allowed() {
+        let mut rule = rule_json("rust-project", &[], &["rust-guide"]);
+        rule["conditions"]["file_signals"] = json!([{
+            "path": "Cargo.toml", "must_exist": true,
+            "contains": null, "modified_since": null
+        }]);
+        let mission = test_fixtures::mission(mission_json(
+            "signals",
+            vec![template_json("rust-guide", 1, "Use cargo.")],
+            vec![rule],
+        ));
+        let crate_dir = env!("CARGO_MANIFEST_DIR");
+        let coordinate = |state: &AppState, project_path: &str| {
+            state
+                .casial_engine
+                .coordinate(casial_core::CoordinationRequest {
+                    tool_name: "build".to_string(),
+                    tool_args: json!({}),
+                    project_path: Some(project_path.to_string()),
+                    ..Default::default()
+                })
+        };
+
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        state.casial_engine.load_mission(mission.clone()).unwrap();
+        let ignored = coordinate(&state, crate_dir).unwrap();
+        assert!(ignored.activated_rules.is_empty());
+
+        let mut config = ServerConfig::default();
+        config.server.allow_file_signals = true;
+        config.server.file_signal_root = Some(PathBuf::from(crate_dir).join(".."));
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        state.casial_engine.load_mission(mission).unwrap();
+        let sandboxed = coordinate(&state, "casial-server").unwrap();
+        assert_eq!(sandboxed.activated_rules, vec!["rust-project"]);
+
+        let escaped = coordinate(&state, "casial-server/../../..").unwrap_err();
+        assert!(escaped.to_string().contains("sandbox root"), "{}", escaped);
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_rejects_unauthenticated_when_guarded() {
+        use tower::ServiceExt;
+
+        let mut config = ServerConfig::default();
+        config.metrics.require_auth = true;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let router = build_router(state).await.unwrap();
+
+        let response = router.oneshot(metrics_request()).await.unwrap();
+        assert!(matches!(
+            response.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ));
+    }
+
+    #[tokio::test]
+    async fn readiness_waits_for_required_federation() {
+        use tower::ServiceExt;
+
+        let ready_request = || {
+            Request::builder()
+                .uri("/ready")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let mut config = ServerConfig::default();
+        config.federation.enabled = true;
+        config.federation.required = true;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let router = build_router(state.clone()).await.unwrap();
+
+        let response = router.clone().oneshot(ready_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        state.ready.store(true, Ordering::Release);
+        let response = router.oneshot(ready_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        assert!(state.ready.load(Ordering::Acquire));
+    }
+
+    #[tokio::test]
+    async fn history_export_streams_one_json_object_per_line() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        {
+            let engine = &state.casial_engine;
+            engine.load_mission(empty_mission("history")).unwrap();
+            for tool_name in ["first_tool", "second_tool", "third_tool"] {
+                engine
+                    .coordinate(casial_core::CoordinationRequest::new(tool_name, json!({})))
+                    .unwrap();
+            }
+        }
+
+        let params = HistoryExportParams {
+            after: Some(1),
+            limit: None,
+        };
+        let response = debug_history_ndjson(State(state), Query(params))
+            .await
+            .into_response();
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/x-ndjson"
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.ends_with('\n'));
+
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+            .collect();
+        let sequences: Vec<u64> = lines
+            .iter()
+            .map(|line| line["sequence"].as_u64().unwrap())
+            .collect();
+        assert_eq!(sequences, vec![2, 3]);
+        assert!(lines[0]["result"].get("modified_args").is_some());
+    }
+
+    #[tokio::test]
+    async fn perceptions_endpoint_lists_mission_and_session_perceptions() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let mut perceptive = test_fixtures::mission_json("perceptive", vec![], vec![]);
+        perceptive["perceptions"] = json!([{
+            "id": PerceptionId::new(), "name": "optimist", "description": "Sees upside",
+            "confidence": 0.8, "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z", "metadata": {}
+        }]);
+        let perceptive = test_fixtures::mission(perceptive);
+        state
+            .casial_engine
+            .load_mission(perceptive.clone())
+            .unwrap();
+        state
+            .mission_manager
+            .write()
+            .await
+            .add_mission_from_file(perceptive, std::path::Path::new("missions/test.yaml"));
+        let scout = casial_core::Perception {
+            id: PerceptionId::new(),
+            name: "scout".to_string(),
+            description: String::new(),
+            confidence: 0.4,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            metadata: Default::default(),
+        };
+        state.casial_engine.register_perception(scout.clone());
+        let session_id = Uuid::new_v4();
+        let mut session = websocket::WebSocketSession::new(session_id);
+        session.active_perceptions.push(scout.id);
+        state.active_sessions.insert(session_id, session);
+
+        let response = debug_perceptions(State(state))
+            .await
+            .unwrap()
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let perceptions = &value["perceptions"];
+        assert_eq!(perceptions["total_count"], json!(2));
+        assert_eq!(perceptions["missions_with_perceptions"], json!(1));
+        assert!((perceptions["avg_confidence"].as_f64().unwrap() - 0.6).abs() < 1e-9);
+        let items: Vec<(&str, bool)> = perceptions["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| {
+                (
+                    item["name"].as_str().unwrap(),
+                    item["session_active"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(items, [("optimist", false), ("scout", true)]);
+    }
+
+    #[tokio::test]
+    async fn perception_history_endpoint_returns_matching_entries() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let watched = PerceptionId::new();
+        {
+            let engine = &state.casial_engine;
+            engine.load_mission(empty_mission("history")).unwrap();
+            for (tool_name, active) in [("watched_tool", vec![watched]), ("other_tool", vec![])] {
+                engine
+                    .coordinate(casial_core::CoordinationRequest {
+                        tool_name: tool_name.to_string(),
+                        tool_args: json!({ "tool": tool_name }),
+                        active_perceptions: active,
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+        }
+
+        let response = debug_perception_history(State(state), axum::extract::Path(watched))
+            .await
+            .into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(value["count"], json!(1));
+        assert_eq!(value["history"][0]["sequence"], json!(1));
+        assert_eq!(
+            value["history"][0]["result"]["modified_args"]["tool"],
+            json!("watched_tool")
+        );
+    }
+
+    /// Id source that holds each caller until `parties` callers are inside it at once
+    struct RendezvousIdSource {
+        parties: usize,
+        arrived: std::sync::Mutex<usize>,
+        all_arrived: std::sync::Condvar,
+        overlapped: std::sync::atomic::AtomicUsize,
+    }
+
+    impl IdSource for RendezvousIdSource {
+        fn next_id(&self) -> Uuid {
+            let mut arrived = self.arrived.lock().unwrap();
+            *arrived += 1;
+            self.all_arrived.notify_all();
+            // Time out rather than hang when callers can't be inside at the same time
+            let (arrived, wait) = self
+                .all_arrived
+                .wait_timeout_while(arrived, std::time::Duration::from_secs(5), |arrived| {
+                    *arrived < self.parties
+                })
+                .unwrap();
+            if !wait.timed_out() {
+                self.overlapped
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+            drop(arrived);
+            Uuid::new_v4()
+        }
+    }
+
+    #[test]
+    fn concurrent_coordinations_do_not_serialize_on_the_engine() {
+        const WORKERS: usize = 4;
+        let ids = Arc::new(RendezvousIdSource {
+            parties: WORKERS,
+            arrived: std::sync::Mutex::new(0),
+            all_arrived: std::sync::Condvar::new(),
+            overlapped: std::sync::atomic::AtomicUsize::new(0),
+        });
+        let state = AppState::with_id_source(
+            ServerConfig::default(),
+            PitfallAvoidanceShim::default(),
+            Arc::clone(&ids) as Arc<dyn IdSource>,
+        );
+        // A weighted pick draws from the id source in the middle of every coordination
+        let mut rule = rule_json("pick", &["tool_"], &["control", "variant"]);
+        rule["actions"]["selection"] =
+            json!({ "WeightedPick": { "weights": { "control": 1.0, "variant": 1.0 } } });
+        state
+            .casial_engine
+            .load_mission(test_fixtures::mission(mission_json(
+                "concurrent",
+                vec![
+                    template_json("control", 1, "Cite sources."),
+                    template_json("variant", 1, "Quote sources."),
+                ],
+                vec![rule],
+            )))
+            .unwrap();
+
+        // Each coordination waits inside the engine for all the others to get there too,
+        // which only happens if they run at the same time
+        std::thread::scope(|scope| {
+            for worker in 0..WORKERS {
+                let engine = &*state.casial_engine;
+                scope.spawn(move || {
+                    engine
+                        .coordinate(casial_core::CoordinationRequest::new(
+                            format!("tool_{}", worker),
+                            json!({}),
+                        ))
+                        .unwrap();
+                });
+            }
+        });
+        assert_eq!(
+            ids.overlapped.load(std::sync::atomic::Ordering::SeqCst),
+            WORKERS
+        );
+
+        let sequences: Vec<u64> = state
+            .casial_engine
+            .coordination_history_page(None, usize::MAX)
+            .into_iter()
+            .map(|(sequence, _)| sequence)
+            .collect();
+        assert_eq!(sequences, (1..=WORKERS as u64).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn substrate_endpoint_reports_statistics_after_primitive_execution() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        state
+            .substrate_manager
+            .write()
+            .await
+            .execute_primitive_operation(
+                "pattern-primitive",
+                "recognize_pattern",
+                json!({ "data": [], "pattern_type": "trend" }),
+                None,
+            )
+            .unwrap();
+
+        let response = debug_substrate(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let stats: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        for field in [
+            "layer_count",
+            "primitive_count",
+            "integration_point_count",
+            "connection_count",
+            "network_topology",
+        ] {
+            assert!(stats.get(field).is_some(), "missing {}", field);
+        }
+        assert_eq!(stats["primitive_count"], json!(3));
+        assert_eq!(stats["performance_metrics"]["throughput"], json!(1.0));
+        assert!(stats["performance_metrics"]["integration_latency_ms"].is_number());
+    }
+
+    #[test]
+    fn test_coordinate_subcommand_injects_mission_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let mission_path = dir.path().join("mission.yaml");
+        let mission = mission_json(
+            "cli",
+            vec![template_json("guide", 1, "Prefer primary sources.")],
+            vec![rule_json("search-guide", &["search"], &["guide"])],
+        );
+        std::fs::write(&mission_path, serde_yaml::to_string(&mission).unwrap()).unwrap();
+
+        let cli = Cli::try_parse_from([
+            "casial-server",
+            "coordinate",
+            "--mission",
+            mission_path.to_str().unwrap(),
+            "--tool",
+            "web_search",
+            "--args",
+            r#"{"query": "rust"}"#,
+        ])
+        .expect("coordinate arguments parse");
+        let Commands::Coordinate {
+            mission,
+            tool,
+            args,
+            project_path,
+        } = cli.command
+        else {
+            panic!("expected coordinate subcommand");
+        };
+
+        let result = run_coordination(&mission, &tool, &args, project_path).unwrap();
+
+        assert!(result.applied);
+        assert_eq!(result.activated_rules, vec!["search-guide"]);
+        assert!(result.injected_content.contains("Prefer primary sources."));
+        assert!(serde_json::to_string_pretty(&result)
+            .unwrap()
+            .contains("Prefer primary sources."));
+    }
+
+    #[tokio::test]
+    async fn debug_missions_reports_source_and_failed_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let mission_path = dir.path().join("mission.yaml");
+        let mut parked = rule_json("parked", &["search"], &[]);
+        parked["enabled"] = json!(false);
+        let mission = mission_json("reloadable", vec![], vec![parked]);
+        std::fs::write(&mission_path, serde_yaml::to_string(&mission).unwrap()).unwrap();
+
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        load_mission(&state, mission_path.clone()).await.unwrap();
+        let missions_json = |response: Response| async {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["missions"][0].clone()
+        };
+
+        let loaded =
+            missions_json(debug_missions(State(state.clone())).await.into_response()).await;
+        assert_eq!(loaded["source_path"], json!(mission_path));
+        assert_eq!(loaded["last_reload_result"]["status"], "loaded");
+        assert_eq!(loaded["warning_count"], 1);
+        let loaded_at = loaded["loaded_at"].clone();
+        assert!(loaded_at.is_string());
+
+        std::fs::write(&mission_path, "id: [unterminated").unwrap();
+        let reloaded = missions_json(reload_missions(State(state)).await).await;
+        assert_eq!(reloaded["id"], "reloadable");
+        assert_eq!(reloaded["loaded_at"], loaded_at);
+        assert_eq!(reloaded["last_reload_result"]["status"], "failed");
+        assert!(reloaded["last_reload_result"]["error"]
+            .as_str()
+            .unwrap()
+            .contains("Failed to parse mission YAML"));
+    }
+
+    #[tokio::test]
+    async fn reload_replaces_a_mission_whose_id_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let mission_path = dir.path().join("mission.yaml");
+        let write = |id: &str| {
+            let mission = mission_json(id, vec![], vec![]);
+            std::fs::write(&mission_path, serde_yaml::to_string(&mission).unwrap()).unwrap();
+        };
+        write("before");
+
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        load_mission(&state, mission_path.clone()).await.unwrap();
+        write("after");
+        let response = reload_missions(State(state.clone())).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let missions =
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()["missions"].clone();
+
+        let ids: Vec<&str> = missions
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["after"]);
+        assert!(!state.casial_engine.has_mission("before"));
+        assert!(state.casial_engine.has_mission("after"));
+    }
+
+    #[test]
+    fn print_config_reflects_cli_overrides_and_redacts_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.yaml");
+        let mut config = ServerConfig::default();
+        config.federation.downstream_servers = vec![config::DownstreamMcpServer {
+            id: "exa".to_string(),
+            name: "Exa".to_string(),
+            url: "wss://exa.example/mcp".to_string(),
+            connection_type: "websocket".to_string(),
+            enabled: true,
+            timeout_ms: 5_000,
+            priority: 1,
+            auth: Some(config::McpAuth {
+                auth_type: "header".to_string(),
+                token: Some("sk-live-secret".to_string()),
+                username: None,
+                password: None,
+            }),
+        }];
+        std::fs::write(&config_path, serde_yaml::to_string(&config).unwrap()).unwrap();
+
+        let cli = Cli::try_parse_from([
+            "casial-server",
+            "start",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--port",
+            "9100",
+            "--print-config",
+        ])
+        .expect("start arguments parse");
+        let Commands::Start(StartArgs {
+            config,
+            port,
+            print_config,
+            ..
+        }) = cli.command
+        else {
+            panic!("expected start subcommand");
+        };
+        assert!(print_config);
+
+        let printed = render_effective_config(config.as_deref(), port).unwrap();
+        let printed: serde_json::Value = serde_json::from_str(&printed).unwrap();
+
+        assert_eq!(printed["server"]["port"], 9100);
+        let auth = &printed["federation"]["downstream_servers"][0]["auth"];
+        assert_eq!(auth["token"], "***");
+        assert!(auth["password"].is_null());
+    }
 }
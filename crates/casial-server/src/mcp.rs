@@ -2,8 +2,14 @@
 //!
 //! JSON-RPC 2.0 implementation for the Model Context Protocol with consciousness-aware extensions.
 
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+
+/// Page size for `*/list` methods when the client doesn't pass a `limit`
+pub const DEFAULT_LIST_PAGE_SIZE: usize = 100;
 
 /// JSON-RPC 2.0 Request
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +41,17 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
+/// `experimental.casial` initialize capability: the coordination features this server supports
+pub fn casial_capabilities() -> Value {
+    serde_json::json!({
+        "paradox_strategies": casial_core::ParadoxStrategy::ALL,
+        "transform_types": casial_core::TransformType::ALL,
+        "explain": false,
+        "preview": true,
+        "preview_tool": "casial_preview_coordination"
+    })
+}
+
 /// Create a success response
 pub fn create_success_response(id: Value, result: Value) -> JsonRpcResponse {
     JsonRpcResponse {
@@ -64,10 +81,165 @@ pub fn create_error_response(
     }
 }
 
+/// Pick the protocol version to use for a client's `initialize` request
+///
+/// MCP revisions are date strings, and a client speaking one revision is assumed to
+/// speak earlier ones too, so this returns the newest supported version that is not
+/// newer than the requested one. `None` means there is no overlap.
+pub fn negotiate_protocol_version(requested: &str, supported: &[String]) -> Option<String> {
+    supported
+        .iter()
+        .filter(|version| version.as_str() <= requested)
+        .max()
+        .cloned()
+}
+
+/// Newest protocol version the server supports
+pub fn latest_protocol_version(supported: &[String]) -> Option<&str> {
+    supported.iter().max().map(String::as_str)
+}
+
+/// Pagination params accepted by `tools/list` and `resources/list`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ListParams {
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ListParams {
+    /// Parse list params, treating absent params as a request for the first page
+    pub fn from_params(params: &Value) -> Result<Self> {
+        if params.is_null() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_value(params.clone())?)
+    }
+}
+
+/// Slice one page out of `items`, returning it with the cursor for the next page
+///
+/// Cursors are opaque to clients; callers must keep `items` in a stable order.
+pub fn paginate<T>(items: Vec<T>, params: &ListParams) -> Result<(Vec<T>, Option<String>)> {
+    let offset = match &params.cursor {
+        Some(cursor) => decode_cursor(cursor)?,
+        None => 0,
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LIST_PAGE_SIZE).max(1);
+
+    let total = items.len();
+    let end = offset.saturating_add(limit).min(total);
+    let next_cursor = (end < total).then(|| encode_cursor(end));
+    let page = items
+        .into_iter()
+        .skip(offset)
+        .take(end.saturating_sub(offset));
+
+    Ok((page.collect(), next_cursor))
+}
+
+/// Error response for malformed `cursor`/`limit` params on list methods
+pub fn invalid_list_params(id: Value, error: anyhow::Error) -> JsonRpcResponse {
+    create_error_response(
+        id,
+        -32602,
+        "Invalid params",
+        Some(serde_json::json!({ "error": error.to_string() })),
+    )
+}
+
+/// Bind the variables of a URI template whose `{name}` placeholders each fill a whole path segment
+pub fn match_uri_template(template: &str, uri: &str) -> Option<HashMap<String, String>> {
+    let template_segments: Vec<&str> = template.split('/').collect();
+    let uri_segments: Vec<&str> = uri.split('/').collect();
+    if template_segments.len() != uri_segments.len() {
+        return None;
+    }
+
+    let mut variables = HashMap::new();
+    for (pattern, value) in template_segments.iter().zip(uri_segments) {
+        match pattern.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+            Some(name) if !value.is_empty() => {
+                variables.insert(name.to_string(), value.to_string());
+            }
+            None if *pattern == value => {}
+            _ => return None,
+        }
+    }
+    Some(variables)
+}
+
+fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(offset.to_string())
+}
+
+fn decode_cursor(cursor: &str) -> Result<usize> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|offset| offset.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid cursor: {}", cursor))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn versions(list: &[&str]) -> Vec<String> {
+        list.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_negotiates_matching_newer_version() {
+        let supported = versions(&["2024-11-05", "2025-03-26", "2025-06-18"]);
+
+        assert_eq!(
+            negotiate_protocol_version("2025-06-18", &supported).as_deref(),
+            Some("2025-06-18")
+        );
+        assert_eq!(
+            negotiate_protocol_version("2025-05-01", &supported).as_deref(),
+            Some("2025-03-26")
+        );
+    }
+
+    #[test]
+    fn test_negotiation_fails_without_overlap() {
+        let supported = versions(&["2025-03-26", "2025-06-18"]);
+        assert_eq!(negotiate_protocol_version("2024-11-05", &supported), None);
+    }
+
+    #[test]
+    fn test_paginate_walks_all_pages() {
+        let items: Vec<usize> = (0..25).collect();
+        let mut params = ListParams {
+            cursor: None,
+            limit: Some(10),
+        };
+        let mut seen = Vec::new();
+
+        loop {
+            let (page, next) = paginate(items.clone(), &params).unwrap();
+            seen.extend(page);
+            match next {
+                Some(cursor) => params.cursor = Some(cursor),
+                None => break,
+            }
+        }
+
+        assert_eq!(seen, items);
+        assert!(paginate(
+            items,
+            &ListParams {
+                cursor: Some("not a cursor".to_string()),
+                limit: None,
+            }
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_success_response() {
         let response =
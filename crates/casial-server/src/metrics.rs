@@ -2,10 +2,90 @@
 //!
 //! Performance and coordination metrics for the Casial server.
 
+use casial_core::CasialMission;
 use chrono::{DateTime, Utc};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use tracing::info;
 
+/// Template size totals behind `/debug/sprawl` and the sprawl gauges
+#[derive(Debug, Clone, Default)]
+pub struct ContextSprawl {
+    pub template_count: usize,
+    pub total_chars: usize,
+    pub total_tokens: usize,
+    pub active_sessions: usize,
+    pub avg_context_per_session: usize,
+    /// `(template id, characters, estimated tokens)`, largest first, at most 10
+    pub largest_templates: Vec<(String, usize, usize)>,
+    pub categories: HashMap<String, usize>,
+}
+
+impl ContextSprawl {
+    /// Measure the templates of `missions` together, spread across the active sessions
+    pub fn measure<'a>(
+        missions: impl IntoIterator<Item = &'a CasialMission>,
+        active_sessions: usize,
+    ) -> Self {
+        let mut sprawl = Self {
+            active_sessions,
+            ..Self::default()
+        };
+
+        for mission in missions {
+            sprawl.template_count += mission.templates.len();
+
+            for template in &mission.templates {
+                let content_length = template.content.len();
+                let token_estimate = casial_core::estimate_tokens(&template.content);
+                sprawl.total_chars += content_length;
+                sprawl.total_tokens += token_estimate;
+                sprawl.largest_templates.push((
+                    template.id.clone(),
+                    content_length,
+                    token_estimate,
+                ));
+
+                for category in &template.categories {
+                    *sprawl.categories.entry(category.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        // Sort by size, then id so equal sizes don't depend on mission order; keep top 10
+        sprawl
+            .largest_templates
+            .sort_by(|(a_id, a_size, _), (b_id, b_size, _)| {
+                b_size.cmp(a_size).then_with(|| a_id.cmp(b_id))
+            });
+        sprawl.largest_templates.truncate(10);
+
+        sprawl.avg_context_per_session =
+            sprawl.total_chars.checked_div(active_sessions).unwrap_or(0);
+        sprawl
+    }
+}
+
+/// Name, help text and value of a per-mission sprawl gauge
+type SprawlGauge = (&'static str, &'static str, fn(&ContextSprawl) -> usize);
+
+const SPRAWL_GAUGES: [SprawlGauge; 3] = [
+    (
+        "casial_context_total_chars",
+        "Characters across a mission's templates",
+        |sprawl| sprawl.total_chars,
+    ),
+    (
+        "casial_template_count",
+        "Templates in a mission",
+        |sprawl| sprawl.template_count,
+    ),
+    (
+        "casial_avg_context_per_session",
+        "A mission's template characters per active session",
+        |sprawl| sprawl.avg_context_per_session,
+    ),
+];
+
 /// Metrics collector for server performance and coordination statistics
 pub struct MetricsCollector {
     coordination_events: u64,
@@ -13,6 +93,8 @@ pub struct MetricsCollector {
     paradoxes_resolved: u64,
     perception_locks: u64,
     substrate_operations: u64,
+    /// Sprawl of each loaded mission, by mission id
+    mission_sprawl: Vec<(String, ContextSprawl)>,
     last_updated: DateTime<Utc>,
     history: VecDeque<MetricsSnapshot>,
 }
@@ -36,6 +118,7 @@ impl MetricsCollector {
             paradoxes_resolved: 0,
             perception_locks: 0,
             substrate_operations: 0,
+            mission_sprawl: Vec::new(),
             last_updated: Utc::now(),
             history: VecDeque::with_capacity(1000), // Keep last 1000 snapshots
         }
@@ -51,6 +134,34 @@ impl MetricsCollector {
         self.last_updated = Utc::now();
     }
 
+    /// Record each mission's sprawl; the gauges are exported in mission id order
+    pub fn record_context_sprawl(&mut self, mut mission_sprawl: Vec<(String, ContextSprawl)>) {
+        mission_sprawl.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.mission_sprawl = mission_sprawl;
+        self.last_updated = Utc::now();
+    }
+
+    /// Sprawl gauges with one sample per mission, labelled by `mission_id`
+    fn export_sprawl_gauges(&self) -> String {
+        let mut out = String::new();
+        for (name, help, value) in SPRAWL_GAUGES {
+            out.push_str(&format!(
+                "# HELP {} {}\n# TYPE {} gauge\n",
+                name, help, name
+            ));
+            for (mission_id, sprawl) in &self.mission_sprawl {
+                out.push_str(&format!(
+                    "{}{{mission_id=\"{}\"}} {}\n",
+                    name,
+                    escape_label_value(mission_id),
+                    value(sprawl)
+                ));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
     pub fn increment_paradoxes_resolved(&mut self) {
         self.paradoxes_resolved += 1;
         self.last_updated = Utc::now();
@@ -106,7 +217,7 @@ casial_perception_locks_total {}
 # TYPE casial_substrate_operations_total counter
 casial_substrate_operations_total {}
 
-# HELP casial_last_updated_timestamp Last metrics update timestamp
+{}# HELP casial_last_updated_timestamp Last metrics update timestamp
 # TYPE casial_last_updated_timestamp gauge
 casial_last_updated_timestamp {}
 "#,
@@ -115,6 +226,7 @@ casial_last_updated_timestamp {}
             self.paradoxes_resolved,
             self.perception_locks,
             self.substrate_operations,
+            self.export_sprawl_gauges(),
             self.last_updated.timestamp()
         )
     }
@@ -146,6 +258,14 @@ casial_last_updated_timestamp {}
     }
 }
 
+/// Escape a Prometheus label value
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 impl Default for MetricsCollector {
     fn default() -> Self {
         Self::new()
@@ -169,6 +289,30 @@ mod tests {
         assert_eq!(collector.paradoxes_resolved, 1);
     }
 
+    #[test]
+    fn test_sprawl_gauges_are_labelled_per_mission() {
+        let mut collector = MetricsCollector::new();
+        let sprawl = |total_chars| ContextSprawl {
+            template_count: 1,
+            total_chars,
+            ..ContextSprawl::default()
+        };
+        collector.record_context_sprawl(vec![
+            ("beta".to_string(), sprawl(250)),
+            ("al\"pha".to_string(), sprawl(100)),
+        ]);
+
+        let exported = collector.export_prometheus();
+        let alpha = exported
+            .find("casial_context_total_chars{mission_id=\"al\\\"pha\"} 100\n")
+            .expect("alpha sample");
+        let beta = exported
+            .find("casial_context_total_chars{mission_id=\"beta\"} 250\n")
+            .expect("beta sample");
+        assert!(alpha < beta);
+        assert!(!exported.contains("\ncasial_context_total_chars "));
+    }
+
     #[test]
     fn test_metrics_snapshot() {
         let mut collector = MetricsCollector::new();
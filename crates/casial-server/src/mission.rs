@@ -2,26 +2,113 @@
 //!
 //! Mission loading, validation, and management for consciousness-aware context coordination.
 
-use anyhow::{Context, Result};
-use casial_core::{CasialMission, CasialTemplate};
-use std::{collections::HashMap, path::Path};
+use ahash::AHashMap;
+use anyhow::{bail, Context, Result};
+use casial_core::{
+    CasialEngine, CasialMission, CasialTemplate, CoordinationRecord, CoordinationRequest,
+    CoordinationResult, FileSignalPolicy,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 /// Mission manager for handling multiple missions
 pub struct MissionManager {
     missions: HashMap<String, CasialMission>,
+    status: HashMap<String, MissionStatus>,
+}
+
+/// Where a registered mission came from and how its latest (re)load went
+#[derive(Debug, Clone, Serialize)]
+pub struct MissionStatus {
+    pub source_path: Option<PathBuf>,
+    /// When the definition currently in use was loaded
+    pub loaded_at: DateTime<Utc>,
+    pub last_reload_result: ReloadResult,
+    pub warning_count: usize,
+}
+
+/// Outcome of the latest attempt to (re)load a mission
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ReloadResult {
+    Loaded {
+        at: DateTime<Utc>,
+    },
+    /// The previous definition is still in use
+    Failed {
+        at: DateTime<Utc>,
+        error: String,
+    },
 }
 
 impl MissionManager {
     pub fn new() -> Self {
         Self {
             missions: HashMap::new(),
+            status: HashMap::new(),
         }
     }
 
-    pub fn add_mission(&mut self, mission: CasialMission) -> Result<()> {
-        let mission_id = mission.id.clone();
-        self.missions.insert(mission_id, mission);
-        Ok(())
+    /// Register a mission loaded from `source_path`, so it can be reloaded later
+    ///
+    /// Returns the ids this file previously defined under a different id, which are
+    /// dropped here and should be unloaded from the engine too.
+    pub fn add_mission_from_file(
+        &mut self,
+        mission: CasialMission,
+        source_path: &Path,
+    ) -> Vec<String> {
+        let replaced: Vec<String> = self
+            .status
+            .iter()
+            .filter(|(id, status)| {
+                **id != mission.id && status.source_path.as_deref() == Some(source_path)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &replaced {
+            self.remove_mission(id);
+        }
+
+        let now = Utc::now();
+        self.status.insert(
+            mission.id.clone(),
+            MissionStatus {
+                source_path: Some(source_path.to_path_buf()),
+                loaded_at: now,
+                last_reload_result: ReloadResult::Loaded { at: now },
+                warning_count: mission_warnings(&mission).len(),
+            },
+        );
+        self.missions.insert(mission.id.clone(), mission);
+        replaced
+    }
+
+    /// Note a failed reload; the mission keeps its previous definition
+    pub fn record_reload_failure(&mut self, id: &str, error: String) {
+        if let Some(status) = self.status.get_mut(id) {
+            status.last_reload_result = ReloadResult::Failed {
+                at: Utc::now(),
+                error,
+            };
+        }
+    }
+
+    pub fn status(&self, id: &str) -> Option<&MissionStatus> {
+        self.status.get(id)
+    }
+
+    /// Missions loaded from files, by id
+    pub fn file_sources(&self) -> Vec<(String, PathBuf)> {
+        self.status
+            .iter()
+            .filter_map(|(id, status)| Some((id.clone(), status.source_path.clone()?)))
+            .collect()
     }
 
     pub fn get_mission(&self, id: &str) -> Option<&CasialMission> {
@@ -33,6 +120,7 @@ impl MissionManager {
     }
 
     pub fn remove_mission(&mut self, id: &str) -> Option<CasialMission> {
+        self.status.remove(id);
         self.missions.remove(id)
     }
 }
@@ -43,21 +131,385 @@ impl Default for MissionManager {
     }
 }
 
-/// Load mission from YAML file
-pub fn load_mission_from_file<P: AsRef<Path>>(path: P) -> Result<CasialMission> {
-    let content = std::fs::read_to_string(&path)
-        .with_context(|| format!("Failed to read mission file: {}", path.as_ref().display()))?;
+/// Non-fatal issue found in a mission that loads but likely won't behave as intended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissionWarning {
+    DisabledRule {
+        rule_id: String,
+    },
+    ZeroPriorityTemplate {
+        template_id: String,
+    },
+    EmptyTemplateContent {
+        template_id: String,
+    },
+    UnknownTemplateReference {
+        rule_id: String,
+        template_id: String,
+    },
+    EmptyCategoryScope {
+        rule_id: String,
+        category: String,
+    },
+}
+
+impl fmt::Display for MissionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DisabledRule { rule_id } => {
+                write!(f, "rule '{}' is disabled and will never activate", rule_id)
+            }
+            Self::ZeroPriorityTemplate { template_id } => {
+                write!(f, "template '{}' has priority 0", template_id)
+            }
+            Self::EmptyTemplateContent { template_id } => {
+                write!(f, "template '{}' has empty content", template_id)
+            }
+            Self::UnknownTemplateReference {
+                rule_id,
+                template_id,
+            } => write!(
+                f,
+                "rule '{}' references unknown template '{}'",
+                rule_id, template_id
+            ),
+            Self::EmptyCategoryScope { rule_id, category } => write!(
+                f,
+                "rule '{}' scopes category '{}', which has no templates",
+                rule_id, category
+            ),
+        }
+    }
+}
+
+/// Load mission from YAML file, resolving any `extends` includes
+///
+/// Returns the mission together with any soft issues found in the resolved definition.
+pub fn load_mission_from_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<(CasialMission, Vec<MissionWarning>)> {
+    let mut include_stack = Vec::new();
+    let mut mission = load_mission_with_includes(path.as_ref(), &mut include_stack)?;
+    apply_flag_overrides(&mut mission, |name| std::env::var(name).ok());
+    let warnings = mission_warnings(&mission);
+    Ok((mission, warnings))
+}
+
+/// Environment variable overriding a mission feature flag, e.g. `MOP_FLAG_STAGING_ONLY`
+fn flag_env_var(flag: &str) -> String {
+    let name: String = flag
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("MOP_FLAG_{}", name)
+}
+
+/// Override declared and required feature flags from `MOP_FLAG_*` values (`1`/`true`/`yes` or `0`/`false`/`no`)
+pub fn apply_flag_overrides(mission: &mut CasialMission, lookup: impl Fn(&str) -> Option<String>) {
+    let mut flags: Vec<String> = mission.feature_flags.keys().cloned().collect();
+    flags.extend(
+        mission
+            .templates
+            .iter()
+            .flat_map(|t| t.required_flags.iter())
+            .chain(mission.rules.iter().flat_map(|r| r.required_flags.iter()))
+            .cloned(),
+    );
+    flags.sort();
+    flags.dedup();
+
+    for flag in flags {
+        let var = flag_env_var(&flag);
+        let Some(value) = lookup(&var) else {
+            continue;
+        };
+        match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" => {
+                mission.feature_flags.insert(flag, true);
+            }
+            "0" | "false" | "no" => {
+                mission.feature_flags.insert(flag, false);
+            }
+            _ => tracing::warn!("Ignoring {}={:?}: expected true or false", var, value),
+        }
+    }
+}
+
+/// Collect soft issues in a mission definition
+pub fn mission_warnings(mission: &CasialMission) -> Vec<MissionWarning> {
+    let mut warnings = Vec::new();
+
+    for rule in &mission.rules {
+        if !rule.enabled {
+            warnings.push(MissionWarning::DisabledRule {
+                rule_id: rule.id.clone(),
+            });
+        }
+
+        for template_id in &rule.actions.template_ids {
+            if !mission.templates.iter().any(|t| t.id == *template_id) {
+                warnings.push(MissionWarning::UnknownTemplateReference {
+                    rule_id: rule.id.clone(),
+                    template_id: template_id.clone(),
+                });
+            }
+        }
+
+        for category in &rule.category_scope {
+            if mission.get_templates_by_category(category).next().is_none() {
+                warnings.push(MissionWarning::EmptyCategoryScope {
+                    rule_id: rule.id.clone(),
+                    category: category.clone(),
+                });
+            }
+        }
+    }
+
+    for template in &mission.templates {
+        if template.priority == 0 {
+            warnings.push(MissionWarning::ZeroPriorityTemplate {
+                template_id: template.id.clone(),
+            });
+        }
+        if template.content.trim().is_empty() {
+            warnings.push(MissionWarning::EmptyTemplateContent {
+                template_id: template.id.clone(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Integrity report for a mission definition
+#[derive(Debug, Clone, Serialize)]
+pub struct MissionValidation {
+    pub valid: bool,
+    /// Problems that stop the engine loading the mission
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Check a mission the way the engine loads it, without loading it anywhere
+pub fn validate_mission(mission: &CasialMission) -> MissionValidation {
+    let errors = match CasialEngine::new().load_mission(mission.clone()) {
+        Ok(()) => Vec::new(),
+        Err(e) => vec![format!("{:#}", e)],
+    };
+    MissionValidation {
+        valid: errors.is_empty(),
+        errors,
+        warnings: mission_warnings(mission)
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    }
+}
+
+/// Coordinate one tool call against a mission in a throwaway engine
+///
+/// `environment_vars` conditions see only `environment`, never the server's own variables,
+/// so a caller-supplied mission cannot probe server secrets.
+pub fn preview_coordination(
+    mission: CasialMission,
+    tool_name: &str,
+    tool_args: serde_json::Value,
+    environment: AHashMap<String, String>,
+    project_path: Option<String>,
+) -> Result<CoordinationResult> {
+    let engine = CasialEngine::new();
+    engine.load_mission(mission)?;
+    engine.coordinate(CoordinationRequest {
+        tool_name: tool_name.to_string(),
+        tool_args,
+        environment,
+        project_path,
+        ..Default::default()
+    })
+}
+
+/// How one recorded coordination changes when replayed against another mission
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayDiff {
+    pub sequence: u64,
+    pub tool_name: String,
+    pub rules_activated: Vec<String>,
+    pub rules_deactivated: Vec<String>,
+    pub templates_added: Vec<String>,
+    pub templates_removed: Vec<String>,
+    pub injected_content_changed: bool,
+    pub injected_chars_before: usize,
+    pub injected_chars_after: usize,
+}
+
+impl ReplayDiff {
+    /// Whether the replay coordinated exactly as recorded
+    pub fn is_unchanged(&self) -> bool {
+        self.rules_activated.is_empty()
+            && self.rules_deactivated.is_empty()
+            && self.templates_added.is_empty()
+            && self.templates_removed.is_empty()
+            && !self.injected_content_changed
+    }
+}
+
+/// Re-run recorded coordinations against `mission` in a throwaway engine
+///
+/// Takes entries as returned by `CasialEngine::get_coordination_records`.
+pub fn replay_coordinations(
+    mission: CasialMission,
+    records: Vec<(u64, CoordinationRecord)>,
+    file_signals: FileSignalPolicy,
+) -> Result<Vec<ReplayDiff>> {
+    let engine = CasialEngine::new().with_file_signal_policy(file_signals);
+    engine.load_mission(mission)?;
+
+    records
+        .into_iter()
+        .map(|(sequence, record)| {
+            let tool_name = record.request.tool_name.clone();
+            let before = record.result;
+            let after = engine.coordinate(record.request)?;
+            Ok(ReplayDiff {
+                sequence,
+                tool_name,
+                rules_activated: missing_from(&after.activated_rules, &before.activated_rules),
+                rules_deactivated: missing_from(&before.activated_rules, &after.activated_rules),
+                templates_added: missing_from(&after.used_templates, &before.used_templates),
+                templates_removed: missing_from(&before.used_templates, &after.used_templates),
+                injected_content_changed: after.injected_content != before.injected_content,
+                injected_chars_before: before.injected_content.chars().count(),
+                injected_chars_after: after.injected_content.chars().count(),
+            })
+        })
+        .collect()
+}
+
+/// Entries of `items` that aren't in `other`, in order
+fn missing_from(items: &[String], other: &[String]) -> Vec<String> {
+    items
+        .iter()
+        .filter(|item| !other.contains(item))
+        .cloned()
+        .collect()
+}
+
+/// Parse a single mission file without resolving includes
+fn read_mission_file(path: &Path) -> Result<CasialMission> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read mission file: {}", path.display()))?;
 
     let mission: CasialMission = serde_yaml::from_str(&content)
-        .with_context(|| format!("Failed to parse mission YAML: {}", path.as_ref().display()))?;
+        .with_context(|| format!("Failed to parse mission YAML: {}", path.display()))?;
 
     Ok(mission)
 }
 
+/// Load a mission and merge its bases, tracking the include chain to detect cycles
+fn load_mission_with_includes(
+    path: &Path,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<CasialMission> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read mission file: {}", path.display()))?;
+
+    if let Some(start) = include_stack.iter().position(|seen| *seen == canonical) {
+        let chain: Vec<String> = include_stack[start..]
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("Cyclic mission include: {}", chain.join(" -> "));
+    }
+
+    let mut mission = read_mission_file(path)?;
+    if mission.extends.is_empty() {
+        return Ok(mission);
+    }
+
+    include_stack.push(canonical);
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+    // Later bases take precedence over earlier ones; the child always wins
+    for include in mission.extends.clone().iter().rev() {
+        let base_path = resolve_mission_include(base_dir, include)?;
+        let base = load_mission_with_includes(&base_path, include_stack)
+            .with_context(|| format!("Failed to load base mission '{}'", include))?;
+        mission.inherit_from(&base);
+    }
+
+    include_stack.pop();
+    Ok(mission)
+}
+
+/// Resolve an `extends` entry as a path relative to the including file, or a sibling mission id
+fn resolve_mission_include(base_dir: &Path, include: &str) -> Result<PathBuf> {
+    let as_path = base_dir.join(include);
+    if as_path.is_file() {
+        return Ok(as_path);
+    }
+
+    for ext in ["yaml", "yml"] {
+        let by_name = base_dir.join(format!("{}.{}", include, ext));
+        if by_name.is_file() {
+            return Ok(by_name);
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    struct MissionHeader {
+        id: String,
+    }
+
+    // Fall back to scanning sibling mission files for a matching id
+    if let Ok(entries) = std::fs::read_dir(base_dir) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+            if !path.is_file() || !matches!(ext, "yaml" | "yml") {
+                continue;
+            }
+
+            let header = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_yaml::from_str::<MissionHeader>(&content).ok());
+            if header.is_some_and(|header| header.id == include) {
+                return Ok(path);
+            }
+        }
+    }
+
+    bail!(
+        "Mission include '{}' not found in {}",
+        include,
+        base_dir.display()
+    )
+}
+
+/// What to do when a project template shares an id with a mission template
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMergePolicy {
+    /// Keep the mission's template and ignore the project file
+    #[default]
+    Skip,
+    /// Replace the mission's template with the project file
+    Override,
+    /// Fail the merge
+    Error,
+}
+
 /// Merge templates from project templates/ directory with front-matter parsing
 pub fn merge_templates_from_dir(
     mission: &mut CasialMission,
     project_root: &str,
+    policy: TemplateMergePolicy,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use std::fs;
 
@@ -126,10 +578,38 @@ pub fn merge_templates_from_dir(
             perception_affinity: vec![], // Can be set in front-matter
             paradox_resistance: 0.7,     // Default resistance
             metadata: ahash::AHashMap::new(),
+            required_flags: Vec::new(),
         };
 
-        mission.templates.push(template);
-        tracing::info!("Loaded project template: {}", template_id);
+        let existing = mission.templates.iter().position(|t| t.id == template_id);
+        match (existing, policy) {
+            (None, _) => {
+                mission.templates.push(template);
+                tracing::info!("Loaded project template: {}", template_id);
+            }
+            (Some(_), TemplateMergePolicy::Skip) => {
+                tracing::warn!(
+                    "Skipping project template {:?}: mission already defines '{}'",
+                    path,
+                    template_id
+                );
+            }
+            (Some(index), TemplateMergePolicy::Override) => {
+                mission.templates[index] = template;
+                tracing::info!(
+                    "Project template {:?} overrides mission template '{}'",
+                    path,
+                    template_id
+                );
+            }
+            (Some(_), TemplateMergePolicy::Error) => {
+                return Err(format!(
+                    "Project template {:?} conflicts with mission template '{}'",
+                    path, template_id
+                )
+                .into());
+            }
+        }
     }
 
     Ok(())
@@ -206,9 +686,75 @@ fn parse_front_matter(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::{self, rule_json, template_json};
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_flag_overrides_come_from_env_lookup() {
+        let mut rule = rule_json("staging-rule", &[], &[]);
+        rule["required_flags"] = serde_json::json!(["staging-only"]);
+        let mut flags = test_fixtures::mission_json("flags", vec![], vec![rule]);
+        flags["feature_flags"] = serde_json::json!({ "beta": true });
+        let mut mission = test_fixtures::mission(flags);
+
+        apply_flag_overrides(&mut mission, |var| match var {
+            "MOP_FLAG_STAGING_ONLY" => Some("true".to_string()),
+            "MOP_FLAG_BETA" => Some("0".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(mission.feature_flags.get("staging-only"), Some(&true));
+        assert_eq!(mission.feature_flags.get("beta"), Some(&false));
+        assert!(mission.flags_enabled(&["staging-only".to_string()]));
+    }
+
+    #[test]
+    fn test_replay_against_altered_mission_reports_rule_changes() {
+        let mission_json = |rule_tools: &[&str; 2]| {
+            let template = |id: &str| template_json(id, 1, format!("{} guidance", id));
+            test_fixtures::mission(test_fixtures::mission_json(
+                "replay",
+                vec![template("search-guide"), template("crawl-guide")],
+                vec![
+                    rule_json("search-rule", &[rule_tools[0]], &["search-guide"]),
+                    rule_json("crawl-rule", &[rule_tools[1]], &["crawl-guide"]),
+                ],
+            ))
+        };
+
+        let engine = CasialEngine::new();
+        engine
+            .load_mission(mission_json(&["search", "crawl"]))
+            .unwrap();
+        for tool_name in ["web_search", "crawling_exa"] {
+            engine
+                .coordinate(CoordinationRequest::new(
+                    tool_name,
+                    serde_json::json!({ "query": "rust" }),
+                ))
+                .unwrap();
+        }
+
+        // The crawl rule now only matches a tool that was never called
+        let diffs = replay_coordinations(
+            mission_json(&["search", "unused_tool"]),
+            engine.get_coordination_records(),
+            FileSignalPolicy::Disabled,
+        )
+        .unwrap();
+
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].tool_name, "web_search");
+        assert!(diffs[0].is_unchanged());
+        assert_eq!(diffs[1].sequence, 2);
+        assert_eq!(diffs[1].rules_deactivated, vec!["crawl-rule"]);
+        assert_eq!(diffs[1].templates_removed, vec!["crawl-guide"]);
+        assert!(diffs[1].rules_activated.is_empty());
+        assert!(diffs[1].injected_content_changed);
+        assert_eq!(diffs[1].injected_chars_after, 0);
+    }
+
     #[test]
     fn test_mission_manager() {
         let mut manager = MissionManager::new();
@@ -237,10 +783,255 @@ updated_at: "2025-01-01T00:00:00Z"
 "#
         )?;
 
-        let mission = load_mission_from_file(temp_file.path())?;
+        let (mission, warnings) = load_mission_from_file(temp_file.path())?;
+        assert!(warnings.is_empty());
         assert_eq!(mission.id, "test-mission");
         assert_eq!(mission.name, "Test Mission");
 
         Ok(())
     }
+
+    fn write_mission(
+        dir: &Path,
+        file_name: &str,
+        id: &str,
+        extends: &[&str],
+        templates: &[(&str, &str)],
+    ) -> Result<PathBuf> {
+        let templates: Vec<serde_json::Value> = templates
+            .iter()
+            .map(|(template_id, content)| template_json(template_id, 1, *content))
+            .collect();
+        let rules: Vec<serde_json::Value> = templates
+            .iter()
+            .map(|template| {
+                let template_id = template["id"].as_str().unwrap_or_default();
+                rule_json(&format!("{}-rule", template_id), &[], &[template_id])
+            })
+            .collect();
+
+        let mut mission = test_fixtures::mission_json(id, templates, rules);
+        mission["extends"] = serde_json::json!(extends);
+        mission["budgets"]["paradox_overhead"] = serde_json::json!(0.1);
+
+        let path = dir.join(file_name);
+        std::fs::write(&path, serde_yaml::to_string(&mission)?)?;
+        Ok(path)
+    }
+
+    /// Mission with a `shared` template plus a project `templates/shared.md` that collides
+    fn colliding_project(dir: &Path) -> Result<CasialMission> {
+        let path = write_mission(dir, "mission.yaml", "m", &[], &[("shared", "mission")])?;
+        std::fs::create_dir(dir.join("templates"))?;
+        std::fs::write(dir.join("templates/shared.md"), "project")?;
+        std::fs::write(dir.join("templates/notes.md"), "notes")?;
+        Ok(load_mission_from_file(path)?.0)
+    }
+
+    fn template_content<'a>(mission: &'a CasialMission, id: &str) -> Vec<&'a str> {
+        mission
+            .templates
+            .iter()
+            .filter(|t| t.id == id)
+            .map(|t| t.content.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_template_merge_skip_keeps_mission_template() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut mission = colliding_project(dir.path())?;
+
+        merge_templates_from_dir(
+            &mut mission,
+            dir.path().to_str().unwrap(),
+            TemplateMergePolicy::Skip,
+        )
+        .unwrap();
+
+        assert_eq!(template_content(&mission, "shared"), vec!["mission"]);
+        assert_eq!(template_content(&mission, "notes"), vec!["notes"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_merge_override_replaces_mission_template() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut mission = colliding_project(dir.path())?;
+
+        merge_templates_from_dir(
+            &mut mission,
+            dir.path().to_str().unwrap(),
+            TemplateMergePolicy::Override,
+        )
+        .unwrap();
+
+        assert_eq!(template_content(&mission, "shared"), vec!["project"]);
+        assert_eq!(template_content(&mission, "notes"), vec!["notes"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_merge_error_rejects_collision() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut mission = colliding_project(dir.path())?;
+
+        let err = merge_templates_from_dir(
+            &mut mission,
+            dir.path().to_str().unwrap(),
+            TemplateMergePolicy::Error,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("'shared'"));
+        assert_eq!(template_content(&mission, "shared"), vec!["mission"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mission_inherits_from_base_by_path_and_id() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_mission(
+            dir.path(),
+            "common.yaml",
+            "common",
+            &[],
+            &[("shared", "base")],
+        )?;
+        write_mission(
+            dir.path(),
+            "extra-file.yaml",
+            "extra",
+            &[],
+            &[("extra", "more")],
+        )?;
+        let child = write_mission(
+            dir.path(),
+            "child.yaml",
+            "child",
+            &["common.yaml", "extra"],
+            &[("own", "child")],
+        )?;
+
+        let (mission, _) = load_mission_from_file(child)?;
+        let template_ids: Vec<&str> = mission.templates.iter().map(|t| t.id.as_str()).collect();
+        let rule_ids: Vec<&str> = mission.rules.iter().map(|r| r.id.as_str()).collect();
+
+        assert_eq!(mission.id, "child");
+        assert_eq!(template_ids, vec!["shared", "extra", "own"]);
+        assert_eq!(rule_ids, vec!["shared-rule", "extra-rule", "own-rule"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_child_mission_overrides_base_by_id() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_mission(
+            dir.path(),
+            "base.yaml",
+            "base",
+            &[],
+            &[("shared", "base content"), ("base-only", "kept")],
+        )?;
+        let child = write_mission(
+            dir.path(),
+            "child.yaml",
+            "child",
+            &["base.yaml"],
+            &[("shared", "child content")],
+        )?;
+
+        let (mission, _) = load_mission_from_file(child)?;
+        let shared: Vec<&CasialTemplate> = mission
+            .templates
+            .iter()
+            .filter(|t| t.id == "shared")
+            .collect();
+
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].content, "child content");
+        assert!(mission.templates.iter().any(|t| t.id == "base-only"));
+        assert_eq!(
+            mission
+                .rules
+                .iter()
+                .filter(|r| r.id == "shared-rule")
+                .count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cyclic_mission_include_is_rejected() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_mission(dir.path(), "a.yaml", "a", &["b.yaml"], &[])?;
+        let b = write_mission(dir.path(), "b.yaml", "b", &["a.yaml"], &[])?;
+
+        let error = load_mission_from_file(b).expect_err("cycle must be rejected");
+        assert!(format!("{:#}", error).contains("Cyclic mission include"));
+
+        Ok(())
+    }
+
+    fn write_yaml_mission(templates: &str, rules: &str) -> Result<NamedTempFile> {
+        let mut temp_file = NamedTempFile::new()?;
+        writeln!(
+            temp_file,
+            r#"
+id: warned
+name: Warned
+description: Mission with soft issues
+templates: {templates}
+rules: {rules}
+perceptions: []
+budgets:
+  global_char_limit: 1000
+  per_tool_limits: {{}}
+  perception_quotas: {{}}
+  paradox_overhead: 0.1
+created_at: "2025-01-01T00:00:00Z"
+updated_at: "2025-01-01T00:00:00Z"
+"#
+        )?;
+        Ok(temp_file)
+    }
+
+    #[test]
+    fn test_disabled_rule_produces_warning() -> Result<()> {
+        let rules = r#"[{id: dormant, name: Dormant, enabled: false, conditions: {tool_patterns: [], environment_vars: {}, file_signals: [], perception_states: [], min_confidence: null}, actions: {template_ids: [], transform_type: Prepend, target_field: null, char_limit: null, perception_lock: false}, perception_scope: [], paradox_handling: Ignore}]"#;
+        let temp_file = write_yaml_mission("[]", rules)?;
+
+        let (_, warnings) = load_mission_from_file(temp_file.path())?;
+
+        assert_eq!(
+            warnings,
+            vec![MissionWarning::DisabledRule {
+                rule_id: "dormant".to_string()
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_template_content_produces_warning() -> Result<()> {
+        let templates = r#"[{id: blank, name: Blank, description: "", categories: [], priority: 5, enabled: true, content: "  ", perception_affinity: [], paradox_resistance: 0.5, metadata: {}}]"#;
+        let temp_file = write_yaml_mission(templates, "[]")?;
+
+        let (_, warnings) = load_mission_from_file(temp_file.path())?;
+
+        assert_eq!(
+            warnings,
+            vec![MissionWarning::EmptyTemplateContent {
+                template_id: "blank".to_string()
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "template 'blank' has empty content"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,198 @@
+//! # Mission Diff
+//!
+//! Semantic comparison of two mission versions: templates, rules and perceptions are
+//! matched by id and changed items report which fields differ.
+
+use anyhow::Result;
+use casial_core::CasialMission;
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::BTreeSet, fmt};
+
+/// Differences between two missions, grouped by section
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MissionDiff {
+    pub templates: SectionDiff,
+    pub rules: SectionDiff,
+    pub perceptions: SectionDiff,
+}
+
+/// Items added, removed or changed within one mission section
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SectionDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<ItemChange>,
+}
+
+/// An item present in both missions whose definition differs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemChange {
+    pub id: String,
+    /// Dotted paths of the differing fields, e.g. `conditions.tool_patterns`
+    pub fields: Vec<String>,
+}
+
+impl MissionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty() && self.rules.is_empty() && self.perceptions.is_empty()
+    }
+}
+
+impl SectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two missions by template, rule and perception id
+pub fn diff_missions(old: &CasialMission, new: &CasialMission) -> Result<MissionDiff> {
+    Ok(MissionDiff {
+        templates: diff_section(&old.templates, &new.templates, |t| t.id.clone())?,
+        rules: diff_section(&old.rules, &new.rules, |r| r.id.clone())?,
+        perceptions: diff_section(&old.perceptions, &new.perceptions, |p| {
+            serde_json::to_value(p.id)
+                .ok()
+                .and_then(|id| id.as_str().map(str::to_string))
+                .unwrap_or_default()
+        })?,
+    })
+}
+
+fn diff_section<T: Serialize>(
+    old: &[T],
+    new: &[T],
+    id_of: impl Fn(&T) -> String,
+) -> Result<SectionDiff> {
+    let old_items = old
+        .iter()
+        .map(|item| Ok((id_of(item), serde_json::to_value(item)?)))
+        .collect::<Result<Vec<(String, Value)>>>()?;
+    let new_items = new
+        .iter()
+        .map(|item| Ok((id_of(item), serde_json::to_value(item)?)))
+        .collect::<Result<Vec<(String, Value)>>>()?;
+
+    let mut section = SectionDiff::default();
+    for (id, new_value) in &new_items {
+        match old_items.iter().find(|(old_id, _)| old_id == id) {
+            Some((_, old_value)) => {
+                let mut fields = Vec::new();
+                changed_fields("", old_value, new_value, &mut fields);
+                if !fields.is_empty() {
+                    section.changed.push(ItemChange {
+                        id: id.clone(),
+                        fields,
+                    });
+                }
+            }
+            None => section.added.push(id.clone()),
+        }
+    }
+    section.removed = old_items
+        .iter()
+        .filter(|(id, _)| !new_items.iter().any(|(new_id, _)| new_id == id))
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    Ok(section)
+}
+
+/// Collect paths where two values differ, descending into objects but not arrays
+fn changed_fields(path: &str, old: &Value, new: &Value, fields: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let keys: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).collect();
+            for key in keys {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match (old_map.get(key), new_map.get(key)) {
+                    (Some(old_value), Some(new_value)) => {
+                        changed_fields(&field, old_value, new_value, fields)
+                    }
+                    _ => fields.push(field),
+                }
+            }
+        }
+        _ if old != new => fields.push(path.to_string()),
+        _ => {}
+    }
+}
+
+impl fmt::Display for MissionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No semantic changes");
+        }
+
+        for (title, section) in [
+            ("Templates", &self.templates),
+            ("Rules", &self.rules),
+            ("Perceptions", &self.perceptions),
+        ] {
+            if section.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for id in &section.added {
+                writeln!(f, "  + {}", id)?;
+            }
+            for id in &section.removed {
+                writeln!(f, "  - {}", id)?;
+            }
+            for change in &section.changed {
+                writeln!(f, "  ~ {} ({})", change.id, change.fields.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{self, mission_json, rule_json, template_json};
+
+    fn mission(guide_content: &str, tool_patterns: &[&str], extra_template: bool) -> CasialMission {
+        let mut templates = vec![template_json("guide", 1, guide_content)];
+        if extra_template {
+            templates.push(template_json("citations", 1, "Cite every claim."));
+        }
+        test_fixtures::mission(mission_json(
+            "diff",
+            templates,
+            vec![rule_json("search-guide", tool_patterns, &["guide"])],
+        ))
+    }
+
+    #[test]
+    fn test_diff_reports_template_and_rule_condition_changes() {
+        let old = mission("Prefer primary sources.", &["search"], false);
+        let new = mission("Prefer primary sources.", &["search", "crawl"], true);
+
+        let diff = diff_missions(&old, &new).unwrap();
+
+        assert_eq!(diff.templates.added, vec!["citations".to_string()]);
+        assert!(diff.templates.removed.is_empty());
+        assert!(diff.templates.changed.is_empty());
+        assert_eq!(
+            diff.rules.changed,
+            vec![ItemChange {
+                id: "search-guide".to_string(),
+                fields: vec!["conditions.tool_patterns".to_string()],
+            }]
+        );
+        assert!(diff.perceptions.is_empty());
+
+        let text = diff.to_string();
+        assert!(text.contains("  + citations"));
+        assert!(text.contains("  ~ search-guide (conditions.tool_patterns)"));
+
+        let reverse = diff_missions(&new, &old).unwrap();
+        assert_eq!(reverse.templates.removed, vec!["citations".to_string()]);
+        assert!(diff_missions(&old, &old).unwrap().is_empty());
+    }
+}
@@ -8,6 +8,7 @@ use anyhow::Result;
 use chrono::{Local, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use tracing::{debug, info};
 
 /// Configuration for the pitfall avoidance shim
@@ -27,6 +28,21 @@ pub struct ShimConfig {
 
     /// Additional QoL features
     pub features: ShimFeatures,
+
+    /// Per-tool override of where the shim context goes (tools not listed use arguments)
+    #[serde(default)]
+    pub injection_targets: HashMap<String, InjectionTarget>,
+}
+
+/// Where the shim context is injected for a tool call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionTarget {
+    /// Add a `_shim_context` field to the tool arguments
+    #[default]
+    Arguments,
+    /// Prepend (or extend) a system message in the arguments' `messages` array
+    SystemMessage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +71,7 @@ impl Default for ShimConfig {
             timestamp_returns: true,
             custom_extension: None,
             features: ShimFeatures::default(),
+            injection_targets: HashMap::new(),
         }
     }
 }
@@ -96,6 +113,14 @@ impl PitfallAvoidanceShim {
         self.config.enabled
     }
 
+    /// Whether tools/call params ask to skip augmentation for that one call, with
+    /// `_meta.bypassShim` or a top-level `bypass_shim`
+    pub fn bypass_requested(params: &Value) -> bool {
+        let flag = |value: Option<&Value>| value.and_then(Value::as_bool).unwrap_or(false);
+        flag(params.get("_meta").and_then(|meta| meta.get("bypassShim")))
+            || flag(params.get("bypass_shim"))
+    }
+
     /// Augment tool request with pitfall avoidance context
     pub fn augment_request(
         &self,
@@ -210,11 +235,23 @@ impl PitfallAvoidanceShim {
             shim_context.insert("custom_extension".to_string(), json!(extension));
         }
 
-        // Inject shim context
-        augmented.insert("_shim_context".to_string(), json!(shim_context));
-
         info!("Request augmented with {} shim fields", shim_context.len());
 
+        let target = self
+            .config
+            .injection_targets
+            .get(tool_name)
+            .copied()
+            .unwrap_or_default();
+        match augmented.get_mut("messages").and_then(Value::as_array_mut) {
+            Some(messages) if target == InjectionTarget::SystemMessage => {
+                inject_system_message(messages, &shim_context)?;
+            }
+            _ => {
+                augmented.insert("_shim_context".to_string(), json!(shim_context));
+            }
+        }
+
         Ok(json!(augmented))
     }
 
@@ -356,6 +393,30 @@ impl PitfallAvoidanceShim {
     }
 }
 
+/// Put the shim context in the leading system message, creating one if the chat has none
+fn inject_system_message(
+    messages: &mut Vec<Value>,
+    shim_context: &serde_json::Map<String, Value>,
+) -> Result<()> {
+    let context = format!(
+        "Shim context (use as ground truth for dates and environment):\n{}",
+        serde_json::to_string_pretty(shim_context)?
+    );
+
+    let leading_system = messages
+        .first_mut()
+        .filter(|message| message["role"] == "system")
+        .and_then(|message| message.get_mut("content"));
+    match leading_system {
+        Some(Value::String(content)) => {
+            content.push_str("\n\n");
+            content.push_str(&context);
+        }
+        _ => messages.insert(0, json!({ "role": "system", "content": context })),
+    }
+    Ok(())
+}
+
 impl Default for PitfallAvoidanceShim {
     fn default() -> Self {
         Self::new(ShimConfig::default())
@@ -408,6 +469,54 @@ mod tests {
         assert!(augmented["query"].is_string());
     }
 
+    #[test]
+    fn test_chat_tool_gets_shim_context_as_system_message() {
+        let mut config = ShimConfig::default();
+        config.injection_targets.insert(
+            "chat_completion".to_string(),
+            InjectionTarget::SystemMessage,
+        );
+        let shim = PitfallAvoidanceShim::new(config);
+        let original = json!({
+            "model": "small",
+            "messages": [{ "role": "user", "content": "What happened this week?" }]
+        });
+
+        let augmented = shim
+            .augment_request("chat_completion", &original, None)
+            .unwrap();
+
+        assert!(augmented.get("_shim_context").is_none());
+        assert_eq!(augmented["model"], "small");
+        let messages = augmented["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["role"], "system");
+        assert!(messages[0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("current_date"));
+        assert_eq!(messages[1], original["messages"][0]);
+
+        // An existing system prompt is extended rather than duplicated
+        let with_system = json!({
+            "messages": [
+                { "role": "system", "content": "Be brief." },
+                { "role": "user", "content": "Hi" }
+            ]
+        });
+        let augmented = shim
+            .augment_request("chat_completion", &with_system, None)
+            .unwrap();
+        let messages = augmented["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        let system = messages[0]["content"].as_str().unwrap();
+        assert!(system.starts_with("Be brief.\n\nShim context"));
+
+        // Tools without an override keep argument injection
+        let augmented = shim.augment_request("other_chat", &original, None).unwrap();
+        assert!(augmented["_shim_context"].is_object());
+    }
+
     #[test]
     fn test_process_response() {
         let shim = PitfallAvoidanceShim::new(ShimConfig::default());
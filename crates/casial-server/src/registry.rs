@@ -37,6 +37,16 @@ pub enum ToolSource {
     },
 }
 
+impl ToolSource {
+    /// `local`, or the id of the federated server the tool comes from
+    pub fn label(&self) -> &str {
+        match self {
+            ToolSource::Local => "local",
+            ToolSource::Federated { server_id, .. } => server_id,
+        }
+    }
+}
+
 /// Tool registry for managing local and federated tools
 #[derive(Clone)]
 pub struct ToolRegistry {
@@ -213,6 +223,15 @@ impl ToolRegistry {
         tools_to_remove
     }
 
+    /// Fill keys missing from `arguments` with the `default`s declared in the tool's input schema
+    ///
+    /// Nested object properties are filled as well; provided values are never replaced.
+    pub fn apply_schema_defaults(&self, tool_name: &str, arguments: &mut serde_json::Value) {
+        if let Some(tool) = self.get_tool(tool_name) {
+            fill_schema_defaults(&tool.input_schema, arguments);
+        }
+    }
+
     /// Validate tool arguments against schema
     pub async fn validate_tool_arguments(
         &self,
@@ -362,7 +381,7 @@ impl ToolRegistry {
                     "properties": {
                         "target_server": {
                             "type": "string", 
-                            "description": "URL of the target MCP server to proxy to"
+                            "description": "Id or URL of the configured downstream MCP server to proxy to"
                         },
                         "tool_name": {
                             "type": "string",
@@ -416,6 +435,11 @@ impl ToolRegistry {
                                     "description": "Livecrawling preference for fresh content"
                                 }
                             }
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Return the augmented params and resolved target without calling the downstream server",
+                            "default": false
                         }
                     },
                     "required": ["target_server", "tool_name", "original_params"]
@@ -536,6 +560,157 @@ impl ToolRegistry {
                 last_updated: Utc::now(),
                 metadata: serde_json::json!({"category": "research", "consciousness_aware": true}),
             },
+            ToolSpec {
+                name: "deep_researcher_start".to_string(),
+                description: "Start a multi-stage research task (planner, websets, crawlers, synthesizer, verifier) and return its task id. Poll it with deep_researcher_poll.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "instructions": {"type": "string", "description": "What to research"},
+                        "model": {"type": "string", "enum": ["exa-research", "exa-research-pro"], "default": "exa-research"}
+                    },
+                    "required": ["instructions"]
+                }),
+                output_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "task_id": {"type": "string"},
+                        "status": {"type": "string"},
+                        "next_step": {"type": "string"}
+                    }
+                })),
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({"category": "research", "consciousness_aware": true}),
+            },
+            ToolSpec {
+                name: "deep_researcher_poll".to_string(),
+                description: "Advance a research task started with deep_researcher_start by one stage and return its progress and partial results. The completed task's report stays available for five minutes.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "task_id": {"type": "string", "description": "Id returned by deep_researcher_start"}
+                    },
+                    "required": ["task_id"]
+                }),
+                output_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "status": {"type": "string", "enum": ["running", "completed"]},
+                        "progress": {"type": "number"},
+                        "current_stage": {"type": "string"},
+                        "partial_results": {"type": "array"},
+                        "report": {"type": "object"}
+                    }
+                })),
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({"category": "research", "consciousness_aware": true}),
+            },
+            ToolSpec {
+                name: "casial_validate_mission".to_string(),
+                description: "Check a mission definition without loading it. Returns errors that would stop it loading (unparseable JSON, invalid redaction patterns) and warnings such as rules referencing unknown templates.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "mission": {
+                            "type": ["object", "string"],
+                            "description": "Mission definition as an object or JSON string"
+                        }
+                    },
+                    "required": ["mission"]
+                }),
+                output_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "valid": {"type": "boolean"},
+                        "errors": {"type": "array", "items": {"type": "string"}},
+                        "warnings": {"type": "array", "items": {"type": "string"}}
+                    }
+                })),
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({"category": "missions"}),
+            },
+            ToolSpec {
+                name: "casial_preview_coordination".to_string(),
+                description: "Preview how a mission would coordinate one tool call. Runs in a throwaway engine, so the server's missions and history are untouched.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "mission": {
+                            "type": ["object", "string"],
+                            "description": "Mission definition as an object or JSON string"
+                        },
+                        "tool_name": {"type": "string", "description": "Tool the call would target"},
+                        "tool_args": {"type": "object", "description": "Arguments of the previewed call"},
+                        "environment": {
+                            "type": "object",
+                            "additionalProperties": {"type": "string"},
+                            "description": "Environment variables the mission's rules see; the server's own are never exposed"
+                        }
+                    },
+                    "required": ["mission", "tool_name"]
+                }),
+                output_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "applied": {"type": "boolean"},
+                        "injected_content": {"type": "string"},
+                        "activated_rules": {"type": "array", "items": {"type": "string"}},
+                        "used_templates": {"type": "array", "items": {"type": "string"}}
+                    }
+                })),
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({"category": "missions"}),
+            },
+            ToolSpec {
+                name: "casial_get_shim_config".to_string(),
+                description: "Read the pitfall avoidance shim configuration, as GET /debug/shim does. Requires the server's admin token.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "admin_token": {"type": "string", "description": "The server's MOP_ADMIN_TOKEN"}
+                    },
+                    "required": ["admin_token"]
+                }),
+                output_schema: None,
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({"category": "admin"}),
+            },
+            ToolSpec {
+                name: "casial_update_shim_config".to_string(),
+                description: "Replace the pitfall avoidance shim configuration, as POST /debug/shim does, and return the new configuration. Requires the server's admin token.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "admin_token": {"type": "string", "description": "The server's MOP_ADMIN_TOKEN"},
+                        "config": {
+                            "type": "object",
+                            "description": "Complete shim configuration, in the shape casial_get_shim_config returns"
+                        }
+                    },
+                    "required": ["admin_token", "config"]
+                }),
+                output_schema: None,
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({"category": "admin"}),
+            },
             // Add more built-in tools...
         ];
 
@@ -577,6 +752,26 @@ impl Default for ToolRegistry {
     }
 }
 
+fn fill_schema_defaults(schema: &serde_json::Value, value: &mut serde_json::Value) {
+    let (Some(properties), Some(object)) = (
+        schema.get("properties").and_then(|p| p.as_object()),
+        value.as_object_mut(),
+    ) else {
+        return;
+    };
+
+    for (key, property) in properties {
+        match object.get_mut(key) {
+            Some(existing) => fill_schema_defaults(property, existing),
+            None => {
+                if let Some(default) = property.get("default") {
+                    object.insert(key.clone(), default.clone());
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -587,6 +782,17 @@ mod tests {
         assert_eq!(registry.get_all_tools().len(), 0);
     }
 
+    #[test]
+    fn test_local_tools_include_the_research_tools() {
+        let registry = ToolRegistry::new();
+        registry.seed_with_local_tools().unwrap();
+
+        for name in ["deep_researcher_start", "deep_researcher_poll"] {
+            let tool = registry.get_tool(name).expect("research tool is listed");
+            assert_eq!(tool.input_schema["required"].as_array().unwrap().len(), 1);
+        }
+    }
+
     #[tokio::test]
     async fn test_tool_registration() {
         let registry = ToolRegistry::new();
@@ -649,6 +855,47 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_schema_defaults_fill_only_missing_fields() {
+        let registry = ToolRegistry::new();
+        registry
+            .register_tool_sync(ToolSpec {
+                name: "search".to_string(),
+                description: "Search with defaults".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {"type": "string"},
+                        "numResults": {"type": "integer", "default": 5},
+                        "options": {
+                            "type": "object",
+                            "properties": {"livecrawl": {"type": "string", "default": "fallback"}}
+                        }
+                    }
+                }),
+                output_schema: None,
+                source: ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({}),
+            })
+            .unwrap();
+
+        let mut omitted = serde_json::json!({"query": "rust", "options": {}});
+        registry.apply_schema_defaults("search", &mut omitted);
+        assert_eq!(omitted["numResults"], serde_json::json!(5));
+        assert_eq!(
+            omitted["options"]["livecrawl"],
+            serde_json::json!("fallback")
+        );
+
+        let mut provided = serde_json::json!({"query": "rust", "numResults": 20});
+        registry.apply_schema_defaults("search", &mut provided);
+        assert_eq!(provided["numResults"], serde_json::json!(20));
+        assert!(provided.get("options").is_none());
+    }
+
     #[tokio::test]
     async fn test_catalog_generation() {
         let registry = ToolRegistry::new();
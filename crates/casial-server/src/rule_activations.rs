@@ -0,0 +1,131 @@
+//! # Rule Activations
+//!
+//! When each rule last activated, per session, so `cooldown_secs` holds across tool calls
+//! on both the HTTP and WebSocket transports.
+
+use ahash::AHashMap;
+use anyhow::Result;
+use casial_core::{CasialEngine, CoordinationRequest, CoordinationResult};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+#[derive(Default)]
+struct SessionActivations {
+    by_rule: AHashMap<String, DateTime<Utc>>,
+    last_used: Option<DateTime<Utc>>,
+}
+
+/// Rule activations by session id
+#[derive(Default)]
+pub struct RuleActivations {
+    sessions: DashMap<String, Arc<Mutex<SessionActivations>>>,
+}
+
+impl RuleActivations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Coordinate a request with the session's activations and record the rules it activates
+    ///
+    /// Calls in the same session coordinate one at a time, so two concurrent calls can't both
+    /// activate a rule that is cooling down.
+    pub fn coordinate(
+        &self,
+        engine: &CasialEngine,
+        session_id: &str,
+        mut request: CoordinationRequest,
+    ) -> Result<CoordinationResult> {
+        let session = Arc::clone(
+            self.sessions
+                .entry(session_id.to_string())
+                .or_default()
+                .value(),
+        );
+        let mut session = session.lock();
+        request.rule_activations = session.by_rule.clone();
+        let result = engine.coordinate(request)?;
+
+        let now = Utc::now();
+        for rule_id in &result.activated_rules {
+            session.by_rule.insert(rule_id.clone(), now);
+        }
+        session.last_used = Some(now);
+        Ok(result)
+    }
+
+    /// Forget a closed session
+    pub fn remove_session(&self, session_id: &str) {
+        self.sessions.remove(session_id);
+    }
+
+    /// Forget sessions that haven't coordinated for longer than `idle_timeout`
+    pub fn reap(&self, idle_timeout: chrono::Duration) {
+        let cutoff = Utc::now() - idle_timeout;
+        self.sessions.retain(|_, session| {
+            session.try_lock().map_or(true, |session| {
+                session.last_used.map_or(true, |at| at > cutoff)
+            })
+        });
+    }
+
+    #[cfg(test)]
+    pub fn record(&self, session_id: &str, rule_id: &str, at: DateTime<Utc>) {
+        self.sessions
+            .entry(session_id.to_string())
+            .or_default()
+            .lock()
+            .by_rule
+            .insert(rule_id.to_string(), at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{mission, mission_json, rule_json, template_json};
+
+    #[test]
+    fn concurrent_calls_in_a_session_activate_a_cooling_rule_once() {
+        let engine = CasialEngine::new();
+        let mut rule = rule_json("guide-rule", &["search"], &["guide"]);
+        rule["cooldown_secs"] = serde_json::json!(60);
+        engine
+            .load_mission(mission(mission_json(
+                "cooldown",
+                vec![template_json("guide", 1, "Cite sources.")],
+                vec![rule],
+            )))
+            .unwrap();
+        let activations = RuleActivations::new();
+
+        let activated: usize = std::thread::scope(|scope| {
+            let calls: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let request = CoordinationRequest::new(
+                            "web_search",
+                            serde_json::json!({ "query": "rust" }),
+                        );
+                        activations
+                            .coordinate(&engine, "session-a", request)
+                            .unwrap()
+                            .activated_rules
+                            .len()
+                    })
+                })
+                .collect();
+            calls.into_iter().map(|call| call.join().unwrap()).sum()
+        });
+        assert_eq!(activated, 1);
+
+        // Other sessions keep their own activations
+        let request = CoordinationRequest::new("web_search", serde_json::json!({}));
+        let other = activations
+            .coordinate(&engine, "session-b", request)
+            .unwrap();
+        assert_eq!(other.activated_rules, vec!["guide-rule"]);
+    }
+}
@@ -0,0 +1,202 @@
+//! # Session Storage
+//!
+//! Storage for HTTP MCP sessions keyed by `mcp-session-id`. The in-memory store is the
+//! default; the `redis` feature adds a shared store so any instance behind a load
+//! balancer can serve any session.
+
+use crate::http_mcp::SessionData;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::DashMap;
+use std::time::Duration;
+
+/// Backend holding HTTP MCP sessions
+#[async_trait]
+pub trait SessionStore: Send + Sync {
+    async fn get(&self, id: &str) -> Result<Option<SessionData>>;
+
+    /// Fetch a session and mark it accessed now
+    async fn touch(&self, id: &str) -> Result<Option<SessionData>>;
+
+    async fn insert(&self, session: SessionData) -> Result<()>;
+
+    /// Remove a session, returning whether it existed
+    async fn remove(&self, id: &str) -> Result<bool>;
+
+    /// Drop sessions idle for longer than `idle_timeout`, returning how many were removed
+    async fn reap(&self, idle_timeout: Duration) -> Result<usize>;
+}
+
+/// Process-local sessions (the default)
+#[derive(Default)]
+pub struct InMemorySessionStore {
+    sessions: DashMap<String, SessionData>,
+}
+
+impl InMemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn get(&self, id: &str) -> Result<Option<SessionData>> {
+        Ok(self.sessions.get(id).map(|entry| entry.value().clone()))
+    }
+
+    async fn touch(&self, id: &str) -> Result<Option<SessionData>> {
+        Ok(self.sessions.get_mut(id).map(|mut entry| {
+            entry.last_accessed = Utc::now();
+            entry.value().clone()
+        }))
+    }
+
+    async fn insert(&self, session: SessionData) -> Result<()> {
+        self.sessions.insert(session.id.clone(), session);
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<bool> {
+        Ok(self.sessions.remove(id).is_some())
+    }
+
+    async fn reap(&self, idle_timeout: Duration) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(idle_timeout)?;
+        let before = self.sessions.len();
+        self.sessions
+            .retain(|_, session| session.last_accessed > cutoff);
+        Ok(before - self.sessions.len())
+    }
+}
+
+#[cfg(feature = "redis")]
+pub use redis_store::RedisSessionStore;
+
+#[cfg(feature = "redis")]
+mod redis_store {
+    use super::*;
+    use anyhow::Context;
+    use redis::AsyncCommands;
+
+    const KEY_PREFIX: &str = "mop:session:";
+
+    /// Sessions shared through Redis, expiring after the idle timeout
+    pub struct RedisSessionStore {
+        connection: redis::aio::MultiplexedConnection,
+        idle_timeout: Duration,
+    }
+
+    impl RedisSessionStore {
+        pub async fn connect(url: &str, idle_timeout: Duration) -> Result<Self> {
+            let client = redis::Client::open(url).context("Invalid Redis URL")?;
+            let connection = client
+                .get_multiplexed_async_connection()
+                .await
+                .context("Failed to connect to Redis")?;
+            Ok(Self {
+                connection,
+                idle_timeout,
+            })
+        }
+
+        fn key(id: &str) -> String {
+            format!("{}{}", KEY_PREFIX, id)
+        }
+
+        async fn write(&self, session: &SessionData) -> Result<()> {
+            let mut connection = self.connection.clone();
+            let _: () = connection
+                .set_ex(
+                    Self::key(&session.id),
+                    serde_json::to_string(session)?,
+                    self.idle_timeout.as_secs().max(1),
+                )
+                .await?;
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl SessionStore for RedisSessionStore {
+        async fn get(&self, id: &str) -> Result<Option<SessionData>> {
+            let mut connection = self.connection.clone();
+            let value: Option<String> = connection.get(Self::key(id)).await?;
+            value
+                .map(|json| serde_json::from_str(&json).map_err(Into::into))
+                .transpose()
+        }
+
+        async fn touch(&self, id: &str) -> Result<Option<SessionData>> {
+            let Some(mut session) = self.get(id).await? else {
+                return Ok(None);
+            };
+            session.last_accessed = Utc::now();
+            self.write(&session).await?;
+            Ok(Some(session))
+        }
+
+        async fn insert(&self, session: SessionData) -> Result<()> {
+            self.write(&session).await
+        }
+
+        async fn remove(&self, id: &str) -> Result<bool> {
+            let mut connection = self.connection.clone();
+            let removed: usize = connection.del(Self::key(id)).await?;
+            Ok(removed > 0)
+        }
+
+        /// Redis expires idle sessions itself through each key's TTL
+        async fn reap(&self, _idle_timeout: Duration) -> Result<usize> {
+            Ok(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_mcp::SessionConfig;
+
+    fn session(id: &str, idle: chrono::Duration) -> SessionData {
+        let at = Utc::now() - idle;
+        SessionData {
+            id: id.to_string(),
+            config: SessionConfig::default(),
+            created_at: at,
+            last_accessed: at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_get_insert_remove_and_reap() {
+        let store = InMemorySessionStore::new();
+        store
+            .insert(session("fresh", chrono::Duration::zero()))
+            .await
+            .unwrap();
+        store
+            .insert(session("stale", chrono::Duration::hours(2)))
+            .await
+            .unwrap();
+
+        assert_eq!(store.get("fresh").await.unwrap().unwrap().id, "fresh");
+        assert!(store.get("missing").await.unwrap().is_none());
+
+        // Touching moves the stale session's last access forward, so it survives the reap
+        let touched = store.touch("stale").await.unwrap().unwrap();
+        assert!(touched.last_accessed > touched.created_at);
+        store
+            .insert(session("idle", chrono::Duration::hours(2)))
+            .await
+            .unwrap();
+        assert_eq!(store.reap(Duration::from_secs(3600)).await.unwrap(), 1);
+        assert!(store.get("idle").await.unwrap().is_none());
+        assert!(store.get("stale").await.unwrap().is_some());
+
+        assert!(store.remove("fresh").await.unwrap());
+        assert!(!store.remove("fresh").await.unwrap());
+        assert!(store.touch("fresh").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,263 @@
+//! # SSE Session Channels
+//!
+//! Per-session buffers for events pushed to HTTP clients over their GET SSE stream.
+//! Producers never wait on a slow client: a full buffer drops its oldest event or rejects
+//! the new one, and the client is told how many events it missed with a
+//! `notifications/message` warning, as WebSocket sessions report backpressure.
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::Notify;
+
+use crate::config::{SseBufferSettings, SseOverflowPolicy};
+
+/// A push refused because the session's buffer is full under [`SseOverflowPolicy::Reject`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("SSE buffer is full")]
+pub struct SseBufferFull;
+
+#[derive(Debug, Default)]
+struct SseBuffer {
+    events: VecDeque<Value>,
+    /// Events lost since the client was last warned
+    dropped: u64,
+}
+
+/// Bounded event buffer feeding one session's SSE stream
+#[derive(Debug)]
+pub struct SseChannel {
+    buffer: Mutex<SseBuffer>,
+    capacity: usize,
+    overflow: SseOverflowPolicy,
+    ready: Notify,
+    closed: AtomicBool,
+}
+
+impl SseChannel {
+    pub fn new(settings: &SseBufferSettings) -> Self {
+        Self {
+            buffer: Mutex::new(SseBuffer::default()),
+            capacity: settings.capacity.max(1),
+            overflow: settings.overflow,
+            ready: Notify::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Queue a JSON-RPC message for the client without waiting for it to drain
+    pub fn push(&self, message: Value) -> Result<(), SseBufferFull> {
+        {
+            let mut buffer = self.buffer.lock();
+            if buffer.events.len() >= self.capacity {
+                buffer.dropped += 1;
+                match self.overflow {
+                    SseOverflowPolicy::DropOldest => {
+                        buffer.events.pop_front();
+                    }
+                    SseOverflowPolicy::Reject => {
+                        drop(buffer);
+                        self.ready.notify_one();
+                        return Err(SseBufferFull);
+                    }
+                }
+            }
+            buffer.events.push_back(message);
+        }
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    /// Next message for the stream, leading with a drop warning when events were lost
+    ///
+    /// Returns `None` once the channel is closed and drained.
+    pub async fn recv(&self) -> Option<Value> {
+        loop {
+            if let Some(message) = self.try_recv() {
+                return Some(message);
+            }
+            if self.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    fn try_recv(&self) -> Option<Value> {
+        let mut buffer = self.buffer.lock();
+        if buffer.dropped > 0 {
+            let dropped = std::mem::take(&mut buffer.dropped);
+            return Some(self.drop_warning(dropped));
+        }
+        buffer.events.pop_front()
+    }
+
+    /// End the stream once buffered events are delivered
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.ready.notify_one();
+    }
+
+    fn drop_warning(&self, dropped: u64) -> Value {
+        let policy = match self.overflow {
+            SseOverflowPolicy::DropOldest => "drop_oldest",
+            SseOverflowPolicy::Reject => "reject",
+        };
+        json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": {
+                "level": "warning",
+                "logger": "casial-sse",
+                "data": {
+                    "message": format!("Dropped {} events; the SSE stream is not being drained", dropped),
+                    "reason": "backpressure",
+                    "dropped": dropped,
+                    "policy": policy
+                }
+            }
+        })
+    }
+}
+
+/// Open SSE channels by `mcp-session-id`
+#[derive(Debug, Default)]
+pub struct SseChannels {
+    channels: DashMap<String, Arc<SseChannel>>,
+}
+
+impl SseChannels {
+    /// Open the session's channel, closing any stream it had before
+    pub fn open(
+        self: &Arc<Self>,
+        session_id: &str,
+        settings: &SseBufferSettings,
+    ) -> SseSubscription {
+        let channel = Arc::new(SseChannel::new(settings));
+        if let Some(previous) = self
+            .channels
+            .insert(session_id.to_string(), Arc::clone(&channel))
+        {
+            previous.close();
+        }
+        SseSubscription {
+            channels: Arc::clone(self),
+            session_id: session_id.to_string(),
+            channel,
+        }
+    }
+
+    /// The session's open channel, if it has a stream
+    pub fn get(&self, session_id: &str) -> Option<Arc<SseChannel>> {
+        self.channels
+            .get(session_id)
+            .map(|entry| Arc::clone(entry.value()))
+    }
+
+    /// Close and forget the session's channel
+    pub fn close(&self, session_id: &str) {
+        if let Some((_, channel)) = self.channels.remove(session_id) {
+            channel.close();
+        }
+    }
+}
+
+/// A session's end of its channel; unregisters it when the stream is dropped
+pub struct SseSubscription {
+    channels: Arc<SseChannels>,
+    session_id: String,
+    channel: Arc<SseChannel>,
+}
+
+impl SseSubscription {
+    pub async fn recv(&self) -> Option<Value> {
+        self.channel.recv().await
+    }
+}
+
+impl Drop for SseSubscription {
+    fn drop(&mut self) {
+        // A reconnect may already have replaced this channel
+        self.channels
+            .channels
+            .remove_if(&self.session_id, |_, channel| {
+                Arc::ptr_eq(channel, &self.channel)
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn settings(capacity: usize, overflow: SseOverflowPolicy) -> SseBufferSettings {
+        SseBufferSettings { capacity, overflow }
+    }
+
+    async fn recv(subscription: &SseSubscription) -> Value {
+        tokio::time::timeout(Duration::from_secs(1), subscription.recv())
+            .await
+            .expect("buffered event")
+            .expect("channel open")
+    }
+
+    #[tokio::test]
+    async fn full_buffer_drops_oldest_and_warns() {
+        let channels = Arc::new(SseChannels::default());
+        let subscription = channels.open("mop-1", &settings(2, SseOverflowPolicy::DropOldest));
+        let channel = channels.get("mop-1").unwrap();
+
+        // Nobody is reading, so every push has to return straight away
+        tokio::time::timeout(Duration::from_secs(1), async {
+            for n in 0..5 {
+                channel.push(json!({ "n": n })).unwrap();
+            }
+        })
+        .await
+        .expect("producer never blocks");
+
+        let warning = recv(&subscription).await;
+        assert_eq!(warning["method"], "notifications/message");
+        assert_eq!(warning["params"]["level"], "warning");
+        assert_eq!(warning["params"]["data"]["dropped"], 3);
+        assert_eq!(recv(&subscription).await, json!({ "n": 3 }));
+        assert_eq!(recv(&subscription).await, json!({ "n": 4 }));
+    }
+
+    #[tokio::test]
+    async fn reject_policy_refuses_new_events() {
+        let channels = Arc::new(SseChannels::default());
+        let subscription = channels.open("mop-1", &settings(1, SseOverflowPolicy::Reject));
+        let channel = channels.get("mop-1").unwrap();
+
+        channel.push(json!({ "n": 0 })).unwrap();
+        assert_eq!(channel.push(json!({ "n": 1 })), Err(SseBufferFull));
+
+        let warning = recv(&subscription).await;
+        assert_eq!(warning["params"]["data"]["policy"], "reject");
+        assert_eq!(recv(&subscription).await, json!({ "n": 0 }));
+    }
+
+    #[tokio::test]
+    async fn dropped_subscription_unregisters_only_its_channel() {
+        let channels = Arc::new(SseChannels::default());
+        let first = channels.open("mop-1", &SseBufferSettings::default());
+        let second = channels.open("mop-1", &SseBufferSettings::default());
+
+        // The replaced stream ends; the reconnect keeps the session's channel
+        assert!(first.recv().await.is_none());
+        drop(first);
+        assert!(channels.get("mop-1").is_some());
+
+        drop(second);
+        assert!(channels.get("mop-1").is_none());
+    }
+}
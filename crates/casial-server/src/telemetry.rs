@@ -0,0 +1,127 @@
+//! # OpenTelemetry Export
+//!
+//! OTLP/HTTP export of tracing spans and server metrics, compiled in with the `otlp`
+//! feature and switched on by `start --otlp-endpoint`.
+
+use crate::federation::FederationMetrics;
+use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
+use opentelemetry::{global, metrics::Gauge, trace::TracerProvider as _};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{metrics::SdkMeterProvider, trace::SdkTracerProvider, Resource};
+use tracing_subscriber::{registry::LookupSpan, Layer};
+
+const SERVICE_NAME: &str = "casial-server";
+
+/// Tracer and meter providers exporting to an OTLP collector
+pub struct OtlpTelemetry {
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+}
+
+impl OtlpTelemetry {
+    /// Build exporters for a collector base URL (e.g. `http://localhost:4318`)
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let endpoint = endpoint.trim_end_matches('/');
+        let resource = Resource::builder().with_service_name(SERVICE_NAME).build();
+
+        let span_exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/traces", endpoint))
+            .build()
+            .context("Failed to build OTLP span exporter")?;
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(span_exporter)
+            .with_resource(resource.clone())
+            .build();
+
+        let metric_exporter = MetricExporter::builder()
+            .with_http()
+            .with_endpoint(format!("{}/v1/metrics", endpoint))
+            .build()
+            .context("Failed to build OTLP metric exporter")?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_periodic_exporter(metric_exporter)
+            .with_resource(resource)
+            .build();
+
+        Ok(Self {
+            tracer_provider,
+            meter_provider,
+        })
+    }
+
+    /// Build exporters and make the meter provider global so [`record_metrics`] reaches it
+    pub fn install(endpoint: &str) -> Result<Self> {
+        let telemetry = Self::new(endpoint)?;
+        global::set_meter_provider(telemetry.meter_provider.clone());
+        Ok(telemetry)
+    }
+
+    /// Tracing layer forwarding spans to the OTLP tracer
+    pub fn layer<S>(&self) -> impl Layer<S> + Send + Sync
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.tracer_provider.tracer(SERVICE_NAME))
+    }
+
+    /// Flush pending spans and metrics, then stop the exporters
+    pub fn shutdown(&self) -> Result<()> {
+        self.tracer_provider
+            .shutdown()
+            .context("Failed to shut down OTLP tracer")?;
+        self.meter_provider
+            .shutdown()
+            .context("Failed to shut down OTLP meter")?;
+        Ok(())
+    }
+}
+
+/// Gauges mirroring the Prometheus metrics
+struct ServerInstruments {
+    coordination_events: Gauge<u64>,
+    active_sessions: Gauge<u64>,
+    tool_calls_forwarded: Gauge<u64>,
+    federation_errors: Gauge<u64>,
+    federation_retries: Gauge<u64>,
+}
+
+static INSTRUMENTS: Lazy<ServerInstruments> = Lazy::new(|| {
+    let meter = global::meter(SERVICE_NAME);
+    ServerInstruments {
+        coordination_events: meter.u64_gauge("casial.coordination_events").build(),
+        active_sessions: meter.u64_gauge("casial.active_sessions").build(),
+        tool_calls_forwarded: meter
+            .u64_gauge("casial.federation.tool_calls_forwarded")
+            .build(),
+        federation_errors: meter.u64_gauge("casial.federation.errors").build(),
+        federation_retries: meter.u64_gauge("casial.federation.retries").build(),
+    }
+});
+
+/// Record one metrics collection pass on the global meter
+pub fn record_metrics(
+    coordination_events: usize,
+    active_sessions: usize,
+    federation: Option<&FederationMetrics>,
+) {
+    let instruments = &*INSTRUMENTS;
+    instruments
+        .coordination_events
+        .record(coordination_events as u64, &[]);
+    instruments
+        .active_sessions
+        .record(active_sessions as u64, &[]);
+    if let Some(federation) = federation {
+        instruments
+            .tool_calls_forwarded
+            .record(federation.tool_calls_forwarded, &[]);
+        instruments
+            .federation_errors
+            .record(federation.federation_errors, &[]);
+        instruments
+            .federation_retries
+            .record(federation.federation_retries_total, &[]);
+    }
+}
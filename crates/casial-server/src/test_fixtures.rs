@@ -0,0 +1,59 @@
+//! # Test Fixtures
+//!
+//! Mission JSON shared by the server's unit tests. Builders return JSON so a test can
+//! adjust any field before parsing it with [`mission`].
+
+use casial_core::CasialMission;
+use serde_json::{json, Value};
+
+/// An enabled template with no perception affinity
+pub fn template_json(id: &str, priority: u32, content: impl Into<String>) -> Value {
+    json!({
+        "id": id, "name": id, "description": "", "categories": [],
+        "priority": priority, "enabled": true, "content": content.into(),
+        "perception_affinity": [], "paradox_resistance": 0.5, "metadata": {}
+    })
+}
+
+/// An enabled rule prepending `template_ids` to tools matching `tool_patterns`
+pub fn rule_json(id: &str, tool_patterns: &[&str], template_ids: &[&str]) -> Value {
+    json!({
+        "id": id, "name": id, "enabled": true,
+        "conditions": {
+            "tool_patterns": tool_patterns, "environment_vars": {}, "file_signals": [],
+            "perception_states": [], "min_confidence": null
+        },
+        "actions": {
+            "template_ids": template_ids, "transform_type": "Prepend",
+            "target_field": null, "char_limit": null, "perception_lock": false
+        },
+        "perception_scope": [],
+        "paradox_handling": "Ignore"
+    })
+}
+
+/// A mission with a 1000 character budget and no perceptions
+pub fn mission_json(id: &str, templates: Vec<Value>, rules: Vec<Value>) -> Value {
+    json!({
+        "id": id, "name": id, "description": "",
+        "templates": templates,
+        "rules": rules,
+        "perceptions": [],
+        "budgets": {
+            "global_char_limit": 1000, "per_tool_limits": {},
+            "perception_quotas": {}, "paradox_overhead": 0.0
+        },
+        "created_at": "2025-01-01T00:00:00Z",
+        "updated_at": "2025-01-01T00:00:00Z"
+    })
+}
+
+/// Parse mission JSON built by the helpers above
+pub fn mission(value: Value) -> CasialMission {
+    serde_json::from_value(value).expect("valid mission")
+}
+
+/// A mission with no templates or rules
+pub fn empty_mission(id: &str) -> CasialMission {
+    mission(mission_json(id, vec![], vec![]))
+}
@@ -9,8 +9,23 @@ use futures::{sink::SinkExt, stream::StreamExt};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::{mcp, AppState};
-use casial_core::{CoordinationRequest, PerceptionId};
+use crate::{
+    config::InjectionOrder,
+    errors::ErrorCode,
+    executor::{
+        restore_admin_token, strip_injected_fields, take_admin_token, InvalidToolArguments,
+        ProgressReporter,
+    },
+    federation::FederationError,
+    http_mcp::{demo_access_denial, template_ids_arg},
+    large_results::shape_tool_result,
+    mcp,
+    pitfall_shim::PitfallAvoidanceShim,
+    AppState,
+};
+use casial_core::{
+    sanitize_paradox_tolerance, CoordinationRequest, ParadoxStrategy, Perception, PerceptionId,
+};
 
 /// WebSocket session information
 #[derive(Debug, Clone)]
@@ -20,16 +35,22 @@ pub struct WebSocketSession {
     pub message_count: usize,
     pub active_coordination_id: Option<Uuid>,
     pub active_perceptions: Vec<PerceptionId>,
+    /// Perceptions this session registered, unregistered when it closes
+    pub owned_perceptions: Vec<PerceptionId>,
+    /// Connected with the public demo key or none, so tool calls follow `demo_access`
+    pub demo_access: bool,
 }
 
 impl WebSocketSession {
-    fn new() -> Self {
+    pub(crate) fn new(session_id: Uuid) -> Self {
         Self {
-            session_id: Uuid::new_v4(),
+            session_id,
             created_at: Utc::now(),
             message_count: 0,
             active_coordination_id: None,
             active_perceptions: Vec::new(),
+            owned_perceptions: Vec::new(),
+            demo_access: false,
         }
     }
 }
@@ -45,8 +66,11 @@ impl WebSocketHandler {
     }
 
     /// Handle a new WebSocket connection
-    pub async fn handle_connection(self, socket: WebSocket) {
-        let session = WebSocketSession::new();
+    pub async fn handle_connection(self, socket: WebSocket, demo_access: bool) {
+        let session = WebSocketSession {
+            demo_access,
+            ..WebSocketSession::new(self.state.id_source.next_id())
+        };
         let session_id = session.session_id;
 
         info!("🔌 New WebSocket connection: {}", session_id);
@@ -64,11 +88,10 @@ impl WebSocketHandler {
         let (heartbeat_sender, mut heartbeat_receiver) =
             tokio::sync::mpsc::unbounded_channel::<()>();
 
+        let mut heartbeat_interval = self.state.config.ws_heartbeat_timer();
+
         // Spawn writer task with backpressure handling
         let writer_task = tokio::spawn(async move {
-            let mut heartbeat_interval =
-                tokio::time::interval(tokio::time::Duration::from_secs(30));
-
             loop {
                 tokio::select! {
                     // Handle outgoing messages with backpressure
@@ -111,7 +134,10 @@ impl WebSocketHandler {
                 Ok(Message::Text(text)) => {
                     debug!("📨 Received message: {}", text);
 
-                    match self.handle_text_message(&text, session_id).await {
+                    match self
+                        .handle_text_message(&text, session_id, &app_sender)
+                        .await
+                    {
                         Ok(Some(response)) => {
                             // Use bounded channel with backpressure
                             match app_sender.try_send(response) {
@@ -193,33 +219,109 @@ impl WebSocketHandler {
         drop(app_sender); // Close sender to signal writer task to end
         let _ = writer_task.await; // Wait for writer task to complete
 
-        self.state.active_sessions.remove(&session_id);
+        self.close_session(session_id);
         info!("🔌 WebSocket connection ended: {}", session_id);
     }
 
-    /// Handle text messages (JSON-RPC)
-    async fn handle_text_message(&self, text: &str, session_id: Uuid) -> Result<Option<String>> {
+    /// Drop a closed session's state, unregistering the perceptions it registered
+    ///
+    /// A perception another session still has active is handed to that session instead.
+    fn close_session(&self, session_id: Uuid) {
+        let Some((_, session)) = self.state.active_sessions.remove(&session_id) else {
+            return;
+        };
+        for id in session.owned_perceptions {
+            let heir = self
+                .state
+                .active_sessions
+                .iter_mut()
+                .find(|other| other.active_perceptions.contains(&id));
+            match heir {
+                Some(mut heir) => heir.owned_perceptions.push(id),
+                None => {
+                    self.state.casial_engine.unregister_perception(&id);
+                }
+            }
+        }
+        self.state
+            .rule_activations
+            .remove_session(&session_id.to_string());
+    }
+
+    /// Error for a session that would register more than `max_session_perceptions`
+    fn perception_limit_error(&self, id: serde_json::Value) -> mcp::JsonRpcResponse {
+        mcp::create_error_response(
+            id,
+            -32000,
+            "Session perception limit reached",
+            Some(serde_json::json!({
+                "limit": self.state.config.consciousness.max_session_perceptions
+            })),
+        )
+    }
+
+    /// Perceptions this session has registered so far
+    fn owned_perception_count(&self, session_id: Uuid) -> usize {
+        self.state
+            .active_sessions
+            .get(&session_id)
+            .map_or(0, |s| s.owned_perceptions.len())
+    }
+
+    /// Handle text messages (JSON-RPC); `outbound` carries notifications sent before the response
+    async fn handle_text_message(
+        &self,
+        text: &str,
+        session_id: Uuid,
+        outbound: &tokio::sync::mpsc::Sender<String>,
+    ) -> Result<Option<String>> {
         // Parse JSON-RPC request
         let request: mcp::JsonRpcRequest = serde_json::from_str(text)?;
 
         debug!("🔧 Processing JSON-RPC method: {}", request.method);
 
+        // Demo sessions only reach the demo tool allowlist
+        let demo_session = self
+            .state
+            .active_sessions
+            .get(&session_id)
+            .is_some_and(|session| session.demo_access);
+        if demo_session && request.method == "tools/call" {
+            if let Some(denied) =
+                demo_access_denial(&self.state.config.server.demo_access, &request)
+            {
+                warn!("Demo session {} denied a tool call", session_id);
+                return Ok(Some(serde_json::to_string(&denied)?));
+            }
+        }
+
         // Handle different MCP methods
         let response = match request.method.as_str() {
             "initialize" => self.handle_initialize(request).await?,
             "tools/list" => self.handle_tools_list(request).await?,
-            "tools/call" => self.handle_tools_call(request, session_id).await?,
+            "tools/call" => {
+                self.handle_tools_call(request, session_id, outbound)
+                    .await?
+            }
             "resources/list" => self.handle_resources_list(request).await?,
             "resources/read" => self.handle_resources_read(request).await?,
             "casial/debug" => self.handle_casial_debug(request, session_id).await?,
+            "casial/stats" => self.handle_casial_stats(request).await?,
+            "casial/paradox/list" => self.handle_paradox_list(request).await?,
+            "casial/paradox/resolve" => self.handle_paradox_resolve(request).await?,
+            "casial/perception/list" => self.handle_list_perceptions(request, session_id).await?,
             "casial/perception/add" => self.handle_add_perception(request, session_id).await?,
             "casial/perception/remove" => {
                 self.handle_remove_perception(request, session_id).await?
             }
-            _ => mcp::create_error_response(
+            "casial/perception/export" => {
+                self.handle_export_perceptions(request, session_id).await?
+            }
+            "casial/perception/import" => {
+                self.handle_import_perceptions(request, session_id).await?
+            }
+            _ => ErrorCode::MethodNotFound.response(
                 request.id,
-                -32601,
-                "Method not found",
                 Some(serde_json::json!({"method": request.method})),
             ),
         };
@@ -234,8 +336,29 @@ impl WebSocketHandler {
     ) -> Result<mcp::JsonRpcResponse> {
         info!("🤝 MCP initialization requested");
 
+        let supported_versions = &self.state.config.server.supported_protocol_versions;
+        let negotiated_version = match request
+            .params
+            .get("protocolVersion")
+            .and_then(|v| v.as_str())
+        {
+            Some(requested) => mcp::negotiate_protocol_version(requested, supported_versions),
+            None => mcp::latest_protocol_version(supported_versions).map(str::to_string),
+        };
+        let Some(negotiated_version) = negotiated_version else {
+            return Ok(mcp::create_error_response(
+                request.id,
+                -32602,
+                "Unsupported protocol version",
+                Some(serde_json::json!({
+                    "requested": request.params.get("protocolVersion"),
+                    "supported": supported_versions
+                })),
+            ));
+        };
+
         let server_info = serde_json::json!({
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": negotiated_version,
             "capabilities": {
                 "tools": {
                     "listChanged": true
@@ -245,6 +368,9 @@ impl WebSocketHandler {
                     "paradox_handling": true,
                     "perception_coordination": true,
                     "substrate_integration": true
+                },
+                "experimental": {
+                    "casial": mcp::casial_capabilities()
                 }
             },
             "serverInfo": {
@@ -266,8 +392,19 @@ impl WebSocketHandler {
     ) -> Result<mcp::JsonRpcResponse> {
         debug!("🔧 Listing available tools from registry");
 
-        // Get all tools from registry (local + federated)
-        let all_tools = self.state.tool_registry.get_all_tools();
+        let params = match mcp::ListParams::from_params(&request.params) {
+            Ok(params) => params,
+            Err(e) => return Ok(mcp::invalid_list_params(request.id, e)),
+        };
+
+        // Get all tools from registry (local + federated), sorted for stable cursors
+        let mut all_tools = self.state.tool_registry.get_all_tools();
+        all_tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let (all_tools, next_cursor) = match mcp::paginate(all_tools, &params) {
+            Ok(page) => page,
+            Err(e) => return Ok(mcp::invalid_list_params(request.id, e)),
+        };
 
         let tools_json: Vec<serde_json::Value> = all_tools
             .iter()
@@ -276,14 +413,18 @@ impl WebSocketHandler {
                     "name": tool.name,
                     "description": tool.description,
                     "inputSchema": tool.input_schema,
-                    "outputSchema": tool.output_schema
+                    "outputSchema": tool.output_schema,
+                    "source": tool.source.label()
                 })
             })
             .collect();
 
-        let response = serde_json::json!({
+        let mut response = serde_json::json!({
             "tools": tools_json
         });
+        if let Some(cursor) = next_cursor {
+            response["nextCursor"] = serde_json::json!(cursor);
+        }
 
         Ok(mcp::create_success_response(request.id, response))
     }
@@ -295,18 +436,29 @@ impl WebSocketHandler {
     ) -> Result<mcp::JsonRpcResponse> {
         debug!("📋 Listing available resources");
 
-        let resources = serde_json::json!({
-            "resources": [
-                {
-                    "uri": "mcp://catalog",
-                    "name": "Tool Catalog",
-                    "description": "Federated tool specifications and metadata",
-                    "mimeType": "application/json"
-                }
-            ]
-        });
+        let params = match mcp::ListParams::from_params(&request.params) {
+            Ok(params) => params,
+            Err(e) => return Ok(mcp::invalid_list_params(request.id, e)),
+        };
+
+        let resources = vec![serde_json::json!({
+            "uri": "mcp://catalog",
+            "name": "Tool Catalog",
+            "description": "Federated tool specifications and metadata",
+            "mimeType": "application/json"
+        })];
+
+        let (resources, next_cursor) = match mcp::paginate(resources, &params) {
+            Ok(page) => page,
+            Err(e) => return Ok(mcp::invalid_list_params(request.id, e)),
+        };
+
+        let mut response = serde_json::json!({ "resources": resources });
+        if let Some(cursor) = next_cursor {
+            response["nextCursor"] = serde_json::json!(cursor);
+        }
 
-        Ok(mcp::create_success_response(request.id, resources))
+        Ok(mcp::create_success_response(request.id, response))
     }
 
     /// Handle resources/read method
@@ -327,12 +479,8 @@ impl WebSocketHandler {
                 let catalog = self.state.tool_registry.generate_catalog().await;
                 Ok(mcp::create_success_response(request.id, catalog))
             }
-            _ => Ok(mcp::create_error_response(
-                request.id,
-                -32601,
-                "Resource not found",
-                Some(serde_json::json!({"uri": uri})),
-            )),
+            _ => Ok(ErrorCode::UnknownResource
+                .response(request.id, Some(serde_json::json!({"uri": uri})))),
         }
     }
 
@@ -341,12 +489,24 @@ impl WebSocketHandler {
         &self,
         request: mcp::JsonRpcRequest,
         session_id: Uuid,
+        outbound: &tokio::sync::mpsc::Sender<String>,
     ) -> Result<mcp::JsonRpcResponse> {
         let params = request.params;
+        let progress = ProgressReporter::new(
+            ProgressReporter::token_from_params(&params),
+            outbound.clone(),
+        );
         let tool_name = params
             .get("name")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing tool name"))?;
+        let bypass_shim = PitfallAvoidanceShim::bypass_requested(&params);
+
+        if self.state.missing_required_mission(tool_name) {
+            warn!("Refusing {}: no mission is loaded", tool_name);
+            return Ok(ErrorCode::NoMissionLoaded
+                .response(request.id, Some(serde_json::json!({"tool": tool_name}))));
+        }
 
         let mut args = params
             .get("arguments")
@@ -368,6 +528,16 @@ impl WebSocketHandler {
             tool_name, mode
         );
 
+        // Omitted optional arguments take their schema defaults
+        self.state
+            .tool_registry
+            .apply_schema_defaults(tool_name, &mut args);
+
+        if self.state.tool_registry.get_tool(tool_name).is_none() {
+            return Ok(ErrorCode::UnknownTool
+                .response(request.id, Some(serde_json::json!({"tool": tool_name}))));
+        }
+
         // Validate tool arguments against schema
         if let Err(validation_errors) = self
             .state
@@ -375,10 +545,8 @@ impl WebSocketHandler {
             .validate_tool_arguments(tool_name, &args)
             .await
         {
-            return Ok(mcp::create_error_response(
+            return Ok(ErrorCode::InvalidParams.response(
                 request.id,
-                -32602,
-                "Invalid parameters",
                 Some(serde_json::json!({
                     "validation_errors": validation_errors
                 })),
@@ -410,24 +578,38 @@ impl WebSocketHandler {
         if let Some(result) = federation_result {
             match result {
                 Ok(result) => {
-                    let response_content = serde_json::json!({
-                        "content": [{
-                            "type": "text",
-                            "text": serde_json::to_string_pretty(&result)?
-                        }]
-                    });
+                    let response_content = self
+                        .shape_result(tool_name, serde_json::to_string_pretty(&result)?)
+                        .await;
                     return Ok(mcp::create_success_response(request.id, response_content));
                 }
-                Err(e) => {
-                    warn!(
+                // An open circuit means the server is known to be failing, so running the
+                // tool locally instead would hide that from the client
+                Err(e) => match e.downcast_ref::<FederationError>() {
+                    Some(error @ FederationError::CircuitOpen { .. }) => {
+                        return Ok(error.to_response(request.id, tool_name));
+                    }
+                    _ => warn!(
                         "Federation routing failed, falling back to local execution: {}",
                         e
-                    );
-                }
+                    ),
+                },
             }
         }
 
-        // Fallback to local execution with consciousness coordination
+        // Fallback to local execution with consciousness coordination, applying the
+        // pitfall shim before or after it as configured unless the caller bypassed it
+        let shim_first = self.state.config.consciousness.injection_order
+            == InjectionOrder::ShimBeforeCoordination;
+        if bypass_shim {
+            info!("Bypassing the pitfall shim for {}", tool_name);
+        }
+        // Admin credentials are for the executor only
+        let admin_token = take_admin_token(&mut args);
+        if shim_first && !bypass_shim {
+            args = self.state.apply_shim(tool_name, args, None).await;
+        }
+
         let active_perceptions = self
             .state
             .active_sessions
@@ -443,7 +625,21 @@ impl WebSocketHandler {
         let paradox_tolerance = args
             .get("paradoxTolerance")
             .and_then(|v| v.as_f64())
-            .unwrap_or(0.5);
+            .and_then(sanitize_paradox_tolerance);
+        let include_provenance = args
+            .get("includeProvenance")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let include_paradox_evidence = args
+            .get("includeParadoxEvidence")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let template_allow = template_ids_arg(&args, "templateAllow");
+        let template_deny = template_ids_arg(&args, "templateDeny").unwrap_or_default();
+        let max_template_priority = args
+            .get("maxTemplatePriority")
+            .and_then(|v| v.as_u64())
+            .and_then(|max| u32::try_from(max).ok());
 
         let environment = std::env::vars().collect();
 
@@ -454,112 +650,107 @@ impl WebSocketHandler {
             project_path,
             active_perceptions,
             paradox_tolerance,
+            include_provenance,
+            include_paradox_evidence,
+            max_template_priority,
+            template_allow,
+            template_deny,
+            ..Default::default()
         };
 
-        let coordination_result = {
-            let engine = self.state.casial_engine.write().await;
-            engine.coordinate(coordination_request)?
-        };
+        let coordination_result = self.state.rule_activations.coordinate(
+            &self.state.casial_engine,
+            &session_id.to_string(),
+            coordination_request,
+        )?;
 
         if let Some(mut session) = self.state.active_sessions.get_mut(&session_id) {
             session.active_coordination_id = Some(Uuid::new_v4());
         }
 
-        let tool_result = self
-            .execute_tool(tool_name, &coordination_result.modified_args)
-            .await?;
-
-        let response_content = serde_json::json!({
-            "content": [{
-                "type": "text",
-                "text": serde_json::to_string_pretty(&serde_json::json!({
-                    "tool_execution": tool_result,
-                    "consciousness_coordination": {
-                        "applied": coordination_result.applied,
-                        "injected_content": coordination_result.injected_content,
-                        "activated_rules": coordination_result.activated_rules,
-                        "used_templates": coordination_result.used_templates,
-                        "paradoxes_detected": coordination_result.paradoxes_detected.len(),
-                        "paradox_handling": coordination_result.paradoxes_detected.iter().map(|p| {
-                            serde_json::json!({
-                                "id": p.id,
-                                "description": p.description,
-                                "strategy": format!("{:?}", p.resolution_strategy)
-                            })
-                        }).collect::<Vec<_>>(),
-                        "metadata": coordination_result.metadata
+        let tool_args = if shim_first || bypass_shim {
+            coordination_result.modified_args.clone()
+        } else {
+            self.state
+                .apply_shim(tool_name, coordination_result.modified_args.clone(), None)
+                .await
+        };
+        let tool_args = restore_admin_token(tool_args, admin_token);
+        let mut tool_result = match self.execute_tool(tool_name, &tool_args, &progress).await {
+            Ok(result) => result,
+            Err(e) if e.is::<InvalidToolArguments>() => {
+                return Ok(ErrorCode::InvalidParams.response(
+                    request.id,
+                    Some(serde_json::json!({ "tool": tool_name, "detail": e.to_string() })),
+                ));
+            }
+            Err(e) => return Err(e),
+        };
+        if !self.state.config.consciousness.expose_injected_fields {
+            strip_injected_fields(&mut tool_result);
+        }
+
+        // Templates the budget had no room for, so clients can size their own budgets
+        let dropped_template_count = coordination_result
+            .metadata
+            .get("dropped_templates")
+            .and_then(|dropped| dropped.as_array())
+            .map_or(0, Vec::len);
+        let output = serde_json::to_string_pretty(&serde_json::json!({
+            "tool_execution": tool_result,
+            "consciousness_coordination": {
+                "applied": coordination_result.applied,
+                "injected_length": coordination_result.injected_content.chars().count(),
+                "was_truncated": dropped_template_count > 0,
+                "dropped_template_count": dropped_template_count,
+                "injected_content": coordination_result.injected_content,
+                "activated_rules": coordination_result.activated_rules,
+                "used_templates": coordination_result.used_templates,
+                "paradoxes_detected": coordination_result.paradoxes_detected.len(),
+                "paradox_handling": coordination_result.paradoxes_detected.iter().map(|p| {
+                    let mut paradox = serde_json::json!({
+                        "id": p.id,
+                        "description": p.description,
+                        "strategy": format!("{:?}", p.resolution_strategy)
+                    });
+                    if let Some(evidence) = &p.evidence {
+                        paradox["evidence"] = serde_json::json!(evidence);
                     }
-                }))?
-            }]
-        });
+                    paradox
+                }).collect::<Vec<_>>(),
+                "metadata": coordination_result.metadata
+            }
+        }))?;
+        let mut response_content = self.shape_result(tool_name, output).await;
+        if bypass_shim {
+            response_content["_meta"]["casial_shim_bypassed"] = serde_json::json!(true);
+        }
 
         Ok(mcp::create_success_response(request.id, response_content))
     }
 
-    /// Execute tool with coordinated context (simulated)
+    /// Execute tool with coordinated context through the registered executor
     async fn execute_tool(
         &self,
         tool_name: &str,
         args: &serde_json::Value,
+        progress: &ProgressReporter,
     ) -> Result<serde_json::Value> {
-        // This simulates tool execution with the context-modified arguments
-        // In a real implementation, this would call actual external APIs
-
-        match tool_name {
-            "web_search_exa" => Ok(serde_json::json!({
-                "status": "success",
-                "tool": "web_search_exa",
-                "query": args.get("query").unwrap_or(&serde_json::Value::Null),
-                "results_count": args.get("numResults").and_then(|n| n.as_u64()).unwrap_or(5),
-                "context_enhanced": true,
-                "simulation": true
-            })),
-            "deep_researcher_start" => Ok(serde_json::json!({
-                "status": "success",
-                "tool": "deep_researcher_start",
-                "instructions": args.get("instructions").unwrap_or(&serde_json::Value::Null),
-                "model": args.get("model").unwrap_or(&serde_json::json!("exa-research")),
-                "task_id": Uuid::new_v4(),
-                "consciousness_enhanced": true,
-                "simulation": true
-            })),
-            "crawling_exa" => Ok(serde_json::json!({
-                "status": "success",
-                "tool": "crawling_exa",
-                "url": args.get("url").unwrap_or(&serde_json::Value::Null),
-                "max_chars": args.get("maxCharacters").and_then(|n| n.as_u64()).unwrap_or(3000),
-                "context_aware": true,
-                "simulation": true
-            })),
-            "linkedin_search_exa" => Ok(serde_json::json!({
-                "status": "success",
-                "tool": "linkedin_search_exa",
-                "query": args.get("query").unwrap_or(&serde_json::Value::Null),
-                "searchType": args.get("searchType").unwrap_or(&serde_json::json!("all")),
-                "results_count": args.get("numResults").and_then(|n| n.as_u64()).unwrap_or(5),
-                "context_enhanced": true,
-                "professional_network_focus": true,
-                "sample_profile": "breyden-taylor",
-                "simulation": true
-            })),
-            "company_research_exa" => Ok(serde_json::json!({
-                "status": "success",
-                "tool": "company_research_exa",
-                "companyName": args.get("companyName").unwrap_or(&serde_json::Value::Null),
-                "results_count": args.get("numResults").and_then(|n| n.as_u64()).unwrap_or(5),
-                "context_enhanced": true,
-                "mission_driven": true,
-                "research_depth": "comprehensive",
-                "simulation": true
-            })),
-            _ => Ok(serde_json::json!({
-                "status": "success",
-                "tool": tool_name,
-                "args": args,
-                "consciousness_coordinated": true,
-                "simulation": true
-            })),
-        }
+        self.state
+            .tool_executors
+            .execute(tool_name, args.clone(), progress)
+            .await
+    }
+
+    /// Tool result content, chunked or summarized when over `server.large_results`
+    async fn shape_result(&self, tool_name: &str, output: String) -> serde_json::Value {
+        shape_tool_result(
+            tool_name,
+            output,
+            self.state.config.server.large_results.as_ref(),
+            &self.state.tool_executors,
+        )
+        .await
     }
 
     /// Handle Casial debug method
@@ -604,7 +795,7 @@ impl WebSocketHandler {
 
         // Get engine statistics
         let engine_stats = {
-            let engine = self.state.casial_engine.read().await;
+            let engine = &self.state.casial_engine;
             let coordination_history = engine.get_coordination_history();
             let paradox_registry = engine.get_paradox_registry();
 
@@ -642,6 +833,103 @@ impl WebSocketHandler {
         Ok(mcp::create_success_response(request.id, debug_info))
     }
 
+    /// Handle casial/stats: compact counts matching the WASM `getStatistics`
+    async fn handle_casial_stats(
+        &self,
+        request: mcp::JsonRpcRequest,
+    ) -> Result<mcp::JsonRpcResponse> {
+        let engine = &self.state.casial_engine;
+        let paradoxes = engine.get_paradox_registry();
+        // Paradoxes handled with Ignore stay unresolved until resolved explicitly
+        let paradoxes_resolved = paradoxes
+            .iter()
+            .filter(|p| {
+                p.resolved_at.is_some() || !matches!(p.resolution_strategy, ParadoxStrategy::Ignore)
+            })
+            .count();
+
+        let federation = match self.state.federation_manager.read().await.as_ref() {
+            Some(manager) => {
+                let metrics = manager.get_metrics().await;
+                serde_json::json!({
+                    "active_connections": metrics.active_connections,
+                    "total_servers": metrics.total_servers,
+                    "tool_calls_forwarded": metrics.tool_calls_forwarded,
+                    "federation_errors": metrics.federation_errors
+                })
+            }
+            None => serde_json::Value::Null,
+        };
+
+        let stats = serde_json::json!({
+            "coordination_events": engine.get_coordination_history().len(),
+            "paradoxes_detected": paradoxes.len(),
+            "paradoxes_resolved": paradoxes_resolved,
+            "active_sessions": self.state.active_sessions.len(),
+            "federation": federation
+        });
+
+        Ok(mcp::create_success_response(request.id, stats))
+    }
+
+    /// Handle casial/paradox/list: registered paradoxes split into active and resolved
+    async fn handle_paradox_list(
+        &self,
+        request: mcp::JsonRpcRequest,
+    ) -> Result<mcp::JsonRpcResponse> {
+        let (resolved, active): (Vec<_>, Vec<_>) = self
+            .state
+            .casial_engine
+            .get_paradox_registry()
+            .into_iter()
+            .partition(|p| p.resolved_at.is_some());
+
+        Ok(mcp::create_success_response(
+            request.id,
+            serde_json::json!({ "active": active, "resolved": resolved }),
+        ))
+    }
+
+    /// Handle casial/paradox/resolve: resolve one paradox with an explicit strategy
+    async fn handle_paradox_resolve(
+        &self,
+        request: mcp::JsonRpcRequest,
+    ) -> Result<mcp::JsonRpcResponse> {
+        let params = &request.params;
+        let paradox_id = params
+            .get("paradoxId")
+            .and_then(|v| v.as_str())
+            .and_then(|id| Uuid::parse_str(id).ok());
+        let strategy = params
+            .get("strategy")
+            .and_then(|v| serde_json::from_value::<ParadoxStrategy>(v.clone()).ok());
+        let (Some(paradox_id), Some(strategy)) = (paradox_id, strategy) else {
+            return Ok(mcp::create_error_response(
+                request.id,
+                -32602,
+                "Invalid params: expected paradoxId (UUID) and strategy",
+                None,
+            ));
+        };
+
+        match self
+            .state
+            .casial_engine
+            .resolve_paradox(paradox_id, strategy)
+        {
+            Ok(report) => Ok(mcp::create_success_response(
+                request.id,
+                serde_json::to_value(report)?,
+            )),
+            Err(e) => Ok(mcp::create_error_response(
+                request.id,
+                -32602,
+                &e.to_string(),
+                None,
+            )),
+        }
+    }
+
     /// Handle adding perception to session
     async fn handle_add_perception(
         &self,
@@ -654,11 +942,35 @@ impl WebSocketHandler {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing perception name"))?;
 
-        let perception_id = PerceptionId::new();
+        if self.owned_perception_count(session_id)
+            >= self.state.config.consciousness.max_session_perceptions
+        {
+            return Ok(self.perception_limit_error(request.id));
+        }
+
+        let perception_id = self.state.casial_engine.new_perception_id();
+        let now = Utc::now();
+        self.state.casial_engine.register_perception(Perception {
+            id: perception_id,
+            name: perception_name.to_string(),
+            description: params
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            confidence: params
+                .get("confidence")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0),
+            created_at: now,
+            updated_at: now,
+            metadata: Default::default(),
+        });
 
         // Add to session
         if let Some(mut session) = self.state.active_sessions.get_mut(&session_id) {
             session.active_perceptions.push(perception_id);
+            session.owned_perceptions.push(perception_id);
         }
 
         info!(
@@ -717,16 +1029,162 @@ impl WebSocketHandler {
 
         Ok(mcp::create_success_response(request.id, response))
     }
+
+    /// Handle casial/perception/list: mission perceptions and this session's own, flagged if
+    /// active in this session
+    ///
+    /// Perceptions other sessions registered stay private to them.
+    async fn handle_list_perceptions(
+        &self,
+        request: mcp::JsonRpcRequest,
+        session_id: Uuid,
+    ) -> Result<mcp::JsonRpcResponse> {
+        let (active, owned) = self
+            .state
+            .active_sessions
+            .get(&session_id)
+            .map(|s| (s.active_perceptions.clone(), s.owned_perceptions.clone()))
+            .unwrap_or_default();
+
+        let engine = &self.state.casial_engine;
+        let perceptions: Vec<serde_json::Value> = engine
+            .get_perceptions()
+            .into_iter()
+            .filter(|perception| {
+                active.contains(&perception.id)
+                    || owned.contains(&perception.id)
+                    || engine.is_mission_perception(&perception.id)
+            })
+            .map(|perception| {
+                let active_in_session = active.contains(&perception.id);
+                let mut entry = serde_json::to_value(perception)?;
+                entry["active_in_session"] = serde_json::json!(active_in_session);
+                Ok(entry)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(mcp::create_success_response(
+            request.id,
+            serde_json::json!({ "session_id": session_id, "perceptions": perceptions }),
+        ))
+    }
+
+    /// Handle exporting the session's active perceptions for handoff to another session
+    async fn handle_export_perceptions(
+        &self,
+        request: mcp::JsonRpcRequest,
+        session_id: Uuid,
+    ) -> Result<mcp::JsonRpcResponse> {
+        let active = self
+            .state
+            .active_sessions
+            .get(&session_id)
+            .map(|s| s.active_perceptions.clone())
+            .unwrap_or_default();
+
+        let mut perceptions = Vec::new();
+        let mut unregistered = Vec::new();
+        for id in active {
+            match self.state.casial_engine.get_perception(&id) {
+                Some(perception) => perceptions.push(perception),
+                None => unregistered.push(id),
+            }
+        }
+
+        let response = serde_json::json!({
+            "session_id": session_id,
+            "perceptions": perceptions,
+            "unregistered": unregistered
+        });
+
+        Ok(mcp::create_success_response(request.id, response))
+    }
+
+    /// Handle replacing the session's perception set, registering unknown perceptions
+    async fn handle_import_perceptions(
+        &self,
+        request: mcp::JsonRpcRequest,
+        session_id: Uuid,
+    ) -> Result<mcp::JsonRpcResponse> {
+        let perceptions: Vec<Perception> = match request.params.get("perceptions") {
+            Some(value) => match serde_json::from_value(value.clone()) {
+                Ok(perceptions) => perceptions,
+                Err(e) => {
+                    return Ok(mcp::create_error_response(
+                        request.id,
+                        -32602,
+                        &format!("Invalid perceptions: {}", e),
+                        None,
+                    ))
+                }
+            },
+            None => {
+                return Ok(mcp::create_error_response(
+                    request.id,
+                    -32602,
+                    "Missing perceptions",
+                    None,
+                ))
+            }
+        };
+
+        let ids: Vec<PerceptionId> = perceptions.iter().map(|p| p.id).collect();
+        let unseen: std::collections::HashSet<PerceptionId> = ids
+            .iter()
+            .copied()
+            .filter(|id| self.state.casial_engine.get_perception(id).is_none())
+            .collect();
+        if self.owned_perception_count(session_id) + unseen.len()
+            > self.state.config.consciousness.max_session_perceptions
+        {
+            return Ok(self.perception_limit_error(request.id));
+        }
+
+        let new_ids: Vec<PerceptionId> = perceptions
+            .into_iter()
+            .filter(|perception| {
+                self.state
+                    .casial_engine
+                    .register_perception(perception.clone())
+            })
+            .map(|perception| perception.id)
+            .collect();
+        let registered = new_ids.len();
+
+        if let Some(mut session) = self.state.active_sessions.get_mut(&session_id) {
+            session.active_perceptions = ids.clone();
+            session.owned_perceptions.extend(new_ids);
+        }
+
+        info!(
+            "👁️ Imported {} perceptions into session {} ({} new)",
+            ids.len(),
+            session_id,
+            registered
+        );
+
+        let response = serde_json::json!({
+            "session_id": session_id,
+            "active_perceptions": ids,
+            "registered": registered
+        });
+
+        Ok(mcp::create_success_response(request.id, response))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{config::ServerConfig, pitfall_shim::PitfallAvoidanceShim};
+    use crate::{
+        config::ServerConfig,
+        pitfall_shim::PitfallAvoidanceShim,
+        test_fixtures::{mission, mission_json, rule_json, template_json},
+    };
 
     #[test]
     fn test_websocket_session_creation() {
-        let session = WebSocketSession::new();
+        let session = WebSocketSession::new(Uuid::new_v4());
         assert_eq!(session.message_count, 0);
         assert!(session.active_coordination_id.is_none());
         assert_eq!(session.active_perceptions.len(), 0);
@@ -742,4 +1200,819 @@ mod tests {
         // Handler should be created successfully
         assert_eq!(handler.state.active_sessions.len(), 0);
     }
+
+    struct ProgressingExecutor;
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for ProgressingExecutor {
+        async fn execute(
+            &self,
+            _tool_name: &str,
+            _args: serde_json::Value,
+        ) -> Result<serde_json::Value> {
+            Ok(serde_json::json!({ "done": true }))
+        }
+
+        async fn execute_with_progress(
+            &self,
+            tool_name: &str,
+            args: serde_json::Value,
+            progress: &ProgressReporter,
+        ) -> Result<serde_json::Value> {
+            progress.report(1.0, Some(2.0), Some("planning"));
+            progress.report(2.0, Some(2.0), Some("synthesizing"));
+            self.execute(tool_name, args).await
+        }
+    }
+
+    #[tokio::test]
+    async fn tool_progress_is_sent_before_the_result() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        state
+            .tool_registry
+            .register_tool(crate::registry::ToolSpec {
+                name: "long_chain".to_string(),
+                description: "Multi-step chain".to_string(),
+                input_schema: serde_json::json!({ "type": "object" }),
+                output_schema: None,
+                source: crate::registry::ToolSource::Local,
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({}),
+            })
+            .await
+            .unwrap();
+        state
+            .tool_executors
+            .register("long_chain", std::sync::Arc::new(ProgressingExecutor));
+        let handler = WebSocketHandler::new(state);
+
+        let (outbound, mut inbound) = tokio::sync::mpsc::channel(8);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 7, "method": "tools/call",
+            "params": {
+                "name": "long_chain", "arguments": {},
+                "_meta": { "progressToken": "chain-1" }
+            }
+        });
+        let response = handler
+            .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+            .await
+            .unwrap()
+            .expect("tools/call responds");
+
+        // Both notifications were queued on the session channel before the response existed
+        let mut updates = Vec::new();
+        while let Ok(message) = inbound.try_recv() {
+            updates.push(serde_json::from_str::<serde_json::Value>(&message).unwrap());
+        }
+        assert_eq!(updates.len(), 2);
+        for (update, progress) in updates.iter().zip([1.0, 2.0]) {
+            assert_eq!(update["method"], "notifications/progress");
+            assert_eq!(update["params"]["progressToken"], "chain-1");
+            assert_eq!(update["params"]["progress"], progress);
+        }
+
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["id"], 7);
+        assert!(response["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("\"done\": true"));
+    }
+
+    struct ArgsRecorder(std::sync::Mutex<Vec<serde_json::Value>>);
+
+    #[async_trait::async_trait]
+    impl crate::executor::ToolExecutor for ArgsRecorder {
+        async fn execute(
+            &self,
+            _tool_name: &str,
+            args: serde_json::Value,
+        ) -> Result<serde_json::Value> {
+            self.0.lock().unwrap().push(args);
+            Ok(serde_json::json!({ "done": true }))
+        }
+    }
+
+    #[tokio::test]
+    async fn shim_and_coordination_apply_in_the_configured_order() {
+        use crate::config::InjectionOrder;
+
+        for (order, note_survives) in [
+            (InjectionOrder::CoordinationBeforeShim, false),
+            (InjectionOrder::ShimBeforeCoordination, true),
+        ] {
+            let mut config = ServerConfig::default();
+            config.consciousness.injection_order = order;
+            let state = AppState::new(config, PitfallAvoidanceShim::default());
+            let mut rule = rule_json("note-rule", &["search"], &["note"]);
+            rule["actions"]["transform_type"] = serde_json::json!("InjectField");
+            rule["actions"]["target_field"] = serde_json::json!("_shim_context.casial_note");
+            let ordered = mission_json(
+                "ordered",
+                vec![template_json("note", 1, "Cite sources.")],
+                vec![rule],
+            );
+            state.casial_engine.load_mission(mission(ordered)).unwrap();
+            let recorder = std::sync::Arc::new(ArgsRecorder(Default::default()));
+            state
+                .tool_executors
+                .register("exa_search_example", recorder.clone());
+            let handler = WebSocketHandler::new(state);
+
+            let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                "params": { "name": "exa_search_example", "arguments": { "query": "rust" } }
+            });
+            handler
+                .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+                .await
+                .unwrap();
+
+            let calls = recorder.0.lock().unwrap();
+            let shim_context = &calls[0]["_shim_context"];
+            assert!(shim_context["current_date"].is_string(), "{:?}", order);
+            assert_eq!(
+                shim_context.get("casial_note").is_some(),
+                note_survives,
+                "{:?}",
+                order
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn require_mission_refuses_tool_calls_without_a_mission() {
+        let mut config = ServerConfig::default();
+        config.missions.require_mission = true;
+        let handler = WebSocketHandler::new(AppState::new(config, PitfallAvoidanceShim::default()));
+
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+            "params": { "name": "exa_search_example", "arguments": { "query": "rust" } }
+        });
+        let response = handler
+            .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+            .await
+            .unwrap()
+            .expect("tools/call responds");
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+
+        assert!(response.get("result").is_none());
+        assert_eq!(response["error"]["data"]["code"], "no_mission_loaded");
+    }
+
+    #[tokio::test]
+    async fn bypass_shim_is_recorded_in_the_tool_result() {
+        let handler = WebSocketHandler::new(AppState::new(
+            ServerConfig::default(),
+            PitfallAvoidanceShim::default(),
+        ));
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let call = |params: serde_json::Value| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": params
+            });
+            let handler = &handler;
+            let outbound = &outbound;
+            async move {
+                let response = handler
+                    .handle_text_message(&request.to_string(), Uuid::new_v4(), outbound)
+                    .await
+                    .unwrap()
+                    .expect("tools/call responds");
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()["result"].clone()
+            }
+        };
+
+        let shimmed = call(serde_json::json!({
+            "name": "exa_search_example", "arguments": { "query": "rust" }
+        }))
+        .await;
+        assert!(shimmed.get("_meta").is_none());
+
+        let bypassed = call(serde_json::json!({
+            "name": "exa_search_example", "arguments": { "query": "rust" },
+            "_meta": { "bypassShim": true }
+        }))
+        .await;
+        assert_eq!(bypassed["_meta"]["casial_shim_bypassed"], true);
+    }
+
+    #[tokio::test]
+    async fn tool_call_reports_truncation_under_a_tight_budget() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let mut tight = mission_json(
+            "tight",
+            vec![
+                template_json("a", 1, "x".repeat(20)),
+                template_json("b", 2, "x".repeat(200)),
+                template_json("c", 3, "x".repeat(10)),
+            ],
+            vec![rule_json("all", &["search"], &["a", "b", "c"])],
+        );
+        tight["budgets"]["global_char_limit"] = serde_json::json!(100);
+        state.casial_engine.load_mission(mission(tight)).unwrap();
+        let handler = WebSocketHandler::new(state);
+
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+            "params": { "name": "exa_search_example", "arguments": { "query": "rust" } }
+        });
+        let response = handler
+            .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+            .await
+            .unwrap()
+            .expect("tools/call responds");
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let output: serde_json::Value =
+            serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())
+                .unwrap();
+
+        // "b" overflows the budget, and "c" after it is dropped with it
+        let coordination = &output["consciousness_coordination"];
+        assert_eq!(coordination["used_templates"], serde_json::json!(["a"]));
+        assert_eq!(coordination["was_truncated"], true);
+        assert_eq!(coordination["dropped_template_count"], 2);
+        assert_eq!(
+            coordination["injected_length"],
+            coordination["injected_content"]
+                .as_str()
+                .unwrap()
+                .chars()
+                .count()
+        );
+        assert_eq!(
+            coordination["metadata"]["dropped_templates"],
+            serde_json::json!(["b", "c"])
+        );
+    }
+
+    #[tokio::test]
+    async fn max_template_priority_keeps_the_most_important_templates() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let ranked = mission_json(
+            "ranked",
+            vec![
+                template_json("essential", 1, "Cite sources."),
+                template_json("optional", 2, "Add background."),
+            ],
+            vec![rule_json("all", &["search"], &["essential", "optional"])],
+        );
+        state.casial_engine.load_mission(mission(ranked)).unwrap();
+        let handler = WebSocketHandler::new(state);
+
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+            "params": {
+                "name": "exa_search_example",
+                "arguments": { "query": "rust", "maxTemplatePriority": 1 }
+            }
+        });
+        let response = handler
+            .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+            .await
+            .unwrap()
+            .expect("tools/call responds");
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let output: serde_json::Value =
+            serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())
+                .unwrap();
+
+        assert_eq!(
+            output["consciousness_coordination"]["used_templates"],
+            serde_json::json!(["essential"])
+        );
+    }
+
+    #[tokio::test]
+    async fn template_allow_and_deny_arguments_filter_injected_templates() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let filtered = mission_json(
+            "filtered",
+            vec![
+                template_json("citations", 1, "Cite sources."),
+                template_json("background", 2, "Add background."),
+                template_json("tone", 3, "Stay neutral."),
+            ],
+            vec![rule_json(
+                "all",
+                &["search"],
+                &["citations", "background", "tone"],
+            )],
+        );
+        state.casial_engine.load_mission(mission(filtered)).unwrap();
+        let handler = WebSocketHandler::new(state);
+
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+            "params": {
+                "name": "exa_search_example",
+                "arguments": {
+                    "query": "rust",
+                    "templateAllow": ["citations", "background"],
+                    "templateDeny": ["background"]
+                }
+            }
+        });
+        let response = handler
+            .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+            .await
+            .unwrap()
+            .expect("tools/call responds");
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let output: serde_json::Value =
+            serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())
+                .unwrap();
+
+        assert_eq!(
+            output["consciousness_coordination"]["used_templates"],
+            serde_json::json!(["citations"])
+        );
+    }
+
+    #[tokio::test]
+    async fn rule_cooldown_suppresses_repeat_activation_within_a_session() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let mut rule = rule_json("guide-rule", &["search"], &["guide"]);
+        rule["cooldown_secs"] = serde_json::json!(60);
+        let cooldown = mission_json(
+            "cooldown",
+            vec![template_json("guide", 1, "Cite sources.")],
+            vec![rule],
+        );
+        state.casial_engine.load_mission(mission(cooldown)).unwrap();
+        let session_id = Uuid::new_v4();
+        state
+            .active_sessions
+            .insert(session_id, WebSocketSession::new(session_id));
+        let activations = std::sync::Arc::clone(&state.rule_activations);
+        let engine = std::sync::Arc::clone(&state.casial_engine);
+        let handler = WebSocketHandler::new(state);
+
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let call = || async {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                "params": { "name": "exa_search_example", "arguments": { "query": "rust" } }
+            });
+            handler
+                .handle_text_message(&request.to_string(), session_id, &outbound)
+                .await
+                .unwrap();
+            engine
+                .coordination_history_page(None, usize::MAX)
+                .pop()
+                .unwrap()
+                .1
+        };
+
+        assert_eq!(call().await.activated_rules, vec!["guide-rule"]);
+        let suppressed = call().await;
+        assert!(suppressed.activated_rules.is_empty());
+        assert_eq!(
+            suppressed.metadata["suppressed_by_cooldown"],
+            serde_json::json!(["guide-rule"])
+        );
+
+        // Once the cooldown has elapsed the rule activates again
+        activations.record(
+            &session_id.to_string(),
+            "guide-rule",
+            Utc::now() - chrono::Duration::seconds(61),
+        );
+        assert_eq!(call().await.activated_rules, vec!["guide-rule"]);
+    }
+
+    /// Two missions sharing a template with different affinities, coordinated twice
+    fn state_with_paradoxes() -> AppState {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        // The same template with different affinities in two missions is a paradox
+        for (mission_id, strategy) in [("first", "Coexist"), ("second", "Ignore")] {
+            let mut template = template_json("guide", 1, "Cite sources.");
+            template["perception_affinity"] = serde_json::json!([PerceptionId::new()]);
+            let mut rule = rule_json(&format!("{}-rule", mission_id), &["search"], &["guide"]);
+            rule["paradox_handling"] = serde_json::json!(strategy);
+            let conflicting = mission_json(mission_id, vec![template], vec![rule]);
+            state
+                .casial_engine
+                .load_mission(mission(conflicting))
+                .unwrap();
+        }
+        for _ in 0..2 {
+            state
+                .casial_engine
+                .coordinate(CoordinationRequest::new(
+                    "web_search",
+                    serde_json::json!({ "query": "rust" }),
+                ))
+                .unwrap();
+        }
+        state
+    }
+
+    #[tokio::test]
+    async fn stats_method_reports_engine_counts() {
+        let state = state_with_paradoxes();
+        let session_id = Uuid::new_v4();
+        state
+            .active_sessions
+            .insert(session_id, WebSocketSession::new(session_id));
+
+        let engine = std::sync::Arc::clone(&state.casial_engine);
+        let handler = WebSocketHandler::new(state);
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(1);
+        let request = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "casial/stats" });
+        let response = handler
+            .handle_text_message(&request.to_string(), session_id, &outbound)
+            .await
+            .unwrap()
+            .expect("casial/stats responds");
+        let stats = serde_json::from_str::<serde_json::Value>(&response).unwrap()["result"].clone();
+
+        let paradoxes = engine.get_paradox_registry();
+        let resolved = paradoxes
+            .iter()
+            .filter(|p| !matches!(p.resolution_strategy, ParadoxStrategy::Ignore))
+            .count();
+        assert!(!paradoxes.is_empty());
+        assert_eq!(stats["coordination_events"], 2);
+        assert_eq!(
+            stats["coordination_events"],
+            engine.get_coordination_history().len()
+        );
+        assert_eq!(stats["paradoxes_detected"], paradoxes.len());
+        assert_eq!(stats["paradoxes_resolved"], resolved);
+        assert_eq!(stats["active_sessions"], 1);
+        assert!(stats["federation"].is_null());
+    }
+
+    #[tokio::test]
+    async fn paradox_methods_list_and_resolve_with_explicit_strategy() {
+        let handler = WebSocketHandler::new(state_with_paradoxes());
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(1);
+        let call = |method: &str, params: serde_json::Value| {
+            serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+                .to_string()
+        };
+        let send = |text: String| {
+            let handler = &handler;
+            let outbound = &outbound;
+            async move {
+                let response = handler
+                    .handle_text_message(&text, Uuid::new_v4(), outbound)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()
+            }
+        };
+
+        let listed = send(call("casial/paradox/list", serde_json::json!({}))).await;
+        let active = listed["result"]["active"].as_array().unwrap().clone();
+        assert!(!active.is_empty());
+        assert!(listed["result"]["resolved"].as_array().unwrap().is_empty());
+
+        let paradox_id = active[0]["id"].clone();
+        let resolved = send(call(
+            "casial/paradox/resolve",
+            serde_json::json!({ "paradoxId": paradox_id, "strategy": "Synthesize" }),
+        ))
+        .await;
+        assert_eq!(resolved["result"]["resolution_strategy"], "Synthesize");
+        assert!(resolved["result"]["resolved_at"].is_string());
+
+        let listed = send(call("casial/paradox/list", serde_json::json!({}))).await;
+        assert_eq!(
+            listed["result"]["active"].as_array().unwrap().len(),
+            active.len() - 1
+        );
+        assert_eq!(listed["result"]["resolved"][0]["id"], paradox_id);
+
+        let again = send(call(
+            "casial/paradox/resolve",
+            serde_json::json!({ "paradoxId": paradox_id, "strategy": "Ignore" }),
+        ))
+        .await;
+        assert_eq!(again["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn session_perceptions_are_capped_and_dropped_when_the_session_closes() {
+        let mut config = ServerConfig::default();
+        config.consciousness.max_session_perceptions = 2;
+        let state = AppState::new(config, PitfallAvoidanceShim::default());
+        let (owner, other) = (Uuid::new_v4(), Uuid::new_v4());
+        for id in [owner, other] {
+            state.active_sessions.insert(id, WebSocketSession::new(id));
+        }
+        let engine = std::sync::Arc::clone(&state.casial_engine);
+        let handler = WebSocketHandler::new(state);
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let send = |method: &str, params: serde_json::Value, session_id: Uuid| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": method, "params": params
+            })
+            .to_string();
+            let handler = &handler;
+            let outbound = &outbound;
+            async move {
+                let response = handler
+                    .handle_text_message(&request, session_id, outbound)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()
+            }
+        };
+        let perception = |name: &str| {
+            let now = Utc::now();
+            Perception {
+                id: PerceptionId::new(),
+                name: name.to_string(),
+                description: String::new(),
+                confidence: 1.0,
+                created_at: now,
+                updated_at: now,
+                metadata: Default::default(),
+            }
+        };
+        let (kept, dropped) = (perception("kept"), perception("dropped"));
+
+        let imported = send(
+            "casial/perception/import",
+            serde_json::json!({ "perceptions": [kept, dropped] }),
+            owner,
+        )
+        .await;
+        assert_eq!(imported["result"]["registered"], 2);
+        assert_eq!(engine.get_perceptions().len(), 2);
+
+        let over_limit = send(
+            "casial/perception/add",
+            serde_json::json!({ "name": "third" }),
+            owner,
+        )
+        .await;
+        assert_eq!(over_limit["error"]["data"]["limit"], 2);
+        assert_eq!(engine.get_perceptions().len(), 2);
+
+        // The other session shares one of them, so it outlives the owner
+        send(
+            "casial/perception/import",
+            serde_json::json!({ "perceptions": [kept] }),
+            other,
+        )
+        .await;
+        handler.close_session(owner);
+        assert!(engine.get_perception(&kept.id).is_some());
+        assert!(engine.get_perception(&dropped.id).is_none());
+
+        handler.close_session(other);
+        assert!(engine.get_perceptions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn perceptions_exported_from_one_session_import_into_another() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let (source, target) = (Uuid::new_v4(), Uuid::new_v4());
+        for id in [source, target] {
+            state.active_sessions.insert(id, WebSocketSession::new(id));
+        }
+        let sessions = std::sync::Arc::clone(&state.active_sessions);
+        let handler = WebSocketHandler::new(state);
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let call = |method: &str, params: serde_json::Value| {
+            serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+                .to_string()
+        };
+        let send = |request: String, session_id: Uuid| {
+            let handler = &handler;
+            let outbound = &outbound;
+            async move {
+                let response = handler
+                    .handle_text_message(&request, session_id, outbound)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()["result"].clone()
+            }
+        };
+
+        for name in ["optimist", "skeptic"] {
+            send(
+                call("casial/perception/add", serde_json::json!({ "name": name })),
+                source,
+            )
+            .await;
+        }
+        let exported = send(
+            call("casial/perception/export", serde_json::json!({})),
+            source,
+        )
+        .await;
+        let names: Vec<&str> = exported["perceptions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["optimist", "skeptic"]);
+
+        let imported = send(
+            call(
+                "casial/perception/import",
+                serde_json::json!({ "perceptions": exported["perceptions"] }),
+            ),
+            target,
+        )
+        .await;
+        // Both perceptions were already registered by the source session
+        assert_eq!(imported["registered"], 0);
+        assert_eq!(
+            sessions.get(&target).unwrap().active_perceptions,
+            sessions.get(&source).unwrap().active_perceptions
+        );
+        let reexported = send(
+            call("casial/perception/export", serde_json::json!({})),
+            target,
+        )
+        .await;
+        assert_eq!(reexported["perceptions"], exported["perceptions"]);
+    }
+
+    #[tokio::test]
+    async fn perception_list_includes_mission_and_own_session_perceptions() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let mut perceptive = mission_json("perceptive", vec![], vec![]);
+        perceptive["perceptions"] = serde_json::json!([
+                {
+                    "id": Uuid::new_v4(), "name": "optimist", "description": "Sees upside",
+                    "confidence": 0.8, "created_at": "2025-01-01T00:00:00Z",
+                    "updated_at": "2025-01-02T00:00:00Z", "metadata": {}
+                },
+                {
+                    "id": Uuid::new_v4(), "name": "skeptic", "description": "Sees risk",
+                    "confidence": 0.35, "created_at": "2025-01-03T00:00:00Z",
+                    "updated_at": "2025-01-03T00:00:00Z", "metadata": {}
+                }
+        ]);
+        state
+            .casial_engine
+            .load_mission(mission(perceptive))
+            .unwrap();
+        // Registered by some other session, so not this session's to see
+        let now = Utc::now();
+        state.casial_engine.register_perception(Perception {
+            id: PerceptionId::new(),
+            name: "private".to_string(),
+            description: String::new(),
+            confidence: 1.0,
+            created_at: now,
+            updated_at: now,
+            metadata: Default::default(),
+        });
+        let session_id = Uuid::new_v4();
+        state
+            .active_sessions
+            .insert(session_id, WebSocketSession::new(session_id));
+        let handler = WebSocketHandler::new(state);
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let send = |method: &str, params: serde_json::Value| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": method, "params": params
+            });
+            let (handler, outbound) = (&handler, &outbound);
+            async move {
+                let response = handler
+                    .handle_text_message(&request.to_string(), session_id, outbound)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()["result"].clone()
+            }
+        };
+
+        send(
+            "casial/perception/add",
+            serde_json::json!({ "name": "scout", "confidence": 0.6 }),
+        )
+        .await;
+        let listed = send("casial/perception/list", serde_json::json!({})).await;
+
+        let perceptions = listed["perceptions"].as_array().unwrap();
+        let summary: Vec<(&str, f64, bool)> = perceptions
+            .iter()
+            .map(|p| {
+                (
+                    p["name"].as_str().unwrap(),
+                    p["confidence"].as_f64().unwrap(),
+                    p["active_in_session"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("optimist", 0.8, false),
+                ("skeptic", 0.35, false),
+                ("scout", 0.6, true)
+            ]
+        );
+        assert_eq!(perceptions[0]["created_at"], "2025-01-01T00:00:00Z");
+        assert_eq!(perceptions[0]["updated_at"], "2025-01-02T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn demo_sessions_only_call_demo_tools() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        let session_id = Uuid::new_v4();
+        state.active_sessions.insert(
+            session_id,
+            WebSocketSession {
+                demo_access: true,
+                ..WebSocketSession::new(session_id)
+            },
+        );
+        let handler = WebSocketHandler::new(state);
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+        let call = |name: &str| {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+                "params": { "name": name, "arguments": { "query": "rust" } }
+            });
+            let (handler, outbound) = (&handler, &outbound);
+            async move {
+                let response = handler
+                    .handle_text_message(&request.to_string(), session_id, outbound)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                serde_json::from_str::<serde_json::Value>(&response).unwrap()
+            }
+        };
+
+        assert!(call("exa_search_example").await.get("error").is_none());
+        for tool in ["casial_preview_coordination", "casial_get_shim_config"] {
+            let denied = call(tool).await;
+            assert_eq!(denied["error"]["data"]["reason"], "demo_key", "{}", tool);
+            assert_eq!(denied["error"]["data"]["tool"], tool);
+        }
+    }
+
+    #[tokio::test]
+    async fn tools_list_labels_each_tool_with_its_source() {
+        let state = AppState::new(ServerConfig::default(), PitfallAvoidanceShim::default());
+        state
+            .tool_registry
+            .register_tool(crate::registry::ToolSpec {
+                name: "remote_search".to_string(),
+                description: "Search on a downstream server".to_string(),
+                input_schema: serde_json::json!({ "type": "object" }),
+                output_schema: None,
+                source: crate::registry::ToolSource::Federated {
+                    server_id: "exa".to_string(),
+                    server_url: "https://exa.example/mcp".to_string(),
+                },
+                spec_version: "1.0.0".to_string(),
+                spec_hash: String::new(),
+                last_updated: Utc::now(),
+                metadata: serde_json::json!({}),
+            })
+            .await
+            .unwrap();
+        let handler = WebSocketHandler::new(state);
+        let (outbound, _inbound) = tokio::sync::mpsc::channel(8);
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/list", "params": { "limit": 1000 }
+        });
+        let response = handler
+            .handle_text_message(&request.to_string(), Uuid::new_v4(), &outbound)
+            .await
+            .unwrap()
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let source_of = |name: &str| {
+            response["result"]["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|tool| tool["name"] == name)
+                .map(|tool| tool["source"].clone())
+        };
+
+        assert_eq!(source_of("remote_search"), Some(serde_json::json!("exa")));
+        assert_eq!(
+            source_of("orchestrate_mcp_proxy"),
+            Some(serde_json::json!("local"))
+        );
+    }
 }
@@ -0,0 +1,270 @@
+//! # Browser Federation
+//!
+//! Federates with remote MCP servers over the host's `fetch`, using the streamable HTTP
+//! transport: JSON-RPC requests are POSTed and answered with a JSON body. Remote tools are
+//! merged into a local tool list, behind any local tool of the same name.
+
+use anyhow::{Context, Result};
+use casial_core::{
+    call_remote_tool, list_remote_tools, merge_remote_tools, remote_call, McpTransport,
+};
+use serde_json::{json, Value};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{Headers, Request, RequestInit, Response};
+
+#[wasm_bindgen]
+extern "C" {
+    /// The global `fetch`, so the transport works in windows, workers and Node alike
+    #[wasm_bindgen(js_name = fetch)]
+    fn global_fetch(input: &Request) -> js_sys::Promise;
+}
+
+fn js_error(error: JsValue) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{}",
+        error.as_string().unwrap_or_else(|| format!("{:?}", error))
+    )
+}
+
+fn to_js_error(error: anyhow::Error) -> JsValue {
+    JsValue::from_str(&format!("{:#}", error))
+}
+
+/// [`McpTransport`] over `fetch` to one MCP endpoint
+pub struct FetchTransport {
+    url: String,
+    headers: HashMap<String, String>,
+    /// `Mcp-Session-Id` assigned by the server, sent back on later requests
+    session_id: RefCell<Option<String>>,
+}
+
+impl FetchTransport {
+    /// Transport to `url`, adding `headers` (e.g. `Authorization`) to every request
+    pub fn new(url: String, headers: HashMap<String, String>) -> Self {
+        Self {
+            url,
+            headers,
+            session_id: RefCell::new(None),
+        }
+    }
+
+    /// Run the MCP `initialize` handshake, which opens the server session
+    pub async fn initialize(&self) -> Result<Value> {
+        remote_call(
+            self,
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": {
+                    "name": "context-casial-xpress-wasm",
+                    "version": env!("CARGO_PKG_VERSION")
+                }
+            }),
+        )
+        .await
+    }
+
+    fn request_headers(&self) -> Result<Headers> {
+        let headers = Headers::new().map_err(js_error)?;
+        headers
+            .set("Content-Type", "application/json")
+            .map_err(js_error)?;
+        headers
+            .set("Accept", "application/json")
+            .map_err(js_error)?;
+        for (name, value) in &self.headers {
+            headers.set(name, value).map_err(js_error)?;
+        }
+        if let Some(session_id) = self.session_id.borrow().as_deref() {
+            headers
+                .set("Mcp-Session-Id", session_id)
+                .map_err(js_error)?;
+        }
+        Ok(headers)
+    }
+}
+
+impl McpTransport for FetchTransport {
+    async fn send(&self, request: Value) -> Result<Value> {
+        let headers = self.request_headers()?;
+        let init = RequestInit::new();
+        init.set_method("POST");
+        init.set_headers(&headers);
+        init.set_body(&JsValue::from_str(&request.to_string()));
+        let request = Request::new_with_str_and_init(&self.url, &init).map_err(js_error)?;
+
+        let response: Response = JsFuture::from(global_fetch(&request))
+            .await
+            .map_err(js_error)?
+            .dyn_into()
+            .map_err(js_error)?;
+        if let Ok(Some(session_id)) = response.headers().get("Mcp-Session-Id") {
+            self.session_id.replace(Some(session_id));
+        }
+        if !response.ok() {
+            anyhow::bail!("{} answered HTTP {}", self.url, response.status());
+        }
+
+        let body = JsFuture::from(response.text().map_err(js_error)?)
+            .await
+            .map_err(js_error)?
+            .as_string()
+            .context("Response body is not text")?;
+        serde_json::from_str(&body).context("Response is not JSON")
+    }
+}
+
+/// Local tools plus the tools of remote MCP servers reached over `fetch`
+#[wasm_bindgen]
+pub struct CasialFederationWasm {
+    tools: Rc<RefCell<Vec<Value>>>,
+    transports: Rc<RefCell<HashMap<String, Rc<FetchTransport>>>>,
+}
+
+#[wasm_bindgen]
+impl CasialFederationWasm {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CasialFederationWasm {
+        CasialFederationWasm {
+            tools: Rc::new(RefCell::new(Vec::new())),
+            transports: Rc::new(RefCell::new(HashMap::new())),
+        }
+    }
+
+    /// Add a local tool, given as a `tools/list` entry in JSON
+    #[wasm_bindgen(js_name = registerTool)]
+    pub fn register_tool(&self, tool_json: &str) -> Result<(), JsValue> {
+        let tool: Value = serde_json::from_str(tool_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse tool JSON: {}", e)))?;
+        if tool.get("name").and_then(Value::as_str).is_none() {
+            return Err(JsValue::from_str("Tool is missing a name"));
+        }
+        self.tools.borrow_mut().push(tool);
+        Ok(())
+    }
+
+    /// Local and federated tools as a JSON array; federated ones carry their `source` URL
+    #[wasm_bindgen(js_name = getTools)]
+    pub fn get_tools(&self) -> String {
+        serde_json::to_string(&*self.tools.borrow()).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Connect to the MCP endpoint at `url` and merge in its tools
+    ///
+    /// `headers_json` is an optional object of extra request headers. Resolves to the number
+    /// of remote tools added.
+    #[wasm_bindgen(js_name = federate)]
+    pub fn federate(&self, url: String, headers_json: Option<String>) -> js_sys::Promise {
+        let tools = Rc::clone(&self.tools);
+        let transports = Rc::clone(&self.transports);
+        future_to_promise(async move {
+            let headers: HashMap<String, String> = match headers_json {
+                Some(json) => serde_json::from_str(&json)
+                    .context("Failed to parse headers JSON")
+                    .map_err(to_js_error)?,
+                None => HashMap::new(),
+            };
+            let transport = Rc::new(FetchTransport::new(url.clone(), headers));
+            transport.initialize().await.map_err(to_js_error)?;
+            let remote = list_remote_tools(&*transport).await.map_err(to_js_error)?;
+
+            let added = merge_remote_tools(&mut tools.borrow_mut(), remote, &url);
+            transports.borrow_mut().insert(url, transport);
+            Ok(JsValue::from(added as u32))
+        })
+    }
+
+    /// Call a federated tool on the server it came from; resolves to the result as JSON
+    #[wasm_bindgen(js_name = callTool)]
+    pub fn call_tool(&self, name: String, args_json: &str) -> js_sys::Promise {
+        let arguments = serde_json::from_str::<Value>(args_json);
+        let transport = self
+            .tools
+            .borrow()
+            .iter()
+            .find(|tool| tool["name"] == name.as_str())
+            .and_then(|tool| tool.get("source").and_then(Value::as_str))
+            .and_then(|source| self.transports.borrow().get(source).cloned());
+        future_to_promise(async move {
+            let arguments = arguments
+                .map_err(|e| JsValue::from_str(&format!("Failed to parse arguments: {}", e)))?;
+            let transport = transport
+                .ok_or_else(|| JsValue::from_str(&format!("No federated tool '{}'", name)))?;
+            let result = call_remote_tool(&*transport, &name, arguments)
+                .await
+                .map_err(to_js_error)?;
+            Ok(JsValue::from_str(&result.to_string()))
+        })
+    }
+}
+
+impl Default for CasialFederationWasm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Needs a JS host to provide `fetch` and `Response`
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Replace the global `fetch` with a fake MCP server
+    fn mock_fetch() {
+        let fetch = js_sys::Function::new_with_args(
+            "request",
+            r#"
+            return request.text().then(function (body) {
+                var message = JSON.parse(body);
+                var result;
+                if (message.method === "tools/list") {
+                    result = { tools: [
+                        { name: "web_search", description: "remote search" },
+                        { name: "lookup", description: "remote lookup" }
+                    ] };
+                } else if (message.method === "tools/call") {
+                    result = { content: [{ type: "text", text: "looked up " + message.params.arguments.term }] };
+                } else {
+                    result = { protocolVersion: "2024-11-05", capabilities: {} };
+                }
+                return new Response(JSON.stringify({ jsonrpc: "2.0", id: message.id, result: result }), {
+                    headers: { "Content-Type": "application/json", "Mcp-Session-Id": "mock-session" }
+                });
+            });
+            "#,
+        );
+        js_sys::Reflect::set(&js_sys::global(), &JsValue::from_str("fetch"), &fetch).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn remote_tools_merge_into_the_local_view() {
+        mock_fetch();
+        let federation = CasialFederationWasm::new();
+        federation
+            .register_tool(r#"{ "name": "web_search", "description": "local search" }"#)
+            .unwrap();
+
+        let added = JsFuture::from(federation.federate("https://remote.example/mcp".into(), None))
+            .await
+            .unwrap();
+        assert_eq!(added.as_f64(), Some(1.0));
+
+        let tools: Vec<Value> = serde_json::from_str(&federation.get_tools()).unwrap();
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0]["description"], "local search");
+        assert_eq!(tools[1]["name"], "lookup");
+        assert_eq!(tools[1]["source"], "https://remote.example/mcp");
+
+        let result = JsFuture::from(federation.call_tool("lookup".into(), r#"{ "term": "rust" }"#))
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(&result.as_string().unwrap()).unwrap();
+        assert_eq!(result["content"][0]["text"], "looked up rust");
+    }
+}
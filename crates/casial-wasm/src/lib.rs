@@ -3,11 +3,15 @@
 //! WebAssembly bindings for universal consciousness-aware context coordination.
 //! Enables deployment across browsers, edge computing, and any JavaScript environment.
 
-use casial_core::{CasialEngine, CasialMission, CoordinationRequest, PerceptionId};
+use casial_core::{CasialEngine, CasialMission, CoordinationRequest, ParadoxReport, PerceptionId};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{cell::Cell, collections::HashMap};
 use wasm_bindgen::prelude::*;
 
+mod federation;
+
+pub use federation::{CasialFederationWasm, FetchTransport};
+
 // Set up memory allocator for WASM
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
@@ -23,6 +27,9 @@ pub fn main() {
 #[wasm_bindgen]
 pub struct CasialEngineWasm {
     engine: CasialEngine,
+    event_callback: Option<js_sys::Function>,
+    /// Set while the event callback runs so events raised from inside it are dropped
+    dispatching: Cell<bool>,
 }
 
 /// JavaScript-friendly coordination request
@@ -33,7 +40,8 @@ pub struct CoordinationRequestJs {
     pub environment: HashMap<String, String>,
     pub project_path: Option<String>,
     pub active_perceptions: Vec<String>, // Simplified as strings for JS
-    pub paradox_tolerance: f64,
+    #[serde(default)]
+    pub paradox_tolerance: Option<f64>,
 }
 
 /// JavaScript-friendly coordination result
@@ -58,6 +66,21 @@ pub struct ParadoxReportJs {
     pub confidence_impact: f64,
 }
 
+impl From<&ParadoxReport> for ParadoxReportJs {
+    fn from(report: &ParadoxReport) -> Self {
+        Self {
+            id: report.id.to_string(),
+            description: report.description.clone(),
+            severity: report.severity.as_ref().map_or_else(
+                || "unknown".to_string(),
+                |severity| format!("{:?}", severity),
+            ),
+            resolution_strategy: format!("{:?}", report.resolution_strategy),
+            confidence_impact: report.confidence_impact,
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl CasialEngineWasm {
     /// Create a new Casial engine for WASM
@@ -65,9 +88,23 @@ impl CasialEngineWasm {
     pub fn new() -> CasialEngineWasm {
         CasialEngineWasm {
             engine: CasialEngine::new(),
+            event_callback: None,
+            dispatching: Cell::new(false),
         }
     }
 
+    /// Register a callback invoked with each coordination/paradox event as a JSON string
+    #[wasm_bindgen(js_name = setEventCallback)]
+    pub fn set_event_callback(&mut self, callback: js_sys::Function) {
+        self.event_callback = Some(callback);
+    }
+
+    /// Remove the event callback
+    #[wasm_bindgen(js_name = clearEventCallback)]
+    pub fn clear_event_callback(&mut self) {
+        self.event_callback = None;
+    }
+
     /// Load a mission from JSON string
     #[wasm_bindgen(js_name = loadMissionFromJson)]
     pub fn load_mission_from_json(&mut self, mission_json: &str) -> Result<(), JsValue> {
@@ -98,7 +135,10 @@ impl CasialEngineWasm {
                 .iter()
                 .map(|_| PerceptionId::new()) // Simplified conversion
                 .collect(),
-            paradox_tolerance: js_request.paradox_tolerance,
+            paradox_tolerance: js_request
+                .paradox_tolerance
+                .and_then(casial_core::sanitize_paradox_tolerance),
+            ..Default::default()
         };
 
         let result = self
@@ -116,17 +156,23 @@ impl CasialEngineWasm {
             paradoxes_detected: result
                 .paradoxes_detected
                 .iter()
-                .map(|p| ParadoxReportJs {
-                    id: p.id.to_string(),
-                    description: p.description.clone(),
-                    severity: "unknown".to_string(), // severity field not available
-                    resolution_strategy: format!("{:?}", p.resolution_strategy),
-                    confidence_impact: p.confidence_impact,
-                })
+                .map(ParadoxReportJs::from)
                 .collect(),
             metadata: result.metadata.into_iter().collect(),
         };
 
+        for paradox in &js_result.paradoxes_detected {
+            self.emit_event(&serde_json::json!({ "type": "paradox", "paradox": paradox }));
+        }
+        self.emit_event(&serde_json::json!({
+            "type": "coordination",
+            "tool_name": js_result.metadata.get("tool_name"),
+            "applied": js_result.applied,
+            "activated_rules": js_result.activated_rules,
+            "used_templates": js_result.used_templates,
+            "paradox_count": js_result.paradoxes_detected.len()
+        }));
+
         serde_json::to_string(&js_result)
             .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
     }
@@ -163,6 +209,23 @@ impl CasialEngineWasm {
     }
 }
 
+impl CasialEngineWasm {
+    /// Pass an event to the registered callback; callback errors are logged, not propagated
+    fn emit_event(&self, event: &serde_json::Value) {
+        let Some(callback) = &self.event_callback else {
+            return;
+        };
+        if self.dispatching.replace(true) {
+            return;
+        }
+        let payload = JsValue::from_str(&event.to_string());
+        if let Err(error) = callback.call1(&JsValue::NULL, &payload) {
+            web_sys::console::warn_2(&JsValue::from_str("Casial event callback failed:"), &error);
+        }
+        self.dispatching.set(false);
+    }
+}
+
 /// Utility functions for JavaScript integration
 #[wasm_bindgen]
 pub struct CasialUtils;
@@ -244,7 +307,7 @@ impl CasialUtils {
             },
             project_path: Some("./sample-project".to_string()),
             active_perceptions: vec!["human-insight".to_string(), "ai-analysis".to_string()],
-            paradox_tolerance: 0.5,
+            paradox_tolerance: Some(0.5),
         };
 
         serde_json::to_string(&request).unwrap_or_else(|_| "{}".to_string())
@@ -299,7 +362,7 @@ export interface CoordinationRequestJs {
     environment: Record<string, string>;
     project_path?: string;
     active_perceptions: string[];
-    paradox_tolerance: number;
+    paradox_tolerance?: number;
 }
 
 export interface CoordinationResultJs {
@@ -312,6 +375,17 @@ export interface CoordinationResultJs {
     metadata: Record<string, any>;
 }
 
+export type CasialEventJs =
+    | { type: "paradox"; paradox: ParadoxReportJs }
+    | {
+          type: "coordination";
+          tool_name: string;
+          applied: boolean;
+          activated_rules: string[];
+          used_templates: string[];
+          paradox_count: number;
+      };
+
 export interface ParadoxReportJs {
     id: string;
     description: string;
@@ -327,6 +401,16 @@ export class CasialEngineWasm {
     getCoordinationHistory(): string;
     getParadoxRegistry(): string;
     getStatistics(): string;
+    setEventCallback(callback: (event: string) => void): void;
+    clearEventCallback(): void;
+}
+
+export class CasialFederationWasm {
+    constructor();
+    registerTool(tool_json: string): void;
+    getTools(): string;
+    federate(url: string, headers_json?: string): Promise<number>;
+    callTool(name: string, args_json: string): Promise<string>;
 }
 
 export class CasialUtils {
@@ -370,4 +454,80 @@ mod tests {
         assert!(version.contains("context-casial-xpress"));
         assert!(version.contains("ubiquity-os"));
     }
+
+    #[wasm_bindgen_test]
+    fn test_event_callback_fires_during_coordinate() {
+        let paradox_mission = |id: &str, affinity: &str| {
+            serde_json::json!({
+                "id": id, "name": id, "description": "",
+                "templates": [{
+                    "id": "guide", "name": "Guide", "description": "", "categories": [],
+                    "priority": 1, "enabled": true, "content": "Cite sources.",
+                    "perception_affinity": [affinity], "paradox_resistance": 0.5, "metadata": {}
+                }],
+                "rules": [{
+                    "id": format!("{}-rule", id), "name": "Guide", "enabled": true,
+                    "conditions": {
+                        "tool_patterns": ["test"], "environment_vars": {}, "file_signals": [],
+                        "perception_states": [], "min_confidence": null
+                    },
+                    "actions": {
+                        "template_ids": ["guide"], "transform_type": "Prepend",
+                        "target_field": null, "char_limit": null, "perception_lock": false
+                    },
+                    "perception_scope": [],
+                    "paradox_handling": "Coexist"
+                }],
+                "perceptions": [],
+                "budgets": {
+                    "global_char_limit": 1000, "per_tool_limits": {},
+                    "perception_quotas": {}, "paradox_overhead": 0.0
+                },
+                "created_at": "2025-01-01T00:00:00Z",
+                "updated_at": "2025-01-01T00:00:00Z"
+            })
+            .to_string()
+        };
+        let mut engine = CasialEngineWasm::new();
+        // The same template with different affinities in two missions is a paradox
+        engine
+            .load_mission_from_json(&paradox_mission(
+                "first",
+                "00000000-0000-0000-0000-000000000001",
+            ))
+            .unwrap();
+        engine
+            .load_mission_from_json(&paradox_mission(
+                "second",
+                "00000000-0000-0000-0000-000000000002",
+            ))
+            .unwrap();
+
+        let events = js_sys::Array::new();
+        let record = Closure::<dyn Fn(String)>::new({
+            let events = events.clone();
+            move |event: String| {
+                events.push(&JsValue::from_str(&event));
+            }
+        });
+        engine.set_event_callback(record.as_ref().unchecked_ref::<js_sys::Function>().clone());
+
+        engine
+            .coordinate(&CasialUtils::create_sample_request())
+            .unwrap();
+
+        let events: Vec<serde_json::Value> = events
+            .iter()
+            .map(|event| serde_json::from_str(&event.as_string().unwrap()).unwrap())
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["type"], "paradox");
+        assert!(events[0]["paradox"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("guide"));
+        assert_eq!(events[1]["type"], "coordination");
+        assert_eq!(events[1]["tool_name"], "test_tool");
+        assert_eq!(events[1]["paradox_count"], 1);
+    }
 }